@@ -0,0 +1,48 @@
+"""Helpers to create certificates for TLS tests."""
+
+from cryptography import x509
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import ec
+from cryptography.x509.oid import NameOID
+import datetime
+import ipaddress
+
+
+def new_key():
+    return ec.generate_private_key(ec.SECP256R1())
+
+
+def new_cert(subject_key, common_name, issuer_cert=None, issuer_key=None, ip=None):
+    name = x509.Name([x509.NameAttribute(NameOID.COMMON_NAME, common_name)])
+    now = datetime.datetime.now(datetime.timezone.utc)
+    builder = (
+        x509.CertificateBuilder()
+        .subject_name(name)
+        .issuer_name(issuer_cert.subject if issuer_cert else name)
+        .public_key(subject_key.public_key())
+        .serial_number(x509.random_serial_number())
+        .not_valid_before(now - datetime.timedelta(days=1))
+        .not_valid_after(now + datetime.timedelta(days=1))
+        .add_extension(
+            x509.BasicConstraints(ca=issuer_cert is None, path_length=None),
+            critical=True,
+        )
+    )
+    if ip is not None:
+        builder = builder.add_extension(
+            x509.SubjectAlternativeName([x509.IPAddress(ipaddress.ip_address(ip))]),
+            critical=False,
+        )
+    return builder.sign(issuer_key or subject_key, hashes.SHA256())
+
+
+def pem_cert(cert):
+    return cert.public_bytes(serialization.Encoding.PEM)
+
+
+def pem_key(key):
+    return key.private_bytes(
+        serialization.Encoding.PEM,
+        serialization.PrivateFormat.PKCS8,
+        serialization.NoEncryption(),
+    )
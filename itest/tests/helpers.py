@@ -10,7 +10,9 @@ from pyln.testing.fixtures import (
 )
 from pyln.testing.utils import wait_for, SLOW_MACHINE
 import hashlib
+import musig2
 import os
+import time
 
 __all__ = [
     "bitcoind",
@@ -28,6 +30,7 @@ __all__ = [
     "create_swap_no_invoice",
     "create_swap_extended",
     "create_swap",
+    "sign_ownership_proof",
     "whatthefee",
     "postgres_factory",
     "swapd_factory",
@@ -112,3 +115,14 @@ def create_swap(user: ClnNode, swapper: SwapdServer, amount=100_000_000):
         user, swapper, amount
     )
     return address, payment_request, h, preimage
+
+
+def sign_ownership_proof(refund_privkey: PrivateKey, address: str, timestamp=None):
+    if timestamp is None:
+        timestamp = int(time.time())
+    msg = musig2.tagged_hash(
+        "swapd/SwapOwnershipProof",
+        address.encode() + timestamp.to_bytes(8, byteorder="big"),
+    )
+    signature = musig2.schnorr_sign(msg, refund_privkey.to_bytes(), os.urandom(32))
+    return timestamp, signature
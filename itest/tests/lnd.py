@@ -450,6 +450,11 @@ class LndGrpc(object):
         resp = self.stub.NewAddress(payload)
         return {"address": resp.address}
 
+    def send_all(self, address: str):
+        payload = lightning_pb2.SendCoinsRequest(addr=address, send_all=True)
+        resp = self.stub.SendCoins(payload)
+        return {"txid": resp.txid}
+
     def open_channel(self, node_pubkey: str, local_funding_amount: int):
         payload = lightning_pb2.OpenChannelRequest(
             node_pubkey_string=node_pubkey, local_funding_amount=local_funding_amount
@@ -96,6 +96,29 @@ def has_even_y(P: Point) -> bool:
     return y(P) % 2 == 0
 
 
+def schnorr_sign(msg: bytes, seckey: bytes, aux_rand: bytes) -> bytes:
+    d0 = int_from_bytes(seckey)
+    if not (1 <= d0 <= n - 1):
+        raise ValueError("The secret key must be an integer in the range 1..n-1.")
+    if len(aux_rand) != 32:
+        raise ValueError("aux_rand must be 32 bytes instead of %i." % len(aux_rand))
+    P = point_mul(G, d0)
+    assert P is not None
+    d = d0 if has_even_y(P) else n - d0
+    t = bytes_xor(bytes_from_int(d), tagged_hash("BIP0340/aux", aux_rand))
+    k0 = int_from_bytes(tagged_hash("BIP0340/nonce", t + xbytes(P) + msg)) % n
+    if k0 == 0:
+        raise RuntimeError("Failure. This happens only with negligible probability.")
+    R = point_mul(G, k0)
+    assert R is not None
+    k = n - k0 if not has_even_y(R) else k0
+    e = int_from_bytes(tagged_hash("BIP0340/challenge", xbytes(R) + xbytes(P) + msg)) % n
+    sig = xbytes(R) + bytes_from_int((k + e * d) % n)
+    if not schnorr_verify(msg, xbytes(P), sig):
+        raise RuntimeError("The created signature does not pass verification.")
+    return sig
+
+
 def schnorr_verify(msg: bytes, pubkey: bytes, sig: bytes) -> bool:
     if len(msg) != 32:
         raise ValueError("The message must be a 32-byte array.")
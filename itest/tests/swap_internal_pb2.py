@@ -19,7 +19,7 @@ _sym_db = _symbol_database.Default()
 
 
 DESCRIPTOR = _descriptor_pool.Default().AddSerializedFile(
-    b'\n\x13swap_internal.proto\x12\rswap_internal"-\n\x18\x41\x64\x64\x41\x64\x64ressFiltersRequest\x12\x11\n\taddresses\x18\x01 \x03(\t"\x1b\n\x19\x41\x64\x64\x41\x64\x64ressFiltersResponse"\x10\n\x0eGetInfoRequest"8\n\x0fGetInfoResponse\x12\x14\n\x0c\x62lock_height\x18\x01 \x01(\x04\x12\x0f\n\x07network\x18\x02 \x01(\t"\x90\x01\n\x0eGetSwapRequest\x12\x14\n\x07\x61\x64\x64ress\x18\x01 \x01(\tH\x00\x88\x01\x01\x12\x1c\n\x0fpayment_request\x18\x02 \x01(\tH\x01\x88\x01\x01\x12\x19\n\x0cpayment_hash\x18\x03 \x01(\x0cH\x02\x88\x01\x01\x42\n\n\x08_addressB\x12\n\x10_payment_requestB\x0f\n\r_payment_hash"\xf6\x01\n\x0fGetSwapResponse\x12\x0f\n\x07\x61\x64\x64ress\x18\x01 \x01(\t\x12\x15\n\rcreation_time\x18\x02 \x01(\x04\x12\x14\n\x0cpayment_hash\x18\x03 \x01(\t\x12*\n\x07outputs\x18\x04 \x03(\x0b\x32\x19.swap_internal.SwapOutput\x12\x11\n\tlock_time\x18\x05 \x01(\r\x12-\n\x0c\x61\x63tive_locks\x18\x06 \x03(\x0b\x32\x17.swap_internal.SwapLock\x12\x37\n\x10payment_attempts\x18\x07 \x03(\x0b\x32\x1d.swap_internal.PaymentAttempt"\x8d\x01\n\nSwapOutput\x12\x10\n\x08outpoint\x18\x01 \x01(\t\x12\x1b\n\x13\x63onfirmation_height\x18\x02 \x01(\x04\x12\x12\n\nblock_hash\x18\x03 \x01(\t\x12\x32\n\x05spend\x18\x04 \x01(\x0b\x32\x1e.swap_internal.SwapOutputSpendH\x00\x88\x01\x01\x42\x08\n\x06_spend"\x93\x01\n\x0fSwapOutputSpend\x12\x13\n\x0binput_index\x18\x01 \x01(\r\x12\x0c\n\x04txid\x18\x02 \x01(\t\x12,\n\nspend_type\x18\x03 \x01(\x0e\x32\x18.swap_internal.SpendType\x12\x1b\n\x13\x63onfirmation_height\x18\x04 \x01(\x04\x12\x12\n\nblock_hash\x18\x05 \x01(\t"n\n\x08SwapLock\x12"\n\x15payment_attempt_label\x18\x01 \x01(\tH\x00\x88\x01\x01\x12\x16\n\trefund_id\x18\x02 \x01(\tH\x01\x88\x01\x01\x42\x18\n\x16_payment_attempt_labelB\x0c\n\n_refund_id"\xb7\x01\n\x0ePaymentAttempt\x12\x17\n\x0fpayment_request\x18\x01 \x01(\t\x12\x13\n\x0b\x61mount_msat\x18\x02 \x01(\x04\x12\x12\n\x05\x65rror\x18\x03 \x01(\tH\x00\x88\x01\x01\x12\x15\n\rcreation_time\x18\x04 \x01(\x04\x12\x11\n\toutpoints\x18\x05 \x03(\t\x12\r\n\x05label\x18\x06 \x01(\t\x12\x0f\n\x07success\x18\x07 \x01(\x08\x12\x0f\n\x07pending\x18\x08 \x01(\x08\x42\x08\n\x06_error"\x16\n\x14ListClaimableRequest"I\n\x15ListClaimableResponse\x12\x30\n\nclaimables\x18\x01 \x03(\x0b\x32\x1c.swap_internal.ClaimableUtxo"\xc3\x01\n\rClaimableUtxo\x12\x10\n\x08outpoint\x18\x01 \x01(\t\x12\x11\n\tswap_hash\x18\x02 \x01(\t\x12\x11\n\tlock_time\x18\x03 \x01(\r\x12\x1b\n\x13\x63onfirmation_height\x18\x04 \x01(\x04\x12\x12\n\nblock_hash\x18\x05 \x01(\t\x12\x13\n\x0b\x62locks_left\x18\x06 \x01(\x05\x12\x1e\n\x11paid_with_request\x18\x07 \x01(\tH\x00\x88\x01\x01\x42\x14\n\x12_paid_with_request"\x96\x01\n\x0c\x43laimRequest\x12\x11\n\toutpoints\x18\x01 \x03(\t\x12 \n\x13\x64\x65stination_address\x18\x02 \x01(\tH\x00\x88\x01\x01\x12\x17\n\nfee_per_kw\x18\x03 \x01(\rH\x01\x88\x01\x01\x12\x11\n\tauto_bump\x18\x04 \x01(\x08\x42\x16\n\x14_destination_addressB\r\n\x0b_fee_per_kw"2\n\rClaimResponse\x12\r\n\x05tx_id\x18\x01 \x01(\t\x12\x12\n\nfee_per_kw\x18\x02 \x01(\r"\r\n\x0bStopRequest"\x0e\n\x0cStopResponse*R\n\tSpendType\x12\x0b\n\x07UNKNOWN\x10\x00\x12\t\n\x05\x43LAIM\x10\x01\x12\x16\n\x12\x43OOPERATIVE_REFUND\x10\x02\x12\x15\n\x11UNILATERAL_REFUND\x10\x03\x32\xf6\x03\n\x0bSwapManager\x12h\n\x11\x41\x64\x64\x41\x64\x64ressFilters\x12\'.swap_internal.AddAddressFiltersRequest\x1a(.swap_internal.AddAddressFiltersResponse"\x00\x12J\n\x07GetInfo\x12\x1d.swap_internal.GetInfoRequest\x1a\x1e.swap_internal.GetInfoResponse"\x00\x12J\n\x07GetSwap\x12\x1d.swap_internal.GetSwapRequest\x1a\x1e.swap_internal.GetSwapResponse"\x00\x12\\\n\rListClaimable\x12#.swap_internal.ListClaimableRequest\x1a$.swap_internal.ListClaimableResponse"\x00\x12\x44\n\x05\x43laim\x12\x1b.swap_internal.ClaimRequest\x1a\x1c.swap_internal.ClaimResponse"\x00\x12\x41\n\x04Stop\x12\x1a.swap_internal.StopRequest\x1a\x1b.swap_internal.StopResponse"\x00\x62\x06proto3'
+    b'\n\x13swap_internal.proto\x12\rswap_internal"-\n\x18\x41\x64\x64\x41\x64\x64ressFiltersRequest\x12\x11\n\taddresses\x18\x01 \x03(\t"\x1b\n\x19\x41\x64\x64\x41\x64\x64ressFiltersResponse"\x10\n\x0eGetInfoRequest"8\n\x0fGetInfoResponse\x12\x14\n\x0c\x62lock_height\x18\x01 \x01(\x04\x12\x0f\n\x07network\x18\x02 \x01(\t"\x90\x01\n\x0eGetSwapRequest\x12\x14\n\x07\x61\x64\x64ress\x18\x01 \x01(\tH\x00\x88\x01\x01\x12\x1c\n\x0fpayment_request\x18\x02 \x01(\tH\x01\x88\x01\x01\x12\x19\n\x0cpayment_hash\x18\x03 \x01(\x0cH\x02\x88\x01\x01\x42\n\n\x08_addressB\x12\n\x10_payment_requestB\x0f\n\r_payment_hash"\x9f\x03\n\x0fGetSwapResponse\x12\x0f\n\x07\x61\x64\x64ress\x18\x01 \x01(\t\x12\x15\n\rcreation_time\x18\x02 \x01(\x04\x12\x14\n\x0cpayment_hash\x18\x03 \x01(\t\x12*\n\x07outputs\x18\x04 \x03(\x0b\x32\x19.swap_internal.SwapOutput\x12\x11\n\tlock_time\x18\x05 \x01(\r\x12-\n\x0c\x61\x63tive_locks\x18\x06 \x03(\x0b\x32\x17.swap_internal.SwapLock\x12\x37\n\x10payment_attempts\x18\x07 \x03(\x0b\x32\x1d.swap_internal.PaymentAttempt\x12\x1c\n\x0f\x63laim_key_index\x18\x08 \x01(\rH\x00\x88\x01\x01\x12\x1d\n\x10\x63laim_key_family\x18\t \x01(\rH\x01\x88\x01\x01\x12\x41\n\x13unconfirmed_outputs\x18\n \x03(\x0b\x32$.swap_internal.UnconfirmedSwapOutputB\x12\n\x10_claim_key_indexB\x13\n\x11_claim_key_family"\x8d\x01\n\nSwapOutput\x12\x10\n\x08outpoint\x18\x01 \x01(\t\x12\x1b\n\x13\x63onfirmation_height\x18\x02 \x01(\x04\x12\x12\n\nblock_hash\x18\x03 \x01(\t\x12\x32\n\x05spend\x18\x04 \x01(\x0b\x32\x1e.swap_internal.SwapOutputSpendH\x00\x88\x01\x01\x42\x08\n\x06_spend"f\n\x15UnconfirmedSwapOutput\x12\x10\n\x08outpoint\x18\x01 \x01(\t\x12\x12\n\namount_sat\x18\x02 \x01(\x04\x12\x13\n\x0breplaceable\x18\x03 \x01(\x08\x12\x12\n\nfirst_seen\x18\x04 \x01(\x04"\x93\x01\n\x0fSwapOutputSpend\x12\x13\n\x0binput_index\x18\x01 \x01(\r\x12\x0c\n\x04txid\x18\x02 \x01(\t\x12,\n\nspend_type\x18\x03 \x01(\x0e\x32\x18.swap_internal.SpendType\x12\x1b\n\x13\x63onfirmation_height\x18\x04 \x01(\x04\x12\x12\n\nblock_hash\x18\x05 \x01(\t"n\n\x08SwapLock\x12"\n\x15payment_attempt_label\x18\x01 \x01(\tH\x00\x88\x01\x01\x12\x16\n\trefund_id\x18\x02 \x01(\tH\x01\x88\x01\x01\x42\x18\n\x16_payment_attempt_labelB\x0c\n\n_refund_id"\xc8\x01\n\x0ePaymentAttempt\x12\x17\n\x0fpayment_request\x18\x01 \x01(\t\x12\x13\n\x0b\x61mount_msat\x18\x02 \x01(\x04\x12\x12\n\x05\x65rror\x18\x03 \x01(\tH\x00\x88\x01\x01\x12\x15\n\rcreation_time\x18\x04 \x01(\x04\x12\x11\n\toutpoints\x18\x05 \x03(\t\x12\r\n\x05label\x18\x06 \x01(\t\x12\x0f\n\x07success\x18\x07 \x01(\x08\x12\x0f\n\x07pending\x18\x08 \x01(\x08\x12\x0f\n\x07\x66\x65\x65_sat\x18\t \x01(\x04\x42\x08\n\x06_error"-\n\x15GetReverseSwapRequest\x12\x14\n\x0cpayment_hash\x18\x01 \x01(\x0c"\xad\x03\n\x16GetReverseSwapResponse\x12\x0f\n\x07\x61\x64\x64ress\x18\x01 \x01(\t\x12\x15\n\rcreation_time\x18\x02 \x01(\x04\x12\x14\n\x0cpayment_hash\x18\x03 \x01(\t\x12\x17\n\x0fpayment_request\x18\x04 \x01(\t\x12\x12\n\namount_sat\x18\x05 \x01(\x04\x12\x1b\n\x13invoice_amount_msat\x18\x06 \x01(\x04\x12\x13\n\x0block_height\x18\x07 \x01(\r\x12\x0e\n\x06status\x18\x08 \x01(\t\x12\x1a\n\rfunding_tx_id\x18\t \x01(\tH\x00\x88\x01\x01\x12\x15\n\x08preimage\x18\n \x01(\tH\x01\x88\x01\x01\x12*\n\x07outputs\x18\x0b \x03(\x0b\x32\x19.swap_internal.SwapOutput\x12\x1d\n\x10refund_key_index\x18\x0c \x01(\rH\x02\x88\x01\x01\x12\x1e\n\x11refund_key_family\x18\r \x01(\rH\x03\x88\x01\x01\x42\x10\n\x0e_funding_tx_idB\x0b\n\t_preimageB\x13\n\x11_refund_key_indexB\x14\n\x12_refund_key_family"\x16\n\x14ListClaimableRequest"I\n\x15ListClaimableResponse\x12\x30\n\nclaimables\x18\x01 \x03(\x0b\x32\x1c.swap_internal.ClaimableUtxo"\xc3\x01\n\rClaimableUtxo\x12\x10\n\x08outpoint\x18\x01 \x01(\t\x12\x11\n\tswap_hash\x18\x02 \x01(\t\x12\x11\n\tlock_time\x18\x03 \x01(\r\x12\x1b\n\x13\x63onfirmation_height\x18\x04 \x01(\x04\x12\x12\n\nblock_hash\x18\x05 \x01(\t\x12\x13\n\x0b\x62locks_left\x18\x06 \x01(\x05\x12\x1e\n\x11paid_with_request\x18\x07 \x01(\tH\x00\x88\x01\x01\x42\x14\n\x12_paid_with_request"\x96\x01\n\x0c\x43laimRequest\x12\x11\n\toutpoints\x18\x01 \x03(\t\x12 \n\x13\x64\x65stination_address\x18\x02 \x01(\tH\x00\x88\x01\x01\x12\x17\n\nfee_per_kw\x18\x03 \x01(\rH\x01\x88\x01\x01\x12\x11\n\tauto_bump\x18\x04 \x01(\x08\x42\x16\n\x14_destination_addressB\r\n\x0b_fee_per_kw"2\n\rClaimResponse\x12\r\n\x05tx_id\x18\x01 \x01(\t\x12\x12\n\nfee_per_kw\x18\x02 \x01(\r"C\n\x19ReencryptClaimKeysRequest\x12\x17\n\nbatch_size\x18\x01 \x01(\rH\x00\x88\x01\x01\x42\r\n\x0b_batch_size"1\n\x1aReencryptClaimKeysResponse\x12\x13\n\x0breencrypted\x18\x01 \x01(\x04"7\n\rRescanRequest\x12\x13\n\x0b\x66rom_height\x18\x01 \x01(\x04\x12\x11\n\taddresses\x18\x02 \x03(\t"9\n\x0eRescanResponse\x12\x13\n\x0bnew_outputs\x18\x01 \x01(\x04\x12\x12\n\nnew_spends\x18\x02 \x01(\x04"\x1a\n\x18ListUnpaidOutputsRequest"I\n\x19ListUnpaidOutputsResponse\x12,\n\x07outputs\x18\x01 \x03(\x0b\x32\x1b.swap_internal.UnpaidOutput"\x9c\x01\n\x0cUnpaidOutput\x12\x10\n\x08outpoint\x18\x01 \x01(\t\x12\x0f\n\x07\x61\x64\x64ress\x18\x02 \x01(\t\x12\x14\n\x0cpayment_hash\x18\x03 \x01(\t\x12\x12\n\namount_sat\x18\x04 \x01(\x04\x12\x15\n\rcreation_time\x18\x05 \x01(\x04\x12\x18\n\x0b\x64isposition\x18\x06 \x01(\tH\x00\x88\x01\x01\x42\x0e\n\x0c_disposition"f\n\x1bSetOutputDispositionRequest\x12\x10\n\x08outpoint\x18\x01 \x01(\t\x12\x35\n\x0b\x64isposition\x18\x02 \x01(\x0e\x32 .swap_internal.OutputDisposition"\x1e\n\x1cSetOutputDispositionResponse"\x1c\n\x1aSubscribeSwapEventsRequest"\xfd\x03\n\tSwapEvent\x12\x0f\n\x07\x61\x64\x64ress\x18\x01 \x01(\t\x12\x11\n\ttimestamp\x18\x02 \x01(\x04\x12<\n\x11\x64\x65posit_confirmed\x18\x03 \x01(\x0b\x32\x1f.swap_internal.DepositConfirmedH\x00\x12\'\n\x04paid\x18\x04 \x01(\x0b\x32\x17.swap_internal.SwapPaidH\x00\x12:\n\x0epayment_failed\x18\x05 \x01(\x0b\x32 .swap_internal.SwapPaymentFailedH\x00\x12\x38\n\x0f\x63laim_broadcast\x18\x06 \x01(\x0b\x32\x1d.swap_internal.ClaimBroadcastH\x00\x12\x32\n\x0coutput_spent\x18\x07 \x01(\x0b\x32\x1a.swap_internal.OutputSpentH\x00\x12@\n\x13\x64\x65posit_unconfirmed\x18\x08 \x01(\x0b\x32!.swap_internal.DepositUnconfirmedH\x00\x12\x38\n\x0f\x64\x65posit_dropped\x18\t \x01(\x0b\x32\x1d.swap_internal.DepositDroppedH\x00\x12\x36\n\x0erefund_offered\x18\n \x01(\x0b\x32\x1c.swap_internal.RefundOfferedH\x00\x42\x07\n\x05\x65vent"N\n\x10\x44\x65positConfirmed\x12\x10\n\x08outpoint\x18\x01 \x01(\t\x12\x12\n\namount_sat\x18\x02 \x01(\x04\x12\x14\n\x0c\x62lock_height\x18\x03 \x01(\x04"O\n\x12\x44\x65positUnconfirmed\x12\x10\n\x08outpoint\x18\x01 \x01(\t\x12\x12\n\namount_sat\x18\x02 \x01(\x04\x12\x13\n\x0breplaceable\x18\x03 \x01(\x08""\n\x0e\x44\x65positDropped\x12\x10\n\x08outpoint\x18\x01 \x01(\t"5\n\rRefundOffered\x12\x10\n\x08outpoint\x18\x01 \x01(\t\x12\x12\n\namount_sat\x18\x02 \x01(\x04" \n\x08SwapPaid\x12\x14\n\x0cpayment_hash\x18\x01 \x01(\t"8\n\x11SwapPaymentFailed\x12\x14\n\x0cpayment_hash\x18\x01 \x01(\t\x12\r\n\x05\x65rror\x18\x02 \x01(\t"\x1f\n\x0e\x43laimBroadcast\x12\r\n\x05tx_id\x18\x01 \x01(\t"M\n\x0bOutputSpent\x12\x10\n\x08outpoint\x18\x01 \x01(\t\x12\x16\n\x0espending_tx_id\x18\x02 \x01(\t\x12\x14\n\x0c\x62lock_height\x18\x03 \x01(\x04"\r\n\x0bStopRequest"\x0e\n\x0cStopResponse*R\n\tSpendType\x12\x0b\n\x07UNKNOWN\x10\x00\x12\t\n\x05\x43LAIM\x10\x01\x12\x16\n\x12\x43OOPERATIVE_REFUND\x10\x02\x12\x15\n\x11UNILATERAL_REFUND\x10\x03*/\n\x11OutputDisposition\x12\x0b\n\x07\x44ONATED\x10\x00\x12\r\n\tABANDONED\x10\x01\x32\xca\x08\n\x0bSwapManager\x12h\n\x11\x41\x64\x64\x41\x64\x64ressFilters\x12\'.swap_internal.AddAddressFiltersRequest\x1a(.swap_internal.AddAddressFiltersResponse"\x00\x12J\n\x07GetInfo\x12\x1d.swap_internal.GetInfoRequest\x1a\x1e.swap_internal.GetInfoResponse"\x00\x12J\n\x07GetSwap\x12\x1d.swap_internal.GetSwapRequest\x1a\x1e.swap_internal.GetSwapResponse"\x00\x12_\n\x0eGetReverseSwap\x12$.swap_internal.GetReverseSwapRequest\x1a%.swap_internal.GetReverseSwapResponse"\x00\x12\\\n\rListClaimable\x12#.swap_internal.ListClaimableRequest\x1a$.swap_internal.ListClaimableResponse"\x00\x12\x44\n\x05\x43laim\x12\x1b.swap_internal.ClaimRequest\x1a\x1c.swap_internal.ClaimResponse"\x00\x12k\n\x12ReencryptClaimKeys\x12(.swap_internal.ReencryptClaimKeysRequest\x1a).swap_internal.ReencryptClaimKeysResponse"\x00\x12G\n\x06Rescan\x12\x1c.swap_internal.RescanRequest\x1a\x1d.swap_internal.RescanResponse"\x00\x12h\n\x11ListUnpaidOutputs\x12\'.swap_internal.ListUnpaidOutputsRequest\x1a(.swap_internal.ListUnpaidOutputsResponse"\x00\x12q\n\x14SetOutputDisposition\x12*.swap_internal.SetOutputDispositionRequest\x1a+.swap_internal.SetOutputDispositionResponse"\x00\x12^\n\x13SubscribeSwapEvents\x12).swap_internal.SubscribeSwapEventsRequest\x1a\x18.swap_internal.SwapEvent"\x00\x30\x01\x12\x41\n\x04Stop\x12\x1a.swap_internal.StopRequest\x1a\x1b.swap_internal.StopResponse"\x00\x62\x06proto3'
 )
 
 _globals = globals()
@@ -27,8 +27,10 @@ _builder.BuildMessageAndEnumDescriptors(DESCRIPTOR, _globals)
 _builder.BuildTopDescriptorsAndMessages(DESCRIPTOR, "swap_internal_pb2", _globals)
 if not _descriptor._USE_C_DESCRIPTORS:
     DESCRIPTOR._loaded_options = None
-    _globals["_SPENDTYPE"]._serialized_start = 1711
-    _globals["_SPENDTYPE"]._serialized_end = 1793
+    _globals["_SPENDTYPE"]._serialized_start = 4112
+    _globals["_SPENDTYPE"]._serialized_end = 4194
+    _globals["_OUTPUTDISPOSITION"]._serialized_start = 4196
+    _globals["_OUTPUTDISPOSITION"]._serialized_end = 4243
     _globals["_ADDADDRESSFILTERSREQUEST"]._serialized_start = 38
     _globals["_ADDADDRESSFILTERSREQUEST"]._serialized_end = 83
     _globals["_ADDADDRESSFILTERSRESPONSE"]._serialized_start = 85
@@ -40,29 +42,73 @@ if not _descriptor._USE_C_DESCRIPTORS:
     _globals["_GETSWAPREQUEST"]._serialized_start = 191
     _globals["_GETSWAPREQUEST"]._serialized_end = 335
     _globals["_GETSWAPRESPONSE"]._serialized_start = 338
-    _globals["_GETSWAPRESPONSE"]._serialized_end = 584
-    _globals["_SWAPOUTPUT"]._serialized_start = 587
-    _globals["_SWAPOUTPUT"]._serialized_end = 728
-    _globals["_SWAPOUTPUTSPEND"]._serialized_start = 731
-    _globals["_SWAPOUTPUTSPEND"]._serialized_end = 878
-    _globals["_SWAPLOCK"]._serialized_start = 880
-    _globals["_SWAPLOCK"]._serialized_end = 990
-    _globals["_PAYMENTATTEMPT"]._serialized_start = 993
-    _globals["_PAYMENTATTEMPT"]._serialized_end = 1176
-    _globals["_LISTCLAIMABLEREQUEST"]._serialized_start = 1178
-    _globals["_LISTCLAIMABLEREQUEST"]._serialized_end = 1200
-    _globals["_LISTCLAIMABLERESPONSE"]._serialized_start = 1202
-    _globals["_LISTCLAIMABLERESPONSE"]._serialized_end = 1275
-    _globals["_CLAIMABLEUTXO"]._serialized_start = 1278
-    _globals["_CLAIMABLEUTXO"]._serialized_end = 1473
-    _globals["_CLAIMREQUEST"]._serialized_start = 1476
-    _globals["_CLAIMREQUEST"]._serialized_end = 1626
-    _globals["_CLAIMRESPONSE"]._serialized_start = 1628
-    _globals["_CLAIMRESPONSE"]._serialized_end = 1678
-    _globals["_STOPREQUEST"]._serialized_start = 1680
-    _globals["_STOPREQUEST"]._serialized_end = 1693
-    _globals["_STOPRESPONSE"]._serialized_start = 1695
-    _globals["_STOPRESPONSE"]._serialized_end = 1709
-    _globals["_SWAPMANAGER"]._serialized_start = 1796
-    _globals["_SWAPMANAGER"]._serialized_end = 2298
+    _globals["_GETSWAPRESPONSE"]._serialized_end = 753
+    _globals["_SWAPOUTPUT"]._serialized_start = 756
+    _globals["_SWAPOUTPUT"]._serialized_end = 897
+    _globals["_UNCONFIRMEDSWAPOUTPUT"]._serialized_start = 899
+    _globals["_UNCONFIRMEDSWAPOUTPUT"]._serialized_end = 1001
+    _globals["_SWAPOUTPUTSPEND"]._serialized_start = 1004
+    _globals["_SWAPOUTPUTSPEND"]._serialized_end = 1151
+    _globals["_SWAPLOCK"]._serialized_start = 1153
+    _globals["_SWAPLOCK"]._serialized_end = 1263
+    _globals["_PAYMENTATTEMPT"]._serialized_start = 1266
+    _globals["_PAYMENTATTEMPT"]._serialized_end = 1466
+    _globals["_GETREVERSESWAPREQUEST"]._serialized_start = 1468
+    _globals["_GETREVERSESWAPREQUEST"]._serialized_end = 1513
+    _globals["_GETREVERSESWAPRESPONSE"]._serialized_start = 1516
+    _globals["_GETREVERSESWAPRESPONSE"]._serialized_end = 1945
+    _globals["_LISTCLAIMABLEREQUEST"]._serialized_start = 1947
+    _globals["_LISTCLAIMABLEREQUEST"]._serialized_end = 1969
+    _globals["_LISTCLAIMABLERESPONSE"]._serialized_start = 1971
+    _globals["_LISTCLAIMABLERESPONSE"]._serialized_end = 2044
+    _globals["_CLAIMABLEUTXO"]._serialized_start = 2047
+    _globals["_CLAIMABLEUTXO"]._serialized_end = 2242
+    _globals["_CLAIMREQUEST"]._serialized_start = 2245
+    _globals["_CLAIMREQUEST"]._serialized_end = 2395
+    _globals["_CLAIMRESPONSE"]._serialized_start = 2397
+    _globals["_CLAIMRESPONSE"]._serialized_end = 2447
+    _globals["_REENCRYPTCLAIMKEYSREQUEST"]._serialized_start = 2449
+    _globals["_REENCRYPTCLAIMKEYSREQUEST"]._serialized_end = 2516
+    _globals["_REENCRYPTCLAIMKEYSRESPONSE"]._serialized_start = 2518
+    _globals["_REENCRYPTCLAIMKEYSRESPONSE"]._serialized_end = 2567
+    _globals["_RESCANREQUEST"]._serialized_start = 2569
+    _globals["_RESCANREQUEST"]._serialized_end = 2624
+    _globals["_RESCANRESPONSE"]._serialized_start = 2626
+    _globals["_RESCANRESPONSE"]._serialized_end = 2683
+    _globals["_LISTUNPAIDOUTPUTSREQUEST"]._serialized_start = 2685
+    _globals["_LISTUNPAIDOUTPUTSREQUEST"]._serialized_end = 2711
+    _globals["_LISTUNPAIDOUTPUTSRESPONSE"]._serialized_start = 2713
+    _globals["_LISTUNPAIDOUTPUTSRESPONSE"]._serialized_end = 2786
+    _globals["_UNPAIDOUTPUT"]._serialized_start = 2789
+    _globals["_UNPAIDOUTPUT"]._serialized_end = 2945
+    _globals["_SETOUTPUTDISPOSITIONREQUEST"]._serialized_start = 2947
+    _globals["_SETOUTPUTDISPOSITIONREQUEST"]._serialized_end = 3049
+    _globals["_SETOUTPUTDISPOSITIONRESPONSE"]._serialized_start = 3051
+    _globals["_SETOUTPUTDISPOSITIONRESPONSE"]._serialized_end = 3081
+    _globals["_SUBSCRIBESWAPEVENTSREQUEST"]._serialized_start = 3083
+    _globals["_SUBSCRIBESWAPEVENTSREQUEST"]._serialized_end = 3111
+    _globals["_SWAPEVENT"]._serialized_start = 3114
+    _globals["_SWAPEVENT"]._serialized_end = 3623
+    _globals["_DEPOSITCONFIRMED"]._serialized_start = 3625
+    _globals["_DEPOSITCONFIRMED"]._serialized_end = 3703
+    _globals["_DEPOSITUNCONFIRMED"]._serialized_start = 3705
+    _globals["_DEPOSITUNCONFIRMED"]._serialized_end = 3784
+    _globals["_DEPOSITDROPPED"]._serialized_start = 3786
+    _globals["_DEPOSITDROPPED"]._serialized_end = 3820
+    _globals["_REFUNDOFFERED"]._serialized_start = 3822
+    _globals["_REFUNDOFFERED"]._serialized_end = 3875
+    _globals["_SWAPPAID"]._serialized_start = 3877
+    _globals["_SWAPPAID"]._serialized_end = 3909
+    _globals["_SWAPPAYMENTFAILED"]._serialized_start = 3911
+    _globals["_SWAPPAYMENTFAILED"]._serialized_end = 3967
+    _globals["_CLAIMBROADCAST"]._serialized_start = 3969
+    _globals["_CLAIMBROADCAST"]._serialized_end = 4000
+    _globals["_OUTPUTSPENT"]._serialized_start = 4002
+    _globals["_OUTPUTSPENT"]._serialized_end = 4079
+    _globals["_STOPREQUEST"]._serialized_start = 4081
+    _globals["_STOPREQUEST"]._serialized_end = 4094
+    _globals["_STOPRESPONSE"]._serialized_start = 4096
+    _globals["_STOPRESPONSE"]._serialized_end = 4110
+    _globals["_SWAPMANAGER"]._serialized_start = 4246
+    _globals["_SWAPMANAGER"]._serialized_end = 5344
 # @@protoc_insertion_point(module_scope)
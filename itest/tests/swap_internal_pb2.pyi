@@ -19,11 +19,19 @@ class SpendType(int, metaclass=_enum_type_wrapper.EnumTypeWrapper):
     COOPERATIVE_REFUND: _ClassVar[SpendType]
     UNILATERAL_REFUND: _ClassVar[SpendType]
 
+class OutputDisposition(int, metaclass=_enum_type_wrapper.EnumTypeWrapper):
+    __slots__ = ()
+    DONATED: _ClassVar[OutputDisposition]
+    ABANDONED: _ClassVar[OutputDisposition]
+
 UNKNOWN: SpendType
 CLAIM: SpendType
 COOPERATIVE_REFUND: SpendType
 UNILATERAL_REFUND: SpendType
 
+DONATED: OutputDisposition
+ABANDONED: OutputDisposition
+
 class AddAddressFiltersRequest(_message.Message):
     __slots__ = ("addresses",)
     ADDRESSES_FIELD_NUMBER: _ClassVar[int]
@@ -72,6 +80,9 @@ class GetSwapResponse(_message.Message):
         "lock_time",
         "active_locks",
         "payment_attempts",
+        "claim_key_index",
+        "claim_key_family",
+        "unconfirmed_outputs",
     )
     ADDRESS_FIELD_NUMBER: _ClassVar[int]
     CREATION_TIME_FIELD_NUMBER: _ClassVar[int]
@@ -80,6 +91,9 @@ class GetSwapResponse(_message.Message):
     LOCK_TIME_FIELD_NUMBER: _ClassVar[int]
     ACTIVE_LOCKS_FIELD_NUMBER: _ClassVar[int]
     PAYMENT_ATTEMPTS_FIELD_NUMBER: _ClassVar[int]
+    CLAIM_KEY_INDEX_FIELD_NUMBER: _ClassVar[int]
+    CLAIM_KEY_FAMILY_FIELD_NUMBER: _ClassVar[int]
+    UNCONFIRMED_OUTPUTS_FIELD_NUMBER: _ClassVar[int]
     address: str
     creation_time: int
     payment_hash: str
@@ -87,6 +101,9 @@ class GetSwapResponse(_message.Message):
     lock_time: int
     active_locks: _containers.RepeatedCompositeFieldContainer[SwapLock]
     payment_attempts: _containers.RepeatedCompositeFieldContainer[PaymentAttempt]
+    claim_key_index: int
+    claim_key_family: int
+    unconfirmed_outputs: _containers.RepeatedCompositeFieldContainer[UnconfirmedSwapOutput]
     def __init__(
         self,
         address: _Optional[str] = ...,
@@ -96,6 +113,9 @@ class GetSwapResponse(_message.Message):
         lock_time: _Optional[int] = ...,
         active_locks: _Optional[_Iterable[_Union[SwapLock, _Mapping]]] = ...,
         payment_attempts: _Optional[_Iterable[_Union[PaymentAttempt, _Mapping]]] = ...,
+        claim_key_index: _Optional[int] = ...,
+        claim_key_family: _Optional[int] = ...,
+        unconfirmed_outputs: _Optional[_Iterable[_Union[UnconfirmedSwapOutput, _Mapping]]] = ...,
     ) -> None: ...
 
 class SwapOutput(_message.Message):
@@ -116,6 +136,24 @@ class SwapOutput(_message.Message):
         spend: _Optional[_Union[SwapOutputSpend, _Mapping]] = ...,
     ) -> None: ...
 
+class UnconfirmedSwapOutput(_message.Message):
+    __slots__ = ("outpoint", "amount_sat", "replaceable", "first_seen")
+    OUTPOINT_FIELD_NUMBER: _ClassVar[int]
+    AMOUNT_SAT_FIELD_NUMBER: _ClassVar[int]
+    REPLACEABLE_FIELD_NUMBER: _ClassVar[int]
+    FIRST_SEEN_FIELD_NUMBER: _ClassVar[int]
+    outpoint: str
+    amount_sat: int
+    replaceable: bool
+    first_seen: int
+    def __init__(
+        self,
+        outpoint: _Optional[str] = ...,
+        amount_sat: _Optional[int] = ...,
+        replaceable: bool = ...,
+        first_seen: _Optional[int] = ...,
+    ) -> None: ...
+
 class SwapOutputSpend(_message.Message):
     __slots__ = (
         "input_index",
@@ -165,6 +203,7 @@ class PaymentAttempt(_message.Message):
         "label",
         "success",
         "pending",
+        "fee_sat",
     )
     PAYMENT_REQUEST_FIELD_NUMBER: _ClassVar[int]
     AMOUNT_MSAT_FIELD_NUMBER: _ClassVar[int]
@@ -174,6 +213,7 @@ class PaymentAttempt(_message.Message):
     LABEL_FIELD_NUMBER: _ClassVar[int]
     SUCCESS_FIELD_NUMBER: _ClassVar[int]
     PENDING_FIELD_NUMBER: _ClassVar[int]
+    FEE_SAT_FIELD_NUMBER: _ClassVar[int]
     payment_request: str
     amount_msat: int
     error: str
@@ -182,6 +222,7 @@ class PaymentAttempt(_message.Message):
     label: str
     success: bool
     pending: bool
+    fee_sat: int
     def __init__(
         self,
         payment_request: _Optional[str] = ...,
@@ -192,6 +233,72 @@ class PaymentAttempt(_message.Message):
         label: _Optional[str] = ...,
         success: bool = ...,
         pending: bool = ...,
+        fee_sat: _Optional[int] = ...,
+    ) -> None: ...
+
+class GetReverseSwapRequest(_message.Message):
+    __slots__ = ("payment_hash",)
+    PAYMENT_HASH_FIELD_NUMBER: _ClassVar[int]
+    payment_hash: bytes
+    def __init__(self, payment_hash: _Optional[bytes] = ...) -> None: ...
+
+class GetReverseSwapResponse(_message.Message):
+    __slots__ = (
+        "address",
+        "creation_time",
+        "payment_hash",
+        "payment_request",
+        "amount_sat",
+        "invoice_amount_msat",
+        "lock_height",
+        "status",
+        "funding_tx_id",
+        "preimage",
+        "outputs",
+        "refund_key_index",
+        "refund_key_family",
+    )
+    ADDRESS_FIELD_NUMBER: _ClassVar[int]
+    CREATION_TIME_FIELD_NUMBER: _ClassVar[int]
+    PAYMENT_HASH_FIELD_NUMBER: _ClassVar[int]
+    PAYMENT_REQUEST_FIELD_NUMBER: _ClassVar[int]
+    AMOUNT_SAT_FIELD_NUMBER: _ClassVar[int]
+    INVOICE_AMOUNT_MSAT_FIELD_NUMBER: _ClassVar[int]
+    LOCK_HEIGHT_FIELD_NUMBER: _ClassVar[int]
+    STATUS_FIELD_NUMBER: _ClassVar[int]
+    FUNDING_TX_ID_FIELD_NUMBER: _ClassVar[int]
+    PREIMAGE_FIELD_NUMBER: _ClassVar[int]
+    OUTPUTS_FIELD_NUMBER: _ClassVar[int]
+    REFUND_KEY_INDEX_FIELD_NUMBER: _ClassVar[int]
+    REFUND_KEY_FAMILY_FIELD_NUMBER: _ClassVar[int]
+    address: str
+    creation_time: int
+    payment_hash: str
+    payment_request: str
+    amount_sat: int
+    invoice_amount_msat: int
+    lock_height: int
+    status: str
+    funding_tx_id: str
+    preimage: str
+    outputs: _containers.RepeatedCompositeFieldContainer[SwapOutput]
+    refund_key_index: int
+    refund_key_family: int
+    def __init__(
+        self,
+        address: _Optional[str] = ...,
+        creation_time: _Optional[int] = ...,
+        payment_hash: _Optional[str] = ...,
+        payment_request: _Optional[str] = ...,
+        amount_sat: _Optional[int] = ...,
+        invoice_amount_msat: _Optional[int] = ...,
+        lock_height: _Optional[int] = ...,
+        status: _Optional[str] = ...,
+        funding_tx_id: _Optional[str] = ...,
+        preimage: _Optional[str] = ...,
+        outputs: _Optional[_Iterable[_Union[SwapOutput, _Mapping]]] = ...,
+        refund_key_index: _Optional[int] = ...,
+        refund_key_family: _Optional[int] = ...,
     ) -> None: ...
 
 class ListClaimableRequest(_message.Message):
@@ -269,6 +376,233 @@ class ClaimResponse(_message.Message):
         self, tx_id: _Optional[str] = ..., fee_per_kw: _Optional[int] = ...
     ) -> None: ...
 
+class ReencryptClaimKeysRequest(_message.Message):
+    __slots__ = ("batch_size",)
+    BATCH_SIZE_FIELD_NUMBER: _ClassVar[int]
+    batch_size: int
+    def __init__(self, batch_size: _Optional[int] = ...) -> None: ...
+
+class ReencryptClaimKeysResponse(_message.Message):
+    __slots__ = ("reencrypted",)
+    REENCRYPTED_FIELD_NUMBER: _ClassVar[int]
+    reencrypted: int
+    def __init__(self, reencrypted: _Optional[int] = ...) -> None: ...
+
+class RescanRequest(_message.Message):
+    __slots__ = ("from_height", "addresses")
+    FROM_HEIGHT_FIELD_NUMBER: _ClassVar[int]
+    ADDRESSES_FIELD_NUMBER: _ClassVar[int]
+    from_height: int
+    addresses: _containers.RepeatedScalarFieldContainer[str]
+    def __init__(
+        self,
+        from_height: _Optional[int] = ...,
+        addresses: _Optional[_Iterable[str]] = ...,
+    ) -> None: ...
+
+class RescanResponse(_message.Message):
+    __slots__ = ("new_outputs", "new_spends")
+    NEW_OUTPUTS_FIELD_NUMBER: _ClassVar[int]
+    NEW_SPENDS_FIELD_NUMBER: _ClassVar[int]
+    new_outputs: int
+    new_spends: int
+    def __init__(
+        self, new_outputs: _Optional[int] = ..., new_spends: _Optional[int] = ...
+    ) -> None: ...
+
+class ListUnpaidOutputsRequest(_message.Message):
+    __slots__ = ()
+    def __init__(self) -> None: ...
+
+class ListUnpaidOutputsResponse(_message.Message):
+    __slots__ = ("outputs",)
+    OUTPUTS_FIELD_NUMBER: _ClassVar[int]
+    outputs: _containers.RepeatedCompositeFieldContainer[UnpaidOutput]
+    def __init__(
+        self, outputs: _Optional[_Iterable[_Union[UnpaidOutput, _Mapping]]] = ...
+    ) -> None: ...
+
+class UnpaidOutput(_message.Message):
+    __slots__ = (
+        "outpoint",
+        "address",
+        "payment_hash",
+        "amount_sat",
+        "creation_time",
+        "disposition",
+    )
+    OUTPOINT_FIELD_NUMBER: _ClassVar[int]
+    ADDRESS_FIELD_NUMBER: _ClassVar[int]
+    PAYMENT_HASH_FIELD_NUMBER: _ClassVar[int]
+    AMOUNT_SAT_FIELD_NUMBER: _ClassVar[int]
+    CREATION_TIME_FIELD_NUMBER: _ClassVar[int]
+    DISPOSITION_FIELD_NUMBER: _ClassVar[int]
+    outpoint: str
+    address: str
+    payment_hash: str
+    amount_sat: int
+    creation_time: int
+    disposition: str
+    def __init__(
+        self,
+        outpoint: _Optional[str] = ...,
+        address: _Optional[str] = ...,
+        payment_hash: _Optional[str] = ...,
+        amount_sat: _Optional[int] = ...,
+        creation_time: _Optional[int] = ...,
+        disposition: _Optional[str] = ...,
+    ) -> None: ...
+
+class SetOutputDispositionRequest(_message.Message):
+    __slots__ = ("outpoint", "disposition")
+    OUTPOINT_FIELD_NUMBER: _ClassVar[int]
+    DISPOSITION_FIELD_NUMBER: _ClassVar[int]
+    outpoint: str
+    disposition: OutputDisposition
+    def __init__(
+        self,
+        outpoint: _Optional[str] = ...,
+        disposition: _Optional[_Union[OutputDisposition, str]] = ...,
+    ) -> None: ...
+
+class SetOutputDispositionResponse(_message.Message):
+    __slots__ = ()
+    def __init__(self) -> None: ...
+
+class SubscribeSwapEventsRequest(_message.Message):
+    __slots__ = ()
+    def __init__(self) -> None: ...
+
+class SwapEvent(_message.Message):
+    __slots__ = (
+        "address",
+        "timestamp",
+        "deposit_confirmed",
+        "paid",
+        "payment_failed",
+        "claim_broadcast",
+        "output_spent",
+        "deposit_unconfirmed",
+        "deposit_dropped",
+        "refund_offered",
+    )
+    ADDRESS_FIELD_NUMBER: _ClassVar[int]
+    TIMESTAMP_FIELD_NUMBER: _ClassVar[int]
+    DEPOSIT_CONFIRMED_FIELD_NUMBER: _ClassVar[int]
+    PAID_FIELD_NUMBER: _ClassVar[int]
+    PAYMENT_FAILED_FIELD_NUMBER: _ClassVar[int]
+    CLAIM_BROADCAST_FIELD_NUMBER: _ClassVar[int]
+    OUTPUT_SPENT_FIELD_NUMBER: _ClassVar[int]
+    DEPOSIT_UNCONFIRMED_FIELD_NUMBER: _ClassVar[int]
+    DEPOSIT_DROPPED_FIELD_NUMBER: _ClassVar[int]
+    REFUND_OFFERED_FIELD_NUMBER: _ClassVar[int]
+    address: str
+    timestamp: int
+    deposit_confirmed: DepositConfirmed
+    paid: SwapPaid
+    payment_failed: SwapPaymentFailed
+    claim_broadcast: ClaimBroadcast
+    output_spent: OutputSpent
+    deposit_unconfirmed: DepositUnconfirmed
+    deposit_dropped: DepositDropped
+    refund_offered: RefundOffered
+    def __init__(
+        self,
+        address: _Optional[str] = ...,
+        timestamp: _Optional[int] = ...,
+        deposit_confirmed: _Optional[_Union[DepositConfirmed, _Mapping]] = ...,
+        paid: _Optional[_Union[SwapPaid, _Mapping]] = ...,
+        payment_failed: _Optional[_Union[SwapPaymentFailed, _Mapping]] = ...,
+        claim_broadcast: _Optional[_Union[ClaimBroadcast, _Mapping]] = ...,
+        output_spent: _Optional[_Union[OutputSpent, _Mapping]] = ...,
+        deposit_unconfirmed: _Optional[_Union[DepositUnconfirmed, _Mapping]] = ...,
+        deposit_dropped: _Optional[_Union[DepositDropped, _Mapping]] = ...,
+        refund_offered: _Optional[_Union[RefundOffered, _Mapping]] = ...,
+    ) -> None: ...
+
+class DepositConfirmed(_message.Message):
+    __slots__ = ("outpoint", "amount_sat", "block_height")
+    OUTPOINT_FIELD_NUMBER: _ClassVar[int]
+    AMOUNT_SAT_FIELD_NUMBER: _ClassVar[int]
+    BLOCK_HEIGHT_FIELD_NUMBER: _ClassVar[int]
+    outpoint: str
+    amount_sat: int
+    block_height: int
+    def __init__(
+        self,
+        outpoint: _Optional[str] = ...,
+        amount_sat: _Optional[int] = ...,
+        block_height: _Optional[int] = ...,
+    ) -> None: ...
+
+class DepositUnconfirmed(_message.Message):
+    __slots__ = ("outpoint", "amount_sat", "replaceable")
+    OUTPOINT_FIELD_NUMBER: _ClassVar[int]
+    AMOUNT_SAT_FIELD_NUMBER: _ClassVar[int]
+    REPLACEABLE_FIELD_NUMBER: _ClassVar[int]
+    outpoint: str
+    amount_sat: int
+    replaceable: bool
+    def __init__(
+        self,
+        outpoint: _Optional[str] = ...,
+        amount_sat: _Optional[int] = ...,
+        replaceable: bool = ...,
+    ) -> None: ...
+
+class DepositDropped(_message.Message):
+    __slots__ = ("outpoint",)
+    OUTPOINT_FIELD_NUMBER: _ClassVar[int]
+    outpoint: str
+    def __init__(self, outpoint: _Optional[str] = ...) -> None: ...
+
+class RefundOffered(_message.Message):
+    __slots__ = ("outpoint", "amount_sat")
+    OUTPOINT_FIELD_NUMBER: _ClassVar[int]
+    AMOUNT_SAT_FIELD_NUMBER: _ClassVar[int]
+    outpoint: str
+    amount_sat: int
+    def __init__(
+        self, outpoint: _Optional[str] = ..., amount_sat: _Optional[int] = ...
+    ) -> None: ...
+
+class SwapPaid(_message.Message):
+    __slots__ = ("payment_hash",)
+    PAYMENT_HASH_FIELD_NUMBER: _ClassVar[int]
+    payment_hash: str
+    def __init__(self, payment_hash: _Optional[str] = ...) -> None: ...
+
+class SwapPaymentFailed(_message.Message):
+    __slots__ = ("payment_hash", "error")
+    PAYMENT_HASH_FIELD_NUMBER: _ClassVar[int]
+    ERROR_FIELD_NUMBER: _ClassVar[int]
+    payment_hash: str
+    error: str
+    def __init__(
+        self, payment_hash: _Optional[str] = ..., error: _Optional[str] = ...
+    ) -> None: ...
+
+class ClaimBroadcast(_message.Message):
+    __slots__ = ("tx_id",)
+    TX_ID_FIELD_NUMBER: _ClassVar[int]
+    tx_id: str
+    def __init__(self, tx_id: _Optional[str] = ...) -> None: ...
+
+class OutputSpent(_message.Message):
+    __slots__ = ("outpoint", "spending_tx_id", "block_height")
+    OUTPOINT_FIELD_NUMBER: _ClassVar[int]
+    SPENDING_TX_ID_FIELD_NUMBER: _ClassVar[int]
+    BLOCK_HEIGHT_FIELD_NUMBER: _ClassVar[int]
+    outpoint: str
+    spending_tx_id: str
+    block_height: int
+    def __init__(
+        self,
+        outpoint: _Optional[str] = ...,
+        spending_tx_id: _Optional[str] = ...,
+        block_height: _Optional[int] = ...,
+    ) -> None: ...
+
 class StopRequest(_message.Message):
     __slots__ = ()
     def __init__(self) -> None: ...
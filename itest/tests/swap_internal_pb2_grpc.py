@@ -55,6 +55,12 @@ class SwapManagerStub(object):
             response_deserializer=swap__internal__pb2.GetSwapResponse.FromString,
             _registered_method=True,
         )
+        self.GetReverseSwap = channel.unary_unary(
+            "/swap_internal.SwapManager/GetReverseSwap",
+            request_serializer=swap__internal__pb2.GetReverseSwapRequest.SerializeToString,
+            response_deserializer=swap__internal__pb2.GetReverseSwapResponse.FromString,
+            _registered_method=True,
+        )
         self.ListClaimable = channel.unary_unary(
             "/swap_internal.SwapManager/ListClaimable",
             request_serializer=swap__internal__pb2.ListClaimableRequest.SerializeToString,
@@ -67,6 +73,36 @@ class SwapManagerStub(object):
             response_deserializer=swap__internal__pb2.ClaimResponse.FromString,
             _registered_method=True,
         )
+        self.ReencryptClaimKeys = channel.unary_unary(
+            "/swap_internal.SwapManager/ReencryptClaimKeys",
+            request_serializer=swap__internal__pb2.ReencryptClaimKeysRequest.SerializeToString,
+            response_deserializer=swap__internal__pb2.ReencryptClaimKeysResponse.FromString,
+            _registered_method=True,
+        )
+        self.Rescan = channel.unary_unary(
+            "/swap_internal.SwapManager/Rescan",
+            request_serializer=swap__internal__pb2.RescanRequest.SerializeToString,
+            response_deserializer=swap__internal__pb2.RescanResponse.FromString,
+            _registered_method=True,
+        )
+        self.ListUnpaidOutputs = channel.unary_unary(
+            "/swap_internal.SwapManager/ListUnpaidOutputs",
+            request_serializer=swap__internal__pb2.ListUnpaidOutputsRequest.SerializeToString,
+            response_deserializer=swap__internal__pb2.ListUnpaidOutputsResponse.FromString,
+            _registered_method=True,
+        )
+        self.SetOutputDisposition = channel.unary_unary(
+            "/swap_internal.SwapManager/SetOutputDisposition",
+            request_serializer=swap__internal__pb2.SetOutputDispositionRequest.SerializeToString,
+            response_deserializer=swap__internal__pb2.SetOutputDispositionResponse.FromString,
+            _registered_method=True,
+        )
+        self.SubscribeSwapEvents = channel.unary_stream(
+            "/swap_internal.SwapManager/SubscribeSwapEvents",
+            request_serializer=swap__internal__pb2.SubscribeSwapEventsRequest.SerializeToString,
+            response_deserializer=swap__internal__pb2.SwapEvent.FromString,
+            _registered_method=True,
+        )
         self.Stop = channel.unary_unary(
             "/swap_internal.SwapManager/Stop",
             request_serializer=swap__internal__pb2.StopRequest.SerializeToString,
@@ -96,6 +132,12 @@ class SwapManagerServicer(object):
         context.set_details("Method not implemented!")
         raise NotImplementedError("Method not implemented!")
 
+    def GetReverseSwap(self, request, context):
+        """Missing associated documentation comment in .proto file."""
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details("Method not implemented!")
+        raise NotImplementedError("Method not implemented!")
+
     def ListClaimable(self, request, context):
         """Missing associated documentation comment in .proto file."""
         context.set_code(grpc.StatusCode.UNIMPLEMENTED)
@@ -108,6 +150,36 @@ class SwapManagerServicer(object):
         context.set_details("Method not implemented!")
         raise NotImplementedError("Method not implemented!")
 
+    def ReencryptClaimKeys(self, request, context):
+        """Missing associated documentation comment in .proto file."""
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details("Method not implemented!")
+        raise NotImplementedError("Method not implemented!")
+
+    def Rescan(self, request, context):
+        """Missing associated documentation comment in .proto file."""
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details("Method not implemented!")
+        raise NotImplementedError("Method not implemented!")
+
+    def ListUnpaidOutputs(self, request, context):
+        """Missing associated documentation comment in .proto file."""
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details("Method not implemented!")
+        raise NotImplementedError("Method not implemented!")
+
+    def SetOutputDisposition(self, request, context):
+        """Missing associated documentation comment in .proto file."""
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details("Method not implemented!")
+        raise NotImplementedError("Method not implemented!")
+
+    def SubscribeSwapEvents(self, request, context):
+        """Missing associated documentation comment in .proto file."""
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details("Method not implemented!")
+        raise NotImplementedError("Method not implemented!")
+
     def Stop(self, request, context):
         """Missing associated documentation comment in .proto file."""
         context.set_code(grpc.StatusCode.UNIMPLEMENTED)
@@ -132,6 +204,11 @@ def add_SwapManagerServicer_to_server(servicer, server):
             request_deserializer=swap__internal__pb2.GetSwapRequest.FromString,
             response_serializer=swap__internal__pb2.GetSwapResponse.SerializeToString,
         ),
+        "GetReverseSwap": grpc.unary_unary_rpc_method_handler(
+            servicer.GetReverseSwap,
+            request_deserializer=swap__internal__pb2.GetReverseSwapRequest.FromString,
+            response_serializer=swap__internal__pb2.GetReverseSwapResponse.SerializeToString,
+        ),
         "ListClaimable": grpc.unary_unary_rpc_method_handler(
             servicer.ListClaimable,
             request_deserializer=swap__internal__pb2.ListClaimableRequest.FromString,
@@ -142,6 +219,31 @@ def add_SwapManagerServicer_to_server(servicer, server):
             request_deserializer=swap__internal__pb2.ClaimRequest.FromString,
             response_serializer=swap__internal__pb2.ClaimResponse.SerializeToString,
         ),
+        "ReencryptClaimKeys": grpc.unary_unary_rpc_method_handler(
+            servicer.ReencryptClaimKeys,
+            request_deserializer=swap__internal__pb2.ReencryptClaimKeysRequest.FromString,
+            response_serializer=swap__internal__pb2.ReencryptClaimKeysResponse.SerializeToString,
+        ),
+        "Rescan": grpc.unary_unary_rpc_method_handler(
+            servicer.Rescan,
+            request_deserializer=swap__internal__pb2.RescanRequest.FromString,
+            response_serializer=swap__internal__pb2.RescanResponse.SerializeToString,
+        ),
+        "ListUnpaidOutputs": grpc.unary_unary_rpc_method_handler(
+            servicer.ListUnpaidOutputs,
+            request_deserializer=swap__internal__pb2.ListUnpaidOutputsRequest.FromString,
+            response_serializer=swap__internal__pb2.ListUnpaidOutputsResponse.SerializeToString,
+        ),
+        "SetOutputDisposition": grpc.unary_unary_rpc_method_handler(
+            servicer.SetOutputDisposition,
+            request_deserializer=swap__internal__pb2.SetOutputDispositionRequest.FromString,
+            response_serializer=swap__internal__pb2.SetOutputDispositionResponse.SerializeToString,
+        ),
+        "SubscribeSwapEvents": grpc.unary_stream_rpc_method_handler(
+            servicer.SubscribeSwapEvents,
+            request_deserializer=swap__internal__pb2.SubscribeSwapEventsRequest.FromString,
+            response_serializer=swap__internal__pb2.SwapEvent.SerializeToString,
+        ),
         "Stop": grpc.unary_unary_rpc_method_handler(
             servicer.Stop,
             request_deserializer=swap__internal__pb2.StopRequest.FromString,
@@ -251,6 +353,36 @@ class SwapManager(object):
             _registered_method=True,
         )
 
+    @staticmethod
+    def GetReverseSwap(
+        request,
+        target,
+        options=(),
+        channel_credentials=None,
+        call_credentials=None,
+        insecure=False,
+        compression=None,
+        wait_for_ready=None,
+        timeout=None,
+        metadata=None,
+    ):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            "/swap_internal.SwapManager/GetReverseSwap",
+            swap__internal__pb2.GetReverseSwapRequest.SerializeToString,
+            swap__internal__pb2.GetReverseSwapResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True,
+        )
+
     @staticmethod
     def ListClaimable(
         request,
@@ -311,6 +443,156 @@ class SwapManager(object):
             _registered_method=True,
         )
 
+    @staticmethod
+    def ReencryptClaimKeys(
+        request,
+        target,
+        options=(),
+        channel_credentials=None,
+        call_credentials=None,
+        insecure=False,
+        compression=None,
+        wait_for_ready=None,
+        timeout=None,
+        metadata=None,
+    ):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            "/swap_internal.SwapManager/ReencryptClaimKeys",
+            swap__internal__pb2.ReencryptClaimKeysRequest.SerializeToString,
+            swap__internal__pb2.ReencryptClaimKeysResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True,
+        )
+
+    @staticmethod
+    def Rescan(
+        request,
+        target,
+        options=(),
+        channel_credentials=None,
+        call_credentials=None,
+        insecure=False,
+        compression=None,
+        wait_for_ready=None,
+        timeout=None,
+        metadata=None,
+    ):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            "/swap_internal.SwapManager/Rescan",
+            swap__internal__pb2.RescanRequest.SerializeToString,
+            swap__internal__pb2.RescanResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True,
+        )
+
+    @staticmethod
+    def ListUnpaidOutputs(
+        request,
+        target,
+        options=(),
+        channel_credentials=None,
+        call_credentials=None,
+        insecure=False,
+        compression=None,
+        wait_for_ready=None,
+        timeout=None,
+        metadata=None,
+    ):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            "/swap_internal.SwapManager/ListUnpaidOutputs",
+            swap__internal__pb2.ListUnpaidOutputsRequest.SerializeToString,
+            swap__internal__pb2.ListUnpaidOutputsResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True,
+        )
+
+    @staticmethod
+    def SetOutputDisposition(
+        request,
+        target,
+        options=(),
+        channel_credentials=None,
+        call_credentials=None,
+        insecure=False,
+        compression=None,
+        wait_for_ready=None,
+        timeout=None,
+        metadata=None,
+    ):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            "/swap_internal.SwapManager/SetOutputDisposition",
+            swap__internal__pb2.SetOutputDispositionRequest.SerializeToString,
+            swap__internal__pb2.SetOutputDispositionResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True,
+        )
+
+    @staticmethod
+    def SubscribeSwapEvents(
+        request,
+        target,
+        options=(),
+        channel_credentials=None,
+        call_credentials=None,
+        insecure=False,
+        compression=None,
+        wait_for_ready=None,
+        timeout=None,
+        metadata=None,
+    ):
+        return grpc.experimental.unary_stream(
+            request,
+            target,
+            "/swap_internal.SwapManager/SubscribeSwapEvents",
+            swap__internal__pb2.SubscribeSwapEventsRequest.SerializeToString,
+            swap__internal__pb2.SwapEvent.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True,
+        )
+
     @staticmethod
     def Stop(
         request,
@@ -19,7 +19,7 @@ _sym_db = _symbol_database.Default()
 
 
 DESCRIPTOR = _descriptor_pool.Default().AddSerializedFile(
-    b'\n\nswap.proto\x12\x05\x62reez"8\n\x11\x43reateSwapRequest\x12\x0c\n\x04hash\x18\x01 \x01(\x0c\x12\x15\n\rrefund_pubkey\x18\x02 \x01(\x0c"y\n\x12\x43reateSwapResponse\x12\x0f\n\x07\x61\x64\x64ress\x18\x01 \x01(\t\x12\x14\n\x0c\x63laim_pubkey\x18\x02 \x01(\x0c\x12\x11\n\tlock_time\x18\x03 \x01(\r\x12)\n\nparameters\x18\x04 \x01(\x0b\x32\x15.breez.SwapParameters")\n\x0ePaySwapRequest\x12\x17\n\x0fpayment_request\x18\x01 \x01(\t"\x11\n\x0fPaySwapResponse"a\n\x11RefundSwapRequest\x12\x0f\n\x07\x61\x64\x64ress\x18\x01 \x01(\t\x12\x13\n\x0btransaction\x18\x02 \x01(\x0c\x12\x13\n\x0binput_index\x18\x03 \x01(\r\x12\x11\n\tpub_nonce\x18\x04 \x01(\x0c"B\n\x12RefundSwapResponse\x12\x11\n\tpub_nonce\x18\x01 \x01(\x0c\x12\x19\n\x11partial_signature\x18\x02 \x01(\x0c"g\n\x0eSwapParameters\x12\x1b\n\x13max_swap_amount_sat\x18\x01 \x01(\x04\x12\x1b\n\x13min_swap_amount_sat\x18\x02 \x01(\x04\x12\x1b\n\x13min_utxo_amount_sat\x18\x03 \x01(\x04"\x17\n\x15SwapParametersRequest"C\n\x16SwapParametersResponse\x12)\n\nparameters\x18\x01 \x01(\x0b\x32\x15.breez.SwapParameters2\xa7\x02\n\x0eTaprootSwapper\x12\x43\n\nCreateSwap\x12\x18.breez.CreateSwapRequest\x1a\x19.breez.CreateSwapResponse"\x00\x12:\n\x07PaySwap\x12\x15.breez.PaySwapRequest\x1a\x16.breez.PaySwapResponse"\x00\x12\x43\n\nRefundSwap\x12\x18.breez.RefundSwapRequest\x1a\x19.breez.RefundSwapResponse"\x00\x12O\n\x0eSwapParameters\x12\x1c.breez.SwapParametersRequest\x1a\x1d.breez.SwapParametersResponse"\x00\x62\x06proto3'
+    b'\n\nswap.proto\x12\x05\x62reez"8\n\x11\x43reateSwapRequest\x12\x0c\n\x04hash\x18\x01 \x01(\x0c\x12\x15\n\rrefund_pubkey\x18\x02 \x01(\x0c"y\n\x12\x43reateSwapResponse\x12\x0f\n\x07\x61\x64\x64ress\x18\x01 \x01(\t\x12\x14\n\x0c\x63laim_pubkey\x18\x02 \x01(\x0c\x12\x11\n\tlock_time\x18\x03 \x01(\r\x12)\n\nparameters\x18\x04 \x01(\x0b\x32\x15.breez.SwapParameters")\n\x0ePaySwapRequest\x12\x17\n\x0fpayment_request\x18\x01 \x01(\t"\x11\n\x0fPaySwapResponse"a\n\x11RefundSwapRequest\x12\x0f\n\x07\x61\x64\x64ress\x18\x01 \x01(\t\x12\x13\n\x0btransaction\x18\x02 \x01(\x0c\x12\x13\n\x0binput_index\x18\x03 \x01(\r\x12\x11\n\tpub_nonce\x18\x04 \x01(\x0c"B\n\x12RefundSwapResponse\x12\x11\n\tpub_nonce\x18\x01 \x01(\x0c\x12\x19\n\x11partial_signature\x18\x02 \x01(\x0c"P\n\x17\x43laimSwapDetailsRequest\x12\x0f\n\x07\x61\x64\x64ress\x18\x01 \x01(\t\x12\x11\n\ttimestamp\x18\x02 \x01(\x04\x12\x11\n\tsignature\x18\x03 \x01(\x0c"C\n\x18\x43laimSwapDetailsResponse\x12\x13\n\x0btransaction\x18\x01 \x01(\x0c\x12\x12\n\npub_nonces\x18\x02 \x03(\x0c"y\n\x10\x43laimSwapRequest\x12\x0f\n\x07\x61\x64\x64ress\x18\x01 \x01(\t\x12\x12\n\npub_nonces\x18\x02 \x03(\x0c\x12\x1a\n\x12partial_signatures\x18\x03 \x03(\x0c\x12\x11\n\ttimestamp\x18\x04 \x01(\x04\x12\x11\n\tsignature\x18\x05 \x01(\x0c""\n\x11\x43laimSwapResponse\x12\r\n\x05tx_id\x18\x01 \x01(\t"\xcf\x01\n\x0eSwapParameters\x12\x1b\n\x13max_swap_amount_sat\x18\x01 \x01(\x04\x12\x1b\n\x13min_swap_amount_sat\x18\x02 \x01(\x04\x12\x1b\n\x13min_utxo_amount_sat\x18\x03 \x01(\x04\x12\x14\n\x0c\x66\x65\x65_base_sat\x18\x04 \x01(\x04\x12\x0f\n\x07\x66\x65\x65_ppm\x18\x05 \x01(\x04\x12\x1e\n\x16\x66\x65\x65_includes_claim_fee\x18\x06 \x01(\x08\x12\x1f\n\x17\x63laim_fee_quote_seconds\x18\x07 \x01(\x04"\x17\n\x15SwapParametersRequest"C\n\x16SwapParametersResponse\x12)\n\nparameters\x18\x01 \x01(\x0b\x32\x15.breez.SwapParameters"S\n\x1aSubscribeSwapEventsRequest\x12\x0f\n\x07\x61\x64\x64ress\x18\x01 \x01(\t\x12\x11\n\ttimestamp\x18\x02 \x01(\x04\x12\x11\n\tsignature\x18\x03 \x01(\x0c"\xbd\x03\n\tSwapEvent\x12\x0f\n\x07\x61\x64\x64ress\x18\x01 \x01(\t\x12\x11\n\ttimestamp\x18\x02 \x01(\x04\x12\x34\n\x11\x64\x65posit_confirmed\x18\x03 \x01(\x0b\x32\x17.breez.DepositConfirmedH\x00\x12\x1f\n\x04paid\x18\x04 \x01(\x0b\x32\x0f.breez.SwapPaidH\x00\x12\x32\n\x0epayment_failed\x18\x05 \x01(\x0b\x32\x18.breez.SwapPaymentFailedH\x00\x12\x30\n\x0f\x63laim_broadcast\x18\x06 \x01(\x0b\x32\x15.breez.ClaimBroadcastH\x00\x12*\n\x0coutput_spent\x18\x07 \x01(\x0b\x32\x12.breez.OutputSpentH\x00\x12\x38\n\x13\x64\x65posit_unconfirmed\x18\x08 \x01(\x0b\x32\x19.breez.DepositUnconfirmedH\x00\x12\x30\n\x0f\x64\x65posit_dropped\x18\t \x01(\x0b\x32\x15.breez.DepositDroppedH\x00\x12.\n\x0erefund_offered\x18\n \x01(\x0b\x32\x14.breez.RefundOfferedH\x00\x42\x07\n\x05\x65vent"N\n\x10\x44\x65positConfirmed\x12\x10\n\x08outpoint\x18\x01 \x01(\t\x12\x12\n\namount_sat\x18\x02 \x01(\x04\x12\x14\n\x0c\x62lock_height\x18\x03 \x01(\x04"O\n\x12\x44\x65positUnconfirmed\x12\x10\n\x08outpoint\x18\x01 \x01(\t\x12\x12\n\namount_sat\x18\x02 \x01(\x04\x12\x13\n\x0breplaceable\x18\x03 \x01(\x08""\n\x0e\x44\x65positDropped\x12\x10\n\x08outpoint\x18\x01 \x01(\t"5\n\rRefundOffered\x12\x10\n\x08outpoint\x18\x01 \x01(\t\x12\x12\n\namount_sat\x18\x02 \x01(\x04"\n\n\x08SwapPaid"\x13\n\x11SwapPaymentFailed"\x1f\n\x0e\x43laimBroadcast\x12\r\n\x05tx_id\x18\x01 \x01(\t"M\n\x0bOutputSpent\x12\x10\n\x08outpoint\x18\x01 \x01(\t\x12\x16\n\x0espending_tx_id\x18\x02 \x01(\t\x12\x14\n\x0c\x62lock_height\x18\x03 \x01(\x04"M\n\x14GetSwapStatusRequest\x12\x0f\n\x07\x61\x64\x64ress\x18\x01 \x01(\t\x12\x11\n\ttimestamp\x18\x02 \x01(\x04\x12\x11\n\tsignature\x18\x03 \x01(\x0c"\xb9\x03\n\x15GetSwapStatusResponse\x12(\n\x07outputs\x18\x01 \x03(\x0b\x32\x17.breez.SwapStatusOutput\x12\x16\n\x0e\x63urrent_height\x18\x02 \x01(\x04\x12 \n\x13\x62locks_until_refund\x18\x03 \x01(\x05H\x00\x88\x01\x01\x12\x0f\n\x07payable\x18\x04 \x01(\x08\x12\x38\n\x12not_payable_reason\x18\x05 \x01(\x0e\x32\x17.breez.NotPayableReasonH\x01\x88\x01\x01\x12\x1f\n\x12payable_amount_sat\x18\x06 \x01(\x04H\x02\x88\x01\x01\x12\x14\n\x07\x66\x65\x65_sat\x18\x07 \x01(\x04H\x03\x88\x01\x01\x12(\n\x0cpayout_state\x18\x08 \x01(\x0e\x32\x12.breez.PayoutState\x12#\n\x16\x63laim_fee_quote_expiry\x18\t \x01(\x04H\x04\x88\x01\x01\x42\x16\n\x14_blocks_until_refundB\x15\n\x13_not_payable_reasonB\x15\n\x13_payable_amount_satB\n\n\x08_fee_satB\x19\n\x17_claim_fee_quote_expiry"\x7f\n\x10SwapStatusOutput\x12\x10\n\x08outpoint\x18\x01 \x01(\t\x12\x12\n\namount_sat\x18\x02 \x01(\x04\x12\x15\n\rconfirmations\x18\x03 \x01(\x04\x12\x1b\n\x0espending_tx_id\x18\x04 \x01(\tH\x00\x88\x01\x01\x42\x11\n\x0f_spending_tx_id"R\n\x18\x43reateReverseSwapRequest\x12\x0c\n\x04hash\x18\x01 \x01(\x0c\x12\x14\n\x0c\x63laim_pubkey\x18\x02 \x01(\x0c\x12\x12\n\namount_sat\x18\x03 \x01(\x04"\xa3\x01\n\x19\x43reateReverseSwapResponse\x12\x17\n\x0fpayment_request\x18\x01 \x01(\t\x12\x0f\n\x07\x61\x64\x64ress\x18\x02 \x01(\t\x12\x15\n\rrefund_pubkey\x18\x03 \x01(\x0c\x12\x13\n\x0block_height\x18\x04 \x01(\r\x12\x30\n\nparameters\x18\x05 \x01(\x0b\x32\x1c.breez.ReverseSwapParameters"\x87\x01\n\x15ReverseSwapParameters\x12\x16\n\x0emax_amount_sat\x18\x01 \x01(\x04\x12\x16\n\x0emin_amount_sat\x18\x02 \x01(\x04\x12\x14\n\x0c\x66\x65\x65_base_sat\x18\x03 \x01(\x04\x12\x0f\n\x07\x66\x65\x65_ppm\x18\x04 \x01(\x04\x12\x17\n\x0f\x66unding_fee_sat\x18\x05 \x01(\x04"\x1e\n\x1cReverseSwapParametersRequest"Q\n\x1dReverseSwapParametersResponse\x12\x30\n\nparameters\x18\x01 \x01(\x0b\x32\x1c.breez.ReverseSwapParameters*\xe4\x01\n\x10NotPayableReason\x12\x0e\n\nNO_OUTPUTS\x10\x00\x12\x1e\n\x1aINSUFFICIENT_CONFIRMATIONS\x10\x01\x12\x0b\n\x07\x45XPIRED\x10\x02\x12\x11\n\rVALUE_TOO_LOW\x10\x03\x12\x13\n\x0f\x41MOUNT_TOO_HIGH\x10\x04\x12\x12\n\x0e\x41MOUNT_TOO_LOW\x10\x05\x12\x10\n\x0c\x41LREADY_PAID\x10\x06\x12\x17\n\x13PAYMENT_IN_PROGRESS\x10\x07\x12\x16\n\x12REFUND_IN_PROGRESS\x10\x08\x12\x14\n\x10OUTPUTS_FILTERED\x10\t*F\n\x0bPayoutState\x12\x0f\n\x0bNOT_STARTED\x10\x00\x12\x0b\n\x07PENDING\x10\x01\x12\r\n\tSUCCEEDED\x10\x02\x12\n\n\x06\x46\x41ILED\x10\x03\x32\xde\x04\n\x0eTaprootSwapper\x12\x43\n\nCreateSwap\x12\x18.breez.CreateSwapRequest\x1a\x19.breez.CreateSwapResponse"\x00\x12:\n\x07PaySwap\x12\x15.breez.PaySwapRequest\x1a\x16.breez.PaySwapResponse"\x00\x12\x43\n\nRefundSwap\x12\x18.breez.RefundSwapRequest\x1a\x19.breez.RefundSwapResponse"\x00\x12U\n\x10\x43laimSwapDetails\x12\x1e.breez.ClaimSwapDetailsRequest\x1a\x1f.breez.ClaimSwapDetailsResponse"\x00\x12@\n\tClaimSwap\x12\x17.breez.ClaimSwapRequest\x1a\x18.breez.ClaimSwapResponse"\x00\x12O\n\x0eSwapParameters\x12\x1c.breez.SwapParametersRequest\x1a\x1d.breez.SwapParametersResponse"\x00\x12N\n\x13SubscribeSwapEvents\x12!.breez.SubscribeSwapEventsRequest\x1a\x10.breez.SwapEvent"\x00\x30\x01\x12L\n\rGetSwapStatus\x12\x1b.breez.GetSwapStatusRequest\x1a\x1c.breez.GetSwapStatusResponse"\x00\x32\xd0\x01\n\x0eReverseSwapper\x12X\n\x11\x43reateReverseSwap\x12\x1f.breez.CreateReverseSwapRequest\x1a .breez.CreateReverseSwapResponse"\x00\x12\x64\n\x15ReverseSwapParameters\x12#.breez.ReverseSwapParametersRequest\x1a$.breez.ReverseSwapParametersResponse"\x00\x62\x06proto3'
 )
 
 _globals = globals()
@@ -27,6 +27,10 @@ _builder.BuildMessageAndEnumDescriptors(DESCRIPTOR, _globals)
 _builder.BuildTopDescriptorsAndMessages(DESCRIPTOR, "swap_pb2", _globals)
 if not _descriptor._USE_C_DESCRIPTORS:
     DESCRIPTOR._loaded_options = None
+    _globals["_NOTPAYABLEREASON"]._serialized_start = 3131
+    _globals["_NOTPAYABLEREASON"]._serialized_end = 3359
+    _globals["_PAYOUTSTATE"]._serialized_start = 3361
+    _globals["_PAYOUTSTATE"]._serialized_end = 3431
     _globals["_CREATESWAPREQUEST"]._serialized_start = 21
     _globals["_CREATESWAPREQUEST"]._serialized_end = 77
     _globals["_CREATESWAPRESPONSE"]._serialized_start = 79
@@ -39,12 +43,58 @@ if not _descriptor._USE_C_DESCRIPTORS:
     _globals["_REFUNDSWAPREQUEST"]._serialized_end = 361
     _globals["_REFUNDSWAPRESPONSE"]._serialized_start = 363
     _globals["_REFUNDSWAPRESPONSE"]._serialized_end = 429
-    _globals["_SWAPPARAMETERS"]._serialized_start = 431
-    _globals["_SWAPPARAMETERS"]._serialized_end = 534
-    _globals["_SWAPPARAMETERSREQUEST"]._serialized_start = 536
-    _globals["_SWAPPARAMETERSREQUEST"]._serialized_end = 559
-    _globals["_SWAPPARAMETERSRESPONSE"]._serialized_start = 561
-    _globals["_SWAPPARAMETERSRESPONSE"]._serialized_end = 628
-    _globals["_TAPROOTSWAPPER"]._serialized_start = 631
-    _globals["_TAPROOTSWAPPER"]._serialized_end = 926
+    _globals["_CLAIMSWAPDETAILSREQUEST"]._serialized_start = 431
+    _globals["_CLAIMSWAPDETAILSREQUEST"]._serialized_end = 511
+    _globals["_CLAIMSWAPDETAILSRESPONSE"]._serialized_start = 513
+    _globals["_CLAIMSWAPDETAILSRESPONSE"]._serialized_end = 580
+    _globals["_CLAIMSWAPREQUEST"]._serialized_start = 582
+    _globals["_CLAIMSWAPREQUEST"]._serialized_end = 703
+    _globals["_CLAIMSWAPRESPONSE"]._serialized_start = 705
+    _globals["_CLAIMSWAPRESPONSE"]._serialized_end = 739
+    _globals["_SWAPPARAMETERS"]._serialized_start = 742
+    _globals["_SWAPPARAMETERS"]._serialized_end = 949
+    _globals["_SWAPPARAMETERSREQUEST"]._serialized_start = 951
+    _globals["_SWAPPARAMETERSREQUEST"]._serialized_end = 974
+    _globals["_SWAPPARAMETERSRESPONSE"]._serialized_start = 976
+    _globals["_SWAPPARAMETERSRESPONSE"]._serialized_end = 1043
+    _globals["_SUBSCRIBESWAPEVENTSREQUEST"]._serialized_start = 1045
+    _globals["_SUBSCRIBESWAPEVENTSREQUEST"]._serialized_end = 1128
+    _globals["_SWAPEVENT"]._serialized_start = 1131
+    _globals["_SWAPEVENT"]._serialized_end = 1576
+    _globals["_DEPOSITCONFIRMED"]._serialized_start = 1578
+    _globals["_DEPOSITCONFIRMED"]._serialized_end = 1656
+    _globals["_DEPOSITUNCONFIRMED"]._serialized_start = 1658
+    _globals["_DEPOSITUNCONFIRMED"]._serialized_end = 1737
+    _globals["_DEPOSITDROPPED"]._serialized_start = 1739
+    _globals["_DEPOSITDROPPED"]._serialized_end = 1773
+    _globals["_REFUNDOFFERED"]._serialized_start = 1775
+    _globals["_REFUNDOFFERED"]._serialized_end = 1828
+    _globals["_SWAPPAID"]._serialized_start = 1830
+    _globals["_SWAPPAID"]._serialized_end = 1840
+    _globals["_SWAPPAYMENTFAILED"]._serialized_start = 1842
+    _globals["_SWAPPAYMENTFAILED"]._serialized_end = 1861
+    _globals["_CLAIMBROADCAST"]._serialized_start = 1863
+    _globals["_CLAIMBROADCAST"]._serialized_end = 1894
+    _globals["_OUTPUTSPENT"]._serialized_start = 1896
+    _globals["_OUTPUTSPENT"]._serialized_end = 1973
+    _globals["_GETSWAPSTATUSREQUEST"]._serialized_start = 1975
+    _globals["_GETSWAPSTATUSREQUEST"]._serialized_end = 2052
+    _globals["_GETSWAPSTATUSRESPONSE"]._serialized_start = 2055
+    _globals["_GETSWAPSTATUSRESPONSE"]._serialized_end = 2496
+    _globals["_SWAPSTATUSOUTPUT"]._serialized_start = 2498
+    _globals["_SWAPSTATUSOUTPUT"]._serialized_end = 2625
+    _globals["_CREATEREVERSESWAPREQUEST"]._serialized_start = 2627
+    _globals["_CREATEREVERSESWAPREQUEST"]._serialized_end = 2709
+    _globals["_CREATEREVERSESWAPRESPONSE"]._serialized_start = 2712
+    _globals["_CREATEREVERSESWAPRESPONSE"]._serialized_end = 2875
+    _globals["_REVERSESWAPPARAMETERS"]._serialized_start = 2878
+    _globals["_REVERSESWAPPARAMETERS"]._serialized_end = 3013
+    _globals["_REVERSESWAPPARAMETERSREQUEST"]._serialized_start = 3015
+    _globals["_REVERSESWAPPARAMETERSREQUEST"]._serialized_end = 3045
+    _globals["_REVERSESWAPPARAMETERSRESPONSE"]._serialized_start = 3047
+    _globals["_REVERSESWAPPARAMETERSRESPONSE"]._serialized_end = 3128
+    _globals["_TAPROOTSWAPPER"]._serialized_start = 3434
+    _globals["_TAPROOTSWAPPER"]._serialized_end = 4040
+    _globals["_REVERSESWAPPER"]._serialized_start = 4043
+    _globals["_REVERSESWAPPER"]._serialized_end = 4251
 # @@protoc_insertion_point(module_scope)
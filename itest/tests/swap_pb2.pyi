@@ -1,7 +1,10 @@
+from google.protobuf.internal import containers as _containers
+from google.protobuf.internal import enum_type_wrapper as _enum_type_wrapper
 from google.protobuf import descriptor as _descriptor
 from google.protobuf import message as _message
 from typing import (
     ClassVar as _ClassVar,
+    Iterable as _Iterable,
     Mapping as _Mapping,
     Optional as _Optional,
     Union as _Union,
@@ -9,6 +12,42 @@ from typing import (
 
 DESCRIPTOR: _descriptor.FileDescriptor
 
+class NotPayableReason(int, metaclass=_enum_type_wrapper.EnumTypeWrapper):
+    __slots__ = ()
+    NO_OUTPUTS: _ClassVar[NotPayableReason]
+    INSUFFICIENT_CONFIRMATIONS: _ClassVar[NotPayableReason]
+    EXPIRED: _ClassVar[NotPayableReason]
+    VALUE_TOO_LOW: _ClassVar[NotPayableReason]
+    AMOUNT_TOO_HIGH: _ClassVar[NotPayableReason]
+    AMOUNT_TOO_LOW: _ClassVar[NotPayableReason]
+    ALREADY_PAID: _ClassVar[NotPayableReason]
+    PAYMENT_IN_PROGRESS: _ClassVar[NotPayableReason]
+    REFUND_IN_PROGRESS: _ClassVar[NotPayableReason]
+    OUTPUTS_FILTERED: _ClassVar[NotPayableReason]
+
+class PayoutState(int, metaclass=_enum_type_wrapper.EnumTypeWrapper):
+    __slots__ = ()
+    NOT_STARTED: _ClassVar[PayoutState]
+    PENDING: _ClassVar[PayoutState]
+    SUCCEEDED: _ClassVar[PayoutState]
+    FAILED: _ClassVar[PayoutState]
+
+NO_OUTPUTS: NotPayableReason
+INSUFFICIENT_CONFIRMATIONS: NotPayableReason
+EXPIRED: NotPayableReason
+VALUE_TOO_LOW: NotPayableReason
+AMOUNT_TOO_HIGH: NotPayableReason
+AMOUNT_TOO_LOW: NotPayableReason
+ALREADY_PAID: NotPayableReason
+PAYMENT_IN_PROGRESS: NotPayableReason
+REFUND_IN_PROGRESS: NotPayableReason
+OUTPUTS_FILTERED: NotPayableReason
+
+NOT_STARTED: PayoutState
+PENDING: PayoutState
+SUCCEEDED: PayoutState
+FAILED: PayoutState
+
 class CreateSwapRequest(_message.Message):
     __slots__ = ("hash", "refund_pubkey")
     HASH_FIELD_NUMBER: _ClassVar[int]
@@ -77,19 +116,99 @@ class RefundSwapResponse(_message.Message):
         partial_signature: _Optional[bytes] = ...,
     ) -> None: ...
 
+class ClaimSwapDetailsRequest(_message.Message):
+    __slots__ = ("address", "timestamp", "signature")
+    ADDRESS_FIELD_NUMBER: _ClassVar[int]
+    TIMESTAMP_FIELD_NUMBER: _ClassVar[int]
+    SIGNATURE_FIELD_NUMBER: _ClassVar[int]
+    address: str
+    timestamp: int
+    signature: bytes
+    def __init__(
+        self,
+        address: _Optional[str] = ...,
+        timestamp: _Optional[int] = ...,
+        signature: _Optional[bytes] = ...,
+    ) -> None: ...
+
+class ClaimSwapDetailsResponse(_message.Message):
+    __slots__ = ("transaction", "pub_nonces")
+    TRANSACTION_FIELD_NUMBER: _ClassVar[int]
+    PUB_NONCES_FIELD_NUMBER: _ClassVar[int]
+    transaction: bytes
+    pub_nonces: _containers.RepeatedScalarFieldContainer[bytes]
+    def __init__(
+        self,
+        transaction: _Optional[bytes] = ...,
+        pub_nonces: _Optional[_Iterable[bytes]] = ...,
+    ) -> None: ...
+
+class ClaimSwapRequest(_message.Message):
+    __slots__ = (
+        "address",
+        "pub_nonces",
+        "partial_signatures",
+        "timestamp",
+        "signature",
+    )
+    ADDRESS_FIELD_NUMBER: _ClassVar[int]
+    PUB_NONCES_FIELD_NUMBER: _ClassVar[int]
+    PARTIAL_SIGNATURES_FIELD_NUMBER: _ClassVar[int]
+    TIMESTAMP_FIELD_NUMBER: _ClassVar[int]
+    SIGNATURE_FIELD_NUMBER: _ClassVar[int]
+    address: str
+    pub_nonces: _containers.RepeatedScalarFieldContainer[bytes]
+    partial_signatures: _containers.RepeatedScalarFieldContainer[bytes]
+    timestamp: int
+    signature: bytes
+    def __init__(
+        self,
+        address: _Optional[str] = ...,
+        pub_nonces: _Optional[_Iterable[bytes]] = ...,
+        partial_signatures: _Optional[_Iterable[bytes]] = ...,
+        timestamp: _Optional[int] = ...,
+        signature: _Optional[bytes] = ...,
+    ) -> None: ...
+
+class ClaimSwapResponse(_message.Message):
+    __slots__ = ("tx_id",)
+    TX_ID_FIELD_NUMBER: _ClassVar[int]
+    tx_id: str
+    def __init__(self, tx_id: _Optional[str] = ...) -> None: ...
+
 class SwapParameters(_message.Message):
-    __slots__ = ("max_swap_amount_sat", "min_swap_amount_sat", "min_utxo_amount_sat")
+    __slots__ = (
+        "max_swap_amount_sat",
+        "min_swap_amount_sat",
+        "min_utxo_amount_sat",
+        "fee_base_sat",
+        "fee_ppm",
+        "fee_includes_claim_fee",
+        "claim_fee_quote_seconds",
+    )
     MAX_SWAP_AMOUNT_SAT_FIELD_NUMBER: _ClassVar[int]
     MIN_SWAP_AMOUNT_SAT_FIELD_NUMBER: _ClassVar[int]
     MIN_UTXO_AMOUNT_SAT_FIELD_NUMBER: _ClassVar[int]
+    FEE_BASE_SAT_FIELD_NUMBER: _ClassVar[int]
+    FEE_PPM_FIELD_NUMBER: _ClassVar[int]
+    FEE_INCLUDES_CLAIM_FEE_FIELD_NUMBER: _ClassVar[int]
+    CLAIM_FEE_QUOTE_SECONDS_FIELD_NUMBER: _ClassVar[int]
     max_swap_amount_sat: int
     min_swap_amount_sat: int
     min_utxo_amount_sat: int
+    fee_base_sat: int
+    fee_ppm: int
+    fee_includes_claim_fee: bool
+    claim_fee_quote_seconds: int
     def __init__(
         self,
         max_swap_amount_sat: _Optional[int] = ...,
         min_swap_amount_sat: _Optional[int] = ...,
         min_utxo_amount_sat: _Optional[int] = ...,
+        fee_base_sat: _Optional[int] = ...,
+        fee_ppm: _Optional[int] = ...,
+        fee_includes_claim_fee: bool = ...,
+        claim_fee_quote_seconds: _Optional[int] = ...,
     ) -> None: ...
 
 class SwapParametersRequest(_message.Message):
@@ -103,3 +222,297 @@ class SwapParametersResponse(_message.Message):
     def __init__(
         self, parameters: _Optional[_Union[SwapParameters, _Mapping]] = ...
     ) -> None: ...
+
+class SubscribeSwapEventsRequest(_message.Message):
+    __slots__ = ("address", "timestamp", "signature")
+    ADDRESS_FIELD_NUMBER: _ClassVar[int]
+    TIMESTAMP_FIELD_NUMBER: _ClassVar[int]
+    SIGNATURE_FIELD_NUMBER: _ClassVar[int]
+    address: str
+    timestamp: int
+    signature: bytes
+    def __init__(
+        self,
+        address: _Optional[str] = ...,
+        timestamp: _Optional[int] = ...,
+        signature: _Optional[bytes] = ...,
+    ) -> None: ...
+
+class SwapEvent(_message.Message):
+    __slots__ = (
+        "address",
+        "timestamp",
+        "deposit_confirmed",
+        "paid",
+        "payment_failed",
+        "claim_broadcast",
+        "output_spent",
+        "deposit_unconfirmed",
+        "deposit_dropped",
+        "refund_offered",
+    )
+    ADDRESS_FIELD_NUMBER: _ClassVar[int]
+    TIMESTAMP_FIELD_NUMBER: _ClassVar[int]
+    DEPOSIT_CONFIRMED_FIELD_NUMBER: _ClassVar[int]
+    PAID_FIELD_NUMBER: _ClassVar[int]
+    PAYMENT_FAILED_FIELD_NUMBER: _ClassVar[int]
+    CLAIM_BROADCAST_FIELD_NUMBER: _ClassVar[int]
+    OUTPUT_SPENT_FIELD_NUMBER: _ClassVar[int]
+    DEPOSIT_UNCONFIRMED_FIELD_NUMBER: _ClassVar[int]
+    DEPOSIT_DROPPED_FIELD_NUMBER: _ClassVar[int]
+    REFUND_OFFERED_FIELD_NUMBER: _ClassVar[int]
+    address: str
+    timestamp: int
+    deposit_confirmed: DepositConfirmed
+    paid: SwapPaid
+    payment_failed: SwapPaymentFailed
+    claim_broadcast: ClaimBroadcast
+    output_spent: OutputSpent
+    deposit_unconfirmed: DepositUnconfirmed
+    deposit_dropped: DepositDropped
+    refund_offered: RefundOffered
+    def __init__(
+        self,
+        address: _Optional[str] = ...,
+        timestamp: _Optional[int] = ...,
+        deposit_confirmed: _Optional[_Union[DepositConfirmed, _Mapping]] = ...,
+        paid: _Optional[_Union[SwapPaid, _Mapping]] = ...,
+        payment_failed: _Optional[_Union[SwapPaymentFailed, _Mapping]] = ...,
+        claim_broadcast: _Optional[_Union[ClaimBroadcast, _Mapping]] = ...,
+        output_spent: _Optional[_Union[OutputSpent, _Mapping]] = ...,
+        deposit_unconfirmed: _Optional[_Union[DepositUnconfirmed, _Mapping]] = ...,
+        deposit_dropped: _Optional[_Union[DepositDropped, _Mapping]] = ...,
+        refund_offered: _Optional[_Union[RefundOffered, _Mapping]] = ...,
+    ) -> None: ...
+
+class DepositConfirmed(_message.Message):
+    __slots__ = ("outpoint", "amount_sat", "block_height")
+    OUTPOINT_FIELD_NUMBER: _ClassVar[int]
+    AMOUNT_SAT_FIELD_NUMBER: _ClassVar[int]
+    BLOCK_HEIGHT_FIELD_NUMBER: _ClassVar[int]
+    outpoint: str
+    amount_sat: int
+    block_height: int
+    def __init__(
+        self,
+        outpoint: _Optional[str] = ...,
+        amount_sat: _Optional[int] = ...,
+        block_height: _Optional[int] = ...,
+    ) -> None: ...
+
+class DepositUnconfirmed(_message.Message):
+    __slots__ = ("outpoint", "amount_sat", "replaceable")
+    OUTPOINT_FIELD_NUMBER: _ClassVar[int]
+    AMOUNT_SAT_FIELD_NUMBER: _ClassVar[int]
+    REPLACEABLE_FIELD_NUMBER: _ClassVar[int]
+    outpoint: str
+    amount_sat: int
+    replaceable: bool
+    def __init__(
+        self,
+        outpoint: _Optional[str] = ...,
+        amount_sat: _Optional[int] = ...,
+        replaceable: bool = ...,
+    ) -> None: ...
+
+class DepositDropped(_message.Message):
+    __slots__ = ("outpoint",)
+    OUTPOINT_FIELD_NUMBER: _ClassVar[int]
+    outpoint: str
+    def __init__(self, outpoint: _Optional[str] = ...) -> None: ...
+
+class RefundOffered(_message.Message):
+    __slots__ = ("outpoint", "amount_sat")
+    OUTPOINT_FIELD_NUMBER: _ClassVar[int]
+    AMOUNT_SAT_FIELD_NUMBER: _ClassVar[int]
+    outpoint: str
+    amount_sat: int
+    def __init__(
+        self, outpoint: _Optional[str] = ..., amount_sat: _Optional[int] = ...
+    ) -> None: ...
+
+class SwapPaid(_message.Message):
+    __slots__ = ()
+    def __init__(self) -> None: ...
+
+class SwapPaymentFailed(_message.Message):
+    __slots__ = ()
+    def __init__(self) -> None: ...
+
+class ClaimBroadcast(_message.Message):
+    __slots__ = ("tx_id",)
+    TX_ID_FIELD_NUMBER: _ClassVar[int]
+    tx_id: str
+    def __init__(self, tx_id: _Optional[str] = ...) -> None: ...
+
+class OutputSpent(_message.Message):
+    __slots__ = ("outpoint", "spending_tx_id", "block_height")
+    OUTPOINT_FIELD_NUMBER: _ClassVar[int]
+    SPENDING_TX_ID_FIELD_NUMBER: _ClassVar[int]
+    BLOCK_HEIGHT_FIELD_NUMBER: _ClassVar[int]
+    outpoint: str
+    spending_tx_id: str
+    block_height: int
+    def __init__(
+        self,
+        outpoint: _Optional[str] = ...,
+        spending_tx_id: _Optional[str] = ...,
+        block_height: _Optional[int] = ...,
+    ) -> None: ...
+
+class GetSwapStatusRequest(_message.Message):
+    __slots__ = ("address", "timestamp", "signature")
+    ADDRESS_FIELD_NUMBER: _ClassVar[int]
+    TIMESTAMP_FIELD_NUMBER: _ClassVar[int]
+    SIGNATURE_FIELD_NUMBER: _ClassVar[int]
+    address: str
+    timestamp: int
+    signature: bytes
+    def __init__(
+        self,
+        address: _Optional[str] = ...,
+        timestamp: _Optional[int] = ...,
+        signature: _Optional[bytes] = ...,
+    ) -> None: ...
+
+class GetSwapStatusResponse(_message.Message):
+    __slots__ = (
+        "outputs",
+        "current_height",
+        "blocks_until_refund",
+        "payable",
+        "not_payable_reason",
+        "payable_amount_sat",
+        "fee_sat",
+        "payout_state",
+        "claim_fee_quote_expiry",
+    )
+    OUTPUTS_FIELD_NUMBER: _ClassVar[int]
+    CURRENT_HEIGHT_FIELD_NUMBER: _ClassVar[int]
+    BLOCKS_UNTIL_REFUND_FIELD_NUMBER: _ClassVar[int]
+    PAYABLE_FIELD_NUMBER: _ClassVar[int]
+    NOT_PAYABLE_REASON_FIELD_NUMBER: _ClassVar[int]
+    PAYABLE_AMOUNT_SAT_FIELD_NUMBER: _ClassVar[int]
+    FEE_SAT_FIELD_NUMBER: _ClassVar[int]
+    PAYOUT_STATE_FIELD_NUMBER: _ClassVar[int]
+    CLAIM_FEE_QUOTE_EXPIRY_FIELD_NUMBER: _ClassVar[int]
+    outputs: _containers.RepeatedCompositeFieldContainer[SwapStatusOutput]
+    current_height: int
+    blocks_until_refund: int
+    payable: bool
+    not_payable_reason: NotPayableReason
+    payable_amount_sat: int
+    fee_sat: int
+    payout_state: PayoutState
+    claim_fee_quote_expiry: int
+    def __init__(
+        self,
+        outputs: _Optional[_Iterable[_Union[SwapStatusOutput, _Mapping]]] = ...,
+        current_height: _Optional[int] = ...,
+        blocks_until_refund: _Optional[int] = ...,
+        payable: bool = ...,
+        not_payable_reason: _Optional[_Union[NotPayableReason, str]] = ...,
+        payable_amount_sat: _Optional[int] = ...,
+        fee_sat: _Optional[int] = ...,
+        payout_state: _Optional[_Union[PayoutState, str]] = ...,
+        claim_fee_quote_expiry: _Optional[int] = ...,
+    ) -> None: ...
+
+class SwapStatusOutput(_message.Message):
+    __slots__ = ("outpoint", "amount_sat", "confirmations", "spending_tx_id")
+    OUTPOINT_FIELD_NUMBER: _ClassVar[int]
+    AMOUNT_SAT_FIELD_NUMBER: _ClassVar[int]
+    CONFIRMATIONS_FIELD_NUMBER: _ClassVar[int]
+    SPENDING_TX_ID_FIELD_NUMBER: _ClassVar[int]
+    outpoint: str
+    amount_sat: int
+    confirmations: int
+    spending_tx_id: str
+    def __init__(
+        self,
+        outpoint: _Optional[str] = ...,
+        amount_sat: _Optional[int] = ...,
+        confirmations: _Optional[int] = ...,
+        spending_tx_id: _Optional[str] = ...,
+    ) -> None: ...
+
+class CreateReverseSwapRequest(_message.Message):
+    __slots__ = ("hash", "claim_pubkey", "amount_sat")
+    HASH_FIELD_NUMBER: _ClassVar[int]
+    CLAIM_PUBKEY_FIELD_NUMBER: _ClassVar[int]
+    AMOUNT_SAT_FIELD_NUMBER: _ClassVar[int]
+    hash: bytes
+    claim_pubkey: bytes
+    amount_sat: int
+    def __init__(
+        self,
+        hash: _Optional[bytes] = ...,
+        claim_pubkey: _Optional[bytes] = ...,
+        amount_sat: _Optional[int] = ...,
+    ) -> None: ...
+
+class CreateReverseSwapResponse(_message.Message):
+    __slots__ = (
+        "payment_request",
+        "address",
+        "refund_pubkey",
+        "lock_height",
+        "parameters",
+    )
+    PAYMENT_REQUEST_FIELD_NUMBER: _ClassVar[int]
+    ADDRESS_FIELD_NUMBER: _ClassVar[int]
+    REFUND_PUBKEY_FIELD_NUMBER: _ClassVar[int]
+    LOCK_HEIGHT_FIELD_NUMBER: _ClassVar[int]
+    PARAMETERS_FIELD_NUMBER: _ClassVar[int]
+    payment_request: str
+    address: str
+    refund_pubkey: bytes
+    lock_height: int
+    parameters: ReverseSwapParameters
+    def __init__(
+        self,
+        payment_request: _Optional[str] = ...,
+        address: _Optional[str] = ...,
+        refund_pubkey: _Optional[bytes] = ...,
+        lock_height: _Optional[int] = ...,
+        parameters: _Optional[_Union[ReverseSwapParameters, _Mapping]] = ...,
+    ) -> None: ...
+
+class ReverseSwapParameters(_message.Message):
+    __slots__ = (
+        "max_amount_sat",
+        "min_amount_sat",
+        "fee_base_sat",
+        "fee_ppm",
+        "funding_fee_sat",
+    )
+    MAX_AMOUNT_SAT_FIELD_NUMBER: _ClassVar[int]
+    MIN_AMOUNT_SAT_FIELD_NUMBER: _ClassVar[int]
+    FEE_BASE_SAT_FIELD_NUMBER: _ClassVar[int]
+    FEE_PPM_FIELD_NUMBER: _ClassVar[int]
+    FUNDING_FEE_SAT_FIELD_NUMBER: _ClassVar[int]
+    max_amount_sat: int
+    min_amount_sat: int
+    fee_base_sat: int
+    fee_ppm: int
+    funding_fee_sat: int
+    def __init__(
+        self,
+        max_amount_sat: _Optional[int] = ...,
+        min_amount_sat: _Optional[int] = ...,
+        fee_base_sat: _Optional[int] = ...,
+        fee_ppm: _Optional[int] = ...,
+        funding_fee_sat: _Optional[int] = ...,
+    ) -> None: ...
+
+class ReverseSwapParametersRequest(_message.Message):
+    __slots__ = ()
+    def __init__(self) -> None: ...
+
+class ReverseSwapParametersResponse(_message.Message):
+    __slots__ = ("parameters",)
+    PARAMETERS_FIELD_NUMBER: _ClassVar[int]
+    parameters: ReverseSwapParameters
+    def __init__(
+        self, parameters: _Optional[_Union[ReverseSwapParameters, _Mapping]] = ...
+    ) -> None: ...
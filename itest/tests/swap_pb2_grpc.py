@@ -55,12 +55,36 @@ class TaprootSwapperStub(object):
             response_deserializer=swap__pb2.RefundSwapResponse.FromString,
             _registered_method=True,
         )
+        self.ClaimSwapDetails = channel.unary_unary(
+            "/breez.TaprootSwapper/ClaimSwapDetails",
+            request_serializer=swap__pb2.ClaimSwapDetailsRequest.SerializeToString,
+            response_deserializer=swap__pb2.ClaimSwapDetailsResponse.FromString,
+            _registered_method=True,
+        )
+        self.ClaimSwap = channel.unary_unary(
+            "/breez.TaprootSwapper/ClaimSwap",
+            request_serializer=swap__pb2.ClaimSwapRequest.SerializeToString,
+            response_deserializer=swap__pb2.ClaimSwapResponse.FromString,
+            _registered_method=True,
+        )
         self.SwapParameters = channel.unary_unary(
             "/breez.TaprootSwapper/SwapParameters",
             request_serializer=swap__pb2.SwapParametersRequest.SerializeToString,
             response_deserializer=swap__pb2.SwapParametersResponse.FromString,
             _registered_method=True,
         )
+        self.SubscribeSwapEvents = channel.unary_stream(
+            "/breez.TaprootSwapper/SubscribeSwapEvents",
+            request_serializer=swap__pb2.SubscribeSwapEventsRequest.SerializeToString,
+            response_deserializer=swap__pb2.SwapEvent.FromString,
+            _registered_method=True,
+        )
+        self.GetSwapStatus = channel.unary_unary(
+            "/breez.TaprootSwapper/GetSwapStatus",
+            request_serializer=swap__pb2.GetSwapStatusRequest.SerializeToString,
+            response_deserializer=swap__pb2.GetSwapStatusResponse.FromString,
+            _registered_method=True,
+        )
 
 
 class TaprootSwapperServicer(object):
@@ -84,12 +108,36 @@ class TaprootSwapperServicer(object):
         context.set_details("Method not implemented!")
         raise NotImplementedError("Method not implemented!")
 
+    def ClaimSwapDetails(self, request, context):
+        """Missing associated documentation comment in .proto file."""
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details("Method not implemented!")
+        raise NotImplementedError("Method not implemented!")
+
+    def ClaimSwap(self, request, context):
+        """Missing associated documentation comment in .proto file."""
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details("Method not implemented!")
+        raise NotImplementedError("Method not implemented!")
+
     def SwapParameters(self, request, context):
         """Missing associated documentation comment in .proto file."""
         context.set_code(grpc.StatusCode.UNIMPLEMENTED)
         context.set_details("Method not implemented!")
         raise NotImplementedError("Method not implemented!")
 
+    def SubscribeSwapEvents(self, request, context):
+        """Missing associated documentation comment in .proto file."""
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details("Method not implemented!")
+        raise NotImplementedError("Method not implemented!")
+
+    def GetSwapStatus(self, request, context):
+        """Missing associated documentation comment in .proto file."""
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details("Method not implemented!")
+        raise NotImplementedError("Method not implemented!")
+
 
 def add_TaprootSwapperServicer_to_server(servicer, server):
     rpc_method_handlers = {
@@ -108,11 +156,31 @@ def add_TaprootSwapperServicer_to_server(servicer, server):
             request_deserializer=swap__pb2.RefundSwapRequest.FromString,
             response_serializer=swap__pb2.RefundSwapResponse.SerializeToString,
         ),
+        "ClaimSwapDetails": grpc.unary_unary_rpc_method_handler(
+            servicer.ClaimSwapDetails,
+            request_deserializer=swap__pb2.ClaimSwapDetailsRequest.FromString,
+            response_serializer=swap__pb2.ClaimSwapDetailsResponse.SerializeToString,
+        ),
+        "ClaimSwap": grpc.unary_unary_rpc_method_handler(
+            servicer.ClaimSwap,
+            request_deserializer=swap__pb2.ClaimSwapRequest.FromString,
+            response_serializer=swap__pb2.ClaimSwapResponse.SerializeToString,
+        ),
         "SwapParameters": grpc.unary_unary_rpc_method_handler(
             servicer.SwapParameters,
             request_deserializer=swap__pb2.SwapParametersRequest.FromString,
             response_serializer=swap__pb2.SwapParametersResponse.SerializeToString,
         ),
+        "SubscribeSwapEvents": grpc.unary_stream_rpc_method_handler(
+            servicer.SubscribeSwapEvents,
+            request_deserializer=swap__pb2.SubscribeSwapEventsRequest.FromString,
+            response_serializer=swap__pb2.SwapEvent.SerializeToString,
+        ),
+        "GetSwapStatus": grpc.unary_unary_rpc_method_handler(
+            servicer.GetSwapStatus,
+            request_deserializer=swap__pb2.GetSwapStatusRequest.FromString,
+            response_serializer=swap__pb2.GetSwapStatusResponse.SerializeToString,
+        ),
     }
     generic_handler = grpc.method_handlers_generic_handler(
         "breez.TaprootSwapper", rpc_method_handlers
@@ -215,6 +283,66 @@ class TaprootSwapper(object):
             _registered_method=True,
         )
 
+    @staticmethod
+    def ClaimSwapDetails(
+        request,
+        target,
+        options=(),
+        channel_credentials=None,
+        call_credentials=None,
+        insecure=False,
+        compression=None,
+        wait_for_ready=None,
+        timeout=None,
+        metadata=None,
+    ):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            "/breez.TaprootSwapper/ClaimSwapDetails",
+            swap__pb2.ClaimSwapDetailsRequest.SerializeToString,
+            swap__pb2.ClaimSwapDetailsResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True,
+        )
+
+    @staticmethod
+    def ClaimSwap(
+        request,
+        target,
+        options=(),
+        channel_credentials=None,
+        call_credentials=None,
+        insecure=False,
+        compression=None,
+        wait_for_ready=None,
+        timeout=None,
+        metadata=None,
+    ):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            "/breez.TaprootSwapper/ClaimSwap",
+            swap__pb2.ClaimSwapRequest.SerializeToString,
+            swap__pb2.ClaimSwapResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True,
+        )
+
     @staticmethod
     def SwapParameters(
         request,
@@ -244,3 +372,187 @@ class TaprootSwapper(object):
             metadata,
             _registered_method=True,
         )
+
+    @staticmethod
+    def SubscribeSwapEvents(
+        request,
+        target,
+        options=(),
+        channel_credentials=None,
+        call_credentials=None,
+        insecure=False,
+        compression=None,
+        wait_for_ready=None,
+        timeout=None,
+        metadata=None,
+    ):
+        return grpc.experimental.unary_stream(
+            request,
+            target,
+            "/breez.TaprootSwapper/SubscribeSwapEvents",
+            swap__pb2.SubscribeSwapEventsRequest.SerializeToString,
+            swap__pb2.SwapEvent.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True,
+        )
+
+    @staticmethod
+    def GetSwapStatus(
+        request,
+        target,
+        options=(),
+        channel_credentials=None,
+        call_credentials=None,
+        insecure=False,
+        compression=None,
+        wait_for_ready=None,
+        timeout=None,
+        metadata=None,
+    ):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            "/breez.TaprootSwapper/GetSwapStatus",
+            swap__pb2.GetSwapStatusRequest.SerializeToString,
+            swap__pb2.GetSwapStatusResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True,
+        )
+
+
+class ReverseSwapperStub(object):
+    """Missing associated documentation comment in .proto file."""
+
+    def __init__(self, channel):
+        """Constructor.
+
+        Args:
+            channel: A grpc.Channel.
+        """
+        self.CreateReverseSwap = channel.unary_unary(
+            "/breez.ReverseSwapper/CreateReverseSwap",
+            request_serializer=swap__pb2.CreateReverseSwapRequest.SerializeToString,
+            response_deserializer=swap__pb2.CreateReverseSwapResponse.FromString,
+            _registered_method=True,
+        )
+        self.ReverseSwapParameters = channel.unary_unary(
+            "/breez.ReverseSwapper/ReverseSwapParameters",
+            request_serializer=swap__pb2.ReverseSwapParametersRequest.SerializeToString,
+            response_deserializer=swap__pb2.ReverseSwapParametersResponse.FromString,
+            _registered_method=True,
+        )
+
+
+class ReverseSwapperServicer(object):
+    """Missing associated documentation comment in .proto file."""
+
+    def CreateReverseSwap(self, request, context):
+        """Missing associated documentation comment in .proto file."""
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details("Method not implemented!")
+        raise NotImplementedError("Method not implemented!")
+
+    def ReverseSwapParameters(self, request, context):
+        """Missing associated documentation comment in .proto file."""
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details("Method not implemented!")
+        raise NotImplementedError("Method not implemented!")
+
+
+def add_ReverseSwapperServicer_to_server(servicer, server):
+    rpc_method_handlers = {
+        "CreateReverseSwap": grpc.unary_unary_rpc_method_handler(
+            servicer.CreateReverseSwap,
+            request_deserializer=swap__pb2.CreateReverseSwapRequest.FromString,
+            response_serializer=swap__pb2.CreateReverseSwapResponse.SerializeToString,
+        ),
+        "ReverseSwapParameters": grpc.unary_unary_rpc_method_handler(
+            servicer.ReverseSwapParameters,
+            request_deserializer=swap__pb2.ReverseSwapParametersRequest.FromString,
+            response_serializer=swap__pb2.ReverseSwapParametersResponse.SerializeToString,
+        ),
+    }
+    generic_handler = grpc.method_handlers_generic_handler(
+        "breez.ReverseSwapper", rpc_method_handlers
+    )
+    server.add_generic_rpc_handlers((generic_handler,))
+    server.add_registered_method_handlers("breez.ReverseSwapper", rpc_method_handlers)
+
+
+# This class is part of an EXPERIMENTAL API.
+class ReverseSwapper(object):
+    """Missing associated documentation comment in .proto file."""
+
+    @staticmethod
+    def CreateReverseSwap(
+        request,
+        target,
+        options=(),
+        channel_credentials=None,
+        call_credentials=None,
+        insecure=False,
+        compression=None,
+        wait_for_ready=None,
+        timeout=None,
+        metadata=None,
+    ):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            "/breez.ReverseSwapper/CreateReverseSwap",
+            swap__pb2.CreateReverseSwapRequest.SerializeToString,
+            swap__pb2.CreateReverseSwapResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True,
+        )
+
+    @staticmethod
+    def ReverseSwapParameters(
+        request,
+        target,
+        options=(),
+        channel_credentials=None,
+        call_credentials=None,
+        insecure=False,
+        compression=None,
+        wait_for_ready=None,
+        timeout=None,
+        metadata=None,
+    ):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            "/breez.ReverseSwapper/ReverseSwapParameters",
+            swap__pb2.ReverseSwapParametersRequest.SerializeToString,
+            swap__pb2.ReverseSwapParametersResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True,
+        )
@@ -9,7 +9,7 @@ from pyln.testing.utils import (
     reserve_unused_port,
     wait_for,
 )
-from swap_pb2_grpc import TaprootSwapperStub
+from swap_pb2_grpc import ReverseSwapperStub, TaprootSwapperStub
 from swap_internal_pb2_grpc import SwapManagerStub
 from pathlib import Path
 from flask import Flask, request
@@ -31,8 +31,10 @@ SWAPD_CONFIG = OrderedDict(
         "log-level": "swapd=trace,info",
         "chain-poll-interval-seconds": "1",
         "claim-poll-interval-seconds": "1",
+        "cooperative-claim-window-seconds": "0",
         "payment-poll-interval-seconds": "1",
         "preimage-poll-interval-seconds": "1",
+        "swap-expiry-poll-interval-seconds": "1",
         "whatthefee-poll-interval-seconds": "1",
         "max-swap-amount-sat": "4000000",
         "lock-time": "288",
@@ -161,6 +163,14 @@ class SwapdServer(object):
         if options is not None:
             self.daemon.opts.update(options)
 
+        admin_token = self.daemon.opts.get("internal-admin-token")
+        if admin_token is not None:
+            self.internal_rpc = SwapManagerGrpc(
+                host="127.0.0.1",
+                port=self.internal_grpc_port,
+                token=admin_token,
+            )
+
     def _create_grpc_rpc(self, port):
         if port is None:
             self.grpc_port = reserve_unused_port()
@@ -289,6 +299,7 @@ class SwapperGrpc(object):
         self.logger.debug(f"Connecting to grpc interface at {host}:{port}")
         self.channel = grpc.insecure_channel(f"{host}:{port}")
         self.stub = TaprootSwapperStub(self.channel)
+        self.reverse_stub = ReverseSwapperStub(self.channel)
 
     def create_swap(self, lightning_node, refund_pubkey, hash):
         node_id = lightning_node.info["id"]
@@ -314,34 +325,108 @@ class SwapperGrpc(object):
         )
         return self.stub.RefundSwap(payload)
 
+    def claim_swap_details(self, address, timestamp, signature):
+        payload = swap_pb2.ClaimSwapDetailsRequest(
+            address=address, timestamp=timestamp, signature=signature
+        )
+        return self.stub.ClaimSwapDetails(payload)
+
+    def claim_swap(self, address, pub_nonces, partial_signatures, timestamp, signature):
+        payload = swap_pb2.ClaimSwapRequest(
+            address=address,
+            pub_nonces=pub_nonces,
+            partial_signatures=partial_signatures,
+            timestamp=timestamp,
+            signature=signature,
+        )
+        return self.stub.ClaimSwap(payload)
+
+    def create_reverse_swap(self, claim_pubkey, hash, amount_sat):
+        payload = swap_pb2.CreateReverseSwapRequest(
+            hash=hash, claim_pubkey=bytes.fromhex(claim_pubkey), amount_sat=amount_sat
+        )
+        return self.reverse_stub.CreateReverseSwap(payload)
+
+    def reverse_swap_parameters(self):
+        payload = swap_pb2.ReverseSwapParametersRequest()
+        return self.reverse_stub.ReverseSwapParameters(payload)
+
+    def subscribe_swap_events(self, address, timestamp, signature):
+        payload = swap_pb2.SubscribeSwapEventsRequest(
+            address=address, timestamp=timestamp, signature=signature
+        )
+        return self.stub.SubscribeSwapEvents(payload)
+
+    def get_swap_status(self, address, timestamp, signature):
+        payload = swap_pb2.GetSwapStatusRequest(
+            address=address, timestamp=timestamp, signature=signature
+        )
+        return self.stub.GetSwapStatus(payload)
+
 
 class SwapManagerGrpc(object):
     def __init__(
         self,
         host: str,
         port: int,
+        credentials=None,
+        token=None,
     ):
         self.logger = logging.getLogger("SwapManagerGrpc")
         self.logger.debug(f"Connecting to internal grpc interface at {host}:{port}")
-        self.channel = grpc.insecure_channel(f"{host}:{port}")
+        if credentials is None:
+            self.channel = grpc.insecure_channel(f"{host}:{port}")
+        else:
+            self.channel = grpc.secure_channel(f"{host}:{port}", credentials)
         self.stub = SwapManagerStub(self.channel)
+        self.metadata = []
+        if token is not None:
+            self.metadata.append(("authorization", f"Bearer {token}"))
 
     def add_address_filters(self, addresses=[]):
         payload = swap_internal_pb2.AddAddressFiltersRequest(addresses=addresses)
-        return self.stub.AddAddressFilters(payload)
+        return self.stub.AddAddressFilters(payload, metadata=self.metadata)
 
     def get_info(self):
         payload = swap_internal_pb2.GetInfoRequest()
-        return self.stub.GetInfo(payload)
+        return self.stub.GetInfo(payload, metadata=self.metadata)
 
     def get_swap(self, address=None):
         payload = swap_internal_pb2.GetSwapRequest(address=address)
-        return self.stub.GetSwap(payload)
+        return self.stub.GetSwap(payload, metadata=self.metadata)
+
+    def get_reverse_swap(self, payment_hash):
+        payload = swap_internal_pb2.GetReverseSwapRequest(payment_hash=payment_hash)
+        return self.stub.GetReverseSwap(payload, metadata=self.metadata)
+
+    def reencrypt_claim_keys(self, batch_size=None):
+        payload = swap_internal_pb2.ReencryptClaimKeysRequest(batch_size=batch_size)
+        return self.stub.ReencryptClaimKeys(payload, metadata=self.metadata)
+
+    def rescan(self, from_height, addresses=[]):
+        payload = swap_internal_pb2.RescanRequest(
+            from_height=from_height, addresses=addresses
+        )
+        return self.stub.Rescan(payload, metadata=self.metadata)
+
+    def list_unpaid_outputs(self):
+        payload = swap_internal_pb2.ListUnpaidOutputsRequest()
+        return self.stub.ListUnpaidOutputs(payload, metadata=self.metadata)
+
+    def set_output_disposition(self, outpoint, disposition):
+        payload = swap_internal_pb2.SetOutputDispositionRequest(
+            outpoint=outpoint, disposition=disposition
+        )
+        return self.stub.SetOutputDisposition(payload, metadata=self.metadata)
+
+    def subscribe_swap_events(self):
+        payload = swap_internal_pb2.SubscribeSwapEventsRequest()
+        return self.stub.SubscribeSwapEvents(payload, metadata=self.metadata)
 
     def stop(self):
         payload = swap_internal_pb2.StopRequest()
         try:
-            self.stub.Stop(payload)
+            self.stub.Stop(payload, metadata=self.metadata)
         except Exception:
             pass
 
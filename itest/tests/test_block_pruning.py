@@ -0,0 +1,34 @@
+from helpers import *
+
+
+def test_pruned_blocks_keep_swap_outputs(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory,
+        swapd_factory,
+        swapd_opts={"reorg-safety-window-blocks": "5"},
+    )
+    address, payment_request, h, preimage = create_swap(user, swapper)
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+    output = swapper.internal_rpc.get_swap(address).outputs[0]
+
+    # Move the funding block out of the reorg safety window.
+    user.bitcoin.generate_block(10)
+    swapper.daemon.wait_for_log("pruned .* blocks below height")
+
+    # After a restart the chain is loaded from the remaining blocks.
+    swapper.restart()
+    tip = user.bitcoin.rpc.getblockcount()
+    wait_for(lambda: swapper.internal_rpc.get_info().block_height == tip)
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: swapper.internal_rpc.get_info().block_height == tip + 1)
+
+    # The block confirming the swap output is kept.
+    outputs = swapper.internal_rpc.get_swap(address).outputs
+    assert len(outputs) == 1
+    assert outputs[0].confirmation_height == output.confirmation_height
+    assert outputs[0].block_hash == output.block_hash
+
+    swapper.rpc.pay_swap(payment_request)
+    wait_for(lambda: user.list_invoices(payment_hash=h)[0]["paid"])
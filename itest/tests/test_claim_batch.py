@@ -0,0 +1,38 @@
+from helpers import *
+
+
+def test_claims_batched_across_swaps(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory,
+        swapd_factory,
+        swapd_opts={"claim-poll-interval-seconds": "3600"},
+    )
+    address1, payment_request1, h1, _ = create_swap(user, swapper)
+    address2, payment_request2, h2, _ = create_swap(user, swapper)
+    user.bitcoin.rpc.sendtoaddress(address1, 100_000 / 10**8)
+    user.bitcoin.rpc.sendtoaddress(address2, 150_000 / 10**8)
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address1).outputs) > 0)
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address2).outputs) > 0)
+
+    swapper.rpc.pay_swap(payment_request1)
+    swapper.rpc.pay_swap(payment_request2)
+    wait_for(lambda: user.list_invoices(payment_hash=h1)[0]["paid"])
+    wait_for(lambda: user.list_invoices(payment_hash=h2)[0]["paid"])
+
+    # The next block triggers a claim run, claiming both swaps at once.
+    swapper.lightning_node.bitcoin.generate_block(1)
+    wait_for(lambda: swapper.lightning_node.bitcoin.rpc.getmempoolinfo()["size"] == 1)
+    txid = swapper.lightning_node.bitcoin.rpc.getrawmempool()[0]
+    tx = swapper.lightning_node.bitcoin.rpc.getrawtransaction(txid, True)
+    assert len(tx["vin"]) == 2
+
+    swapper.lightning_node.bitcoin.generate_block(1)
+    wait_for(
+        lambda: swapper.internal_rpc.get_swap(address1).outputs[0].HasField("spend")
+    )
+    wait_for(
+        lambda: swapper.internal_rpc.get_swap(address2).outputs[0].HasField("spend")
+    )
+    assert swapper.internal_rpc.get_swap(address1).outputs[0].spend.txid == txid
+    assert swapper.internal_rpc.get_swap(address2).outputs[0].spend.txid == txid
@@ -0,0 +1,25 @@
+from helpers import *
+
+
+def test_claim_to_destination_address(node_factory, swapd_factory, bitcoind):
+    destination = bitcoind.rpc.getnewaddress("", "bech32")
+    user, swapper = setup_user_and_swapper(
+        node_factory,
+        swapd_factory,
+        swapd_opts={"claim-destination-address": destination},
+    )
+    address, payment_request, h, _ = create_swap(user, swapper)
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+
+    swapper.rpc.pay_swap(payment_request)
+    wait_for(lambda: user.list_invoices(payment_hash=h)[0]["paid"])
+
+    swapper.lightning_node.bitcoin.generate_block(1)
+    wait_for(lambda: swapper.lightning_node.bitcoin.rpc.getmempoolinfo()["size"] == 1)
+    txid = swapper.lightning_node.bitcoin.rpc.getrawmempool()[0]
+    tx = swapper.lightning_node.bitcoin.rpc.getrawtransaction(txid, True)
+    assert len(tx["vout"]) == 1
+    assert tx["vout"][0]["scriptPubKey"]["address"] == destination
+
@@ -0,0 +1,71 @@
+from helpers import *
+import grpc
+import pytest
+
+KEY_1 = "1:" + "01" * 32
+KEY_2 = "2:" + "02" * 32
+
+
+def test_claim_key_encryption_rotation(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory,
+        swapd_factory,
+        swapd_opts={"claim-key-encryption-keys": KEY_1},
+    )
+    expected_outputs = len(swapper.lightning_node.list_utxos()) + 1
+    address, payment_request, h, _ = create_swap(user, swapper)
+
+    swapper.daemon.opts["claim-key-encryption-keys"] = f"{KEY_2},{KEY_1}"
+    swapper.restart()
+    resp = swapper.internal_rpc.reencrypt_claim_keys(batch_size=1)
+    assert resp.reencrypted == 1
+    resp = swapper.internal_rpc.reencrypt_claim_keys()
+    assert resp.reencrypted == 0
+
+    # The old key is no longer needed to claim the swap.
+    swapper.daemon.opts["claim-key-encryption-keys"] = KEY_2
+    swapper.restart()
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+    swapper.rpc.pay_swap(payment_request)
+    wait_for(lambda: user.list_invoices(payment_hash=h)[0]["paid"])
+
+    wait_for(lambda: swapper.lightning_node.bitcoin.rpc.getmempoolinfo()["size"] == 1)
+    swapper.lightning_node.bitcoin.generate_block(1)
+    wait_for(lambda: len(swapper.lightning_node.list_utxos()) == expected_outputs)
+
+
+def test_reencrypt_reverse_refund_keys(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory,
+        swapd_factory,
+        swapd_opts={"claim-key-encryption-keys": KEY_1},
+    )
+    if isinstance(swapper.lightning_node, ClnNode):
+        pytest.skip("reverse swaps require hold invoices, which need lnd")
+    h = hashlib.sha256(os.urandom(32)).digest()
+    claim_pubkey = PrivateKey().get_public_key().to_hex()
+    swapper.rpc.create_reverse_swap(claim_pubkey, h, 100_000)
+
+    swapper.daemon.opts["claim-key-encryption-keys"] = f"{KEY_2},{KEY_1}"
+    swapper.restart()
+    resp = swapper.internal_rpc.reencrypt_claim_keys()
+    assert resp.reencrypted == 1
+
+    # The refund key can be decrypted without the old key.
+    swapper.daemon.opts["claim-key-encryption-keys"] = KEY_2
+    swapper.restart()
+    resp = swapper.internal_rpc.get_reverse_swap(h)
+    assert resp.payment_hash == h.hex()
+
+
+def test_reencrypt_claim_keys_without_key(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(node_factory, swapd_factory)
+    create_swap(user, swapper)
+    try:
+        swapper.internal_rpc.reencrypt_claim_keys()
+        assert False
+    except grpc._channel._InactiveRpcError as e:
+        assert e.code() == grpc.StatusCode.FAILED_PRECONDITION
+        assert e.details() == "no encryption key configured"
@@ -0,0 +1,201 @@
+from helpers import *
+import grpc
+import musig2
+import os
+import pytest
+from bitcoinutils.ripemd160 import ripemd160
+from bitcoinutils.script import Script
+from bitcoinutils.utils import get_tag_hashed_merkle_root, tagged_hash
+from bitcoinutils.setup import setup
+from bitcoinutils.keys import P2trAddress, PrivateKey, PublicKey
+from bitcoinutils.transactions import Transaction
+
+COOPERATIVE_CLAIM_OPTS = {"cooperative-claim-window-seconds": "600"}
+
+
+def get_claim_details(swapper, address, refund_privkey):
+    try:
+        return swapper.rpc.claim_swap_details(
+            address, *sign_ownership_proof(refund_privkey, address)
+        )
+    except grpc._channel._InactiveRpcError:
+        return None
+
+
+def cooperative_claim_signature(
+    address, h, refund_privkey, claim_pubkey, lock_time, tx, their_pub_nonce
+):
+    tx_digest = tx.get_transaction_taproot_digest(
+        0, [P2trAddress(address).to_script_pub_key()], [100_000]
+    )
+    refund_pubkey_bytes = bytes.fromhex(refund_privkey.get_public_key().to_hex())
+    pubkeys = musig2.key_sort([claim_pubkey, refund_pubkey_bytes])
+    agg_ctx = musig2.key_agg(pubkeys)
+    aggpk = musig2.get_xonly_pk(agg_ctx)
+    refund_privkey_bytes = refund_privkey.to_bytes()
+
+    scripts = [
+        Script(
+            [
+                "OP_HASH160",
+                ripemd160(bytes.fromhex(h)).hex(),
+                "OP_EQUALVERIFY",
+                PublicKey(claim_pubkey.hex()).to_x_only_hex(),
+                "OP_CHECKSIG",
+            ]
+        ),
+        Script(
+            [
+                refund_privkey.get_public_key().to_x_only_hex(),
+                "OP_CHECKSIGVERIFY",
+                lock_time,
+                "OP_CHECKSEQUENCEVERIFY",
+            ]
+        ),
+    ]
+    merkle_root = get_tag_hashed_merkle_root(scripts)
+    tweak = tagged_hash(aggpk + merkle_root, "TapTweak")
+    tweaked_internal_key = musig2.get_xonly_pk(
+        musig2.apply_tweak(agg_ctx, tweak, is_xonly=True)
+    )
+    secnonce, pubnonce = musig2.nonce_gen(
+        refund_privkey_bytes,
+        refund_pubkey_bytes,
+        tweaked_internal_key,
+        tx_digest,
+        os.urandom(32),
+    )
+    agg_nonce = musig2.nonce_agg([their_pub_nonce, pubnonce])
+    session = musig2.SessionContext(agg_nonce, pubkeys, [tweak], [True], tx_digest)
+    partial_sig = musig2.sign(secnonce, refund_privkey_bytes, session)
+    return pubnonce, partial_sig
+
+
+def test_cooperative_claim_success(node_factory, swapd_factory):
+    setup("regtest")
+    user, swapper = setup_user_and_swapper(
+        node_factory, swapd_factory, swapd_opts=COOPERATIVE_CLAIM_OPTS
+    )
+    expected_outputs = len(swapper.lightning_node.list_utxos()) + 1
+    address, payment_request, h, _, refund_privkey, claim_pubkey, lock_time = (
+        create_swap_extended(user, swapper)
+    )
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+    swapper.rpc.pay_swap(payment_request)
+    wait_for(lambda: user.list_invoices(payment_hash=h)[0]["paid"])
+
+    wait_for(lambda: get_claim_details(swapper, address, refund_privkey) is not None)
+    details = get_claim_details(swapper, address, refund_privkey)
+    assert len(details.pub_nonces) == 1
+    tx = Transaction.from_raw(details.transaction.hex())
+    pub_nonce, partial_sig = cooperative_claim_signature(
+        address, h, refund_privkey, claim_pubkey, lock_time, tx, details.pub_nonces[0]
+    )
+    resp = swapper.rpc.claim_swap(
+        address,
+        [pub_nonce],
+        [partial_sig],
+        *sign_ownership_proof(refund_privkey, address),
+    )
+
+    claim_tx = user.bitcoin.rpc.getrawtransaction(resp.tx_id, True)
+    assert len(claim_tx["vin"][0]["txinwitness"]) == 1
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: len(swapper.lightning_node.list_utxos()) == expected_outputs)
+
+
+def test_cooperative_claim_invalid_signature(node_factory, swapd_factory):
+    setup("regtest")
+    user, swapper = setup_user_and_swapper(
+        node_factory, swapd_factory, swapd_opts=COOPERATIVE_CLAIM_OPTS
+    )
+    address, payment_request, h, _, refund_privkey, claim_pubkey, lock_time = (
+        create_swap_extended(user, swapper)
+    )
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+    swapper.rpc.pay_swap(payment_request)
+    wait_for(lambda: get_claim_details(swapper, address, refund_privkey) is not None)
+
+    details = get_claim_details(swapper, address, refund_privkey)
+    try:
+        swapper.rpc.claim_swap(
+            address,
+            [details.pub_nonces[0]],
+            [os.urandom(32)],
+            *sign_ownership_proof(refund_privkey, address),
+        )
+        assert False
+    except grpc._channel._InactiveRpcError as e:
+        assert e.details() == "invalid partial signature"
+
+    # The claim is still pending, a valid signature completes it.
+    assert get_claim_details(swapper, address, refund_privkey) == details
+    tx = Transaction.from_raw(details.transaction.hex())
+    pub_nonce, partial_sig = cooperative_claim_signature(
+        address, h, refund_privkey, claim_pubkey, lock_time, tx, details.pub_nonces[0]
+    )
+    resp = swapper.rpc.claim_swap(
+        address,
+        [pub_nonce],
+        [partial_sig],
+        *sign_ownership_proof(refund_privkey, address),
+    )
+    claim_tx = user.bitcoin.rpc.getrawtransaction(resp.tx_id, True)
+    assert len(claim_tx["vin"][0]["txinwitness"]) == 1
+
+
+def test_cooperative_claim_requires_ownership_proof(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory, swapd_factory, swapd_opts=COOPERATIVE_CLAIM_OPTS
+    )
+    address, payment_request, h, _, refund_privkey, claim_pubkey, lock_time = (
+        create_swap_extended(user, swapper)
+    )
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+    swapper.rpc.pay_swap(payment_request)
+    wait_for(lambda: get_claim_details(swapper, address, refund_privkey) is not None)
+    details = get_claim_details(swapper, address, refund_privkey)
+
+    with pytest.raises(grpc.RpcError) as e:
+        swapper.rpc.claim_swap_details(
+            address, *sign_ownership_proof(PrivateKey(), address)
+        )
+    assert e.value.code() == grpc.StatusCode.UNAUTHENTICATED
+
+    with pytest.raises(grpc.RpcError) as e:
+        swapper.rpc.claim_swap(
+            address,
+            [details.pub_nonces[0]],
+            [os.urandom(32)],
+            *sign_ownership_proof(PrivateKey(), address),
+        )
+    assert e.value.code() == grpc.StatusCode.UNAUTHENTICATED
+
+
+def test_cooperative_claim_window_expired(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory,
+        swapd_factory,
+        swapd_opts={"cooperative-claim-window-seconds": "2"},
+    )
+    address, payment_request, h, _ = create_swap(user, swapper)
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+    swapper.rpc.pay_swap(payment_request)
+
+    # The user never signs, so the swap is claimed through the script path.
+    wait_for(lambda: user.bitcoin.rpc.getmempoolinfo()["size"] == 1)
+    claim_txid = user.bitcoin.rpc.getrawmempool()[0]
+    claim_tx = user.bitcoin.rpc.getrawtransaction(claim_txid, True)
+    assert len(claim_tx["vin"][0]["txinwitness"]) == 4
@@ -0,0 +1,67 @@
+from helpers import *
+from health_pb2 import HealthCheckRequest, HealthCheckResponse
+from health_pb2_grpc import HealthStub
+import grpc
+import pytest
+
+
+def check(port, service=""):
+    channel = grpc.insecure_channel("127.0.0.1:{}".format(port))
+    stub = HealthStub(channel)
+    return stub.Check(HealthCheckRequest(service=service)).status
+
+
+def test_health_serving(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory,
+        swapd_factory,
+        swapd_opts={"connection-check-interval-seconds": "1"},
+    )
+
+    wait_for(lambda: check(swapper.grpc_port) == HealthCheckResponse.SERVING)
+    assert (
+        check(swapper.grpc_port, "breez.TaprootSwapper") == HealthCheckResponse.SERVING
+    )
+    assert (
+        check(swapper.grpc_port, "breez.ReverseSwapper") == HealthCheckResponse.SERVING
+    )
+    assert check(swapper.internal_grpc_port) == HealthCheckResponse.SERVING
+    assert (
+        check(swapper.internal_grpc_port, "swap_internal.SwapManager")
+        == HealthCheckResponse.SERVING
+    )
+
+    with pytest.raises(grpc.RpcError) as e:
+        check(swapper.grpc_port, "swap_internal.SwapManager")
+    assert e.value.code() == grpc.StatusCode.NOT_FOUND
+
+
+def test_health_not_serving_without_lightning(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory,
+        swapd_factory,
+        swapd_opts={"connection-check-interval-seconds": "1"},
+    )
+    wait_for(lambda: check(swapper.grpc_port) == HealthCheckResponse.SERVING)
+
+    swapper.lightning_node.stop()
+    wait_for(lambda: check(swapper.grpc_port) == HealthCheckResponse.NOT_SERVING)
+    assert check(swapper.internal_grpc_port) == HealthCheckResponse.NOT_SERVING
+    swapper.daemon.wait_for_log("swapd is not ready: lightning node unreachable")
+
+
+def test_health_not_serving_when_chain_lags(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory,
+        swapd_factory,
+        swapd_opts={
+            "connection-check-interval-seconds": "1",
+            "max-chain-lag-blocks": "0",
+            "chain-poll-interval-seconds": "3600",
+        },
+    )
+    wait_for(lambda: check(swapper.grpc_port) == HealthCheckResponse.SERVING)
+
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: check(swapper.grpc_port) == HealthCheckResponse.NOT_SERVING)
+    swapper.daemon.wait_for_log("swapd is not ready: chain sync is 1 blocks behind")
@@ -0,0 +1,95 @@
+from helpers import *
+from certs import new_cert, new_key, pem_cert, pem_key
+from swapd import SwapManagerGrpc
+import grpc
+import pytest
+import swap_internal_pb2
+
+ADMIN_TOKEN = "admin-secret"
+READONLY_TOKEN = "readonly-secret"
+
+
+def test_internal_tokens(node_factory, swapd_factory):
+    swapper = swapd_factory.get_swapd(
+        options={
+            "internal-admin-token": ADMIN_TOKEN,
+            "internal-readonly-token": READONLY_TOKEN,
+        }
+    )
+
+    # The harness uses the admin token, so it was able to wait for the sync.
+    assert swapper.internal_rpc.get_info().block_height > 0
+
+    readonly = SwapManagerGrpc(
+        "127.0.0.1", swapper.internal_grpc_port, token=READONLY_TOKEN
+    )
+    assert readonly.get_info().block_height > 0
+    with pytest.raises(grpc.RpcError) as e:
+        readonly.reencrypt_claim_keys()
+    assert e.value.code() == grpc.StatusCode.PERMISSION_DENIED
+
+    anonymous = SwapManagerGrpc("127.0.0.1", swapper.internal_grpc_port)
+    with pytest.raises(grpc.RpcError) as e:
+        anonymous.get_info()
+    assert e.value.code() == grpc.StatusCode.UNAUTHENTICATED
+
+    wrong = SwapManagerGrpc("127.0.0.1", swapper.internal_grpc_port, token="wrong")
+    with pytest.raises(grpc.RpcError) as e:
+        wrong.get_info()
+    assert e.value.code() == grpc.StatusCode.UNAUTHENTICATED
+
+    # Stop is an admin call, so the read-only token cannot stop swapd.
+    with pytest.raises(grpc.RpcError) as e:
+        readonly.stub.Stop(swap_internal_pb2.StopRequest(), metadata=readonly.metadata)
+    assert e.value.code() == grpc.StatusCode.PERMISSION_DENIED
+
+
+def test_internal_tls_client_cert(node_factory, swapd_factory, tmp_path):
+    ca_key = new_key()
+    ca_cert = new_cert(ca_key, "swapd ca")
+    server_key = new_key()
+    server_cert = new_cert(server_key, "swapd", ca_cert, ca_key, ip="127.0.0.1")
+    client_key = new_key()
+    client_cert = new_cert(client_key, "swap-cli", ca_cert, ca_key)
+
+    files = {
+        "ca.pem": pem_cert(ca_cert),
+        "server.pem": pem_cert(server_cert),
+        "server-key.pem": pem_key(server_key),
+    }
+    for name, content in files.items():
+        (tmp_path / name).write_bytes(content)
+
+    swapper = swapd_factory.get_swapd(
+        options={
+            "internal-tls-cert": str(tmp_path / "server.pem"),
+            "internal-tls-key": str(tmp_path / "server-key.pem"),
+            "internal-tls-client-ca-cert": str(tmp_path / "ca.pem"),
+        },
+        start=False,
+    )
+    swapper.internal_rpc = SwapManagerGrpc(
+        "127.0.0.1",
+        swapper.internal_grpc_port,
+        credentials=grpc.ssl_channel_credentials(
+            root_certificates=pem_cert(ca_cert),
+            private_key=pem_key(client_key),
+            certificate_chain=pem_cert(client_cert),
+        ),
+    )
+    swapper.start()
+    assert swapper.internal_rpc.get_info().block_height > 0
+
+    without_client_cert = SwapManagerGrpc(
+        "127.0.0.1",
+        swapper.internal_grpc_port,
+        credentials=grpc.ssl_channel_credentials(root_certificates=pem_cert(ca_cert)),
+    )
+    with pytest.raises(grpc.RpcError) as e:
+        without_client_cert.get_info()
+    assert e.value.code() == grpc.StatusCode.UNAVAILABLE
+
+    plaintext = SwapManagerGrpc("127.0.0.1", swapper.internal_grpc_port)
+    with pytest.raises(grpc.RpcError) as e:
+        plaintext.get_info()
+    assert e.value.code() == grpc.StatusCode.UNAVAILABLE
@@ -0,0 +1,72 @@
+from helpers import *
+from bitcoinutils.setup import setup
+from test_coop_refund import coop_refund
+from test_reverse_swap import create_reverse_swap
+import pytest
+
+REMOTE_SIGNING_OPTS = {"lnd-remote-signing": None}
+
+
+def setup_remote_signing(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory, swapd_factory, swapd_opts=REMOTE_SIGNING_OPTS
+    )
+    if isinstance(swapper.lightning_node, ClnNode):
+        pytest.skip("remote signing requires lnd")
+    return user, swapper
+
+
+def test_lnd_remote_signing_claim(node_factory, swapd_factory):
+    user, swapper = setup_remote_signing(node_factory, swapd_factory)
+    expected_outputs = len(swapper.lightning_node.list_utxos()) + 1
+    address, payment_request, h, _ = create_swap(user, swapper)
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+    swap = swapper.internal_rpc.get_swap(address)
+    assert swap.claim_key_family == 1984
+
+    swapper.rpc.pay_swap(payment_request)
+    wait_for(lambda: user.list_invoices(payment_hash=h)[0]["paid"])
+
+    wait_for(lambda: swapper.lightning_node.bitcoin.rpc.getmempoolinfo()["size"] == 1)
+    swapper.lightning_node.bitcoin.generate_block(1)
+    wait_for(lambda: len(swapper.lightning_node.list_utxos()) == expected_outputs)
+
+
+def test_lnd_remote_signing_cooperative_refund(node_factory, swapd_factory):
+    setup("regtest")
+    user, swapper = setup_remote_signing(node_factory, swapd_factory)
+    address, _, h, refund_privkey, claim_pubkey, lock_time = (
+        create_swap_no_invoice_extended(user, swapper)
+    )
+    to_spend_txid = user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+
+    tx = coop_refund(
+        user,
+        swapper,
+        address,
+        h,
+        refund_privkey,
+        claim_pubkey,
+        lock_time,
+        to_spend_txid,
+        99_000,
+    )
+
+    expected_utxos = len(user.list_utxos()) + 1
+    user.bitcoin.rpc.sendrawtransaction(tx)
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: len(user.list_utxos()) == expected_utxos)
+
+
+def test_lnd_remote_signing_reverse_refund_key(node_factory, swapd_factory):
+    user, swapper = setup_remote_signing(node_factory, swapd_factory)
+    _, _, h = create_reverse_swap(swapper)
+
+    reverse_swap = swapper.internal_rpc.get_reverse_swap(h)
+    assert reverse_swap.refund_key_family == 1985
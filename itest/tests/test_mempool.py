@@ -0,0 +1,113 @@
+from helpers import *
+import grpc
+
+
+def mempool_opts(swapd_factory):
+    return {
+        "watch-mempool": None,
+        "bitcoind-zmq-tx-address": "tcp://127.0.0.1:{}".format(
+            swapd_factory.bitcoind.txport
+        ),
+        "zero-conf-max-amount-sat": "200000",
+        "zero-conf-max-outstanding-sat": "250000",
+    }
+
+
+def test_zero_conf_payout(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory, swapd_factory, swapd_opts=mempool_opts(swapd_factory)
+    )
+    address, payment_request, h, preimage = create_swap(user, swapper)
+
+    # Opt out of replace-by-fee, so the deposit is eligible for zero-conf.
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8, "", "", False, False)
+    wait_for(
+        lambda: len(swapper.internal_rpc.get_swap(address).unconfirmed_outputs) > 0
+    )
+    output = swapper.internal_rpc.get_swap(address).unconfirmed_outputs[0]
+    assert output.amount_sat == 100_000
+    assert not output.replaceable
+
+    swapper.rpc.pay_swap(payment_request)
+    wait_for(lambda: user.list_invoices(payment_hash=h)[0]["paid"])
+
+    # Once confirmed, the output moves from the mempool to the chain.
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+    wait_for(
+        lambda: len(swapper.internal_rpc.get_swap(address).unconfirmed_outputs) == 0
+    )
+
+
+def test_replaceable_deposit_not_payable(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory, swapd_factory, swapd_opts=mempool_opts(swapd_factory)
+    )
+    address, payment_request, h, preimage = create_swap(user, swapper)
+
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8, "", "", False, True)
+    wait_for(
+        lambda: len(swapper.internal_rpc.get_swap(address).unconfirmed_outputs) > 0
+    )
+    assert swapper.internal_rpc.get_swap(address).unconfirmed_outputs[0].replaceable
+
+    try:
+        swapper.rpc.pay_swap(payment_request)
+        assert False
+    except grpc._channel._InactiveRpcError as e:
+        assert e.details() == "no utxos found"
+
+
+def test_zero_conf_amount_above_max(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory, swapd_factory, swapd_opts=mempool_opts(swapd_factory)
+    )
+    address, payment_request, h, preimage = create_swap(user, swapper)
+
+    user.bitcoin.rpc.sendtoaddress(address, 300_000 / 10**8, "", "", False, False)
+    wait_for(
+        lambda: len(swapper.internal_rpc.get_swap(address).unconfirmed_outputs) > 0
+    )
+
+    try:
+        swapper.rpc.pay_swap(payment_request)
+        assert False
+    except grpc._channel._InactiveRpcError as e:
+        assert e.details() == "no utxos found"
+
+
+def test_zero_conf_outstanding_above_max(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory, swapd_factory, swapd_opts=mempool_opts(swapd_factory)
+    )
+    address1, payment_request1, h1, preimage1 = create_swap(
+        user, swapper, 150_000_000
+    )
+    address2, payment_request2, h2, preimage2 = create_swap(
+        user, swapper, 150_000_000
+    )
+
+    user.bitcoin.rpc.sendtoaddress(address1, 150_000 / 10**8, "", "", False, False)
+    user.bitcoin.rpc.sendtoaddress(address2, 150_000 / 10**8, "", "", False, False)
+    wait_for(
+        lambda: len(swapper.internal_rpc.get_swap(address1).unconfirmed_outputs) > 0
+        and len(swapper.internal_rpc.get_swap(address2).unconfirmed_outputs) > 0
+    )
+
+    swapper.rpc.pay_swap(payment_request1)
+    wait_for(lambda: user.list_invoices(payment_hash=h1)[0]["paid"])
+
+    # The first payout is still outstanding, together they exceed the max.
+    try:
+        swapper.rpc.pay_swap(payment_request2)
+        assert False
+    except grpc._channel._InactiveRpcError as e:
+        assert e.details() == "no utxos found"
+
+    # Once the first deposit confirms, there is room for the second one.
+    user.bitcoin.generate_block(1)
+    wait_for(
+        lambda: len(swapper.internal_rpc.get_swap(address1).unconfirmed_outputs) == 0
+    )
+    swapper.rpc.pay_swap(payment_request2)
+    wait_for(lambda: user.list_invoices(payment_hash=h2)[0]["paid"])
@@ -0,0 +1,50 @@
+from helpers import *
+from pyln.testing.utils import reserve_unused_port
+import urllib.request
+
+
+def get_metrics(port):
+    with urllib.request.urlopen("http://127.0.0.1:{}/metrics".format(port)) as resp:
+        metrics = {}
+        for line in resp.read().decode().splitlines():
+            if line.startswith("#"):
+                continue
+            name, value = line.rsplit(" ", 1)
+            metrics[name] = float(value)
+        return metrics
+
+
+def test_metrics(node_factory, swapd_factory):
+    metrics_port = reserve_unused_port()
+    user, swapper = setup_user_and_swapper(
+        node_factory,
+        swapd_factory,
+        swapd_opts={
+            "metrics-address": "127.0.0.1:{}".format(metrics_port),
+            "connection-check-interval-seconds": "1",
+        },
+    )
+    wait_for(lambda: get_metrics(metrics_port)["swapd_lightning_up"] == 1)
+    wait_for(lambda: get_metrics(metrics_port)["swapd_chain_client_up"] == 1)
+
+    address, payment_request, h, preimage = create_swap(user, swapper)
+    assert get_metrics(metrics_port)["swapd_swaps_created_total"] == 1
+
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+
+    swapper.rpc.pay_swap(payment_request)
+    wait_for(lambda: user.list_invoices(payment_hash=h)[0]["paid"])
+
+    metrics = get_metrics(metrics_port)
+    assert metrics["swapd_swaps_paid_total"] == 1
+    assert metrics["swapd_payout_latency_seconds_count"] == 1
+    assert metrics['swapd_fee_estimates_total{source="primary"}'] > 0
+    assert metrics["swapd_chain_synced_height"] > 0
+    assert metrics["swapd_chain_tip_height"] >= metrics["swapd_chain_synced_height"]
+
+    # The paid output is unclaimed until the claim monitor picks it up.
+    wait_for(lambda: get_metrics(metrics_port)["swapd_claim_txs_pending"] == 1)
+    swapper.lightning_node.bitcoin.generate_block(1)
+    wait_for(lambda: get_metrics(metrics_port)["swapd_unclaimed_value_sat"] == 0)
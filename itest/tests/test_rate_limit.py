@@ -0,0 +1,91 @@
+from helpers import *
+from bitcoinutils.keys import PrivateKey
+import grpc
+import hashlib
+import os
+import pytest
+
+
+def new_refund_pubkey():
+    return PrivateKey().get_public_key().to_hex()
+
+
+def try_create_swap(user, swapper, refund_pubkey):
+    h = hashlib.sha256(os.urandom(32)).digest()
+    return swapper.rpc.create_swap(user, refund_pubkey, h)
+
+
+def assert_rejected(user, swapper, refund_pubkey):
+    with pytest.raises(grpc.RpcError) as e:
+        try_create_swap(user, swapper, refund_pubkey)
+    assert e.value.code() == grpc.StatusCode.RESOURCE_EXHAUSTED
+
+
+def test_rate_limit_per_refund_pubkey(node_factory, swapd_factory):
+    user = node_factory.get_node()
+    swapper = swapd_factory.get_swapd(
+        options={"create-swap-rate-limit-per-refund-pubkey": "2"}
+    )
+    refund_pubkey = new_refund_pubkey()
+    try_create_swap(user, swapper, refund_pubkey)
+    try_create_swap(user, swapper, refund_pubkey)
+    assert_rejected(user, swapper, refund_pubkey)
+
+    # Other refund pubkeys are not affected.
+    try_create_swap(user, swapper, new_refund_pubkey())
+
+
+def test_rate_limit_per_ip(node_factory, swapd_factory):
+    user = node_factory.get_node()
+    swapper = swapd_factory.get_swapd(options={"create-swap-rate-limit-per-ip": "2"})
+    try_create_swap(user, swapper, new_refund_pubkey())
+    try_create_swap(user, swapper, new_refund_pubkey())
+    assert_rejected(user, swapper, new_refund_pubkey())
+
+
+def test_rate_limit_per_ip_without_client_ip_header(node_factory, swapd_factory):
+    user = node_factory.get_node()
+    swapper = swapd_factory.get_swapd(
+        options={
+            "create-swap-rate-limit-per-ip": "2",
+            "client-ip-header": "x-forwarded-for",
+        }
+    )
+
+    # Requests without the header are limited by their peer address.
+    try_create_swap(user, swapper, new_refund_pubkey())
+    try_create_swap(user, swapper, new_refund_pubkey())
+    assert_rejected(user, swapper, new_refund_pubkey())
+
+
+def test_max_unfunded_swaps(node_factory, swapd_factory):
+    user = node_factory.get_node()
+    swapper = swapd_factory.get_swapd(options={"max-unfunded-swaps": "1"})
+    address = try_create_swap(user, swapper, new_refund_pubkey()).address
+    assert_rejected(user, swapper, new_refund_pubkey())
+
+    # Once the swap is funded, new swaps can be created again.
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+    try_create_swap(user, swapper, new_refund_pubkey())
+
+
+def test_unfunded_swap_expires(node_factory, swapd_factory, lock_time):
+    user = node_factory.get_node()
+    swapper = swapd_factory.get_swapd(options={"max-unfunded-swaps": "1"})
+    address, payment_request, _, _ = create_swap(user, swapper)
+    assert_rejected(user, swapper, new_refund_pubkey())
+
+    user.bitcoin.generate_block(lock_time + 1)
+    swapper.daemon.wait_for_log("expired unfunded swaps")
+
+    # The expired swap no longer counts towards the unfunded swaps.
+    try_create_swap(user, swapper, new_refund_pubkey())
+
+    # Deposits to the expired swap are not paid out.
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+    with pytest.raises(grpc.RpcError) as e:
+        swapper.rpc.pay_swap(payment_request)
+    assert e.value.details() == "swap expired"
@@ -0,0 +1,110 @@
+from helpers import *
+import grpc
+import psycopg2
+import pytest
+
+
+def test_birthday_height(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory, swapd_factory, swapd_opts={"birthday-height": "1"}
+    )
+    swapper.daemon.wait_for_log("using block 1 as birthday")
+
+
+def test_rescan_finds_missed_deposit(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(node_factory, swapd_factory)
+    address, payment_request, h, preimage = create_swap(user, swapper)
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+    height = swapper.internal_rpc.get_swap(address).outputs[0].confirmation_height
+
+    # Forget the deposit, as if it was missed.
+    with psycopg2.connect(swapper.daemon.opts["db-url"]) as conn:
+        with conn.cursor() as cur:
+            cur.execute("DELETE FROM tx_outputs WHERE address = %s", (address,))
+    assert len(swapper.internal_rpc.get_swap(address).outputs) == 0
+
+    resp = swapper.internal_rpc.rescan(height, [address])
+    assert resp.new_outputs == 1
+    outputs = swapper.internal_rpc.get_swap(address).outputs
+    assert len(outputs) == 1
+    assert outputs[0].confirmation_height == height
+
+    # Nothing new is found the second time.
+    resp = swapper.internal_rpc.rescan(height)
+    assert resp.new_outputs == 0
+
+    swapper.rpc.pay_swap(payment_request)
+    wait_for(lambda: user.list_invoices(payment_hash=h)[0]["paid"])
+
+
+def test_rescan_expired_swap(node_factory, swapd_factory, lock_time):
+    user, swapper = setup_user_and_swapper(
+        node_factory,
+        swapd_factory,
+        swapd_opts={"retention-poll-interval-seconds": "1"},
+    )
+    address, payment_request, h, preimage = create_swap(user, swapper)
+    user.bitcoin.generate_block(lock_time + 1)
+    swapper.daemon.wait_for_log("expired unfunded swaps")
+    swapper.daemon.wait_for_log("swap completed.*reason=.?expired")
+
+    # The expired swap is no longer watched, so a late deposit is missed.
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+    wait_for(swapper.is_synced)
+    height = swapper.internal_rpc.get_info().block_height
+    assert len(swapper.internal_rpc.get_swap(address).outputs) == 0
+
+    resp = swapper.internal_rpc.rescan(height, [address])
+    assert resp.new_outputs == 1
+    assert len(swapper.internal_rpc.get_swap(address).outputs) == 1
+
+    # The swap is watched again and no longer expired or completed.
+    with psycopg2.connect(swapper.daemon.opts["db-url"]) as conn:
+        with conn.cursor() as cur:
+            cur.execute(
+                "SELECT expiry_time, completion_time, completion_reason FROM swaps WHERE address = %s",
+                (address,),
+            )
+            assert cur.fetchone() == (None, None, None)
+            cur.execute(
+                "SELECT COUNT(*) FROM watch_addresses WHERE address = %s", (address,)
+            )
+            assert cur.fetchone()[0] == 1
+
+
+def test_rescan_archived_swap(node_factory, swapd_factory, lock_time):
+    user, swapper = setup_user_and_swapper(
+        node_factory,
+        swapd_factory,
+        swapd_opts={
+            "retention-poll-interval-seconds": "1",
+            "retention-period-seconds": "1",
+        },
+    )
+    address, payment_request, h, preimage = create_swap(user, swapper)
+    user.bitcoin.generate_block(lock_time + 1)
+    swapper.daemon.wait_for_log("swap completed.*reason=.?expired")
+    swapper.daemon.wait_for_log("archived completed swaps")
+
+    height = swapper.internal_rpc.get_info().block_height
+    with pytest.raises(grpc.RpcError) as e:
+        swapper.internal_rpc.rescan(height, [address])
+    assert e.value.code() == grpc.StatusCode.FAILED_PRECONDITION
+    assert "archived" in e.value.details()
+
+
+def test_rescan_invalid_arguments(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(node_factory, swapd_factory)
+    tip = swapper.internal_rpc.get_info().block_height
+
+    with pytest.raises(grpc.RpcError) as e:
+        swapper.internal_rpc.rescan(tip + 10)
+    assert e.value.code() == grpc.StatusCode.INVALID_ARGUMENT
+
+    unwatched = user.bitcoin.rpc.getnewaddress()
+    with pytest.raises(grpc.RpcError) as e:
+        swapper.internal_rpc.rescan(tip, [unwatched])
+    assert e.value.code() == grpc.StatusCode.INVALID_ARGUMENT
@@ -0,0 +1,48 @@
+from helpers import *
+from bitcoinutils.keys import PrivateKey
+import grpc
+import pytest
+
+
+def test_claimed_swap_archived(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory,
+        swapd_factory,
+        swapd_opts={
+            "retention-poll-interval-seconds": "1",
+            "retention-min-confirmations": "2",
+            "retention-period-seconds": "1",
+        },
+    )
+    address, payment_request, h, preimage = create_swap(user, swapper)
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+
+    swapper.rpc.pay_swap(payment_request)
+    wait_for(lambda: user.list_invoices(payment_hash=h)[0]["paid"])
+    wait_for(lambda: swapper.lightning_node.bitcoin.rpc.getmempoolinfo()["size"] == 1)
+
+    # The claim needs 2 confirmations before the swap is completed.
+    swapper.lightning_node.bitcoin.generate_block(1)
+    wait_for(
+        lambda: swapper.internal_rpc.get_swap(address).outputs[0].HasField("spend")
+    )
+    assert swapper.internal_rpc.get_swap(address).payment_hash == h
+
+    swapper.lightning_node.bitcoin.generate_block(1)
+    swapper.daemon.wait_for_log("swap completed.*reason=.?claimed")
+    swapper.daemon.wait_for_log("archived completed swaps")
+
+    with pytest.raises(grpc.RpcError) as e:
+        swapper.internal_rpc.get_swap(address)
+    assert e.value.code() == grpc.StatusCode.NOT_FOUND
+
+    # The payment hash of an archived swap can't be used again.
+    with pytest.raises(grpc.RpcError) as e:
+        swapper.rpc.create_swap(
+            user,
+            PrivateKey().get_public_key().to_hex(),
+            bytes.fromhex(h),
+        )
+    assert e.value.code() == grpc.StatusCode.ALREADY_EXISTS
@@ -0,0 +1,91 @@
+from helpers import *
+from bitcoinutils.keys import PrivateKey
+import grpc
+import hashlib
+import os
+import pytest
+import threading
+import time
+
+# Default of swapd's reverse-min-claim-blocks.
+REVERSE_MIN_CLAIM_BLOCKS = 72
+
+
+def setup_reverse(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(node_factory, swapd_factory)
+    if isinstance(swapper.lightning_node, ClnNode):
+        pytest.skip("reverse swaps require hold invoices, which need lnd")
+    return user, swapper
+
+
+def create_reverse_swap(swapper, amount_sat=100_000):
+    preimage = os.urandom(32)
+    h = hashlib.sha256(preimage).digest()
+    claim_privkey = PrivateKey()
+    claim_pubkey = claim_privkey.get_public_key().to_hex()
+    resp = swapper.rpc.create_reverse_swap(claim_pubkey, h, amount_sat)
+    return resp, preimage, h
+
+
+def test_reverse_swap_funded(node_factory, swapd_factory):
+    user, swapper = setup_reverse(node_factory, swapd_factory)
+    resp, preimage, h = create_reverse_swap(swapper)
+
+    # The hold invoice is only settled once the user claims onchain, so the
+    # payment stays in-flight.
+    threading.Thread(
+        target=lambda: user.call("pay", {"bolt11": resp.payment_request}),
+        daemon=True,
+    ).start()
+
+    wait_for(lambda: swapper.internal_rpc.get_reverse_swap(h).status == "funded")
+    reverse_swap = swapper.internal_rpc.get_reverse_swap(h)
+    assert reverse_swap.address == resp.address
+    assert reverse_swap.amount_sat == 100_000
+    assert reverse_swap.funding_tx_id != ""
+
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: len(swapper.internal_rpc.get_reverse_swap(h).outputs) > 0)
+
+
+def test_reverse_swap_funding_fails(node_factory, swapd_factory):
+    user, swapper = setup_reverse(node_factory, swapd_factory)
+    resp, _, h = create_reverse_swap(swapper)
+
+    # Leave the swapper's wallet unable to fund the swap output.
+    swapper.lightning_node.rpc.send_all(user.bitcoin.rpc.getnewaddress())
+    swapper.lightning_node.bitcoin.generate_block(1)
+
+    threading.Thread(
+        target=lambda: user.call("pay", {"bolt11": resp.payment_request}),
+        daemon=True,
+    ).start()
+
+    # A failed send may still have been published, so the swap keeps looking
+    # for the funding tx rather than canceling right away.
+    wait_for(lambda: swapper.internal_rpc.get_reverse_swap(h).status == "funding")
+    swapper.lightning_node.bitcoin.generate_block(1)
+    time.sleep(2)
+    assert swapper.internal_rpc.get_reverse_swap(h).status == "funding"
+
+    # Once the user no longer has time to claim and no funding tx exists, the
+    # invoice is canceled, long before the htlcs expire.
+    height = swapper.lightning_node.bitcoin.rpc.getblockcount()
+    swapper.lightning_node.bitcoin.generate_block(
+        resp.lock_height - REVERSE_MIN_CLAIM_BLOCKS - height + 1
+    )
+    wait_for(lambda: swapper.internal_rpc.get_reverse_swap(h).status == "canceled")
+    reverse_swap = swapper.internal_rpc.get_reverse_swap(h)
+    assert not reverse_swap.HasField("funding_tx_id")
+    assert len(reverse_swap.outputs) == 0
+
+
+def test_reverse_swap_below_min_amount(node_factory, swapd_factory):
+    user, swapper = setup_reverse(node_factory, swapd_factory)
+    params = swapper.rpc.reverse_swap_parameters().parameters
+    try:
+        create_reverse_swap(swapper, amount_sat=params.min_amount_sat - 1)
+        assert False
+    except grpc._channel._InactiveRpcError as e:
+        assert e.code() == grpc.StatusCode.INVALID_ARGUMENT
+        assert e.details() == "amount is below min swap amount"
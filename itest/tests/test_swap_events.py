@@ -0,0 +1,142 @@
+from helpers import *
+from bitcoinutils.keys import PrivateKey
+import grpc
+import threading
+
+
+def collect_events(stream):
+    events = []
+
+    def run():
+        try:
+            for event in stream:
+                events.append(event)
+        except grpc.RpcError as e:
+            if e.code() != grpc.StatusCode.CANCELLED:
+                raise
+
+    thread = threading.Thread(target=run, daemon=True)
+    thread.start()
+    return events
+
+
+def event_types(events):
+    return [event.WhichOneof("event") for event in events]
+
+
+def test_swap_events(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(node_factory, swapd_factory)
+    address, payment_request, h, _, refund_privkey, _, _ = create_swap_extended(
+        user, swapper
+    )
+    public_stream = swapper.rpc.subscribe_swap_events(
+        address, *sign_ownership_proof(refund_privkey, address)
+    )
+    internal_stream = swapper.internal_rpc.subscribe_swap_events()
+    public_events = collect_events(public_stream)
+    internal_events = collect_events(internal_stream)
+
+    txid = user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: "deposit_confirmed" in event_types(public_events))
+
+    swapper.rpc.pay_swap(payment_request)
+    wait_for(lambda: "paid" in event_types(public_events))
+    wait_for(lambda: "claim_broadcast" in event_types(public_events))
+
+    swapper.lightning_node.bitcoin.generate_block(1)
+    wait_for(lambda: "output_spent" in event_types(public_events))
+    wait_for(lambda: "output_spent" in event_types(internal_events))
+
+    assert event_types(public_events) == [
+        "deposit_confirmed",
+        "paid",
+        "claim_broadcast",
+        "output_spent",
+    ]
+    assert all(event.address == address for event in public_events)
+    deposit = public_events[0].deposit_confirmed
+    assert deposit.outpoint.startswith(txid)
+    assert deposit.amount_sat == 100_000
+    claim_tx_id = public_events[2].claim_broadcast.tx_id
+    assert public_events[3].output_spent.spending_tx_id == claim_tx_id
+
+    swap_events = [event for event in internal_events if event.address == address]
+    assert event_types(swap_events) == event_types(public_events)
+    assert swap_events[1].paid.payment_hash == h
+
+    public_stream.cancel()
+    internal_stream.cancel()
+
+
+def assert_subscribe_fails(swapper, code, address, timestamp, signature):
+    try:
+        next(swapper.rpc.subscribe_swap_events(address, timestamp, signature))
+        assert False
+    except grpc.RpcError as e:
+        assert e.code() == code
+
+
+def test_swap_events_unknown_swap(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(node_factory, swapd_factory)
+    refund_privkey = PrivateKey()
+    address = user.bitcoin.rpc.getnewaddress("", "bech32m")
+    assert_subscribe_fails(
+        swapper,
+        grpc.StatusCode.UNAUTHENTICATED,
+        address,
+        *sign_ownership_proof(refund_privkey, address)
+    )
+
+
+def test_swap_events_invalid_ownership_proof(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(node_factory, swapd_factory)
+    address, _, _ = create_swap_no_invoice(user, swapper)
+    assert_subscribe_fails(
+        swapper,
+        grpc.StatusCode.UNAUTHENTICATED,
+        address,
+        *sign_ownership_proof(PrivateKey(), address)
+    )
+
+
+def test_swap_events_subscription_limit(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory,
+        swapd_factory,
+        swapd_opts={"max-event-subscriptions-per-ip": "2"},
+    )
+    address, _, _, _, refund_privkey, _, _ = create_swap_extended(user, swapper)
+
+    def subscribe():
+        return swapper.rpc.subscribe_swap_events(
+            address, *sign_ownership_proof(refund_privkey, address)
+        )
+
+    first = subscribe()
+    second = subscribe()
+    first.initial_metadata()
+    second.initial_metadata()
+    assert_subscribe_fails(
+        swapper,
+        grpc.StatusCode.RESOURCE_EXHAUSTED,
+        address,
+        *sign_ownership_proof(refund_privkey, address)
+    )
+
+    # Closing a stream makes room for a new subscription. The server notices
+    # the closed stream asynchronously, so retry until it is accepted.
+    first.cancel()
+
+    def subscribe_accepted():
+        stream = subscribe()
+        # Accepted streams send their headers without waiting for events,
+        # rejected ones end right away.
+        stream.initial_metadata()
+        if stream.done():
+            return False
+        stream.cancel()
+        return True
+
+    wait_for(subscribe_accepted)
+    second.cancel()
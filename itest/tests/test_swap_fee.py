@@ -0,0 +1,45 @@
+from helpers import *
+import grpc
+
+SWAP_FEE_OPTS = {
+    "swap-fee-base-sat": "1000",
+    "swap-fee-ppm": "10000",
+}
+
+
+def test_swap_fee_payout_success(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory, swapd_factory, swapd_opts=SWAP_FEE_OPTS
+    )
+    # 100_000 sat utxo, 1000 sat base fee, 1% proportional fee.
+    address, payment_request, h, preimage = create_swap(
+        user, swapper, amount=98_000_000
+    )
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+
+    swapper.rpc.pay_swap(payment_request)
+    wait_for(lambda: user.list_invoices(payment_hash=h)[0]["paid"])
+
+    attempts = swapper.internal_rpc.get_swap(address).payment_attempts
+    assert len(attempts) == 1
+    assert attempts[0].fee_sat == 2_000
+
+
+def test_swap_fee_full_amount_fails(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory, swapd_factory, swapd_opts=SWAP_FEE_OPTS
+    )
+    address, payment_request, h, preimage = create_swap(user, swapper)
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+
+    try:
+        swapper.rpc.pay_swap(payment_request)
+        assert False
+    except grpc._channel._InactiveRpcError as e:
+        assert e.details() == "confirmed utxo values don't match invoice value"
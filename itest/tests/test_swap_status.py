@@ -0,0 +1,119 @@
+from helpers import *
+from bitcoinutils.keys import PrivateKey
+import grpc
+import swap_pb2
+import time
+
+SWAP_FEE_OPTS = {
+    "swap-fee-base-sat": "1000",
+    "swap-fee-ppm": "10000",
+}
+
+
+def test_swap_status(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory, swapd_factory, swapd_opts=SWAP_FEE_OPTS
+    )
+    # 100_000 sat utxo, 1000 sat base fee, 1% proportional fee.
+    address, payment_request, h, _, refund_privkey, _, _ = create_swap_extended(
+        user, swapper, amount=98_000_000
+    )
+
+    status = swapper.rpc.get_swap_status(
+        address, *sign_ownership_proof(refund_privkey, address)
+    )
+    assert len(status.outputs) == 0
+    assert not status.payable
+    assert status.not_payable_reason == swap_pb2.NO_OUTPUTS
+    assert not status.HasField("blocks_until_refund")
+    assert status.payout_state == swap_pb2.NOT_STARTED
+
+    txid = user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+
+    status = swapper.rpc.get_swap_status(
+        address, *sign_ownership_proof(refund_privkey, address)
+    )
+    assert len(status.outputs) == 1
+    assert status.outputs[0].outpoint.startswith(txid)
+    assert status.outputs[0].amount_sat == 100_000
+    assert status.outputs[0].confirmations == 1
+    assert status.payable
+    assert status.payable_amount_sat == 98_000
+    assert status.fee_sat == 2_000
+    assert status.HasField("blocks_until_refund")
+
+    swapper.rpc.pay_swap(payment_request)
+    wait_for(lambda: user.list_invoices(payment_hash=h)[0]["paid"])
+
+    status = swapper.rpc.get_swap_status(
+        address, *sign_ownership_proof(refund_privkey, address)
+    )
+    assert not status.payable
+    assert status.not_payable_reason == swap_pb2.ALREADY_PAID
+    assert status.payout_state == swap_pb2.SUCCEEDED
+
+
+def test_swap_status_claim_fee_quote(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory,
+        swapd_factory,
+        swapd_opts={"swap-fee-include-claim-fee": None},
+    )
+    address, preimage, h, refund_privkey, _, _ = create_swap_no_invoice_extended(
+        user, swapper
+    )
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+
+    status = swapper.rpc.get_swap_status(
+        address, *sign_ownership_proof(refund_privkey, address)
+    )
+    assert status.payable
+    assert status.fee_sat > 0
+    assert status.HasField("claim_fee_quote_expiry")
+    assert 0 < status.claim_fee_quote_expiry - time.time() <= 600
+
+    # The quote is kept, so the amount doesn't move while it is valid.
+    again = swapper.rpc.get_swap_status(
+        address, *sign_ownership_proof(refund_privkey, address)
+    )
+    assert again.payable_amount_sat == status.payable_amount_sat
+    assert again.claim_fee_quote_expiry == status.claim_fee_quote_expiry
+
+    payment_request = user.create_invoice(
+        status.payable_amount_sat * 1000,
+        description="test",
+        preimage=preimage,
+    )
+    swapper.rpc.pay_swap(payment_request)
+    wait_for(lambda: user.list_invoices(payment_hash=h)[0]["paid"])
+
+
+def test_swap_status_invalid_proof(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(node_factory, swapd_factory)
+    address, _, _, _, refund_privkey, _, _ = create_swap_extended(user, swapper)
+
+    def assert_unauthenticated(address, timestamp, signature):
+        try:
+            swapper.rpc.get_swap_status(address, timestamp, signature)
+            assert False
+        except grpc.RpcError as e:
+            assert e.code() == grpc.StatusCode.UNAUTHENTICATED
+
+    # Signed by another key.
+    assert_unauthenticated(address, *sign_ownership_proof(PrivateKey(), address))
+
+    # Timestamp too far in the past.
+    timestamp, signature = sign_ownership_proof(
+        refund_privkey, address, int(time.time()) - 3600
+    )
+    assert_unauthenticated(address, timestamp, signature)
+
+    # Unknown swap.
+    unknown_address = user.bitcoin.rpc.getnewaddress("", "bech32m")
+    assert_unauthenticated(
+        unknown_address, *sign_ownership_proof(refund_privkey, unknown_address)
+    )
@@ -0,0 +1,55 @@
+from helpers import *
+from certs import new_cert, new_key, pem_cert, pem_key
+from health_pb2 import HealthCheckRequest, HealthCheckResponse
+from health_pb2_grpc import HealthStub
+import grpc
+import pytest
+
+
+def new_server_certs():
+    ca_key = new_key()
+    ca_cert = new_cert(ca_key, "swapd ca")
+    server_key = new_key()
+    server_cert = new_cert(server_key, "swapd", ca_cert, ca_key, ip="127.0.0.1")
+    return ca_cert, server_cert, server_key
+
+
+def check(port, ca_cert):
+    credentials = grpc.ssl_channel_credentials(root_certificates=pem_cert(ca_cert))
+    channel = grpc.secure_channel("127.0.0.1:{}".format(port), credentials)
+    return HealthStub(channel).Check(HealthCheckRequest()).status
+
+
+def test_tls_cert_reload(node_factory, swapd_factory, tmp_path):
+    cert_path = tmp_path / "server.pem"
+    key_path = tmp_path / "server-key.pem"
+    ca_cert, server_cert, server_key = new_server_certs()
+    cert_path.write_bytes(pem_cert(server_cert))
+    key_path.write_bytes(pem_key(server_key))
+
+    swapper = swapd_factory.get_swapd(
+        options={
+            "tls-cert": str(cert_path),
+            "tls-key": str(key_path),
+            "tls-reload-interval-seconds": "1",
+            "connection-check-interval-seconds": "1",
+        }
+    )
+    wait_for(lambda: check(swapper.grpc_port, ca_cert) == HealthCheckResponse.SERVING)
+
+    # Plaintext connections are refused.
+    channel = grpc.insecure_channel("127.0.0.1:{}".format(swapper.grpc_port))
+    with pytest.raises(grpc.RpcError) as e:
+        HealthStub(channel).Check(HealthCheckRequest())
+    assert e.value.code() == grpc.StatusCode.UNAVAILABLE
+
+    # Rotate to a certificate from another CA, without restarting swapd.
+    new_ca_cert, new_server_cert, new_server_key = new_server_certs()
+    cert_path.write_bytes(pem_cert(new_server_cert))
+    key_path.write_bytes(pem_key(new_server_key))
+    swapper.daemon.wait_for_log("reloaded tls certificate")
+
+    assert check(swapper.grpc_port, new_ca_cert) == HealthCheckResponse.SERVING
+    with pytest.raises(grpc.RpcError) as e:
+        check(swapper.grpc_port, ca_cert)
+    assert e.value.code() == grpc.StatusCode.UNAVAILABLE
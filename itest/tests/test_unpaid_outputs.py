@@ -0,0 +1,58 @@
+from helpers import *
+import grpc
+import pytest
+import swap_internal_pb2
+
+
+def test_unpaid_output_claimed_when_donated(node_factory, swapd_factory):
+    user, swapper = setup_user_and_swapper(
+        node_factory,
+        swapd_factory,
+        swapd_opts={"unpaid-output-poll-interval-seconds": "1"},
+    )
+    address, payment_request, h, _ = create_swap(user, swapper)
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+
+    swapper.rpc.pay_swap(payment_request)
+    wait_for(lambda: user.list_invoices(payment_hash=h)[0]["paid"])
+    swapper.lightning_node.bitcoin.generate_block(1)
+    wait_for(lambda: swapper.lightning_node.bitcoin.rpc.getmempoolinfo()["size"] == 1)
+    swapper.lightning_node.bitcoin.generate_block(1)
+
+    # A second deposit after the swap was paid is not paid over lightning.
+    txid = user.bitcoin.rpc.sendtoaddress(address, 50_000 / 10**8)
+    user.bitcoin.generate_block(1)
+    wait_for(lambda: len(swapper.internal_rpc.list_unpaid_outputs().outputs) == 1)
+    output = swapper.internal_rpc.list_unpaid_outputs().outputs[0]
+    assert output.outpoint.startswith(txid)
+    assert output.address == address
+    assert output.amount_sat == 50_000
+    assert not output.HasField("disposition")
+
+    # The unpaid output is not claimed.
+    swapper.lightning_node.bitcoin.generate_block(1)
+    time.sleep(1)
+    assert swapper.lightning_node.bitcoin.rpc.getmempoolinfo()["size"] == 0
+
+    swapper.internal_rpc.set_output_disposition(
+        output.outpoint, swap_internal_pb2.DONATED
+    )
+    output = swapper.internal_rpc.list_unpaid_outputs().outputs[0]
+    assert output.disposition == "donated"
+
+    swapper.lightning_node.bitcoin.generate_block(1)
+    wait_for(lambda: swapper.lightning_node.bitcoin.rpc.getmempoolinfo()["size"] == 1)
+    claim_txid = swapper.lightning_node.bitcoin.rpc.getrawmempool()[0]
+    claim_tx = swapper.lightning_node.bitcoin.rpc.getrawtransaction(claim_txid, True)
+    assert claim_tx["vin"][0]["txid"] == txid
+
+
+def test_set_output_disposition_unknown_output(node_factory, swapd_factory):
+    _, swapper = setup_user_and_swapper(node_factory, swapd_factory)
+    with pytest.raises(grpc.RpcError) as e:
+        swapper.internal_rpc.set_output_disposition(
+            "00" * 32 + ":0", swap_internal_pb2.ABANDONED
+        )
+    assert e.value.code() == grpc.StatusCode.NOT_FOUND
@@ -0,0 +1,32 @@
+from helpers import *
+
+
+def test_zmq_block_notifications(node_factory, swapd_factory):
+    # Poll so rarely that only block notifications can trigger a sync or claim.
+    user, swapper = setup_user_and_swapper(
+        node_factory,
+        swapd_factory,
+        swapd_opts={
+            "bitcoind-zmq-block-address": "tcp://127.0.0.1:{}".format(
+                swapd_factory.bitcoind.blockport
+            ),
+            "chain-poll-interval-seconds": "3600",
+            "claim-poll-interval-seconds": "3600",
+        },
+    )
+    address, payment_request, h, preimage = create_swap(user, swapper)
+    user.bitcoin.rpc.sendtoaddress(address, 100_000 / 10**8)
+    user.bitcoin.generate_block(1)
+
+    wait_for(lambda: len(swapper.internal_rpc.get_swap(address).outputs) > 0)
+
+    swapper.rpc.pay_swap(payment_request)
+    wait_for(lambda: user.list_invoices(payment_hash=h)[0]["paid"])
+
+    # The claim monitor wakes up after the chain monitor processed the block.
+    swapper.lightning_node.bitcoin.generate_block(1)
+    wait_for(lambda: swapper.lightning_node.bitcoin.rpc.getmempoolinfo()["size"] == 1)
+    swapper.lightning_node.bitcoin.generate_block(1)
+    wait_for(
+        lambda: swapper.internal_rpc.get_swap(address).outputs[0].HasField("spend")
+    )
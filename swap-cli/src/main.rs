@@ -2,32 +2,78 @@ use std::{
     fs::File,
     io::{BufRead, BufReader},
     path::PathBuf,
+    str::FromStr,
 };
 
+use bitcoin::{
+    bip32::{DerivationPath, Xpriv},
+    secp256k1::Secp256k1,
+};
 use clap::{Parser, Subcommand};
 use internal_swap_api::{
     swap_manager_client::SwapManagerClient, AddAddressFiltersRequest, GetInfoRequest,
-    GetSwapRequest, ListClaimableRequest,
+    GetReverseSwapRequest, GetSwapRequest, ListClaimableRequest, ListUnpaidOutputsRequest,
+    OutputDisposition, ReencryptClaimKeysRequest, RescanRequest, SetOutputDispositionRequest,
+    SubscribeSwapEventsRequest,
 };
 use tonic::{
-    transport::{Channel, Uri},
-    Request,
+    metadata::{Ascii, MetadataValue},
+    service::{interceptor::InterceptedService, Interceptor},
+    transport::{Certificate, Channel, ClientTlsConfig, Identity, Uri},
+    Request, Status,
 };
 
 mod internal_swap_api {
     tonic::include_proto!("swap_internal");
 }
 
+/// Derivation path prefix of swap claim keys. Must match the path swapd
+/// derives claim keys at.
+const CLAIM_KEY_DERIVATION_PATH: &str = "m/1984'/0'";
+const REVERSE_REFUND_KEY_DERIVATION_PATH: &str = "m/1984'/1'";
+
+type Client = SwapManagerClient<InterceptedService<Channel, TokenInterceptor>>;
+
 fn parse_hex(s: &str) -> Result<Vec<u8>, hex::FromHexError> {
     hex::decode(s)
 }
 
+fn parse_disposition(s: &str) -> Result<OutputDisposition, String> {
+    OutputDisposition::from_str_name(&s.to_uppercase()).ok_or(format!(
+        "invalid disposition '{}', expected donated or abandoned",
+        s
+    ))
+}
+
 #[derive(Parser)]
 struct Args {
     /// Address to the internal grpc server.
     #[arg(long, default_value = "http://127.0.0.1:58050")]
     pub grpc_uri: Uri,
 
+    /// CA cert to verify the certificate of the internal grpc server with.
+    /// Enables TLS. Use an https grpc_uri with TLS.
+    #[arg(long)]
+    pub tls_ca_cert: Option<PathBuf>,
+
+    /// Client certificate, for when swapd verifies client certificates.
+    #[arg(long)]
+    pub tls_client_cert: Option<PathBuf>,
+
+    /// Private key of tls_client_cert.
+    #[arg(long)]
+    pub tls_client_key: Option<PathBuf>,
+
+    /// Domain name to verify the server certificate against, if it differs
+    /// from the host in grpc_uri.
+    #[arg(long)]
+    pub tls_domain_name: Option<String>,
+
+    /// Admin or read-only token of the internal grpc server, or a path to a
+    /// file containing it.
+    #[arg(long)]
+    pub token: Option<String>,
+
     #[command(subcommand)]
     command: Command,
 }
@@ -40,6 +86,10 @@ enum Command {
         command: AddressFiltersCommand,
     },
     GetInfo,
+    GetReverseSwap {
+        #[clap(long, value_parser(parse_hex))]
+        hash: Vec<u8>,
+    },
     GetSwap {
         #[clap(long)]
         address: Option<String>,
@@ -49,6 +99,56 @@ enum Command {
         hash: Option<Vec<u8>>,
     },
     ListClaimable,
+    /// Lists outputs of paid swaps that were not paid over lightning.
+    ListUnpaidOutputs,
+    /// Re-derives the claim key of a swap from the master key swapd was
+    /// configured with. Works offline, without connecting to swapd.
+    RecoverClaimKey {
+        /// Master extended private key, or a path to a file containing it.
+        #[clap(long)]
+        xprv: String,
+        /// Claim key index of the swap, as returned by get-swap.
+        #[clap(long)]
+        index: u32,
+        /// Derive the refund key of a reverse swap instead, for the refund
+        /// key index returned by get-reverse-swap.
+        #[clap(long)]
+        reverse_refund: bool,
+    },
+    /// Re-encrypts all claim keys and reverse swap refund keys that are not
+    /// encrypted with the current claim key encryption key. Safe to run
+    /// while swapd is running.
+    ReencryptClaimKeys {
+        /// Number of keys to re-encrypt per database query.
+        #[clap(long)]
+        batch_size: Option<u32>,
+    },
+    /// Processes the chain again from the given height, to pick up deposits
+    /// and spends that were missed. Waits for the rescan to finish.
+    Rescan {
+        /// Height of the first block to rescan.
+        #[clap(long)]
+        from_height: u64,
+        /// Only rescan for this watched address, or the address of an
+        /// expired swap, which is watched again. Can be repeated. Rescans
+        /// all watched addresses if not set.
+        #[clap(long)]
+        address: Vec<String>,
+    },
+    /// Decides what happens to an output that was not paid over lightning.
+    /// Donated outputs are claimed, abandoned outputs are left for the user to
+    /// refund.
+    SetOutputDisposition {
+        /// Outpoint of the unpaid output, as txid:vout.
+        #[clap(long)]
+        outpoint: String,
+        /// Either donated or abandoned.
+        #[clap(long, value_parser(parse_disposition))]
+        disposition: OutputDisposition,
+    },
+    /// Prints swap events as they happen, one json object per line, until
+    /// interrupted.
+    SubscribeSwapEvents,
 }
 
 #[derive(Subcommand)]
@@ -64,7 +164,16 @@ enum AddressFiltersCommand {
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
-    let mut client = SwapManagerClient::connect(args.grpc_uri).await?;
+    if let Command::RecoverClaimKey {
+        xprv,
+        index,
+        reverse_refund,
+    } = &args.command
+    {
+        return recover_claim_key(xprv, *index, *reverse_refund);
+    }
+
+    let mut client = connect(&args).await?;
 
     match args.command {
         Command::AddressFilters { command } => {
@@ -77,6 +186,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .into_inner();
             println!("{}", serde_json::to_string_pretty(&resp)?);
         }
+        Command::GetReverseSwap { hash } => {
+            let resp = client
+                .get_reverse_swap(Request::new(GetReverseSwapRequest { payment_hash: hash }))
+                .await?
+                .into_inner();
+            println!("{}", serde_json::to_string_pretty(&resp)?);
+        }
         Command::GetSwap {
             address,
             payment_request,
@@ -99,17 +215,150 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .into_inner();
             println!("{}", serde_json::to_string_pretty(&resp)?)
         }
+        Command::ListUnpaidOutputs => {
+            let resp = client
+                .list_unpaid_outputs(Request::new(ListUnpaidOutputsRequest::default()))
+                .await?
+                .into_inner();
+            println!("{}", serde_json::to_string_pretty(&resp)?)
+        }
+        Command::RecoverClaimKey { .. } => unreachable!("handled without a connection"),
+        Command::ReencryptClaimKeys { batch_size } => {
+            let resp = client
+                .reencrypt_claim_keys(Request::new(ReencryptClaimKeysRequest { batch_size }))
+                .await?
+                .into_inner();
+            println!("{}", serde_json::to_string_pretty(&resp)?)
+        }
+        Command::Rescan {
+            from_height,
+            address,
+        } => {
+            let resp = client
+                .rescan(Request::new(RescanRequest {
+                    from_height,
+                    addresses: address,
+                }))
+                .await?
+                .into_inner();
+            println!("{}", serde_json::to_string_pretty(&resp)?)
+        }
+        Command::SetOutputDisposition {
+            outpoint,
+            disposition,
+        } => {
+            let resp = client
+                .set_output_disposition(Request::new(SetOutputDispositionRequest {
+                    outpoint,
+                    disposition: disposition.into(),
+                }))
+                .await?
+                .into_inner();
+            println!("{}", serde_json::to_string_pretty(&resp)?)
+        }
+        Command::SubscribeSwapEvents => {
+            let mut stream = client
+                .subscribe_swap_events(Request::new(SubscribeSwapEventsRequest::default()))
+                .await?
+                .into_inner();
+            while let Some(event) = stream.message().await? {
+                println!("{}", serde_json::to_string(&event)?);
+            }
+        }
     }
 
     Ok(())
 }
 
+/// Reads the file at `value` if it exists, otherwise returns `value` itself.
+fn read_value_or_file(value: &str) -> String {
+    match std::fs::read_to_string(value) {
+        Ok(content) => content,
+        Err(_) => value.to_string(),
+    }
+}
+
+async fn connect(args: &Args) -> Result<Client, Box<dyn std::error::Error>> {
+    let mut endpoint = Channel::builder(args.grpc_uri.clone());
+    if args.tls_ca_cert.is_some() || args.tls_client_cert.is_some() {
+        let mut tls_config = ClientTlsConfig::new();
+        if let Some(ca_cert) = &args.tls_ca_cert {
+            tls_config = tls_config.ca_certificate(Certificate::from_pem(std::fs::read(ca_cert)?));
+        }
+        match (&args.tls_client_cert, &args.tls_client_key) {
+            (Some(cert), Some(key)) => {
+                tls_config = tls_config.identity(Identity::from_pem(
+                    std::fs::read(cert)?,
+                    std::fs::read(key)?,
+                ))
+            }
+            (None, None) => {}
+            _ => return Err("tls_client_cert and tls_client_key must be set together".into()),
+        }
+        if let Some(domain_name) = &args.tls_domain_name {
+            tls_config = tls_config.domain_name(domain_name);
+        }
+        endpoint = endpoint.tls_config(tls_config)?;
+    }
+
+    let channel = endpoint.connect().await?;
+    let authorization = match &args.token {
+        Some(token) => Some(format!("Bearer {}", read_value_or_file(token).trim()).parse()?),
+        None => None,
+    };
+    Ok(SwapManagerClient::with_interceptor(
+        channel,
+        TokenInterceptor { authorization },
+    ))
+}
+
+/// Passes the token to swapd in the authorization header of every call.
+#[derive(Clone)]
+struct TokenInterceptor {
+    authorization: Option<MetadataValue<Ascii>>,
+}
+
+impl Interceptor for TokenInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        if let Some(authorization) = &self.authorization {
+            request
+                .metadata_mut()
+                .insert("authorization", authorization.clone());
+        }
+        Ok(request)
+    }
+}
+
+fn recover_claim_key(
+    xprv: &str,
+    index: u32,
+    reverse_refund: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let xprv = read_value_or_file(xprv);
+    let master_key = Xpriv::from_str(xprv.trim())?;
+    let (prefix, name) = match reverse_refund {
+        true => (REVERSE_REFUND_KEY_DERIVATION_PATH, "refund"),
+        false => (CLAIM_KEY_DERIVATION_PATH, "claim"),
+    };
+    let path = DerivationPath::from_str(&format!("{}/{}'", prefix, index))?;
+    let secp = Secp256k1::new();
+    let key = master_key.derive_priv(&secp, &path)?;
+    let output = serde_json::json!({
+        "index": index,
+        "path": path.to_string(),
+        format!("{}_privkey", name): hex::encode(key.private_key.secret_bytes()),
+        format!("{}_pubkey", name): key.private_key.public_key(&secp).to_string(),
+    });
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(())
+}
+
 struct AddressFilterHandler {
-    client: SwapManagerClient<Channel>,
+    client: Client,
 }
 
 impl AddressFilterHandler {
-    fn new(client: SwapManagerClient<Channel>) -> Self {
+    fn new(client: Client) -> Self {
         Self { client }
     }
 
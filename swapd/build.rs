@@ -16,6 +16,12 @@ fn main() {
         )
         .unwrap();
 
+    tonic_build::configure()
+        .emit_rerun_if_changed(true)
+        .build_server(true)
+        .compile_protos(&["proto/health/health.proto"], &["proto/health"])
+        .unwrap();
+
     tonic_build::configure()
         .emit_rerun_if_changed(true)
         .build_client(true)
@@ -26,7 +32,13 @@ fn main() {
         .emit_rerun_if_changed(true)
         .build_client(true)
         .compile_protos(
-            &["proto/lnd/lightning.proto", "proto/lnd/router.proto"],
+            &[
+                "proto/lnd/invoices.proto",
+                "proto/lnd/lightning.proto",
+                "proto/lnd/router.proto",
+                "proto/lnd/signer.proto",
+                "proto/lnd/walletkit.proto",
+            ],
             &["proto/lnd"],
         )
         .unwrap();
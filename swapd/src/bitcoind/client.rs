@@ -12,14 +12,21 @@ use thiserror::Error;
 use tokio::sync::Mutex;
 use tracing::trace;
 
-use crate::chain::{BlockHeader, BroadcastError, ChainClient, ChainError};
+use crate::{
+    chain::{BlockHeader, BroadcastError, ChainClient, ChainError},
+    mempool::MempoolClient,
+};
 
 use super::{
     EstimateSmartFeeResponse, GetBestBlockHashResponse, GetBlockCountResponse,
-    GetBlockHeaderResponse, GetBlockResponse, GetRawTransactionResponse, RpcError, RpcRequest,
-    RpcServerMessage, RpcServerMessageBody, SendRawTransactionResponse,
+    GetBlockHashResponse, GetBlockHeaderResponse, GetBlockResponse, GetMempoolEntryResponse,
+    GetRawMempoolResponse, GetRawTransactionResponse, RpcError, RpcRequest, RpcServerMessage,
+    RpcServerMessageBody, SendRawTransactionResponse,
 };
 
+/// Returned by bitcoind if a transaction is not found.
+const RPC_INVALID_ADDRESS_OR_KEY: i64 = -5;
+
 #[derive(Debug)]
 pub struct BitcoindClient {
     address: String,
@@ -145,6 +152,21 @@ impl BitcoindClient {
         )
     }
 
+    async fn getblockhash(&self, height: u64) -> Result<GetBlockHashResponse, CallError> {
+        Ok(
+            match self
+                .call(
+                    "getblockhash",
+                    Value::Array(vec![Value::Number(height.into())]),
+                )
+                .await
+            {
+                Ok(v) => v,
+                Err(e) => return Err(e),
+            },
+        )
+    }
+
     async fn getblockcount(&self) -> Result<GetBlockCountResponse, CallError> {
         Ok(
             match self.call("getblockcount", Value::Array(Vec::new())).await {
@@ -172,6 +194,27 @@ impl BitcoindClient {
         )
     }
 
+    async fn getmempoolentry(&self, txid: String) -> Result<GetMempoolEntryResponse, CallError> {
+        Ok(
+            match self
+                .call("getmempoolentry", Value::Array(vec![Value::String(txid)]))
+                .await
+            {
+                Ok(v) => v,
+                Err(e) => return Err(e),
+            },
+        )
+    }
+
+    async fn getrawmempool(&self) -> Result<GetRawMempoolResponse, CallError> {
+        Ok(
+            match self.call("getrawmempool", Value::Array(Vec::new())).await {
+                Ok(v) => v,
+                Err(e) => return Err(e),
+            },
+        )
+    }
+
     async fn getrawtransaction(
         &self,
         txid: String,
@@ -227,6 +270,11 @@ impl ChainClient for BitcoindClient {
         Ok(deserialize(&raw)?)
     }
 
+    async fn get_block_hash(&self, height: u64) -> Result<BlockHash, ChainError> {
+        let hex = self.getblockhash(height).await?.hex;
+        Ok(BlockHash::from_raw_hash(sha256d::Hash::from_str(&hex)?))
+    }
+
     async fn get_block_header(&self, hash: &BlockHash) -> Result<BlockHeader, ChainError> {
         let resp = self.getblockheader(hash.to_string()).await?;
         Ok(BlockHeader {
@@ -264,13 +312,47 @@ impl ChainClient for BitcoindClient {
 
     async fn get_transaction(&self, tx_id: &Txid) -> Result<Transaction, ChainError> {
         let tx = self.getrawtransaction(tx_id.to_string()).await?;
-        let tx = hex::decode(tx.str)
-            .map_err(|e| ChainError::General(format!("invalid tx hex: {:?}", e).into()))?;
-        let tx = Transaction::consensus_decode(&mut &tx[..])?;
-        Ok(tx)
+        decode_tx(tx.str)
     }
 }
 
+#[async_trait::async_trait]
+impl MempoolClient for BitcoindClient {
+    async fn get_mempool_txids(&self) -> Result<Vec<Txid>, ChainError> {
+        self.getrawmempool()
+            .await?
+            .tx_ids
+            .iter()
+            .map(|tx_id| Ok(tx_id.parse()?))
+            .collect()
+    }
+
+    async fn get_mempool_transaction(
+        &self,
+        tx_id: &Txid,
+    ) -> Result<Option<Transaction>, ChainError> {
+        match self.getrawtransaction(tx_id.to_string()).await {
+            Ok(tx) => Ok(Some(decode_tx(tx.str)?)),
+            Err(CallError::RpcError(e)) if e.code == RPC_INVALID_ADDRESS_OR_KEY => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn is_replaceable(&self, tx_id: &Txid) -> Result<Option<bool>, ChainError> {
+        match self.getmempoolentry(tx_id.to_string()).await {
+            Ok(entry) => Ok(Some(entry.bip125_replaceable)),
+            Err(CallError::RpcError(e)) if e.code == RPC_INVALID_ADDRESS_OR_KEY => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+fn decode_tx(hex: String) -> Result<Transaction, ChainError> {
+    let tx = hex::decode(hex)
+        .map_err(|e| ChainError::General(format!("invalid tx hex: {:?}", e).into()))?;
+    Ok(Transaction::consensus_decode(&mut &tx[..])?)
+}
+
 impl From<bitcoin::consensus::encode::Error> for ChainError {
     fn from(value: bitcoin::consensus::encode::Error) -> Self {
         ChainError::General(Box::new(value))
@@ -20,6 +20,21 @@ impl<'de> Deserialize<'de> for GetBestBlockHashResponse {
     }
 }
 
+pub struct GetBlockHashResponse {
+    pub hex: String,
+}
+
+impl<'de> Deserialize<'de> for GetBlockHashResponse {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(GetBlockHashResponse {
+            hex: Deserialize::deserialize(deserializer)?,
+        })
+    }
+}
+
 #[derive(Deserialize)]
 pub struct GetBlockHeaderResponse {
     pub hash: String,
@@ -57,6 +72,27 @@ impl<'de> Deserialize<'de> for GetBlockCountResponse {
     }
 }
 
+#[derive(Deserialize)]
+pub struct GetMempoolEntryResponse {
+    #[serde(rename = "bip125-replaceable")]
+    pub bip125_replaceable: bool,
+}
+
+pub struct GetRawMempoolResponse {
+    pub tx_ids: Vec<String>,
+}
+
+impl<'de> Deserialize<'de> for GetRawMempoolResponse {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(GetRawMempoolResponse {
+            tx_ids: Deserialize::deserialize(deserializer)?,
+        })
+    }
+}
+
 pub struct GetRawTransactionResponse {
     pub str: String,
 }
@@ -2,8 +2,10 @@ mod client;
 mod fee_estimator;
 mod jsonrpc;
 mod messages;
+mod zmq;
 
 pub use client::BitcoindClient;
 pub use fee_estimator::FeeEstimator;
-use jsonrpc::*;
+pub(crate) use jsonrpc::*;
 use messages::*;
+pub use zmq::{ZmqBlockListener, ZmqTxListener};
@@ -0,0 +1,243 @@
+use std::{sync::Arc, time::Duration};
+
+use bitcoin::{
+    block::Header,
+    consensus::{deserialize, deserialize_partial},
+    hashes::Hash,
+    BlockHash, Transaction,
+};
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, field, info, trace, warn};
+use zeromq::{Socket, SocketRecv, SubSocket, ZmqError, ZmqMessage};
+
+use crate::chain::BlockNotifier;
+
+const TOPIC_HASHBLOCK: &str = "hashblock";
+const TOPIC_RAWBLOCK: &str = "rawblock";
+const TOPIC_RAWTX: &str = "rawtx";
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Listens to bitcoind's `zmqpubhashblock` or `zmqpubrawblock` notifications
+/// and forwards new blocks to a `BlockNotifier`. If the connection drops, it is
+/// reestablished after `reconnect_interval`.
+pub struct ZmqBlockListener {
+    address: String,
+    block_notifier: Arc<BlockNotifier>,
+    reconnect_interval: Duration,
+}
+
+impl ZmqBlockListener {
+    pub fn new(address: String, block_notifier: Arc<BlockNotifier>) -> Self {
+        Self {
+            address,
+            block_notifier,
+            reconnect_interval: RECONNECT_INTERVAL,
+        }
+    }
+
+    pub async fn start(&self, token: CancellationToken) -> Result<(), ZmqError> {
+        subscribe(
+            &self.address,
+            &[TOPIC_HASHBLOCK, TOPIC_RAWBLOCK],
+            self.reconnect_interval,
+            &token,
+            |message| match parse_block_hash(message) {
+                Some(hash) => {
+                    trace!("got zmq block notification for block {}", hash);
+                    self.block_notifier.notify(hash);
+                }
+                None => debug!("ignoring unrecognized zmq message"),
+            },
+        )
+        .await
+    }
+}
+
+/// Listens to bitcoind's `zmqpubrawtx` notifications and forwards the
+/// transactions to `sender`. Note bitcoind also notifies the transactions of
+/// connected blocks. If the connection drops, it is reestablished after
+/// `reconnect_interval`.
+pub struct ZmqTxListener {
+    address: String,
+    sender: broadcast::Sender<Transaction>,
+    reconnect_interval: Duration,
+}
+
+impl ZmqTxListener {
+    pub fn new(address: String, sender: broadcast::Sender<Transaction>) -> Self {
+        Self {
+            address,
+            sender,
+            reconnect_interval: RECONNECT_INTERVAL,
+        }
+    }
+
+    pub async fn start(&self, token: CancellationToken) -> Result<(), ZmqError> {
+        subscribe(
+            &self.address,
+            &[TOPIC_RAWTX],
+            self.reconnect_interval,
+            &token,
+            |message| match parse_tx(message) {
+                Some(tx) => {
+                    trace!("got zmq tx notification for tx {}", tx.compute_txid());
+                    // Sending only fails if there are no subscribers.
+                    let _ = self.sender.send(tx);
+                }
+                None => debug!("ignoring unrecognized zmq message"),
+            },
+        )
+        .await
+    }
+}
+
+/// Subscribes to `topics` and passes every message to `handle`, reconnecting
+/// after `reconnect_interval` if the subscription fails.
+async fn subscribe<F>(
+    address: &str,
+    topics: &[&str],
+    reconnect_interval: Duration,
+    token: &CancellationToken,
+    mut handle: F,
+) -> Result<(), ZmqError>
+where
+    F: FnMut(&ZmqMessage),
+{
+    loop {
+        if token.is_cancelled() {
+            return Ok(());
+        }
+
+        if let Err(e) = listen(address, topics, token, &mut handle).await {
+            warn!(
+                address = field::display(address),
+                "zmq subscription failed: {:?}, reconnecting", e
+            );
+        }
+
+        tokio::select! {
+            _ = token.cancelled() => {
+                debug!("zmq listener shutting down");
+                break;
+            }
+            _ = tokio::time::sleep(reconnect_interval) => {}
+        }
+    }
+
+    Ok(())
+}
+
+async fn listen<F>(
+    address: &str,
+    topics: &[&str],
+    token: &CancellationToken,
+    handle: &mut F,
+) -> Result<(), ZmqError>
+where
+    F: FnMut(&ZmqMessage),
+{
+    let mut socket = SubSocket::new();
+    socket.connect(address).await?;
+    for topic in topics {
+        socket.subscribe(topic).await?;
+    }
+    info!(
+        address = field::display(address),
+        "subscribed to zmq {} notifications",
+        topics.join(", ")
+    );
+
+    loop {
+        let message = tokio::select! {
+            _ = token.cancelled() => return Ok(()),
+            message = socket.recv() => message?,
+        };
+
+        handle(&message);
+    }
+}
+
+/// Extracts the block hash from a `hashblock` or `rawblock` message. Messages
+/// consist of the topic, the body and a sequence number.
+fn parse_block_hash(message: &ZmqMessage) -> Option<BlockHash> {
+    let topic = message.get(0)?;
+    let body = message.get(1)?;
+    if topic == TOPIC_HASHBLOCK.as_bytes() {
+        // The block hash is sent in rpc byte order, which is reversed.
+        let mut hash: [u8; 32] = body.as_ref().try_into().ok()?;
+        hash.reverse();
+        return Some(BlockHash::from_byte_array(hash));
+    }
+
+    if topic == TOPIC_RAWBLOCK.as_bytes() {
+        let (header, _): (Header, usize) = deserialize_partial(body).ok()?;
+        return Some(header.block_hash());
+    }
+
+    None
+}
+
+/// Extracts the transaction from a `rawtx` message.
+fn parse_tx(message: &ZmqMessage) -> Option<Transaction> {
+    let topic = message.get(0)?;
+    let body = message.get(1)?;
+    if topic != TOPIC_RAWTX.as_bytes() {
+        return None;
+    }
+
+    deserialize(body).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        blockdata::constants::genesis_block, consensus::serialize, hashes::Hash, Network,
+    };
+    use zeromq::ZmqMessage;
+
+    use super::{parse_block_hash, parse_tx};
+
+    fn message(topic: &'static [u8], body: Vec<u8>) -> ZmqMessage {
+        let mut message = ZmqMessage::from(topic.to_vec());
+        message.push_back(body.into());
+        message.push_back(vec![0u8; 4].into());
+        message
+    }
+
+    #[test]
+    fn test_parse_hashblock() {
+        let block = genesis_block(Network::Regtest);
+        let mut body = block.block_hash().to_byte_array().to_vec();
+        body.reverse();
+        let hash = parse_block_hash(&message(b"hashblock", body));
+        assert_eq!(hash, Some(block.block_hash()));
+    }
+
+    #[test]
+    fn test_parse_rawblock() {
+        let block = genesis_block(Network::Regtest);
+        let hash = parse_block_hash(&message(b"rawblock", serialize(&block)));
+        assert_eq!(hash, Some(block.block_hash()));
+    }
+
+    #[test]
+    fn test_parse_unknown_topic() {
+        let hash = parse_block_hash(&message(b"rawtx", vec![0u8; 32]));
+        assert_eq!(hash, None);
+    }
+
+    #[test]
+    fn test_parse_rawtx() {
+        let tx = genesis_block(Network::Regtest).txdata[0].clone();
+        let parsed = parse_tx(&message(b"rawtx", serialize(&tx)));
+        assert_eq!(parsed, Some(tx));
+    }
+
+    #[test]
+    fn test_parse_rawtx_wrong_topic() {
+        let tx = genesis_block(Network::Regtest).txdata[0].clone();
+        let parsed = parse_tx(&message(b"rawblock", serialize(&tx)));
+        assert_eq!(parsed, None);
+    }
+}
@@ -0,0 +1,58 @@
+use bitcoin::BlockHash;
+use tokio::sync::watch;
+use tracing::trace;
+
+/// Wakes up tasks waiting for new blocks. Only the latest block is kept, a
+/// subscriber that is busy while several blocks come in is woken up once.
+#[derive(Debug)]
+pub struct BlockNotifier {
+    sender: watch::Sender<Option<BlockHash>>,
+}
+
+impl BlockNotifier {
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(None);
+        Self { sender }
+    }
+
+    /// Wakes up all subscribers, unless `hash` is the last notified block.
+    pub fn notify(&self, hash: BlockHash) {
+        let modified = self.sender.send_if_modified(|current| {
+            if *current == Some(hash) {
+                return false;
+            }
+
+            *current = Some(hash);
+            true
+        });
+        if modified {
+            trace!("notified new block {}", hash);
+        }
+    }
+
+    pub fn subscribe(&self) -> BlockSubscription {
+        BlockSubscription {
+            receiver: self.sender.subscribe(),
+        }
+    }
+}
+
+impl Default for BlockNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct BlockSubscription {
+    receiver: watch::Receiver<Option<BlockHash>>,
+}
+
+impl BlockSubscription {
+    /// Waits for a block notified after the previous call returned. Never
+    /// returns if the notifier is dropped.
+    pub async fn wait(&mut self) {
+        if self.receiver.changed().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
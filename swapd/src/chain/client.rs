@@ -33,6 +33,7 @@ pub trait ChainClient {
     async fn get_blockheight(&self) -> Result<u64, ChainError>;
     async fn get_tip_hash(&self) -> Result<BlockHash, ChainError>;
     async fn get_block(&self, hash: &BlockHash) -> Result<Block, ChainError>;
+    async fn get_block_hash(&self, height: u64) -> Result<BlockHash, ChainError>;
     async fn get_block_header(
         &self,
         hash: &BlockHash,
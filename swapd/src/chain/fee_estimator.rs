@@ -1,6 +1,10 @@
+use std::sync::Arc;
+
 use thiserror::Error;
 use tracing::error;
 
+use crate::metrics::{Metrics, FEE_SOURCE_FALLBACK, FEE_SOURCE_PRIMARY, FEE_SOURCE_UNAVAILABLE};
+
 #[derive(Debug)]
 pub struct FeeEstimate {
     pub sat_per_kw: u32,
@@ -27,6 +31,7 @@ where
 {
     estimator1: E1,
     estimator2: E2,
+    metrics: Arc<Metrics>,
 }
 
 impl<E1, E2> FallbackFeeEstimator<E1, E2>
@@ -34,10 +39,11 @@ where
     E1: FeeEstimator,
     E2: FeeEstimator,
 {
-    pub fn new(estimator1: E1, estimator2: E2) -> Self {
+    pub fn new(estimator1: E1, estimator2: E2, metrics: Arc<Metrics>) -> Self {
         Self {
             estimator1,
             estimator2,
+            metrics,
         }
     }
 }
@@ -50,19 +56,26 @@ where
 {
     async fn estimate_fee(&self, conf_target: i32) -> Result<FeeEstimate, FeeEstimateError> {
         match self.estimator1.estimate_fee(conf_target).await {
-            Ok(res) => return Ok(res),
+            Ok(res) => {
+                self.metrics.fee_estimates.inc(FEE_SOURCE_PRIMARY);
+                return Ok(res);
+            }
             Err(e) => {
                 error!("fee estimator 1 returned error: {:?}", e)
             }
         }
 
         match self.estimator2.estimate_fee(conf_target).await {
-            Ok(res) => return Ok(res),
+            Ok(res) => {
+                self.metrics.fee_estimates.inc(FEE_SOURCE_FALLBACK);
+                return Ok(res);
+            }
             Err(e) => {
                 error!("fee estimator 2 returned error: {:?}", e)
             }
         }
 
+        self.metrics.fee_estimates.inc(FEE_SOURCE_UNAVAILABLE);
         Err(FeeEstimateError::Unavailable)
     }
 }
@@ -95,6 +95,24 @@ impl Chain {
         Ok(())
     }
 
+    /// Drops the blocks below `min_height` from the start of the chain. The
+    /// tip is always kept.
+    pub(super) fn prune(&mut self, min_height: u64) {
+        while self.base != self.tip {
+            let base = self
+                .blocks
+                .get(&self.base)
+                .expect("chain doesn't contain its own base");
+            if base.header.height >= min_height {
+                break;
+            }
+
+            let next = base.next.expect("chain misses expected block");
+            self.blocks.remove(&self.base);
+            self.base = next;
+        }
+    }
+
     #[allow(unused)]
     pub(super) fn rebase(&mut self, other: &Chain) -> Result<(), ChainError> {
         let mut next_block = self
@@ -388,4 +406,24 @@ mod tests {
         assert_eq!(blocks[1], header(2));
         assert_eq!(blocks[2], header(1));
     }
+
+    #[test]
+    fn test_prune_success() {
+        let mut chain = Chain::try_from(vec![header(4), header(3), header(2), header(1)]).unwrap();
+        chain.prune(3);
+        assert_eq!(chain.base(), header(3));
+        assert_eq!(chain.tip(), header(4));
+        assert!(!chain.contains_block(&hash(1)));
+        assert!(!chain.contains_block(&hash(2)));
+        let blocks: Vec<_> = chain.iter_backwards().cloned().collect();
+        assert_eq!(blocks, vec![header(4), header(3)]);
+    }
+
+    #[test]
+    fn test_prune_keeps_tip() {
+        let mut chain = Chain::try_from(vec![header(2), header(1)]).unwrap();
+        chain.prune(10);
+        assert_eq!(chain.base(), header(2));
+        assert_eq!(chain.tip(), header(2));
+    }
 }
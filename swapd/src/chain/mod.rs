@@ -1,12 +1,18 @@
+mod block_notifier;
 mod client;
 mod fee_estimator;
 mod memchain;
 mod monitor;
 mod repository;
+mod rescan;
 mod types;
 
+pub use block_notifier::BlockNotifier;
 pub use client::{BroadcastError, ChainClient, ChainError};
 pub use fee_estimator::{FallbackFeeEstimator, FeeEstimate, FeeEstimateError, FeeEstimator};
-pub use monitor::ChainMonitor;
-pub use repository::{AddressUtxo, ChainRepository, ChainRepositoryError, SpentTxo};
+pub use monitor::{publish_new_txos, ChainMonitor};
+pub use repository::{
+    AddressSpentTxo, AddressUtxo, ChainRepository, ChainRepositoryError, NewTxos, SpentTxo,
+};
+pub use rescan::{rescan_channel, RescanError, RescanRequests, RescanResult, Rescanner};
 pub use types::{BlockHeader, Txo, TxoSpend, TxoWithSpend};
@@ -4,14 +4,25 @@ use std::{
     time::{Duration, SystemTime},
 };
 
-use bitcoin::{block::Bip34Error, Address, Block, Network, OutPoint};
+use bitcoin::{block::Bip34Error, Address, Block, BlockHash, Network, OutPoint};
+use tokio::sync::{watch, Mutex};
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 use tracing::{debug, error, field, info, trace};
 
-use crate::chain::{AddressUtxo, ChainClient, ChainRepository, SpentTxo, Txo};
+use crate::{
+    chain::{
+        AddressUtxo, BlockNotifier, ChainClient, ChainRepository, NewTxos, RescanError,
+        RescanRequests, RescanResult, SpentTxo, Txo,
+    },
+    event::{EventBus, SwapEvent, SwapEventKind},
+    metrics::Metrics,
+};
 
 use super::{memchain::Chain, types::BlockHeader, ChainError, ChainRepositoryError};
 
+/// Number of blocks a rescan processes at a time while holding the sync lock.
+const RESCAN_CHUNK_BLOCKS: u64 = 10;
+
 pub struct ChainMonitor<C, R>
 where
     C: ChainClient,
@@ -20,8 +31,22 @@ where
     network: Network,
     chain_client: Arc<C>,
     chain_repository: Arc<R>,
+    event_bus: Arc<EventBus>,
+    metrics: Arc<Metrics>,
+    new_blocks: Arc<BlockNotifier>,
+    synced_blocks: Arc<BlockNotifier>,
     poll_interval: Duration,
     full_sync_interval: Duration,
+    birthday_height: Option<u64>,
+    reorg_safety_window: u64,
+    rescan_requests: Mutex<RescanRequests>,
+    /// Held while adding or pruning blocks, so the full sync can't add
+    /// transactions to a block that is being pruned.
+    block_lock: Mutex<()>,
+    /// Held by the tip sync while syncing, and by rescans while processing a
+    /// chunk of blocks, so a rescan never processes a block that was just
+    /// reorged out.
+    sync_lock: Mutex<()>,
 }
 
 impl<C, R> ChainMonitor<C, R>
@@ -29,37 +54,57 @@ where
     C: ChainClient + Sync + Send + 'static,
     R: ChainRepository + Sync + Send + 'static,
 {
+    /// The chain tip is synced every `poll_interval`, or as soon as a block is
+    /// notified on `new_blocks`. Every time the synced tip changes, the new tip
+    /// is notified on `synced_blocks`. Only the last `reorg_safety_window`
+    /// blocks are kept in memory, older blocks are pruned from the repository
+    /// unless they confirm stored outputs or spends. Syncing starts from
+    /// `birthday_height`, or 20 blocks below the tip if not set. Rescans
+    /// received on `rescan_requests` run alongside the tip sync, up to the
+    /// tip synced when they start.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         network: Network,
         chain_client: Arc<C>,
         chain_repository: Arc<R>,
+        event_bus: Arc<EventBus>,
+        metrics: Arc<Metrics>,
+        new_blocks: Arc<BlockNotifier>,
+        synced_blocks: Arc<BlockNotifier>,
         poll_interval: Duration,
+        birthday_height: Option<u64>,
+        reorg_safety_window: u64,
+        rescan_requests: RescanRequests,
     ) -> Self {
         Self {
             chain_client,
             network,
             chain_repository,
+            event_bus,
+            metrics,
+            new_blocks,
+            synced_blocks,
             poll_interval,
             full_sync_interval: Duration::from_secs(60 * 60 * 24),
+            birthday_height,
+            reorg_safety_window,
+            rescan_requests: Mutex::new(rescan_requests),
+            block_lock: Mutex::new(()),
+            sync_lock: Mutex::new(()),
         }
     }
 
     pub async fn start(self: Arc<Self>, token: CancellationToken) -> Result<(), ChainError> {
-        let blocks = self.chain_repository.get_block_headers().await?;
+        let min_height = match self.chain_repository.get_tip().await? {
+            Some(tip) => tip.height.saturating_sub(self.reorg_safety_window),
+            None => 0,
+        };
+        let blocks = self.chain_repository.get_block_headers(min_height).await?;
         let chain = match Chain::try_from(blocks) {
             Ok(chain) => chain,
             Err(e) => match e {
                 ChainError::EmptyChain => {
-                    // If the chain is empty, set the birthday to 20 blocks ago.
-                    let tip_hash = self.chain_client.get_tip_hash().await?;
-                    let mut birthday_header = self.chain_client.get_block_header(&tip_hash).await?;
-                    for _n in 0..20 {
-                        birthday_header = self
-                            .chain_client
-                            .get_block_header(&birthday_header.prev)
-                            .await?;
-                    }
-
+                    let birthday_header = self.get_birthday_header().await?;
                     info!(
                         birthday_hash = field::display(birthday_header.hash),
                         "using block {} as birthday", birthday_header.height
@@ -73,18 +118,48 @@ where
             },
         };
 
-        let birthday_chain = Chain::new(chain.base());
+        let mut birthday = match self.chain_repository.get_birthday().await? {
+            Some(birthday) => birthday,
+            None => chain.base(),
+        };
+        // Moving the birthday back makes the full sync process the blocks
+        // since the new birthday.
+        if let Some(birthday_height) = self.birthday_height {
+            if birthday_height < birthday.height {
+                let hash = self.chain_client.get_block_hash(birthday_height).await?;
+                birthday = self.chain_client.get_block_header(&hash).await?;
+                info!(
+                    birthday_hash = field::display(birthday.hash),
+                    "moving birthday back to block {}", birthday.height
+                );
+                self.chain_repository
+                    .add_block(&birthday, &Vec::new(), &Vec::new())
+                    .await?;
+            }
+        }
+        let birthday_chain = Chain::new(birthday);
+        let (synced_chain_sender, synced_chain) = watch::channel(chain.clone());
         let tracker = TaskTracker::new();
         let self1 = Arc::clone(&self);
         let self2 = Arc::clone(&self);
+        let self3 = Arc::clone(&self);
         let token1 = token.clone();
         let token2 = token.clone();
+        let token3 = token.clone();
         tracker.spawn(async move {
-            if let Err(e) = self1.start_tip_sync(chain, token1.child_token()).await {
+            if let Err(e) = self1
+                .start_tip_sync(chain, synced_chain_sender, token1.child_token())
+                .await
+            {
                 error!("chain tip sync exited with error: {:?}", e);
             }
             token1.cancel();
         });
+        tracker.spawn(async move {
+            self3
+                .start_rescans(synced_chain, token3.child_token())
+                .await;
+        });
         tracker.spawn(async move {
             if let Err(e) = self2
                 .start_full_sync(birthday_chain, token2.child_token())
@@ -128,20 +203,36 @@ where
     async fn start_tip_sync(
         &self,
         mut chain: Chain,
+        synced_chain: watch::Sender<Chain>,
         token: CancellationToken,
     ) -> Result<(), ChainError> {
+        let mut new_blocks = self.new_blocks.subscribe();
         loop {
             if token.is_cancelled() {
                 return Ok(());
             }
 
+            let sync_lock = self.sync_lock.lock().await;
+            let tip = chain.tip().hash;
             self.do_sync(&mut chain, token.child_token()).await?;
+            self.metrics
+                .chain_synced_height
+                .set(chain.tip().height as i64);
+            if chain.tip().hash != tip {
+                self.synced_blocks.notify(chain.tip().hash);
+                self.prune(&mut chain).await?;
+                synced_chain.send_replace(chain.clone());
+            }
+            drop(sync_lock);
 
             tokio::select! {
                 _ = token.cancelled() => {
                     debug!("chain monitor tip sync shutting down");
                     break;
                 }
+                _ = new_blocks.wait() => {
+                    debug!("got new block notification, syncing chain tip");
+                }
                 _ = tokio::time::sleep(self.poll_interval) => {}
             }
         }
@@ -149,6 +240,26 @@ where
         Ok(())
     }
 
+    /// Handles rescan requests one at a time, so a long rescan doesn't hold
+    /// up the tip sync.
+    async fn start_rescans(&self, synced_chain: watch::Receiver<Chain>, token: CancellationToken) {
+        let mut rescan_requests = self.rescan_requests.lock().await;
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => {
+                    debug!("chain monitor rescans shutting down");
+                    break;
+                }
+                request = rescan_requests.recv() => {
+                    let result = self
+                        .rescan(&synced_chain, request.from_height, &request.addresses, &token)
+                        .await;
+                    request.reply(result);
+                }
+            }
+        }
+    }
+
     async fn do_sync(
         &self,
         existing_chain: &mut Chain,
@@ -167,6 +278,9 @@ where
         let mut new_chain = Chain::new(current_header.clone());
 
         debug!("chain tip is at height {}", current_header.height);
+        self.metrics
+            .chain_tip_height
+            .set(current_header.height as i64);
 
         // Iterate backwards from the tip to get the missed block headers.
         loop {
@@ -184,6 +298,17 @@ where
                 break;
             }
 
+            // Blocks below the base are no longer known, so a reorg this deep
+            // can't be undone.
+            if current_header.height <= existing_chain.base().height {
+                error!(
+                    "chain reorged below block {} ({}), deeper than the reorg safety window",
+                    existing_chain.base().height,
+                    existing_chain.base().hash
+                );
+                return Err(ChainError::InvalidChain);
+            }
+
             current_header = self
                 .chain_client
                 .get_block_header(&current_header.prev)
@@ -228,7 +353,7 @@ where
             );
             let start = SystemTime::now();
             let block = self.chain_client.get_block(&current_block.hash).await?;
-            self.process_block(&block).await?;
+            self.process_block(&block, &[]).await?;
             if let Ok(elapsed) = start.elapsed() {
                 trace!(
                     "processed block {}, height {} in {}s",
@@ -243,7 +368,105 @@ where
         Ok(())
     }
 
-    async fn process_block(&self, block: &Block) -> Result<(), ChainError> {
+    async fn get_birthday_header(&self) -> Result<BlockHeader, ChainError> {
+        if let Some(birthday_height) = self.birthday_height {
+            let hash = self.chain_client.get_block_hash(birthday_height).await?;
+            return self.chain_client.get_block_header(&hash).await;
+        }
+
+        // If no birthday is set, use the block 20 blocks ago.
+        let tip_hash = self.chain_client.get_tip_hash().await?;
+        let mut birthday_header = self.chain_client.get_block_header(&tip_hash).await?;
+        for _n in 0..20 {
+            birthday_header = self
+                .chain_client
+                .get_block_header(&birthday_header.prev)
+                .await?;
+        }
+
+        Ok(birthday_header)
+    }
+
+    /// Processes the blocks from `from_height` up to the synced tip again,
+    /// for `addresses` or all watched addresses if empty. Blocks are
+    /// processed as usual, so outputs and spends that were missed are
+    /// published as new. Blocks are processed in chunks in between tip syncs.
+    async fn rescan(
+        &self,
+        synced_chain: &watch::Receiver<Chain>,
+        from_height: u64,
+        addresses: &[Address],
+        token: &CancellationToken,
+    ) -> Result<RescanResult, RescanError> {
+        let tip_height = synced_chain.borrow().tip().height;
+        if from_height > tip_height {
+            return Err(RescanError::InvalidHeight {
+                from_height,
+                tip_height,
+            });
+        }
+
+        info!(
+            addresses = addresses.len(),
+            "rescanning blocks {} to {}", from_height, tip_height
+        );
+        let mut result = RescanResult::default();
+        let mut height = from_height;
+        while height <= tip_height {
+            let _lock = self.sync_lock.lock().await;
+            // Blocks in the reorg safety window are taken from the synced
+            // chain as it is now, older blocks are fetched by height.
+            let synced: HashMap<u64, BlockHash> = synced_chain
+                .borrow()
+                .iter_forwards()
+                .map(|header| (header.height, header.hash))
+                .collect();
+            let chunk_end = tip_height.min(height + RESCAN_CHUNK_BLOCKS - 1);
+            while height <= chunk_end {
+                if token.is_cancelled() {
+                    return Ok(result);
+                }
+
+                let hash = match synced.get(&height) {
+                    Some(hash) => *hash,
+                    None => self.chain_client.get_block_hash(height).await?,
+                };
+                let block = self.chain_client.get_block(&hash).await?;
+                result.add(&self.process_block(&block, addresses).await?);
+                height += 1;
+            }
+        }
+
+        info!(
+            new_outputs = result.new_outputs,
+            new_spends = result.new_spends,
+            "rescanned blocks {} to {}",
+            from_height,
+            tip_height
+        );
+        Ok(result)
+    }
+
+    async fn prune(&self, chain: &mut Chain) -> Result<(), ChainError> {
+        let min_height = chain.tip().height.saturating_sub(self.reorg_safety_window);
+        chain.prune(min_height);
+
+        let _lock = self.block_lock.lock().await;
+        let pruned = self.chain_repository.prune_blocks(min_height).await?;
+        if pruned > 0 {
+            debug!("pruned {} blocks below height {}", pruned, min_height);
+        }
+
+        Ok(())
+    }
+
+    /// Adds the block with its outputs to and spends from watched addresses.
+    /// If `addresses` is not empty, only those addresses are considered.
+    async fn process_block(
+        &self,
+        block: &Block,
+        addresses: &[Address],
+    ) -> Result<NewTxos, ChainError> {
         // Check all transactions in the block
         // - does an output send to a known address?
         // - does an input spend a known utxo?
@@ -251,7 +474,7 @@ where
         let prev_block_hash = block.header.prev_blockhash;
         let block_height = block.bip34_block_height()?;
         let mut spent_txos = Vec::new();
-        let mut addresses = Vec::new();
+        let mut block_addresses = Vec::new();
         let mut address_utxos = HashMap::new();
         for tx in &block.txdata {
             let txid = tx.compute_txid();
@@ -260,7 +483,11 @@ where
                     Ok(address) => address,
                     Err(_) => continue,
                 };
-                addresses.push(address.clone());
+                if !addresses.is_empty() && !addresses.contains(&address) {
+                    continue;
+                }
+
+                block_addresses.push(address.clone());
                 let entry = address_utxos.entry(address).or_insert(Vec::new());
                 entry.push((OutPoint::new(txid, vout as u32), output.clone()));
             }
@@ -276,7 +503,7 @@ where
 
         let watch_addresses = self
             .chain_repository
-            .filter_watch_addresses(&addresses)
+            .filter_watch_addresses(&block_addresses)
             .await?;
         let watch_utxos: Vec<AddressUtxo> = watch_addresses
             .into_iter()
@@ -304,13 +531,8 @@ where
             watch_utxos.len()
         );
 
-        for utxo in &watch_utxos {
-            info!(
-                "block {} ({}) contains output {} for address {}, amount {}",
-                block_height, block_hash, utxo.utxo.outpoint, utxo.address, utxo.utxo.tx_out.value
-            );
-        }
-        let spent_txos = self
+        let _lock = self.block_lock.lock().await;
+        let new_txos = self
             .chain_repository
             .add_block(
                 &BlockHeader {
@@ -322,18 +544,58 @@ where
                 &spent_txos,
             )
             .await?;
+        publish_new_txos(&self.event_bus, block_height, watch_utxos, &new_txos);
+        Ok(new_txos)
+    }
+}
+
+/// Logs and publishes the outputs and spends that were seen for the first
+/// time in the block at `block_height`. `utxos` are the watched outputs in the
+/// block that were passed to `ChainRepository::add_block`.
+pub fn publish_new_txos(
+    event_bus: &EventBus,
+    block_height: u64,
+    utxos: Vec<AddressUtxo>,
+    new_txos: &NewTxos,
+) {
+    for utxo in utxos
+        .into_iter()
+        .filter(|utxo| new_txos.utxos.contains(&utxo.utxo.outpoint))
+    {
+        info!(
+            "block {} ({}) contains output {} for address {}, amount {}",
+            block_height,
+            utxo.utxo.block_hash,
+            utxo.utxo.outpoint,
+            utxo.address,
+            utxo.utxo.tx_out.value
+        );
+        event_bus.publish(SwapEvent::new(
+            utxo.address,
+            SwapEventKind::DepositConfirmed {
+                outpoint: utxo.utxo.outpoint,
+                amount: utxo.utxo.tx_out.value,
+                block_height,
+            },
+        ));
+    }
 
-        for spent_txo in &spent_txos {
-            info!(
-                "block {} ({}) contains tx {} spending output {} in input {}",
+    for spent_txo in &new_txos.spent_txos {
+        info!(
+            "block {} contains tx {} spending output {} in input {}",
+            block_height,
+            spent_txo.spent_txo.spending_tx,
+            spent_txo.spent_txo.outpoint,
+            spent_txo.spent_txo.spending_input_index,
+        );
+        event_bus.publish(SwapEvent::new(
+            spent_txo.address.clone(),
+            SwapEventKind::OutputSpent {
+                outpoint: spent_txo.spent_txo.outpoint,
+                spending_tx_id: spent_txo.spent_txo.spending_tx,
                 block_height,
-                block_hash,
-                spent_txo.spending_tx,
-                spent_txo.outpoint,
-                spent_txo.spending_input_index,
-            );
-        }
-        Ok(())
+            },
+        ));
     }
 }
 
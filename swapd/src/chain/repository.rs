@@ -24,6 +24,20 @@ pub struct SpentTxo {
     pub spending_input_index: u32,
 }
 
+#[derive(Debug)]
+pub struct AddressSpentTxo {
+    pub address: Address,
+    pub spent_txo: SpentTxo,
+}
+
+/// The outputs and spends that were seen for the first time when adding a
+/// block. Re-adding a block yields nothing new.
+#[derive(Debug, Default)]
+pub struct NewTxos {
+    pub utxos: Vec<OutPoint>,
+    pub spent_txos: Vec<AddressSpentTxo>,
+}
+
 #[async_trait::async_trait]
 pub trait ChainRepository {
     async fn add_block(
@@ -31,13 +45,21 @@ pub trait ChainRepository {
         block: &BlockHeader,
         tx_outputs: &[AddressUtxo],
         tx_inputs: &[SpentTxo],
-    ) -> Result<Vec<SpentTxo>, ChainRepositoryError>;
+    ) -> Result<NewTxos, ChainRepositoryError>;
     async fn add_watch_address(&self, address: &Address) -> Result<(), ChainRepositoryError>;
     async fn filter_watch_addresses(
         &self,
         addresses: &[Address],
     ) -> Result<Vec<Address>, ChainRepositoryError>;
-    async fn get_block_headers(&self) -> Result<Vec<BlockHeader>, ChainRepositoryError>;
+    /// Returns the block headers from `min_height` up to the tip, highest
+    /// first.
+    async fn get_block_headers(
+        &self,
+        min_height: u64,
+    ) -> Result<Vec<BlockHeader>, ChainRepositoryError>;
+    /// Returns the lowest block, the block the chain was synced from.
+    async fn get_birthday(&self) -> Result<Option<BlockHeader>, ChainRepositoryError>;
+    async fn get_watch_addresses(&self) -> Result<Vec<Address>, ChainRepositoryError>;
     async fn get_tip(&self) -> Result<Option<BlockHeader>, ChainRepositoryError>;
     async fn get_txos_for_address(
         &self,
@@ -48,5 +70,9 @@ pub trait ChainRepository {
         address: &Address,
     ) -> Result<Vec<TxoWithSpend>, ChainRepositoryError>;
     async fn get_utxos(&self) -> Result<Vec<AddressUtxo>, ChainRepositoryError>;
+    /// Removes the blocks below `min_height` that no longer confirm any
+    /// stored output or spend, except the birthday. Returns the number of
+    /// removed blocks.
+    async fn prune_blocks(&self, min_height: u64) -> Result<u64, ChainRepositoryError>;
     async fn undo_block(&self, hash: BlockHash) -> Result<(), ChainRepositoryError>;
 }
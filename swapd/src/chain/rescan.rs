@@ -0,0 +1,107 @@
+use bitcoin::Address;
+use thiserror::Error;
+use tokio::sync::{mpsc, oneshot};
+
+use super::{ChainError, ChainRepositoryError, NewTxos};
+
+#[derive(Debug, Error)]
+pub enum RescanError {
+    #[error("rescan height {from_height} is above the synced tip {tip_height}")]
+    InvalidHeight { from_height: u64, tip_height: u64 },
+    #[error("chain sync is not running")]
+    Unavailable,
+    #[error("{0}")]
+    Chain(ChainError),
+}
+
+/// The outputs and spends that were found for the first time by a rescan.
+#[derive(Debug, Default)]
+pub struct RescanResult {
+    pub new_outputs: u64,
+    pub new_spends: u64,
+}
+
+impl RescanResult {
+    pub fn add(&mut self, new_txos: &NewTxos) {
+        self.new_outputs += new_txos.utxos.len() as u64;
+        self.new_spends += new_txos.spent_txos.len() as u64;
+    }
+}
+
+/// A request to process the chain again from `from_height` for `addresses`,
+/// or for all watched addresses if `addresses` is empty.
+#[derive(Debug)]
+pub struct RescanRequest {
+    pub from_height: u64,
+    pub addresses: Vec<Address>,
+    reply: oneshot::Sender<Result<RescanResult, RescanError>>,
+}
+
+impl RescanRequest {
+    pub fn reply(self, result: Result<RescanResult, RescanError>) {
+        // The requester may have gone away, nothing to do about that.
+        let _ = self.reply.send(result);
+    }
+}
+
+/// Sends rescan requests to the chain sync, which handles them alongside the
+/// tip sync.
+#[derive(Debug, Clone)]
+pub struct Rescanner {
+    sender: mpsc::Sender<RescanRequest>,
+}
+
+pub struct RescanRequests {
+    receiver: mpsc::Receiver<RescanRequest>,
+}
+
+/// Creates a `Rescanner` and the `RescanRequests` to be handled by the chain
+/// sync. Rescans fail with `RescanError::Unavailable` if the `RescanRequests`
+/// is dropped.
+pub fn rescan_channel() -> (Rescanner, RescanRequests) {
+    let (sender, receiver) = mpsc::channel(16);
+    (Rescanner { sender }, RescanRequests { receiver })
+}
+
+impl Rescanner {
+    /// Rescans the chain and waits for the rescan to finish.
+    pub async fn rescan(
+        &self,
+        from_height: u64,
+        addresses: Vec<Address>,
+    ) -> Result<RescanResult, RescanError> {
+        let (reply, result) = oneshot::channel();
+        self.sender
+            .send(RescanRequest {
+                from_height,
+                addresses,
+                reply,
+            })
+            .await
+            .map_err(|_| RescanError::Unavailable)?;
+        result.await.map_err(|_| RescanError::Unavailable)?
+    }
+}
+
+impl RescanRequests {
+    /// Waits for the next rescan request. Never returns once all
+    /// `Rescanner`s are dropped.
+    pub async fn recv(&mut self) -> RescanRequest {
+        match self.receiver.recv().await {
+            Some(request) => request,
+            None => std::future::pending().await,
+        }
+    }
+}
+
+impl From<ChainError> for RescanError {
+    fn from(value: ChainError) -> Self {
+        RescanError::Chain(value)
+    }
+}
+
+impl From<ChainRepositoryError> for RescanError {
+    fn from(value: ChainRepositoryError) -> Self {
+        RescanError::Chain(ChainError::Database(value))
+    }
+}
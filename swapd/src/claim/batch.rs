@@ -0,0 +1,71 @@
+/// Splits claimables into claim batches. Claimables are bucketed by the
+/// blocks left before they can be refunded, in buckets of `bucket_blocks`
+/// blocks, so claimables in the same transaction need a similar fee rate. A
+/// `bucket_blocks` of 0 puts everything in a single bucket. Every batch has at
+/// most `max_inputs` claimables. Batches are ordered by urgency, most urgent
+/// first.
+pub(super) fn batch_claimables<T>(
+    mut claimables: Vec<T>,
+    blocks_left: impl Fn(&T) -> i32,
+    bucket_blocks: u32,
+    max_inputs: usize,
+) -> Vec<Vec<T>> {
+    claimables.sort_by_key(&blocks_left);
+    let bucket = |claimable: &T| match bucket_blocks {
+        0 => 0,
+        _ => blocks_left(claimable).max(0) as u32 / bucket_blocks,
+    };
+
+    let max_inputs = max_inputs.max(1);
+    let mut batches: Vec<Vec<T>> = Vec::new();
+    let mut current_bucket = None;
+    for claimable in claimables {
+        let claimable_bucket = bucket(&claimable);
+        match batches.last_mut() {
+            Some(batch) if current_bucket == Some(claimable_bucket) && batch.len() < max_inputs => {
+                batch.push(claimable)
+            }
+            _ => {
+                current_bucket = Some(claimable_bucket);
+                batches.push(vec![claimable]);
+            }
+        }
+    }
+
+    batches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::batch_claimables;
+
+    #[test]
+    fn test_batch_by_bucket() {
+        let batches = batch_claimables(vec![100, 10, 50, 20, 140], |b| *b, 48, 100);
+        assert_eq!(batches, vec![vec![10, 20], vec![50], vec![100, 140]]);
+    }
+
+    #[test]
+    fn test_batch_max_inputs() {
+        let batches = batch_claimables(vec![5, 4, 3, 2, 1], |b| *b, 48, 2);
+        assert_eq!(batches, vec![vec![1, 2], vec![3, 4], vec![5]]);
+    }
+
+    #[test]
+    fn test_batch_single_bucket() {
+        let batches = batch_claimables(vec![300, -5, 10], |b| *b, 0, 100);
+        assert_eq!(batches, vec![vec![-5, 10, 300]]);
+    }
+
+    #[test]
+    fn test_batch_expired_in_first_bucket() {
+        let batches = batch_claimables(vec![-10, 47, 48], |b| *b, 48, 100);
+        assert_eq!(batches, vec![vec![-10, 47], vec![48]]);
+    }
+
+    #[test]
+    fn test_batch_empty() {
+        let batches = batch_claimables(Vec::<i32>::new(), |b| *b, 48, 100);
+        assert!(batches.is_empty());
+    }
+}
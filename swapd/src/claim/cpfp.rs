@@ -0,0 +1,114 @@
+use bitcoin::{Address, OutPoint, Transaction};
+
+/// Estimated weight of a CPFP child, spending a single output to a single
+/// wallet output. Used for the first child of a claim, later children are
+/// estimated from the signed weight of the child before them.
+pub(super) const CPFP_CHILD_WEIGHT: u64 = 440;
+
+/// Maximum number of CPFP children chained below a claim. Mempool policy
+/// allows 25 unconfirmed transactions in a package, including the claim.
+pub(super) const MAX_CPFP_DESCENDANTS: u64 = 24;
+
+/// The output of `tx` paying to the wallet `address`, to spend in a CPFP
+/// child.
+pub(super) fn wallet_outpoint(tx: &Transaction, address: &Address) -> Option<OutPoint> {
+    let script_pubkey = address.script_pubkey();
+    let vout = tx
+        .output
+        .iter()
+        .position(|output| output.script_pubkey == script_pubkey)?;
+    Some(OutPoint::new(tx.compute_txid(), vout as u32))
+}
+
+/// Fee rate a child of `child_weight` needs so that its unconfirmed
+/// ancestors, of `ancestors_weight` paying `ancestors_fee_per_kw`, and the
+/// child together pay `package_fee_per_kw`. The child never pays less than
+/// `package_fee_per_kw` itself.
+pub(super) fn child_fee_per_kw(
+    ancestors_weight: u64,
+    ancestors_fee_per_kw: u32,
+    child_weight: u64,
+    package_fee_per_kw: u32,
+) -> u32 {
+    let package_fee = package_fee_per_kw as u64 * (ancestors_weight + child_weight);
+    let ancestors_fee = ancestors_fee_per_kw as u64 * ancestors_weight;
+    let child_fee = package_fee.saturating_sub(ancestors_fee);
+    let child_fee_per_kw = child_fee.div_ceil(child_weight);
+    u32::try_from(child_fee_per_kw)
+        .unwrap_or(u32::MAX)
+        .max(package_fee_per_kw)
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute::LockTime, transaction::Version, Address, Amount, Network, OutPoint, ScriptBuf,
+        Transaction, TxOut,
+    };
+
+    use super::{child_fee_per_kw, wallet_outpoint, CPFP_CHILD_WEIGHT};
+
+    #[test]
+    fn test_child_pays_for_parent() {
+        // The parent is as heavy as the child, so the child pays the
+        // difference twice.
+        let fee_per_kw = child_fee_per_kw(CPFP_CHILD_WEIGHT, 1000, CPFP_CHILD_WEIGHT, 3000);
+        assert_eq!(fee_per_kw, 5000);
+    }
+
+    #[test]
+    fn test_child_pays_for_heavy_parent() {
+        let fee_per_kw = child_fee_per_kw(4 * CPFP_CHILD_WEIGHT, 250, CPFP_CHILD_WEIGHT, 1000);
+        assert_eq!(fee_per_kw, 4000);
+    }
+
+    #[test]
+    fn test_child_pays_by_own_weight() {
+        // A lighter child pays the same package fee at a higher rate.
+        let fee_per_kw = child_fee_per_kw(1000, 1000, 500, 2000);
+        assert_eq!(fee_per_kw, 4000);
+    }
+
+    #[test]
+    fn test_child_rounds_up() {
+        let fee_per_kw = child_fee_per_kw(1000, 253, CPFP_CHILD_WEIGHT, 254);
+        assert_eq!(fee_per_kw, 257);
+    }
+
+    #[test]
+    fn test_child_pays_at_least_package_rate() {
+        let fee_per_kw = child_fee_per_kw(1000, 5000, CPFP_CHILD_WEIGHT, 3000);
+        assert_eq!(fee_per_kw, 3000);
+    }
+
+    #[test]
+    fn test_wallet_outpoint_is_looked_up_by_address() {
+        let wallet_address: Address = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080"
+            .parse::<Address<_>>()
+            .unwrap()
+            .require_network(Network::Regtest)
+            .unwrap();
+        let mut tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: Vec::new(),
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(1000),
+                    script_pubkey: ScriptBuf::new_op_return([]),
+                },
+                TxOut {
+                    value: Amount::from_sat(2000),
+                    script_pubkey: wallet_address.script_pubkey(),
+                },
+            ],
+        };
+        assert_eq!(
+            wallet_outpoint(&tx, &wallet_address),
+            Some(OutPoint::new(tx.compute_txid(), 1))
+        );
+
+        tx.output.truncate(1);
+        assert_eq!(wallet_outpoint(&tx, &wallet_address), None);
+    }
+}
@@ -0,0 +1,588 @@
+use std::{str::FromStr, sync::Arc, time::SystemTime};
+
+use bitcoin::{
+    bip32::{self, ChildNumber, DerivationPath, Xpub},
+    secp256k1::Secp256k1,
+    Address, CompressedPublicKey, Network, NetworkKind,
+};
+use thiserror::Error;
+use tracing::{debug, field};
+
+use crate::wallet::Wallet;
+
+use super::{ClaimError, ClaimRepository, DestinationAddress};
+
+#[derive(Debug, Error)]
+pub enum DescriptorError {
+    #[error("invalid descriptor: {0}")]
+    Invalid(String),
+    #[error("descriptor key is for another network")]
+    WrongNetwork,
+    #[error("{0}")]
+    Bip32(bip32::Error),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ScriptKind {
+    Wpkh,
+    Tr,
+}
+
+/// Watch-only descriptor to derive claim addresses from. Supports single key
+/// `wpkh(...)` and `tr(...)` descriptors with an extended public key ending
+/// in an unhardened wildcard, like `wpkh([fingerprint/84'/0'/0']xpub/0/*)`.
+/// A bare extended public key is taken as `wpkh(xpub/0/*)`.
+#[derive(Clone, Debug)]
+pub struct ClaimDescriptor {
+    descriptor: String,
+    kind: ScriptKind,
+    network: Network,
+    path: DerivationPath,
+    xpub: Xpub,
+}
+
+impl ClaimDescriptor {
+    pub fn parse(descriptor: &str, network: Network) -> Result<Self, DescriptorError> {
+        // The checksum is optional, but has to match if present.
+        let descriptor = descriptor.trim();
+        let descriptor = match descriptor.split_once('#') {
+            Some((descriptor, checksum)) => {
+                let expected = descriptor_checksum(descriptor)
+                    .ok_or(DescriptorError::Invalid("invalid character".to_string()))?;
+                if checksum != expected {
+                    return Err(DescriptorError::Invalid("checksum mismatch".to_string()));
+                }
+                descriptor
+            }
+            None => descriptor,
+        };
+        let (kind, key) = if let Some(key) = strip_function(descriptor, "wpkh") {
+            (ScriptKind::Wpkh, key.to_string())
+        } else if let Some(key) = strip_function(descriptor, "tr") {
+            (ScriptKind::Tr, key.to_string())
+        } else if !descriptor.contains(['(', ')', '/', '[']) {
+            (ScriptKind::Wpkh, format!("{}/0/*", descriptor))
+        } else {
+            return Err(DescriptorError::Invalid(
+                "expected a wpkh() or tr() descriptor".to_string(),
+            ));
+        };
+
+        // The key origin is informational only.
+        let key = match key.strip_prefix('[') {
+            Some(key) => match key.split_once(']') {
+                Some((_, key)) => key,
+                None => return Err(DescriptorError::Invalid("unclosed key origin".to_string())),
+            },
+            None => key.as_str(),
+        };
+        let mut parts = key.split('/');
+        let xpub = parts
+            .next()
+            .ok_or(DescriptorError::Invalid("missing key".to_string()))
+            .and_then(|xpub| Xpub::from_str(xpub).map_err(DescriptorError::Bip32))?;
+        if xpub.network != NetworkKind::from(network) {
+            return Err(DescriptorError::WrongNetwork);
+        }
+
+        let parts: Vec<_> = parts.collect();
+        let (wildcard, steps) = match parts.split_last() {
+            Some(split) => split,
+            None => return Err(DescriptorError::Invalid("missing wildcard".to_string())),
+        };
+        if *wildcard != "*" {
+            return Err(DescriptorError::Invalid(
+                "expected the path to end in an unhardened wildcard".to_string(),
+            ));
+        }
+        let mut path = Vec::new();
+        for step in steps {
+            let index = step.parse::<u32>().map_err(|_| {
+                DescriptorError::Invalid(format!("invalid unhardened path step '{}'", step))
+            })?;
+            path.push(ChildNumber::from_normal_idx(index).map_err(DescriptorError::Bip32)?);
+        }
+        let path = DerivationPath::from(path);
+        let descriptor = match kind {
+            ScriptKind::Wpkh => format!("wpkh({}/{}*)", xpub, path_prefix(&path)),
+            ScriptKind::Tr => format!("tr({}/{}*)", xpub, path_prefix(&path)),
+        };
+
+        Ok(Self {
+            descriptor,
+            kind,
+            network,
+            path,
+            xpub,
+        })
+    }
+
+    /// The descriptor without key origin and checksum, to identify it by.
+    pub fn descriptor(&self) -> &str {
+        &self.descriptor
+    }
+
+    /// Derives the address at `index`.
+    pub fn address(&self, index: u32) -> Result<Address, DescriptorError> {
+        let secp = Secp256k1::verification_only();
+        let path = self
+            .path
+            .child(ChildNumber::from_normal_idx(index).map_err(DescriptorError::Bip32)?);
+        let key = self
+            .xpub
+            .derive_pub(&secp, &path)
+            .map_err(DescriptorError::Bip32)?
+            .public_key;
+        Ok(match self.kind {
+            ScriptKind::Wpkh => Address::p2wpkh(&CompressedPublicKey(key), self.network),
+            ScriptKind::Tr => Address::p2tr(&secp, key.x_only_public_key().0, None, self.network),
+        })
+    }
+}
+
+fn strip_function<'a>(descriptor: &'a str, name: &str) -> Option<&'a str> {
+    descriptor
+        .strip_prefix(name)?
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+fn path_prefix(path: &DerivationPath) -> String {
+    path.into_iter().map(|step| format!("{}/", step)).collect()
+}
+
+/// Where claims pay to, other than the lightning node's wallet.
+#[derive(Clone, Debug)]
+pub enum ColdDestination {
+    /// A single static address.
+    Address(Address),
+    /// A new address derived from a watch-only descriptor for every claim.
+    Descriptor(ClaimDescriptor),
+}
+
+/// Decides where claims pay to. Without a cold destination, claims pay to a
+/// new address of the lightning node's wallet.
+#[derive(Clone, Debug)]
+pub struct ClaimDestinationPolicy {
+    pub cold: Option<ColdDestination>,
+    /// Claims pay to the node wallet while its balance is below this amount,
+    /// to keep funds available for channel opens, and to the cold destination
+    /// otherwise.
+    pub wallet_reserve_sat: u64,
+}
+
+/// Resolves the claim destination policy to addresses.
+pub(super) struct ClaimDestination<RR, W>
+where
+    RR: ClaimRepository,
+    W: Wallet,
+{
+    claim_repository: Arc<RR>,
+    policy: ClaimDestinationPolicy,
+    wallet: Arc<W>,
+}
+
+impl<RR, W> ClaimDestination<RR, W>
+where
+    RR: ClaimRepository,
+    W: Wallet,
+{
+    pub fn new(policy: ClaimDestinationPolicy, claim_repository: Arc<RR>, wallet: Arc<W>) -> Self {
+        Self {
+            claim_repository,
+            policy,
+            wallet,
+        }
+    }
+
+    /// Gets the address for a new claim.
+    pub async fn next_address(&self) -> Result<Address, ClaimError> {
+        let cold = match &self.policy.cold {
+            Some(cold) => cold,
+            None => return Ok(self.wallet.new_address().await?),
+        };
+        if self.policy.wallet_reserve_sat > 0 {
+            let balance_sat = self.wallet.balance_sat().await?;
+            if balance_sat < self.policy.wallet_reserve_sat {
+                debug!(
+                    balance_sat,
+                    wallet_reserve_sat = self.policy.wallet_reserve_sat,
+                    "wallet below reserve, claiming to the node wallet"
+                );
+                return Ok(self.wallet.new_address().await?);
+            }
+        }
+
+        match cold {
+            ColdDestination::Address(address) => Ok(address.clone()),
+            ColdDestination::Descriptor(descriptor) => {
+                // Indices are reserved and addresses persisted before use,
+                // so they are never reused, also if the claim fails.
+                let derivation_index = self
+                    .claim_repository
+                    .reserve_destination_index(descriptor.descriptor())
+                    .await?;
+                let address = descriptor.address(derivation_index)?;
+                self.claim_repository
+                    .add_destination_address(&DestinationAddress {
+                        address: address.clone(),
+                        descriptor: descriptor.descriptor().to_string(),
+                        derivation_index,
+                        creation_time: SystemTime::now(),
+                    })
+                    .await?;
+                debug!(
+                    address = field::display(&address),
+                    derivation_index, "derived claim address from descriptor"
+                );
+                Ok(address)
+            }
+        }
+    }
+
+    /// Whether the node wallet can spend outputs to `address`, for bumping
+    /// claims through CPFP.
+    pub async fn is_wallet_address(&self, address: &Address) -> Result<bool, ClaimError> {
+        Ok(match &self.policy.cold {
+            None => true,
+            Some(ColdDestination::Address(cold)) => address != cold,
+            Some(ColdDestination::Descriptor(_)) => {
+                !self
+                    .claim_repository
+                    .is_destination_address(address)
+                    .await?
+            }
+        })
+    }
+}
+
+impl From<DescriptorError> for ClaimError {
+    fn from(value: DescriptorError) -> Self {
+        ClaimError::General(Box::new(value))
+    }
+}
+
+const CHECKSUM_INPUT_CHARSET: &str = "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// The BIP380 descriptor checksum. Returns None if the descriptor contains
+/// characters that can't be part of a descriptor.
+fn descriptor_checksum(descriptor: &str) -> Option<String> {
+    fn polymod(c: u64, value: u64) -> u64 {
+        const GENERATOR: [u64; 5] = [
+            0xf5dee51989,
+            0xa9fdca3312,
+            0x1bab10e32d,
+            0x3706b1677a,
+            0x644d626ffd,
+        ];
+        let top = c >> 35;
+        let mut c = ((c & 0x7ffffffff) << 5) ^ value;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                c ^= generator;
+            }
+        }
+        c
+    }
+
+    let mut c = 1;
+    let mut class = 0;
+    let mut class_count = 0;
+    for ch in descriptor.chars() {
+        let position = CHECKSUM_INPUT_CHARSET.find(ch)? as u64;
+        c = polymod(c, position & 31);
+        class = class * 3 + (position >> 5);
+        class_count += 1;
+        if class_count == 3 {
+            c = polymod(c, class);
+            class = 0;
+            class_count = 0;
+        }
+    }
+    if class_count > 0 {
+        c = polymod(c, class);
+    }
+    for _ in 0..8 {
+        c = polymod(c, 0);
+    }
+    c ^= 1;
+
+    Some(
+        (0..8)
+            .map(|i| CHECKSUM_CHARSET[((c >> (5 * (7 - i))) & 31) as usize] as char)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashSet,
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    use bitcoin::{
+        bip32::{DerivationPath, Xpriv, Xpub},
+        secp256k1::Secp256k1,
+        Address, Network, OutPoint, Txid,
+    };
+
+    use crate::{
+        claim::{
+            Claim, ClaimCpfp, ClaimRepository, ClaimRepositoryError, DestinationAddress,
+            OutputDisposition, UnpaidOutput,
+        },
+        wallet::{Wallet, WalletError},
+    };
+
+    use super::{
+        descriptor_checksum, ClaimDescriptor, ClaimDestination, ClaimDestinationPolicy,
+        ColdDestination, DescriptorError,
+    };
+
+    // Root key of the "abandon abandon ... about" mnemonic.
+    const ROOT_XPRV: &str = "xprv9s21ZrQH143K3GJpoapnV8SFfukcVBSfeCficPSGfubmSFDxo1kuHnLisriDvSnRRuL2Qrg5ggqHKNVpxR86QEC8w35uxmGoggxtQTPvfUu";
+
+    fn account_xpub(path: &str) -> Xpub {
+        let secp = Secp256k1::new();
+        let xprv = Xpriv::from_str(ROOT_XPRV).unwrap();
+        let xprv = xprv
+            .derive_priv(&secp, &DerivationPath::from_str(path).unwrap())
+            .unwrap();
+        Xpub::from_priv(&secp, &xprv)
+    }
+
+    #[test]
+    fn test_wpkh_address() {
+        let xpub = account_xpub("m/84'/0'/0'");
+        let descriptor = format!("wpkh([73c5da0a/84'/0'/0']{}/0/*)", xpub);
+        let descriptor = ClaimDescriptor::parse(
+            &format!(
+                "{}#{}",
+                descriptor,
+                descriptor_checksum(&descriptor).unwrap()
+            ),
+            Network::Bitcoin,
+        )
+        .unwrap();
+        assert_eq!(descriptor.descriptor(), format!("wpkh({}/0/*)", xpub));
+        assert_eq!(
+            descriptor.address(0).unwrap().to_string(),
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+        );
+    }
+
+    #[test]
+    fn test_tr_address() {
+        let xpub = account_xpub("m/86'/0'/0'");
+        let descriptor =
+            ClaimDescriptor::parse(&format!("tr({}/0/*)", xpub), Network::Bitcoin).unwrap();
+        assert_eq!(
+            descriptor.address(0).unwrap().to_string(),
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+        );
+    }
+
+    #[test]
+    fn test_bare_xpub() {
+        let xpub = account_xpub("m/84'/0'/0'");
+        let descriptor = ClaimDescriptor::parse(&xpub.to_string(), Network::Bitcoin).unwrap();
+        assert_eq!(descriptor.descriptor(), format!("wpkh({}/0/*)", xpub));
+        assert_eq!(
+            descriptor.address(0).unwrap().to_string(),
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+        );
+    }
+
+    #[test]
+    fn test_checksum() {
+        // Test vector from BIP380.
+        assert_eq!(descriptor_checksum("raw(deadbeef)").unwrap(), "89f8spxm");
+        assert!(descriptor_checksum("raw(deadbeef)\u{e9}").is_none());
+    }
+
+    #[test]
+    fn test_checksum_mismatch() {
+        let xpub = account_xpub("m/84'/0'/0'");
+        let descriptor = format!("wpkh({}/0/*)", xpub);
+        let mut checksum = descriptor_checksum(&descriptor).unwrap();
+        let last = match checksum.pop().unwrap() {
+            'q' => 'p',
+            _ => 'q',
+        };
+        checksum.push(last);
+        let result =
+            ClaimDescriptor::parse(&format!("{}#{}", descriptor, checksum), Network::Bitcoin);
+        assert!(matches!(result, Err(DescriptorError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_wrong_network() {
+        let xpub = account_xpub("m/84'/0'/0'");
+        let result = ClaimDescriptor::parse(&format!("wpkh({}/0/*)", xpub), Network::Regtest);
+        assert!(matches!(result, Err(DescriptorError::WrongNetwork)));
+    }
+
+    #[test]
+    fn test_invalid_descriptors() {
+        let xpub = account_xpub("m/84'/0'/0'");
+        for descriptor in [
+            format!("pkh({}/0/*)", xpub),
+            format!("wpkh({}/0'/*)", xpub),
+            format!("wpkh({}/0/1)", xpub),
+            format!("wpkh({})", xpub),
+            format!("wpkh([73c5da0a{}/0/*)", xpub),
+        ] {
+            assert!(
+                ClaimDescriptor::parse(&descriptor, Network::Bitcoin).is_err(),
+                "{}",
+                descriptor
+            );
+        }
+    }
+
+    /// Reserves indices atomically like the database does, and rejects
+    /// addresses that were recorded before.
+    #[derive(Default)]
+    struct MockClaimRepository {
+        next_index: Mutex<u32>,
+        addresses: Mutex<HashSet<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ClaimRepository for MockClaimRepository {
+        async fn add_claim(&self, _claim: &Claim) -> Result<(), ClaimRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn add_cpfp(&self, _cpfp: &ClaimCpfp) -> Result<(), ClaimRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn get_claims(
+            &self,
+            _outpoints: &[OutPoint],
+        ) -> Result<Vec<Claim>, ClaimRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn add_unpaid_outputs(
+            &self,
+            _outputs: &[UnpaidOutput],
+        ) -> Result<Vec<OutPoint>, ClaimRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn get_unpaid_outputs(&self) -> Result<Vec<UnpaidOutput>, ClaimRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn set_output_disposition(
+            &self,
+            _outpoint: &OutPoint,
+            _disposition: OutputDisposition,
+        ) -> Result<(), ClaimRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn reserve_destination_index(
+            &self,
+            _descriptor: &str,
+        ) -> Result<u32, ClaimRepositoryError> {
+            let mut next_index = self.next_index.lock().unwrap();
+            let index = *next_index;
+            *next_index += 1;
+            Ok(index)
+        }
+
+        async fn add_destination_address(
+            &self,
+            address: &DestinationAddress,
+        ) -> Result<(), ClaimRepositoryError> {
+            match self
+                .addresses
+                .lock()
+                .unwrap()
+                .insert(address.address.to_string())
+            {
+                true => Ok(()),
+                false => Err(ClaimRepositoryError::General(
+                    "duplicate destination address".into(),
+                )),
+            }
+        }
+
+        async fn is_destination_address(
+            &self,
+            _address: &Address,
+        ) -> Result<bool, ClaimRepositoryError> {
+            unimplemented!()
+        }
+    }
+
+    struct MockWallet;
+
+    #[async_trait::async_trait]
+    impl Wallet for MockWallet {
+        async fn new_address(&self) -> Result<Address, WalletError> {
+            unimplemented!()
+        }
+
+        async fn balance_sat(&self) -> Result<u64, WalletError> {
+            unimplemented!()
+        }
+
+        async fn send_to_address(
+            &self,
+            _address: &Address,
+            _amount_sat: u64,
+            _fee_per_kw: u32,
+        ) -> Result<Txid, WalletError> {
+            unimplemented!()
+        }
+
+        async fn find_tx_to_address(
+            &self,
+            _address: &Address,
+        ) -> Result<Option<Txid>, WalletError> {
+            unimplemented!()
+        }
+
+        async fn sweep_output(
+            &self,
+            _outpoint: &OutPoint,
+            _address: &Address,
+            _fee_per_kw: u32,
+        ) -> Result<Txid, WalletError> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_descriptor_addresses_are_unique() {
+        let xpub = account_xpub("m/84'/0'/0'");
+        let descriptor = ClaimDescriptor::parse(&xpub.to_string(), Network::Bitcoin).unwrap();
+        let destination = Arc::new(ClaimDestination::new(
+            ClaimDestinationPolicy {
+                cold: Some(ColdDestination::Descriptor(descriptor)),
+                wallet_reserve_sat: 0,
+            },
+            Arc::new(MockClaimRepository::default()),
+            Arc::new(MockWallet),
+        ));
+
+        let tasks: Vec<_> = (0..32)
+            .map(|_| {
+                let destination = Arc::clone(&destination);
+                tokio::spawn(async move { destination.next_address().await.unwrap() })
+            })
+            .collect();
+        let mut addresses = HashSet::new();
+        for task in tasks {
+            assert!(addresses.insert(task.await.unwrap()));
+        }
+        assert_eq!(addresses.len(), 32);
+    }
+}
@@ -0,0 +1,183 @@
+/// Lowest fee rate a claim is relayed with, 1 sat/vbyte.
+const MIN_RELAY_FEE_PER_KW: u32 = 253;
+
+/// Policy to raise claim fee rates as the refund deadline of a swap nears.
+/// Until the user can refund, both parties can spend the swap output, so a
+/// claim that is not confirmed in time may lose to the user's refund.
+#[derive(Clone, Debug)]
+pub struct FeeEscalation {
+    /// Number of blocks left before the refund from which the estimated fee
+    /// rate is escalated.
+    pub escalation_blocks: u32,
+    /// Factor the estimated fee rate is multiplied with when no blocks are
+    /// left. The factor grows quadratically from 1 at `escalation_blocks`, so
+    /// the fee rate rises faster the closer the deadline is.
+    pub max_multiplier: f64,
+    /// Maximum claim fee, as a percentage of the claimed value.
+    pub max_fee_percent: f64,
+    /// Claims with this number of blocks left or less are at risk of losing
+    /// to the user's refund.
+    pub at_risk_blocks: u32,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct EscalatedFee {
+    pub fee_per_kw: u32,
+    /// Whether the fee rate was lowered to stay within the maximum fee.
+    pub capped: bool,
+}
+
+impl FeeEscalation {
+    /// Factor to multiply the estimated fee rate with, with `blocks_left`
+    /// before the refund.
+    pub fn multiplier(&self, blocks_left: i32) -> f64 {
+        if self.escalation_blocks == 0 || blocks_left >= self.escalation_blocks as i32 {
+            return 1.0;
+        }
+
+        let progress = 1.0 - blocks_left.max(0) as f64 / self.escalation_blocks as f64;
+        1.0 + (self.max_multiplier - 1.0).max(0.0) * progress * progress
+    }
+
+    /// Escalates the estimated fee rate of a claim of `weight` claiming
+    /// `value_sat` with `blocks_left` before the refund, capped to the
+    /// maximum fee.
+    pub fn fee_per_kw(
+        &self,
+        estimate_per_kw: u32,
+        blocks_left: i32,
+        weight: u64,
+        value_sat: u64,
+    ) -> EscalatedFee {
+        let escalated = (estimate_per_kw as f64 * self.multiplier(blocks_left)).ceil() as u32;
+        let escalated = escalated.max(estimate_per_kw);
+        if weight == 0 {
+            return EscalatedFee {
+                fee_per_kw: escalated,
+                capped: false,
+            };
+        }
+
+        let max_fee_sat = value_sat as f64 * self.max_fee_percent / 100.0;
+        let max_fee_per_kw = (max_fee_sat * 1000.0 / weight as f64).floor() as u32;
+        let max_fee_per_kw = max_fee_per_kw.max(MIN_RELAY_FEE_PER_KW);
+        if escalated > max_fee_per_kw {
+            return EscalatedFee {
+                fee_per_kw: max_fee_per_kw,
+                capped: true,
+            };
+        }
+
+        EscalatedFee {
+            fee_per_kw: escalated,
+            capped: false,
+        }
+    }
+
+    pub fn at_risk(&self, blocks_left: i32) -> bool {
+        blocks_left <= self.at_risk_blocks as i32
+    }
+
+    /// Whether a claim with `blocks_left` before the refund is escalated or
+    /// at risk. Such claims should not wait for the user to co-sign.
+    pub fn near_deadline(&self, blocks_left: i32) -> bool {
+        blocks_left < self.escalation_blocks as i32 || self.at_risk(blocks_left)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EscalatedFee, FeeEscalation};
+
+    fn escalation() -> FeeEscalation {
+        FeeEscalation {
+            escalation_blocks: 72,
+            max_multiplier: 5.0,
+            max_fee_percent: 10.0,
+            at_risk_blocks: 6,
+        }
+    }
+
+    #[test]
+    fn test_no_escalation_before_escalation_blocks() {
+        let escalation = escalation();
+        assert_eq!(escalation.multiplier(72), 1.0);
+        assert_eq!(escalation.multiplier(500), 1.0);
+    }
+
+    #[test]
+    fn test_escalation_grows_quadratically() {
+        let escalation = escalation();
+        assert_eq!(escalation.multiplier(36), 2.0);
+        assert_eq!(escalation.multiplier(0), 5.0);
+        assert_eq!(escalation.multiplier(-10), 5.0);
+    }
+
+    #[test]
+    fn test_escalation_disabled() {
+        let escalation = FeeEscalation {
+            escalation_blocks: 0,
+            ..escalation()
+        };
+        assert_eq!(escalation.multiplier(0), 1.0);
+    }
+
+    #[test]
+    fn test_fee_escalated() {
+        let fee = escalation().fee_per_kw(1000, 36, 1000, 1_000_000);
+        assert_eq!(
+            fee,
+            EscalatedFee {
+                fee_per_kw: 2000,
+                capped: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_fee_capped() {
+        // 10% of 10_000 sat is 1000 sat, for 1000 wu that is 1000 sat/kw.
+        let fee = escalation().fee_per_kw(2000, 100, 1000, 10_000);
+        assert_eq!(
+            fee,
+            EscalatedFee {
+                fee_per_kw: 1000,
+                capped: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_fee_cap_above_min_relay_fee() {
+        let fee = escalation().fee_per_kw(1000, 100, 1000, 100);
+        assert_eq!(
+            fee,
+            EscalatedFee {
+                fee_per_kw: 253,
+                capped: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_at_risk() {
+        let escalation = escalation();
+        assert!(!escalation.at_risk(7));
+        assert!(escalation.at_risk(6));
+        assert!(escalation.at_risk(-1));
+    }
+
+    #[test]
+    fn test_near_deadline() {
+        let escalation = escalation();
+        assert!(!escalation.near_deadline(72));
+        assert!(escalation.near_deadline(71));
+
+        let escalation = FeeEscalation {
+            escalation_blocks: 0,
+            ..escalation
+        };
+        assert!(!escalation.near_deadline(7));
+        assert!(escalation.near_deadline(6));
+    }
+}
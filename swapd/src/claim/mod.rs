@@ -1,9 +1,22 @@
+mod batch;
+mod cpfp;
+mod destination;
+mod escalation;
 mod monitor;
 mod preimage_monitor;
 mod repository;
+mod reverse_refund;
 mod service;
+mod unpaid_monitor;
 
+pub use destination::{ClaimDescriptor, ClaimDestinationPolicy, ColdDestination};
+pub use escalation::FeeEscalation;
 pub use monitor::{ClaimMonitor, ClaimMonitorParams};
 pub use preimage_monitor::PreimageMonitor;
-pub use repository::{Claim, ClaimRepository, ClaimRepositoryError};
-pub use service::{ClaimError, ClaimService, ClaimServiceError};
+pub use repository::{
+    Claim, ClaimCpfp, ClaimRepository, ClaimRepositoryError, DestinationAddress, OutputDisposition,
+    UnpaidOutput,
+};
+pub use reverse_refund::{ReverseRefundMonitor, ReverseRefundMonitorParams};
+pub use service::{ClaimError, ClaimService, ClaimServiceError, CooperativeClaimError};
+pub use unpaid_monitor::{UnpaidOutputMonitor, UnpaidOutputMonitorParams};
@@ -1,31 +1,39 @@
 use std::collections::{HashMap, HashSet};
 use std::pin::Pin;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use std::{future::Future, sync::Arc};
 
-use bitcoin::OutPoint;
+use bitcoin::{Address, OutPoint, Transaction, Weight};
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
-use tokio::join;
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error, field, instrument, trace};
+use tracing::{debug, error, field, instrument, trace, warn};
 
 use crate::chain::BroadcastError;
+use crate::event::{EventBus, SwapEvent, SwapEventKind};
+use crate::metrics::Metrics;
 use crate::swap::ClaimableUtxo;
 use crate::{
     chain::{
-        ChainClient, ChainError, ChainRepository, ChainRepositoryError, FeeEstimateError,
-        FeeEstimator,
+        BlockNotifier, ChainClient, ChainError, ChainRepository, ChainRepositoryError, FeeEstimate,
+        FeeEstimateError, FeeEstimator,
     },
+    reverse::ReverseSwapRepositoryError,
     swap::{GetSwapsError, PrivateKeyProvider, SwapRepository},
     wallet::{Wallet, WalletError},
 };
 
+use super::batch::batch_claimables;
+use super::cpfp::{child_fee_per_kw, wallet_outpoint, CPFP_CHILD_WEIGHT, MAX_CPFP_DESCENDANTS};
+use super::destination::{ClaimDestination, ClaimDestinationPolicy};
+use super::escalation::FeeEscalation;
 use super::service::{ClaimService, ClaimServiceError};
 use super::ClaimError;
-use super::{repository::ClaimRepository, Claim, ClaimRepositoryError};
+use super::{
+    repository::ClaimRepository, Claim, ClaimCpfp, ClaimRepositoryError, OutputDisposition,
+};
 
-const MIN_REPLACEMENT_DIFF_SAT_PER_KW: u32 = 250;
+pub(super) const MIN_REPLACEMENT_DIFF_SAT_PER_KW: u32 = 250;
 type ClaimFut<'a> = Pin<
     Box<
         dyn Future<Output = (Result<(), ClaimError>, Option<Claim>, Vec<ClaimableUtxo>)>
@@ -44,8 +52,23 @@ where
     RR: ClaimRepository,
     W: Wallet,
 {
+    /// Script path claims are batched across swaps by the blocks left before
+    /// they can be refunded, in buckets of `batch_bucket_blocks` blocks.
+    pub batch_bucket_blocks: u32,
+    /// Maximum number of inputs of a batched claim transaction.
+    pub batch_max_inputs: usize,
     pub chain_client: Arc<CC>,
+    pub cooperative_claim_window: Duration,
+    /// Decides where new claims pay to.
+    pub destination_policy: ClaimDestinationPolicy,
+    pub event_bus: Arc<EventBus>,
+    /// Raises the estimated fee rates as the refund deadline nears.
+    pub fee_escalation: FeeEscalation,
     pub fee_estimator: Arc<FE>,
+    pub metrics: Arc<Metrics>,
+    /// Claims are attempted every `poll_interval`, or as soon as a block is
+    /// notified.
+    pub new_blocks: Arc<BlockNotifier>,
     pub poll_interval: Duration,
     pub claim_repository: Arc<RR>,
     pub claim_service: Arc<ClaimService<CC, CR, RR, SR, P>>,
@@ -62,8 +85,16 @@ where
     RR: ClaimRepository,
     W: Wallet,
 {
+    batch_bucket_blocks: u32,
+    batch_max_inputs: usize,
     chain_client: Arc<CC>,
+    cooperative_claim_window: Duration,
+    destination: ClaimDestination<RR, W>,
+    event_bus: Arc<EventBus>,
+    fee_escalation: FeeEscalation,
     fee_estimator: Arc<FE>,
+    metrics: Arc<Metrics>,
+    new_blocks: Arc<BlockNotifier>,
     poll_interval: Duration,
     claim_repository: Arc<RR>,
     claim_service: Arc<ClaimService<CC, CR, RR, SR, P>>,
@@ -82,8 +113,20 @@ where
 {
     pub fn new(params: ClaimMonitorParams<CC, CR, FE, SR, P, RR, W>) -> Self {
         Self {
+            batch_bucket_blocks: params.batch_bucket_blocks,
+            batch_max_inputs: params.batch_max_inputs,
             chain_client: params.chain_client,
+            cooperative_claim_window: params.cooperative_claim_window,
+            destination: ClaimDestination::new(
+                params.destination_policy,
+                Arc::clone(&params.claim_repository),
+                Arc::clone(&params.wallet),
+            ),
+            event_bus: params.event_bus,
+            fee_escalation: params.fee_escalation,
             fee_estimator: params.fee_estimator,
+            metrics: params.metrics,
+            new_blocks: params.new_blocks,
             poll_interval: params.poll_interval,
             claim_repository: params.claim_repository,
             claim_service: params.claim_service,
@@ -92,6 +135,7 @@ where
     }
 
     pub async fn start(&self, token: CancellationToken) -> Result<(), ClaimError> {
+        let mut new_blocks = self.new_blocks.subscribe();
         loop {
             if token.is_cancelled() {
                 return Ok(());
@@ -108,6 +152,9 @@ where
                     debug!("claim monitor shutting down");
                     break;
                 }
+                _ = new_blocks.wait() => {
+                    debug!("got new block notification, starting claim task");
+                }
                 _ = tokio::time::sleep(self.poll_interval) => {}
             }
         }
@@ -124,13 +171,23 @@ where
             .map(|claimable| (claimable.utxo.outpoint, claimable))
             .collect();
         let outpoints: Vec<_> = claimables.keys().cloned().collect();
+        let donated: HashSet<_> = self
+            .claim_repository
+            .get_unpaid_outputs()
+            .await?
+            .into_iter()
+            .filter(|output| output.disposition == Some(OutputDisposition::Donated))
+            .map(|output| output.outpoint)
+            .collect();
 
         // Get existing claims, sorted by highest fee rate and then creation time.
         let claims = self.claim_repository.get_claims(&outpoints).await?;
 
         // First remove all the outpoints where there is already an in-progress
         // claim transaction published. These in-progress claim transactions
-        // will be rechecked for fees below.
+        // will be rechecked for fees below. Claims spending outpoints of a
+        // claim with a higher fee rate were replaced, only the latest
+        // replacement is rechecked.
         let mut recheck_claims = Vec::new();
         let mut unhandled_outpoints: HashSet<OutPoint> = outpoints.iter().cloned().collect();
         for claim in claims {
@@ -140,6 +197,7 @@ where
                 .iter()
                 .map(|input| input.previous_output)
                 .collect();
+
             // Only reprocess this claim if it is still spending valid outputs.
             if !outpoints
                 .iter()
@@ -154,6 +212,17 @@ where
                 continue;
             }
 
+            if outpoints
+                .iter()
+                .any(|outpoint| !unhandled_outpoints.contains(outpoint))
+            {
+                trace!(
+                    tx_id = field::display(claim.tx.compute_txid()),
+                    "skipping replaced claim tx"
+                );
+                continue;
+            }
+
             let mut current_claimables: Vec<ClaimableUtxo> = Vec::new();
             for outpoint in &outpoints {
                 unhandled_outpoints.remove(outpoint);
@@ -168,9 +237,9 @@ where
             recheck_claims.push((claim, current_claimables));
         }
 
-        // Now group the remaining utxos by swap. Note grouping by swap is
-        // pretty much an arbitrary decision. they might as well be grouped by
-        // remaining timelock to save on fees.
+        self.record_metrics(current_height, &claimables, &recheck_claims);
+
+        // Now group the remaining utxos by swap, for the cooperative claims.
         let mut swaps = HashMap::new();
         for unhandled_outpoint in &unhandled_outpoints {
             let claimable = claimables
@@ -180,10 +249,10 @@ where
             // Be a good citizen and don't claim any funds that were not paid
             // over lightning. That can happen if the user sends another onchain
             // transaction to the same address multiple times. Users may not
-            // know this is unsafe for them to do.
-            if claimable.paid_with_request.is_none() {
-                // TODO: Handle the case where the payment result was not
-                //       persisted.
+            // know this is unsafe for them to do. Unless the output was
+            // donated, these are left to the unpaid output monitor.
+            if claimable.paid_with_request.is_none() && !donated.contains(&claimable.utxo.outpoint)
+            {
                 debug!(
                     "skipping claim for utxo '{}' as it was not paid over lightning",
                     claimable.utxo.outpoint
@@ -197,6 +266,11 @@ where
             entry.push(claimable.clone());
         }
 
+        let unclaimed_swaps: HashSet<_> = swaps.keys().cloned().collect();
+        self.claim_service
+            .retain_cooperative_claims(&unclaimed_swaps)
+            .await;
+
         let mut futures: FuturesUnordered<ClaimFut> = FuturesUnordered::new();
         for (claim, claimables) in recheck_claims {
             let fut = self.recheck_claim(current_height, claim.clone(), claimables.clone());
@@ -206,9 +280,45 @@ where
             }));
         }
 
-        for (_, claimables) in swaps {
-            let fut = self.claim(current_height, claimables.clone());
+        // Swaps are claimed on their own through the cheaper key path, while
+        // the user gets to co-sign. Everything else is claimed through the
+        // script path, batched across swaps to save on fees. Swaps close to
+        // the refund deadline don't wait for the user.
+        let mut script_path_claimables = Vec::new();
+        for (hash, claimables) in swaps {
+            let elapsed = self.claim_service.cooperative_claim_elapsed(&hash);
+            let near_deadline = claimables.iter().any(|c| {
+                self.fee_escalation
+                    .near_deadline(c.blocks_left(current_height))
+            });
+            if !near_deadline && elapsed.unwrap_or_default() < self.cooperative_claim_window {
+                let fut = self.claim_cooperatively(current_height, claimables.clone());
+                futures.push(Box::pin(async move {
+                    let res = fut.await;
+                    (res, None, claimables)
+                }));
+                continue;
+            }
 
+            if elapsed.is_some() {
+                debug!(
+                    hash = field::display(hash),
+                    near_deadline, "cooperative claim not signed, claiming through script path"
+                );
+                self.claim_service.remove_cooperative_claim(&hash).await;
+            }
+
+            script_path_claimables.extend(claimables);
+        }
+
+        let batches = batch_claimables(
+            script_path_claimables,
+            |claimable| claimable.blocks_left(current_height),
+            self.batch_bucket_blocks,
+            self.batch_max_inputs,
+        );
+        for claimables in batches {
+            let fut = self.claim(current_height, claimables.clone());
             futures.push(Box::pin(async move {
                 let res = fut.await;
                 (res, None, claimables)
@@ -248,19 +358,14 @@ where
         claim: Claim,
         claimables: Vec<ClaimableUtxo>,
     ) -> Result<(), ClaimError> {
-        let blocks_left = match claimables
-            .iter()
-            .map(|r| r.blocks_left(current_height))
-            .min()
-        {
-            Some(blocks_left) => blocks_left,
-            None => return Err(ClaimError::General("blocks_left returned none".into())),
-        };
-        let fee_estimate = self.fee_estimator.estimate_fee(blocks_left).await?;
+        let fee_estimate = self
+            .claim_fee(current_height, &claimables, claim.tx.weight())
+            .await?;
 
         let claim_txid = claim.tx.compute_txid();
         // If the feerate is still sufficient, rebroadcast the same transaction.
-        if claim.fee_per_kw + MIN_REPLACEMENT_DIFF_SAT_PER_KW > fee_estimate.sat_per_kw {
+        if claim.effective_fee_per_kw() + MIN_REPLACEMENT_DIFF_SAT_PER_KW > fee_estimate.sat_per_kw
+        {
             return match self.chain_client.broadcast_tx(claim.tx.clone()).await {
                 Ok(_) => {
                     debug!("succesfully rebroadcast claim tx '{}'", claim_txid);
@@ -280,47 +385,208 @@ where
             };
         }
 
+        // A replacement would have to pay for the CPFP child as well, so keep
+        // bumping through children once there is one.
+        if claim.cpfp.is_some() {
+            return self.bump_with_child(&claim, &fee_estimate).await;
+        }
+
         // The fee rate is not sufficient, craft a replacement transaction.
-        let replacement = self
+        let replacement = match self
             .claim_service
             .claim(
                 &claimables,
                 &fee_estimate,
                 current_height,
-                claim.destination_address,
+                claim.destination_address.clone(),
                 claim.auto_bump,
             )
-            .await?;
+            .await
+        {
+            Ok(replacement) => replacement,
+            Err(ClaimError::InsufficientFeeRejectingReplacement(e)) => {
+                debug!(
+                    tx_id = field::display(claim_txid),
+                    "replacement claim tx was rejected: '{}', bumping through cpfp instead", e
+                );
+                return self.bump_with_child(&claim, &fee_estimate).await;
+            }
+            Err(e) => return Err(e),
+        };
         debug!(
             tx_id = field::display(replacement.compute_txid()),
             prev_tx_id = field::display(claim_txid),
             "broadcasted replacement claim tx"
         );
+        self.publish_claim(&replacement, &claimables);
+        Ok(())
+    }
+
+    /// Bumps the fee of a claim with a child transaction from the node wallet,
+    /// so the claim and its descendants together pay the estimated fee rate.
+    /// The child spends the claim output, or the output of the previous child
+    /// if the claim was bumped before.
+    async fn bump_with_child(
+        &self,
+        claim: &Claim,
+        fee_estimate: &FeeEstimate,
+    ) -> Result<(), ClaimError> {
+        let claim_txid = claim.tx.compute_txid();
+        let (outpoint, ancestors_weight, ancestors_fee_per_kw, child_weight) = match &claim.cpfp {
+            // Every bump spends the output of the previous child, so the
+            // package grows by one transaction per bump. The new child has
+            // the same shape as the previous one, so it is estimated to weigh
+            // the same.
+            Some(cpfp) => {
+                if cpfp.descendants >= MAX_CPFP_DESCENDANTS {
+                    warn!(
+                        tx_id = field::display(claim_txid),
+                        cpfp_tx_id = field::display(cpfp.tx_id),
+                        "claim tx has too many cpfp descendants to bump again"
+                    );
+                    return Ok(());
+                }
+
+                let child = self.chain_client.get_transaction(&cpfp.tx_id).await?;
+                (
+                    wallet_outpoint(&child, &cpfp.destination_address).ok_or(
+                        ClaimError::General("cpfp tx does not pay to the wallet".into()),
+                    )?,
+                    claim.tx.weight().to_wu() + cpfp.descendants_weight,
+                    cpfp.package_fee_per_kw,
+                    cpfp.weight,
+                )
+            }
+            None => {
+                // Claims to a cold destination can't be bumped from the node
+                // wallet, they rely on replacements.
+                if !self
+                    .destination
+                    .is_wallet_address(&claim.destination_address)
+                    .await?
+                {
+                    warn!(
+                        tx_id = field::display(claim_txid),
+                        destination_address = field::display(&claim.destination_address),
+                        "cannot bump claim tx to a cold destination through cpfp"
+                    );
+                    return Ok(());
+                }
+
+                (
+                    wallet_outpoint(&claim.tx, &claim.destination_address).ok_or(
+                        ClaimError::General(
+                            "claim tx does not pay to its destination address".into(),
+                        ),
+                    )?,
+                    claim.tx.weight().to_wu(),
+                    claim.fee_per_kw,
+                    CPFP_CHILD_WEIGHT,
+                )
+            }
+        };
+
+        let fee_per_kw = child_fee_per_kw(
+            ancestors_weight,
+            ancestors_fee_per_kw,
+            child_weight,
+            fee_estimate.sat_per_kw,
+        );
+        let destination_address = self.wallet.new_address().await?;
+        let tx_id = self
+            .wallet
+            .sweep_output(&outpoint, &destination_address, fee_per_kw)
+            .await?;
+        debug!(
+            tx_id = field::display(tx_id),
+            claim_tx_id = field::display(claim_txid),
+            fee_per_kw,
+            package_fee_per_kw = fee_estimate.sat_per_kw,
+            "broadcasted cpfp child of claim tx"
+        );
+
+        // The child is already published, so it is recorded even if its
+        // signed weight can't be looked up.
+        let weight = match self.chain_client.get_transaction(&tx_id).await {
+            Ok(child) => child.weight().to_wu(),
+            Err(e) => {
+                warn!(
+                    tx_id = field::display(tx_id),
+                    "failed to get cpfp tx, recording its estimated weight: {:?}", e
+                );
+                child_weight
+            }
+        };
+        let (descendants, descendants_weight) = match &claim.cpfp {
+            Some(cpfp) => (cpfp.descendants + 1, cpfp.descendants_weight + weight),
+            None => (1, weight),
+        };
+        self.claim_repository
+            .add_cpfp(&ClaimCpfp {
+                creation_time: SystemTime::now(),
+                claim_tx_id: claim_txid,
+                tx_id,
+                destination_address,
+                fee_per_kw,
+                package_fee_per_kw: fee_estimate.sat_per_kw,
+                weight,
+                descendants,
+                descendants_weight,
+            })
+            .await?;
         Ok(())
     }
 
+    /// Prepares a key path claim for the claimables of a single swap, for the
+    /// user to co-sign.
     #[instrument(skip(self), level = "trace")]
-    async fn claim(
+    async fn claim_cooperatively(
         &self,
         current_height: u64,
         claimables: Vec<ClaimableUtxo>,
     ) -> Result<(), ClaimError> {
-        let blocks_left = match claimables
-            .iter()
-            .map(|r| r.blocks_left(current_height))
-            .min()
-        {
-            Some(blocks_left) => blocks_left,
-            None => return Err(ClaimError::General("blocks_left returned none".into())),
+        let hash = match claimables.first() {
+            Some(claimable) => claimable.swap.public.hash,
+            None => return Err(ClaimError::General("no claimables".into())),
         };
-        let fee_estimate_fut = self.fee_estimator.estimate_fee(blocks_left);
-        let address_fut = self.wallet.new_address();
-        let (fee_estimate_res, address_res) = join!(fee_estimate_fut, address_fut);
-        let fee_estimate = fee_estimate_res?;
-        let destination_address = address_res?;
+        if self
+            .claim_service
+            .has_pending_cooperative_claim(&claimables)
+        {
+            trace!(
+                hash = field::display(hash),
+                "waiting for user to sign cooperative claim"
+            );
+            return Ok(());
+        }
 
-        // Craft a claim transaction
+        let (fee_estimate, destination_address) =
+            self.claim_parameters(current_height, &claimables).await?;
         self.claim_service
+            .prepare_cooperative_claim(
+                &claimables,
+                &fee_estimate,
+                current_height,
+                destination_address,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Claims the claimables, possibly of several swaps, through the script
+    /// path in a single transaction.
+    #[instrument(skip(self), level = "trace")]
+    async fn claim(
+        &self,
+        current_height: u64,
+        claimables: Vec<ClaimableUtxo>,
+    ) -> Result<(), ClaimError> {
+        let (fee_estimate, destination_address) =
+            self.claim_parameters(current_height, &claimables).await?;
+
+        // Craft a claim transaction
+        let tx = self
+            .claim_service
             .claim(
                 &claimables,
                 &fee_estimate,
@@ -329,8 +595,154 @@ where
                 true,
             )
             .await?;
+        debug!(
+            tx_id = field::display(tx.compute_txid()),
+            inputs = claimables.len(),
+            "broadcasted claim tx"
+        );
+        self.publish_claim(&tx, &claimables);
         Ok(())
     }
+
+    /// Gets a new address to claim to, following the destination policy, and
+    /// the fee for claiming the claimables to it.
+    async fn claim_parameters(
+        &self,
+        current_height: u64,
+        claimables: &[ClaimableUtxo],
+    ) -> Result<(FeeEstimate, Address), ClaimError> {
+        let address = self.destination.next_address().await?;
+        let weight = self.claim_service.estimate_claim_weight(
+            claimables,
+            current_height,
+            address.clone(),
+        )?;
+        let fee_estimate = self.claim_fee(current_height, claimables, weight).await?;
+        Ok((fee_estimate, address))
+    }
+
+    /// Estimates the fee for the most urgent of the claimables, escalated by
+    /// the blocks left before the refund and capped to the maximum fee for
+    /// the claimed value.
+    async fn claim_fee(
+        &self,
+        current_height: u64,
+        claimables: &[ClaimableUtxo],
+        weight: Weight,
+    ) -> Result<FeeEstimate, ClaimError> {
+        let blocks_left = match claimables
+            .iter()
+            .map(|r| r.blocks_left(current_height))
+            .min()
+        {
+            Some(blocks_left) => blocks_left,
+            None => return Err(ClaimError::General("blocks_left returned none".into())),
+        };
+
+        let fee_estimate = self.fee_estimator.estimate_fee(blocks_left).await?;
+        let value_sat: u64 = claimables
+            .iter()
+            .map(|claimable| claimable.utxo.tx_out.value.to_sat())
+            .sum();
+        let fee = self.fee_escalation.fee_per_kw(
+            fee_estimate.sat_per_kw,
+            blocks_left,
+            weight.to_wu(),
+            value_sat,
+        );
+        let outpoints = claimables
+            .iter()
+            .map(|claimable| claimable.utxo.outpoint.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        if fee.capped {
+            warn!(
+                outpoints,
+                estimate_per_kw = fee_estimate.sat_per_kw,
+                fee_per_kw = fee.fee_per_kw,
+                "claim fee rate capped to the maximum fee"
+            );
+        }
+        if self.fee_escalation.at_risk(blocks_left) {
+            error!(
+                outpoints,
+                blocks_left,
+                fee_per_kw = fee.fee_per_kw,
+                "claim is at risk of losing to the user's refund"
+            );
+        }
+
+        Ok(FeeEstimate {
+            sat_per_kw: fee.fee_per_kw,
+        })
+    }
+
+    /// Records the value that was paid out but not claimed yet, the outputs
+    /// at risk of being refunded, and the claim transactions still waiting for
+    /// confirmation.
+    fn record_metrics(
+        &self,
+        current_height: u64,
+        claimables: &HashMap<OutPoint, ClaimableUtxo>,
+        pending_claims: &[(Claim, Vec<ClaimableUtxo>)],
+    ) {
+        let unclaimed_value_sat: u64 = claimables
+            .values()
+            .filter(|claimable| claimable.paid_with_request.is_some())
+            .map(|claimable| claimable.utxo.tx_out.value.to_sat())
+            .sum();
+        self.metrics
+            .unclaimed_value_sat
+            .set(unclaimed_value_sat as i64);
+        let at_risk = claimables
+            .values()
+            .filter(|claimable| claimable.paid_with_request.is_some())
+            .filter(|claimable| {
+                self.fee_escalation
+                    .at_risk(claimable.blocks_left(current_height))
+            })
+            .count();
+        self.metrics.claim_outputs_at_risk.set(at_risk as i64);
+
+        // Claims are sorted by fee rate, so replaced claims come after their
+        // replacement.
+        let mut spent = HashSet::new();
+        let mut fee_rates = Vec::new();
+        for (claim, _) in pending_claims {
+            let outpoints: Vec<_> = claim
+                .tx
+                .input
+                .iter()
+                .map(|input| input.previous_output)
+                .collect();
+            if outpoints.iter().any(|outpoint| spent.contains(outpoint)) {
+                continue;
+            }
+
+            spent.extend(outpoints);
+            fee_rates.push((
+                claim.tx.compute_txid().to_string(),
+                claim.effective_fee_per_kw() as i64,
+            ));
+        }
+
+        self.metrics.claim_txs_pending.set(fee_rates.len() as i64);
+        self.metrics.claim_tx_fee_rate.replace(fee_rates);
+    }
+
+    fn publish_claim(&self, tx: &Transaction, claimables: &[ClaimableUtxo]) {
+        let tx_id = tx.compute_txid();
+        let addresses: HashSet<_> = claimables
+            .iter()
+            .map(|claimable| &claimable.swap.public.address)
+            .collect();
+        for address in addresses {
+            self.event_bus.publish(SwapEvent::new(
+                address.clone(),
+                SwapEventKind::ClaimBroadcast { tx_id },
+            ));
+        }
+    }
 }
 
 impl From<ChainRepositoryError> for ClaimError {
@@ -356,7 +768,12 @@ impl From<ChainError> for ClaimError {
 
 impl From<BroadcastError> for ClaimError {
     fn from(value: BroadcastError) -> Self {
-        ClaimError::General(Box::new(value))
+        match value {
+            BroadcastError::InsufficientFeeRejectingReplacement(e) => {
+                ClaimError::InsufficientFeeRejectingReplacement(e)
+            }
+            _ => ClaimError::General(Box::new(value)),
+        }
     }
 }
 
@@ -389,3 +806,9 @@ impl From<ClaimServiceError> for ClaimError {
         ClaimError::General(Box::new(value))
     }
 }
+
+impl From<ReverseSwapRepositoryError> for ClaimError {
+    fn from(value: ReverseSwapRepositoryError) -> Self {
+        ClaimError::General(Box::new(value))
+    }
+}
@@ -6,6 +6,7 @@ use tracing::{debug, error, field};
 
 use crate::{
     chain::ChainRepository,
+    event::{EventBus, SwapEvent, SwapEventKind},
     lightning::{LightningClient, PaymentResult},
     swap::SwapRepository,
 };
@@ -17,6 +18,7 @@ where
     SR: SwapRepository,
 {
     chain_repository: Arc<CR>,
+    event_bus: Arc<EventBus>,
     lightning_client: Arc<C>,
     poll_interval: Duration,
     swap_repository: Arc<SR>,
@@ -30,12 +32,14 @@ where
 {
     pub fn new(
         chain_repository: Arc<CR>,
+        event_bus: Arc<EventBus>,
         lightning_client: Arc<C>,
         poll_interval: Duration,
         swap_repository: Arc<SR>,
     ) -> Self {
         Self {
             chain_repository,
+            event_bus,
             lightning_client,
             poll_interval,
             swap_repository,
@@ -67,25 +71,25 @@ where
     async fn do_query_preimages(&self) -> Result<(), Box<dyn std::error::Error>> {
         let utxos = self.chain_repository.get_utxos().await?;
         let addresses: Vec<_> = utxos.iter().map(|u| u.address.clone()).collect();
-        let hashes: Vec<_> = self
+        let swaps: Vec<_> = self
             .swap_repository
             .get_swaps(&addresses)
             .await?
             .into_iter()
             .filter(|swap| swap.1.preimage.is_none())
-            .map(|swap| swap.1.swap.public.hash)
+            .map(|swap| (swap.1.swap.public.hash, swap.1.swap.public.address))
             .collect();
 
         let mut futures = FuturesUnordered::new();
-        for hash in hashes {
+        for (hash, address) in swaps {
             let fut = self.lightning_client.get_preimage(hash);
             futures.push(async move {
                 let result = fut.await;
-                (hash, result)
+                (hash, address, result)
             });
         }
 
-        while let Some((hash, result)) = futures.next().await {
+        while let Some((hash, address, result)) = futures.next().await {
             let maybe_preimage = match result {
                 Ok(maybe_preimage) => maybe_preimage,
                 Err(e) => {
@@ -124,6 +128,11 @@ where
                 );
                 continue;
             }
+
+            self.event_bus.publish(SwapEvent::new(
+                address,
+                SwapEventKind::Paid { payment_hash: hash },
+            ));
         }
 
         Ok(())
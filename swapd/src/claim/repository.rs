@@ -1,6 +1,6 @@
-use std::time::SystemTime;
+use std::{fmt::Display, str::FromStr, time::SystemTime};
 
-use bitcoin::{Address, OutPoint, Transaction};
+use bitcoin::{hashes::sha256, Address, OutPoint, Transaction, Txid};
 use thiserror::Error;
 
 #[derive(Clone, Debug)]
@@ -10,12 +10,131 @@ pub struct Claim {
     pub destination_address: Address,
     pub fee_per_kw: u32,
     pub auto_bump: bool,
+    /// The child transaction with the highest package fee rate spending the
+    /// claim output, if the claim was bumped through CPFP.
+    pub cpfp: Option<ClaimCpfp>,
+}
+
+impl Claim {
+    /// Fee rate the claim effectively pays, including its CPFP child.
+    pub fn effective_fee_per_kw(&self) -> u32 {
+        match &self.cpfp {
+            Some(cpfp) => cpfp.package_fee_per_kw.max(self.fee_per_kw),
+            None => self.fee_per_kw,
+        }
+    }
+}
+
+/// A child transaction from the node wallet spending the output of a claim
+/// transaction, paying for the claim and itself as a package.
+#[derive(Clone, Debug)]
+pub struct ClaimCpfp {
+    pub creation_time: SystemTime,
+    pub claim_tx_id: Txid,
+    pub tx_id: Txid,
+    pub destination_address: Address,
+    /// Fee rate of the child transaction itself.
+    pub fee_per_kw: u32,
+    /// Fee rate of the claim and its children together.
+    pub package_fee_per_kw: u32,
+    /// Weight of the signed child transaction.
+    pub weight: u64,
+    /// Number of children chained below the claim, including this one.
+    pub descendants: u64,
+    /// Weight of this child and the earlier children it spends from, without
+    /// the claim.
+    pub descendants_weight: u64,
+}
+
+/// What happens to a swap output that was not paid over lightning, because
+/// it was sent to the swap address after the swap was paid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputDisposition {
+    /// The user left the output to the operator. It is claimed like a paid
+    /// output.
+    Donated,
+    /// The operator leaves the output for the user to refund after the lock
+    /// time.
+    Abandoned,
+    /// A cooperative refund was signed for the output.
+    Refunded,
+}
+
+impl OutputDisposition {
+    /// Whether an output with disposition `current` can get this
+    /// disposition. An output that is refunded cannot be donated and the
+    /// other way around, because then both parties could spend it.
+    pub fn can_replace(&self, current: Option<OutputDisposition>) -> bool {
+        !matches!(
+            (current, self),
+            (
+                Some(OutputDisposition::Refunded),
+                OutputDisposition::Donated
+            ) | (
+                Some(OutputDisposition::Refunded),
+                OutputDisposition::Abandoned
+            ) | (
+                Some(OutputDisposition::Donated),
+                OutputDisposition::Refunded
+            )
+        )
+    }
+}
+
+impl Display for OutputDisposition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputDisposition::Donated => write!(f, "donated"),
+            OutputDisposition::Abandoned => write!(f, "abandoned"),
+            OutputDisposition::Refunded => write!(f, "refunded"),
+        }
+    }
+}
+
+impl FromStr for OutputDisposition {
+    type Err = ClaimRepositoryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "donated" => Ok(OutputDisposition::Donated),
+            "abandoned" => Ok(OutputDisposition::Abandoned),
+            "refunded" => Ok(OutputDisposition::Refunded),
+            _ => Err(ClaimRepositoryError::InvalidDisposition(s.to_string())),
+        }
+    }
+}
+
+/// A swap output that was not paid over lightning, while the swap itself was
+/// paid.
+#[derive(Clone, Debug)]
+pub struct UnpaidOutput {
+    pub outpoint: OutPoint,
+    pub swap_hash: sha256::Hash,
+    pub address: Address,
+    pub amount_sat: u64,
+    pub creation_time: SystemTime,
+    pub disposition: Option<OutputDisposition>,
+}
+
+/// A claim address derived from a watch-only descriptor.
+#[derive(Clone, Debug)]
+pub struct DestinationAddress {
+    pub address: Address,
+    pub descriptor: String,
+    pub derivation_index: u32,
+    pub creation_time: SystemTime,
 }
 
 #[derive(Debug, Error)]
 pub enum ClaimRepositoryError {
     #[error("invalid timestamp")]
     InvalidTimestamp,
+    #[error("invalid disposition: {0}")]
+    InvalidDisposition(String),
+    #[error("unpaid output not found")]
+    UnpaidOutputNotFound,
+    #[error("unpaid output is already {0}")]
+    DispositionConflict(OutputDisposition),
     #[error("{0}")]
     General(Box<dyn std::error::Error + Sync + Send>),
 }
@@ -24,7 +143,46 @@ pub enum ClaimRepositoryError {
 pub trait ClaimRepository {
     async fn add_claim(&self, claim: &Claim) -> Result<(), ClaimRepositoryError>;
 
+    /// Records a CPFP child of an existing claim.
+    async fn add_cpfp(&self, cpfp: &ClaimCpfp) -> Result<(), ClaimRepositoryError>;
+
     /// Get all claims where the inputs haven't been spent yet, sorted by fee
     /// rate desc, then creation time desc.
     async fn get_claims(&self, outpoints: &[OutPoint]) -> Result<Vec<Claim>, ClaimRepositoryError>;
+
+    /// Records outputs that were not paid over lightning. Outputs that were
+    /// recorded before are left as is. Returns the newly recorded outputs.
+    async fn add_unpaid_outputs(
+        &self,
+        outputs: &[UnpaidOutput],
+    ) -> Result<Vec<OutPoint>, ClaimRepositoryError>;
+
+    /// Get the recorded unpaid outputs that are not spent in a block yet.
+    async fn get_unpaid_outputs(&self) -> Result<Vec<UnpaidOutput>, ClaimRepositoryError>;
+
+    /// Sets the disposition of a recorded unpaid output, if it can replace
+    /// the current disposition.
+    async fn set_output_disposition(
+        &self,
+        outpoint: &OutPoint,
+        disposition: OutputDisposition,
+    ) -> Result<(), ClaimRepositoryError>;
+
+    /// Reserves the next index to derive an address from the descriptor at.
+    /// Every call returns a different index, also when called concurrently.
+    async fn reserve_destination_index(
+        &self,
+        descriptor: &str,
+    ) -> Result<u32, ClaimRepositoryError>;
+
+    /// Records an address derived from a descriptor, so it is not derived
+    /// again.
+    async fn add_destination_address(
+        &self,
+        address: &DestinationAddress,
+    ) -> Result<(), ClaimRepositoryError>;
+
+    /// Whether the address was derived from a descriptor.
+    async fn is_destination_address(&self, address: &Address)
+        -> Result<bool, ClaimRepositoryError>;
 }
@@ -0,0 +1,209 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, field, info, instrument};
+
+use crate::{
+    chain::{BroadcastError, ChainClient, ChainRepository, FeeEstimator},
+    reverse::{ReverseSwapRefund, ReverseSwapRepository, ReverseSwapState, ReverseSwapStatus},
+    swap::{PrivateKeyProvider, SwapService},
+    wallet::Wallet,
+};
+
+use super::{monitor::MIN_REPLACEMENT_DIFF_SAT_PER_KW, ClaimError};
+
+const REFUND_CONF_TARGET: i32 = 6;
+
+pub struct ReverseRefundMonitorParams<CC, CR, FE, P, RR, W>
+where
+    CC: ChainClient,
+    CR: ChainRepository,
+    FE: FeeEstimator,
+    P: PrivateKeyProvider,
+    RR: ReverseSwapRepository,
+    W: Wallet,
+{
+    pub chain_client: Arc<CC>,
+    pub chain_repository: Arc<CR>,
+    pub fee_estimator: Arc<FE>,
+    pub poll_interval: Duration,
+    pub reverse_swap_repository: Arc<RR>,
+    pub swap_service: Arc<SwapService<P>>,
+    pub wallet: Arc<W>,
+}
+
+/// Takes back the funds of reverse swaps the user did not claim before the
+/// lock height, through the refund leaf of the swap output.
+pub struct ReverseRefundMonitor<CC, CR, FE, P, RR, W>
+where
+    CC: ChainClient,
+    CR: ChainRepository,
+    FE: FeeEstimator,
+    P: PrivateKeyProvider,
+    RR: ReverseSwapRepository,
+    W: Wallet,
+{
+    chain_client: Arc<CC>,
+    chain_repository: Arc<CR>,
+    fee_estimator: Arc<FE>,
+    poll_interval: Duration,
+    reverse_swap_repository: Arc<RR>,
+    swap_service: Arc<SwapService<P>>,
+    wallet: Arc<W>,
+}
+
+impl<CC, CR, FE, P, RR, W> ReverseRefundMonitor<CC, CR, FE, P, RR, W>
+where
+    CC: ChainClient + Send + Sync,
+    CR: ChainRepository + Send + Sync,
+    FE: FeeEstimator + Send + Sync,
+    P: PrivateKeyProvider + Send + Sync,
+    RR: ReverseSwapRepository + Send + Sync,
+    W: Wallet + Send + Sync,
+{
+    pub fn new(params: ReverseRefundMonitorParams<CC, CR, FE, P, RR, W>) -> Self {
+        Self {
+            chain_client: params.chain_client,
+            chain_repository: params.chain_repository,
+            fee_estimator: params.fee_estimator,
+            poll_interval: params.poll_interval,
+            reverse_swap_repository: params.reverse_swap_repository,
+            swap_service: params.swap_service,
+            wallet: params.wallet,
+        }
+    }
+
+    pub async fn start(&self, token: CancellationToken) -> Result<(), ClaimError> {
+        loop {
+            if token.is_cancelled() {
+                return Ok(());
+            }
+
+            debug!("starting reverse refund task");
+            match self.do_refund().await {
+                Ok(_) => debug!("reverse refund task completed succesfully"),
+                Err(e) => error!("reverse refund task failed with: {:?}", e),
+            }
+
+            tokio::select! {
+                _ = token.cancelled() => {
+                    debug!("reverse refund monitor shutting down");
+                    break;
+                }
+                _ = tokio::time::sleep(self.poll_interval) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self), level = "trace")]
+    async fn do_refund(&self) -> Result<(), ClaimError> {
+        let current_height = self.chain_client.get_blockheight().await?;
+        let reverse_swaps = self
+            .reverse_swap_repository
+            .get_reverse_swaps_by_status(&[ReverseSwapStatus::Funded])
+            .await?;
+        for reverse_swap in reverse_swaps {
+            if (reverse_swap.reverse_swap.public.lock_height as u64) > current_height {
+                continue;
+            }
+
+            if let Err(e) = self.refund(current_height, &reverse_swap).await {
+                error!(
+                    hash = field::display(reverse_swap.reverse_swap.public.hash),
+                    "failed to refund reverse swap: {:?}", e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn refund(
+        &self,
+        current_height: u64,
+        reverse_swap: &ReverseSwapState,
+    ) -> Result<(), ClaimError> {
+        let public = &reverse_swap.reverse_swap.public;
+        let txos = self
+            .chain_repository
+            .get_txos_for_address_with_spends(&public.address)
+            .await?;
+        let utxo = match txos.into_iter().find(|txo| {
+            txo.spend.is_none() && Some(txo.txo.outpoint.txid) == reverse_swap.funding_tx_id
+        }) {
+            Some(utxo) => utxo.txo,
+            None => return Ok(()),
+        };
+
+        let fee_estimate = self.fee_estimator.estimate_fee(REFUND_CONF_TARGET).await?;
+        let refunds = self
+            .reverse_swap_repository
+            .get_refunds(&public.hash)
+            .await?;
+        let destination_address = match refunds.first() {
+            Some(refund) => {
+                // If the feerate is still sufficient, rebroadcast the same
+                // transaction.
+                if refund.fee_per_kw + MIN_REPLACEMENT_DIFF_SAT_PER_KW > fee_estimate.sat_per_kw {
+                    let tx_id = refund.tx.compute_txid();
+                    return match self.chain_client.broadcast_tx(refund.tx.clone()).await {
+                        Ok(_) => {
+                            debug!("succesfully rebroadcast reverse refund tx '{}'", tx_id);
+                            Ok(())
+                        }
+                        Err(BroadcastError::InsufficientFeeRejectingReplacement(e)) => {
+                            debug!(
+                                "rebroadcast reverse refund tx '{}' returned expected error '{}'",
+                                tx_id, e
+                            );
+                            Ok(())
+                        }
+                        Err(e) => Err(e.into()),
+                    };
+                }
+
+                refund.destination_address.clone()
+            }
+            None => self.wallet.new_address().await?,
+        };
+
+        let tx = self
+            .swap_service
+            .create_reverse_refund_tx(
+                &reverse_swap.reverse_swap,
+                &utxo,
+                &fee_estimate,
+                current_height,
+                destination_address.clone(),
+            )
+            .await?;
+        info!(
+            hash = field::display(public.hash),
+            fee_per_kw = fee_estimate.sat_per_kw,
+            outpoint = field::display(utxo.outpoint),
+            tx_id = field::display(tx.compute_txid()),
+            "broadcasting reverse refund tx"
+        );
+        self.chain_client.broadcast_tx(tx.clone()).await?;
+        self.reverse_swap_repository
+            .add_refund(
+                &public.hash,
+                &ReverseSwapRefund {
+                    creation_time: SystemTime::now(),
+                    tx,
+                    destination_address: destination_address.clone(),
+                    fee_per_kw: fee_estimate.sat_per_kw,
+                },
+            )
+            .await?;
+        self.chain_repository
+            .add_watch_address(&destination_address)
+            .await?;
+        Ok(())
+    }
+}
@@ -1,6 +1,11 @@
-use std::{sync::Arc, time::SystemTime};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
 
-use bitcoin::{Address, Transaction};
+use bitcoin::{hashes::sha256, Address, OutPoint, Transaction, Weight};
+use secp256k1::musig::{MusigPartialSignature, MusigPubNonce};
 use thiserror::Error;
 use tracing::{debug, field};
 
@@ -8,11 +13,12 @@ use crate::{
     chain::{ChainClient, ChainRepository, ChainRepositoryError, FeeEstimate},
     claim::Claim,
     swap::{
-        ClaimableUtxo, GetSwapsError, PrivateKeyProvider, SwapError, SwapRepository, SwapService,
+        ClaimableUtxo, CooperativeClaimTx, GetSwapsError, PrivateKeyProvider, SwapError,
+        SwapRepository, SwapService,
     },
 };
 
-use super::ClaimRepository;
+use super::{ClaimRepository, ClaimRepositoryError, OutputDisposition, UnpaidOutput};
 
 #[derive(Debug, Error)]
 pub enum ClaimServiceError {
@@ -24,10 +30,38 @@ pub enum ClaimServiceError {
 
 #[derive(Debug, Error)]
 pub enum ClaimError {
+    #[error("insufficient fee, rejecting replacement: {0}")]
+    InsufficientFeeRejectingReplacement(String),
+    #[error("{0}")]
+    General(Box<dyn std::error::Error + Sync + Send>),
+}
+
+#[derive(Debug, Error)]
+pub enum CooperativeClaimError {
+    #[error("no cooperative claim pending")]
+    NotFound,
+    #[error("invalid signature")]
+    InvalidSignature,
     #[error("{0}")]
     General(Box<dyn std::error::Error + Sync + Send>),
 }
 
+/// A key path claim transaction waiting for the user's signatures.
+#[derive(Debug)]
+struct PendingCooperativeClaim {
+    claim_tx: CooperativeClaimTx,
+    destination_address: Address,
+    fee_per_kw: u32,
+}
+
+#[derive(Debug)]
+struct CooperativeClaim {
+    started_at: Instant,
+    // Taken out while the user's signatures are being processed, so every
+    // nonce is used at most once.
+    pending: Option<PendingCooperativeClaim>,
+}
+
 #[derive(Debug)]
 pub struct ClaimService<CC, CR, RR, SR, P>
 where
@@ -42,6 +76,7 @@ where
     claim_repository: Arc<RR>,
     swap_repository: Arc<SR>,
     swap_service: Arc<SwapService<P>>,
+    cooperative_claims: Mutex<HashMap<sha256::Hash, CooperativeClaim>>,
 }
 
 impl<CC, CR, RR, SR, P> ClaimService<CC, CR, RR, SR, P>
@@ -65,6 +100,7 @@ where
             claim_repository,
             swap_repository,
             swap_service,
+            cooperative_claims: Mutex::new(HashMap::new()),
         }
     }
 
@@ -111,19 +147,260 @@ where
         destination_address: Address,
         auto_bump: bool,
     ) -> Result<Transaction, ClaimError> {
-        let tx = self.swap_service.create_claim_tx(
+        let tx = self
+            .swap_service
+            .create_claim_tx(
+                claimables,
+                fee_estimate,
+                current_height,
+                destination_address.clone(),
+            )
+            .await?;
+
+        self.publish_claim(
+            tx,
+            claimables,
+            fee_estimate.sat_per_kw,
+            destination_address,
+            auto_bump,
+        )
+        .await
+    }
+
+    /// Unpaid swap outputs that are not spent in a block yet.
+    pub async fn get_unpaid_outputs(&self) -> Result<Vec<UnpaidOutput>, ClaimRepositoryError> {
+        self.claim_repository.get_unpaid_outputs().await
+    }
+
+    pub async fn set_output_disposition(
+        &self,
+        outpoint: &OutPoint,
+        disposition: OutputDisposition,
+    ) -> Result<(), ClaimRepositoryError> {
+        self.claim_repository
+            .set_output_disposition(outpoint, disposition)
+            .await
+    }
+
+    /// Weight of the script path claim transaction of the claimables.
+    pub fn estimate_claim_weight(
+        &self,
+        claimables: &[ClaimableUtxo],
+        current_height: u64,
+        destination_address: Address,
+    ) -> Result<Weight, ClaimError> {
+        Ok(self.swap_service.estimate_claim_weight(
             claimables,
-            fee_estimate,
             current_height,
-            destination_address.clone(),
-        )?;
+            destination_address,
+        )?)
+    }
+
+    /// Time since the cooperative claim for the swap with the given hash was
+    /// first prepared, if any.
+    pub fn cooperative_claim_elapsed(&self, hash: &sha256::Hash) -> Option<Duration> {
+        let cooperative_claims = self.cooperative_claims.lock().unwrap();
+        cooperative_claims
+            .get(hash)
+            .map(|claim| claim.started_at.elapsed())
+    }
+
+    /// Whether a cooperative claim transaction spending exactly these
+    /// claimables is waiting for the user's signatures.
+    pub fn has_pending_cooperative_claim(&self, claimables: &[ClaimableUtxo]) -> bool {
+        let hash = match claimables.first() {
+            Some(claimable) => claimable.swap.public.hash,
+            None => return false,
+        };
+        let outpoints: HashSet<OutPoint> = claimables.iter().map(|c| c.utxo.outpoint).collect();
+        let cooperative_claims = self.cooperative_claims.lock().unwrap();
+        match cooperative_claims
+            .get(&hash)
+            .and_then(|c| c.pending.as_ref())
+        {
+            Some(pending) => {
+                let pending_outpoints: HashSet<OutPoint> = pending
+                    .claim_tx
+                    .tx
+                    .input
+                    .iter()
+                    .map(|input| input.previous_output)
+                    .collect();
+                pending_outpoints == outpoints
+            }
+            None => false,
+        }
+    }
+
+    /// Prepares a key path claim transaction for the claimables of a single
+    /// swap, for the user to co-sign. Replaces an existing pending claim for
+    /// the same swap, but keeps its start time.
+    pub async fn prepare_cooperative_claim(
+        &self,
+        claimables: &[ClaimableUtxo],
+        fee_estimate: &FeeEstimate,
+        current_height: u64,
+        destination_address: Address,
+    ) -> Result<(), ClaimError> {
+        let hash = match claimables.first() {
+            Some(claimable) => claimable.swap.public.hash,
+            None => return Err(ClaimError::General("no claimables".into())),
+        };
+        let claim_tx = self
+            .swap_service
+            .create_cooperative_claim_tx(
+                claimables,
+                fee_estimate,
+                current_height,
+                destination_address.clone(),
+            )
+            .await?;
+        debug!(
+            hash = field::display(hash),
+            tx_id = field::display(claim_tx.tx.compute_txid()),
+            "prepared cooperative claim tx"
+        );
+        let pending = PendingCooperativeClaim {
+            claim_tx,
+            destination_address,
+            fee_per_kw: fee_estimate.sat_per_kw,
+        };
+        let replaced = {
+            let mut cooperative_claims = self.cooperative_claims.lock().unwrap();
+            cooperative_claims
+                .entry(hash)
+                .or_insert(CooperativeClaim {
+                    started_at: Instant::now(),
+                    pending: None,
+                })
+                .pending
+                .replace(pending)
+        };
+        if let Some(replaced) = replaced {
+            self.swap_service
+                .release_cooperative_claim_tx(replaced.claim_tx)
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Gets the unsigned claim transaction and our nonces for the pending
+    /// cooperative claim of the swap with the given hash.
+    pub fn get_cooperative_claim(
+        &self,
+        hash: &sha256::Hash,
+    ) -> Result<(Transaction, Vec<MusigPubNonce>), CooperativeClaimError> {
+        let cooperative_claims = self.cooperative_claims.lock().unwrap();
+        match cooperative_claims
+            .get(hash)
+            .and_then(|c| c.pending.as_ref())
+        {
+            Some(pending) => Ok((
+                pending.claim_tx.tx.clone(),
+                pending.claim_tx.our_pub_nonces.clone(),
+            )),
+            None => Err(CooperativeClaimError::NotFound),
+        }
+    }
+
+    /// Completes the pending cooperative claim of the swap with the given
+    /// hash with the user's nonces and partial signatures, and publishes it.
+    pub async fn complete_cooperative_claim(
+        &self,
+        hash: &sha256::Hash,
+        their_pub_nonces: &[MusigPubNonce],
+        their_partial_sigs: &[MusigPartialSignature],
+    ) -> Result<Transaction, CooperativeClaimError> {
+        let pending = {
+            let mut cooperative_claims = self.cooperative_claims.lock().unwrap();
+            let cooperative_claim = cooperative_claims
+                .get_mut(hash)
+                .ok_or(CooperativeClaimError::NotFound)?;
+            let pending = cooperative_claim
+                .pending
+                .as_ref()
+                .ok_or(CooperativeClaimError::NotFound)?;
+
+            // Verify before taking the claim, so invalid signatures leave it
+            // pending and our nonces are only ever used with valid ones.
+            self.swap_service.verify_cooperative_claim_signatures(
+                &pending.claim_tx,
+                their_pub_nonces,
+                their_partial_sigs,
+            )?;
+            cooperative_claim
+                .pending
+                .take()
+                .ok_or(CooperativeClaimError::NotFound)?
+        };
+
+        let claimables = pending.claim_tx.claimables.clone();
+        let tx = self
+            .swap_service
+            .sign_cooperative_claim_tx(pending.claim_tx, their_pub_nonces, their_partial_sigs)
+            .await?;
+        let tx = self
+            .publish_claim(
+                tx,
+                &claimables,
+                pending.fee_per_kw,
+                pending.destination_address,
+                true,
+            )
+            .await?;
 
+        self.cooperative_claims.lock().unwrap().remove(hash);
+        Ok(tx)
+    }
+
+    /// Drops the cooperative claim for the swap with the given hash, so it
+    /// will be claimed through the script path.
+    pub async fn remove_cooperative_claim(&self, hash: &sha256::Hash) {
+        let removed = self.cooperative_claims.lock().unwrap().remove(hash);
+        if let Some(pending) = removed.and_then(|c| c.pending) {
+            self.swap_service
+                .release_cooperative_claim_tx(pending.claim_tx)
+                .await;
+        }
+    }
+
+    /// Drops cooperative claims of swaps that are no longer waiting to be
+    /// claimed.
+    pub async fn retain_cooperative_claims(&self, hashes: &HashSet<sha256::Hash>) {
+        let removed: Vec<_> = {
+            let mut cooperative_claims = self.cooperative_claims.lock().unwrap();
+            let removed_hashes: Vec<_> = cooperative_claims
+                .keys()
+                .filter(|hash| !hashes.contains(*hash))
+                .cloned()
+                .collect();
+            removed_hashes
+                .iter()
+                .filter_map(|hash| cooperative_claims.remove(hash))
+                .filter_map(|c| c.pending)
+                .collect()
+        };
+        for pending in removed {
+            self.swap_service
+                .release_cooperative_claim_tx(pending.claim_tx)
+                .await;
+        }
+    }
+
+    async fn publish_claim(
+        &self,
+        tx: Transaction,
+        claimables: &[ClaimableUtxo],
+        fee_per_kw: u32,
+        destination_address: Address,
+        auto_bump: bool,
+    ) -> Result<Transaction, ClaimError> {
         let outpoints: Vec<_> = claimables
             .iter()
             .map(|r| r.utxo.outpoint.to_string())
             .collect();
         debug!(
-            fee_per_kw = fee_estimate.sat_per_kw,
+            fee_per_kw,
             outpoints = field::debug(outpoints),
             tx_id = field::display(tx.compute_txid()),
             "broadcasting claim tx"
@@ -133,9 +410,10 @@ where
             .add_claim(&Claim {
                 creation_time: SystemTime::now(),
                 destination_address: destination_address.clone(),
-                fee_per_kw: fee_estimate.sat_per_kw,
+                fee_per_kw,
                 tx: tx.clone(),
                 auto_bump,
+                cpfp: None,
             })
             .await?;
         self.chain_repository
@@ -162,3 +440,23 @@ impl From<GetSwapsError> for ClaimServiceError {
         ClaimServiceError::GetSwaps(value)
     }
 }
+
+impl From<SwapError> for CooperativeClaimError {
+    fn from(value: SwapError) -> Self {
+        match value {
+            SwapError::InvalidCooperativeSignature => CooperativeClaimError::InvalidSignature,
+            e => CooperativeClaimError::General(Box::new(e)),
+        }
+    }
+}
+
+impl From<ClaimError> for CooperativeClaimError {
+    fn from(value: ClaimError) -> Self {
+        match value {
+            ClaimError::InsufficientFeeRejectingReplacement(_) => {
+                CooperativeClaimError::General(Box::new(value))
+            }
+            ClaimError::General(e) => CooperativeClaimError::General(e),
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use bitcoin::Amount;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, field, warn};
+
+use crate::{
+    chain::{ChainClient, ChainRepository},
+    event::{EventBus, SwapEvent, SwapEventKind},
+    lightning::{LightningClient, LightningError, PaymentResult, PaymentState},
+    metrics::Metrics,
+    swap::{PaymentAttempt, PrivateKeyProvider, SwapRepository},
+};
+
+use super::{ClaimRepository, ClaimService, UnpaidOutput};
+
+pub struct UnpaidOutputMonitorParams<CC, CR, L, P, RR, SR>
+where
+    CC: ChainClient,
+    CR: ChainRepository,
+    L: LightningClient,
+    P: PrivateKeyProvider,
+    RR: ClaimRepository,
+    SR: SwapRepository,
+{
+    pub claim_repository: Arc<RR>,
+    pub claim_service: Arc<ClaimService<CC, CR, RR, SR, P>>,
+    pub event_bus: Arc<EventBus>,
+    pub lightning_client: Arc<L>,
+    pub metrics: Arc<Metrics>,
+    pub poll_interval: Duration,
+    pub swap_repository: Arc<SR>,
+}
+
+/// Finds outputs of paid swaps that were not paid over lightning. These are
+/// not claimed, unless the operator marks them as donated. Payment attempts
+/// of these swaps without a persisted result are reconciled with the
+/// lightning node first, because the output may have been paid after all.
+/// The user is offered a cooperative refund for the outputs that remain.
+pub struct UnpaidOutputMonitor<CC, CR, L, P, RR, SR>
+where
+    CC: ChainClient,
+    CR: ChainRepository,
+    L: LightningClient,
+    P: PrivateKeyProvider,
+    RR: ClaimRepository,
+    SR: SwapRepository,
+{
+    claim_repository: Arc<RR>,
+    claim_service: Arc<ClaimService<CC, CR, RR, SR, P>>,
+    event_bus: Arc<EventBus>,
+    lightning_client: Arc<L>,
+    metrics: Arc<Metrics>,
+    poll_interval: Duration,
+    swap_repository: Arc<SR>,
+}
+
+impl<CC, CR, L, P, RR, SR> UnpaidOutputMonitor<CC, CR, L, P, RR, SR>
+where
+    CC: ChainClient,
+    CR: ChainRepository,
+    L: LightningClient,
+    P: PrivateKeyProvider,
+    RR: ClaimRepository,
+    SR: SwapRepository,
+{
+    pub fn new(params: UnpaidOutputMonitorParams<CC, CR, L, P, RR, SR>) -> Self {
+        Self {
+            claim_repository: params.claim_repository,
+            claim_service: params.claim_service,
+            event_bus: params.event_bus,
+            lightning_client: params.lightning_client,
+            metrics: params.metrics,
+            poll_interval: params.poll_interval,
+            swap_repository: params.swap_repository,
+        }
+    }
+
+    pub async fn start(&self, token: CancellationToken) -> Result<(), Box<dyn std::error::Error>> {
+        loop {
+            if token.is_cancelled() {
+                return Ok(());
+            }
+
+            if let Err(e) = self.do_check_unpaid_outputs().await {
+                error!("failed to check unpaid outputs: {:?}", e);
+            }
+
+            tokio::select! {
+                _ = token.cancelled() => {
+                    debug!("unpaid output monitor shutting down");
+                    break;
+                }
+                _ = tokio::time::sleep(self.poll_interval) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn do_check_unpaid_outputs(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let claimables = self.claim_service.list_claimable().await?;
+        let unpaid: Vec<_> = claimables
+            .into_iter()
+            .filter(|claimable| claimable.paid_with_request.is_none())
+            .collect();
+        let hashes: HashSet<_> = unpaid
+            .iter()
+            .map(|claimable| claimable.swap.public.hash)
+            .collect();
+
+        // Outputs of swaps with a payment attempt without result may have
+        // been paid after all. The swap is paid already, so no payment is in
+        // flight anymore and the node knows the final result. Swaps with
+        // attempts that are reconciled are checked again in the next round.
+        let mut unreconciled = HashSet::new();
+        let attempts = self
+            .swap_repository
+            .get_unhandled_payment_attempts()
+            .await?;
+        for attempt in attempts
+            .iter()
+            .filter(|attempt| hashes.contains(&attempt.payment_hash))
+        {
+            unreconciled.insert(attempt.payment_hash);
+            if let Err(e) = self.reconcile_payment_attempt(attempt).await {
+                error!(
+                    payment_hash = field::display(attempt.payment_hash),
+                    label = attempt.label,
+                    "failed to reconcile payment attempt: {:?}",
+                    e
+                );
+            }
+        }
+
+        let now = SystemTime::now();
+        let outputs: Vec<_> = unpaid
+            .iter()
+            .filter(|claimable| !unreconciled.contains(&claimable.swap.public.hash))
+            .map(|claimable| UnpaidOutput {
+                outpoint: claimable.utxo.outpoint,
+                swap_hash: claimable.swap.public.hash,
+                address: claimable.swap.public.address.clone(),
+                amount_sat: claimable.utxo.tx_out.value.to_sat(),
+                creation_time: now,
+                disposition: None,
+            })
+            .collect();
+        if !outputs.is_empty() {
+            let added = self.claim_repository.add_unpaid_outputs(&outputs).await?;
+            for output in outputs
+                .iter()
+                .filter(|output| added.contains(&output.outpoint))
+            {
+                warn!(
+                    outpoint = field::display(output.outpoint),
+                    address = field::display(&output.address),
+                    amount_sat = output.amount_sat,
+                    "swap output was not paid over lightning, offering a cooperative refund"
+                );
+                self.event_bus.publish(SwapEvent::new(
+                    output.address.clone(),
+                    SwapEventKind::RefundOffered {
+                        outpoint: output.outpoint,
+                        amount: Amount::from_sat(output.amount_sat),
+                    },
+                ));
+            }
+        }
+
+        let undecided: Vec<_> = self
+            .claim_repository
+            .get_unpaid_outputs()
+            .await?
+            .into_iter()
+            .filter(|output| output.disposition.is_none())
+            .collect();
+        self.metrics.unpaid_outputs.set(undecided.len() as i64);
+        self.metrics
+            .unpaid_value_sat
+            .set(undecided.iter().map(|o| o.amount_sat).sum::<u64>() as i64);
+        Ok(())
+    }
+
+    async fn reconcile_payment_attempt(
+        &self,
+        attempt: &PaymentAttempt,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let result = match self
+            .lightning_client
+            .get_payment_state(attempt.payment_hash, &attempt.label)
+            .await
+        {
+            Ok(PaymentState::Success { preimage }) => PaymentResult::Success { preimage },
+            Ok(PaymentState::Failure { error }) => PaymentResult::Failure { error },
+            Ok(PaymentState::Pending) => return Ok(()),
+            Err(LightningError::PaymentNotFound) => PaymentResult::Failure {
+                error: "cancelled".to_string(),
+            },
+            Err(e) => return Err(e.into()),
+        };
+
+        debug!(
+            payment_hash = field::display(attempt.payment_hash),
+            label = attempt.label,
+            success = matches!(result, PaymentResult::Success { .. }),
+            "reconciled payment attempt without result"
+        );
+        self.swap_repository
+            .unlock_add_payment_result(&attempt.payment_hash, &attempt.label, &result)
+            .await?;
+        Ok(())
+    }
+}
@@ -18,8 +18,8 @@ use crate::lightning::{
 
 use super::cln_api::{
     listpays_pays::ListpaysPaysStatus, listsendpays_request::ListsendpaysStatus,
-    node_client::NodeClient, pay_response::PayStatus, Amount, ListpaysRequest, ListsendpaysRequest,
-    PayRequest, WaitsendpayRequest,
+    node_client::NodeClient, pay_response::PayStatus, Amount, GetinfoRequest, ListpaysRequest,
+    ListsendpaysRequest, PayRequest, WaitsendpayRequest,
 };
 
 pub struct ClientConnection {
@@ -227,6 +227,13 @@ impl LightningClient for Client {
         };
         Ok(resp)
     }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn ping(&self) -> Result<(), LightningError> {
+        let mut client = self.get_client().await?;
+        client.getinfo(GetinfoRequest {}).await?;
+        Ok(())
+    }
 }
 
 async fn wait_payment(
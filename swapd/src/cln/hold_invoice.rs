@@ -0,0 +1,39 @@
+use bitcoin::hashes::sha256;
+use tonic::Status;
+
+use crate::lightning::{HoldInvoiceClient, HoldInvoiceRequest, HoldInvoiceState, LightningError};
+
+use super::Client;
+
+// NOTE: cln does not support hold invoices natively. Reverse swaps are
+// therefore only available with lnd for now.
+#[async_trait::async_trait]
+impl HoldInvoiceClient for Client {
+    async fn add_hold_invoice(
+        &self,
+        _request: HoldInvoiceRequest,
+    ) -> Result<String, LightningError> {
+        Err(unsupported())
+    }
+
+    async fn cancel_hold_invoice(&self, _hash: &sha256::Hash) -> Result<(), LightningError> {
+        Err(unsupported())
+    }
+
+    async fn get_hold_invoice_state(
+        &self,
+        _hash: &sha256::Hash,
+    ) -> Result<HoldInvoiceState, LightningError> {
+        Err(unsupported())
+    }
+
+    async fn settle_hold_invoice(&self, _preimage: &[u8; 32]) -> Result<(), LightningError> {
+        Err(unsupported())
+    }
+}
+
+fn unsupported() -> LightningError {
+    LightningError::General(Status::unimplemented(
+        "hold invoices are not supported with cln",
+    ))
+}
@@ -1,4 +1,5 @@
 mod client;
+mod hold_invoice;
 mod wallet;
 
 pub use client::{Client, ClientConnection};
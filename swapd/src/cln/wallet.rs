@@ -1,8 +1,15 @@
-use bitcoin::{address::NetworkUnchecked, Address};
+use bitcoin::{
+    address::NetworkUnchecked, consensus::deserialize, hashes::Hash, Address, OutPoint,
+    Transaction, Txid,
+};
 use tonic::Request;
 
 use crate::{
-    cln::cln_api::NewaddrRequest,
+    cln::cln_api::{
+        amount_or_all, feerate, listfunds_outputs::ListfundsOutputsStatus, Amount, AmountOrAll,
+        Feerate, ListfundsRequest, ListtransactionsRequest, NewaddrRequest, Outpoint,
+        WithdrawRequest,
+    },
     wallet::{Wallet, WalletError},
 };
 
@@ -29,6 +36,110 @@ impl Wallet for Client {
         let address = address.require_network(self.network)?;
         Ok(address)
     }
+
+    async fn balance_sat(&self) -> Result<u64, WalletError> {
+        let mut client = self.get_client().await?;
+        let resp = client
+            .list_funds(Request::new(ListfundsRequest { spent: Some(false) }))
+            .await?
+            .into_inner();
+        let balance_msat: u64 = resp
+            .outputs
+            .iter()
+            .filter(|output| {
+                output.status() == ListfundsOutputsStatus::Confirmed
+                    || output.status() == ListfundsOutputsStatus::Unconfirmed
+            })
+            .filter_map(|output| output.amount_msat.as_ref())
+            .map(|amount| amount.msat)
+            .sum();
+        Ok(balance_msat / 1000)
+    }
+
+    async fn send_to_address(
+        &self,
+        address: &Address,
+        amount_sat: u64,
+        fee_per_kw: u32,
+    ) -> Result<Txid, WalletError> {
+        let mut client = self.get_client().await?;
+        let resp = client
+            .withdraw(Request::new(WithdrawRequest {
+                destination: address.to_string(),
+                satoshi: Some(AmountOrAll {
+                    value: Some(amount_or_all::Value::Amount(Amount {
+                        msat: amount_sat * 1000,
+                    })),
+                }),
+                feerate: Some(Feerate {
+                    style: Some(feerate::Style::Perkw(fee_per_kw)),
+                }),
+                ..Default::default()
+            }))
+            .await?
+            .into_inner();
+
+        // Derive the txid from the transaction itself, to not depend on the
+        // byte order of the returned txid.
+        let tx: Transaction =
+            deserialize(&resp.tx).map_err(|e| WalletError::General(Box::new(e)))?;
+        Ok(tx.compute_txid())
+    }
+
+    async fn find_tx_to_address(&self, address: &Address) -> Result<Option<Txid>, WalletError> {
+        let mut client = self.get_client().await?;
+        let resp = client
+            .list_transactions(Request::new(ListtransactionsRequest {}))
+            .await?
+            .into_inner();
+        let script_pubkey = address.script_pubkey();
+        for tx in resp.transactions {
+            let tx: Transaction =
+                deserialize(&tx.rawtx).map_err(|e| WalletError::General(Box::new(e)))?;
+            if tx
+                .output
+                .iter()
+                .any(|output| output.script_pubkey == script_pubkey)
+            {
+                return Ok(Some(tx.compute_txid()));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn sweep_output(
+        &self,
+        outpoint: &OutPoint,
+        address: &Address,
+        fee_per_kw: u32,
+    ) -> Result<Txid, WalletError> {
+        let mut client = self.get_client().await?;
+        // cln takes txids in the byte order they are displayed in.
+        let mut txid = outpoint.txid.to_byte_array().to_vec();
+        txid.reverse();
+        let resp = client
+            .withdraw(Request::new(WithdrawRequest {
+                destination: address.to_string(),
+                satoshi: Some(AmountOrAll {
+                    value: Some(amount_or_all::Value::All(true)),
+                }),
+                minconf: Some(0),
+                utxos: vec![Outpoint {
+                    txid,
+                    outnum: outpoint.vout,
+                }],
+                feerate: Some(Feerate {
+                    style: Some(feerate::Style::Perkw(fee_per_kw)),
+                }),
+            }))
+            .await?
+            .into_inner();
+
+        let tx: Transaction =
+            deserialize(&resp.tx).map_err(|e| WalletError::General(Box::new(e)))?;
+        Ok(tx.compute_txid())
+    }
 }
 
 impl From<GetClientError> for WalletError {
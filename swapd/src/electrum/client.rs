@@ -0,0 +1,632 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use bitcoin::{
+    block::Header,
+    consensus::{deserialize, encode::serialize_hex},
+    hashes::{sha256, Hash},
+    Address, Block, BlockHash, Network, OutPoint, Script, Transaction, Txid,
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use serde_with::{serde_as, DisplayFromStr};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    net::TcpStream,
+    sync::{broadcast, oneshot, Mutex},
+};
+use tokio_rustls::{
+    rustls::{crypto::ring::default_provider, pki_types::ServerName, ClientConfig, RootCertStore},
+    TlsConnector,
+};
+use tracing::{debug, field, trace, warn};
+
+use crate::{
+    bitcoind::{RpcError, RpcRequest, RpcServerMessage, RpcServerMessageBody},
+    chain::{BlockHeader, BroadcastError, ChainClient, ChainError},
+};
+
+const CALL_TIMEOUT: Duration = Duration::from_secs(60);
+const NOTIFICATION_BUFFER_SIZE: usize = 1024;
+
+/// Notifications pushed by the electrum server for subscriptions made through
+/// the client.
+#[derive(Clone, Debug)]
+pub enum ElectrumNotification {
+    /// The server has a new chain tip.
+    Header { height: u64 },
+    /// The history of a subscribed script hash changed.
+    ScriptHash {
+        script_hash: String,
+        status: Option<String>,
+    },
+    /// The connection dropped, all subscriptions are gone.
+    Disconnected,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HeaderNotification {
+    pub height: u64,
+    pub hex: String,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+pub struct HistoryItem {
+    #[serde_as(as = "DisplayFromStr")]
+    pub tx_hash: Txid,
+    /// Confirmation height, 0 or -1 for mempool transactions.
+    pub height: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct HeadersResponse {
+    hex: String,
+}
+
+#[derive(Debug, Error)]
+#[error("invalid electrum url '{0}', expected tcp://host:port or ssl://host:port")]
+pub struct InvalidElectrumUrl(String);
+
+#[derive(Debug, Error)]
+pub(super) enum CallError {
+    #[error("rpc error: {0:?}")]
+    RpcError(RpcError),
+
+    #[error("deserialize error: {0}")]
+    Deserialize(serde_json::error::Error),
+
+    #[error("connection closed")]
+    Disconnected,
+
+    #[error("call timed out")]
+    Timeout,
+
+    #[error("{0}")]
+    General(Box<dyn std::error::Error + Sync + Send>),
+}
+
+type PendingCalls = HashMap<String, oneshot::Sender<Result<Value, CallError>>>;
+
+/// A single connection to the electrum server. Responses and notifications
+/// are read by a background task, which stops when the connection drops.
+struct Connection {
+    writer: Mutex<Box<dyn AsyncWrite + Send + Unpin>>,
+    pending: Arc<std::sync::Mutex<PendingCalls>>,
+    closed: Arc<AtomicBool>,
+}
+
+impl Connection {
+    async fn connect(
+        host: &str,
+        port: u16,
+        tls: bool,
+        notifications: broadcast::Sender<ElectrumNotification>,
+    ) -> Result<Self, CallError> {
+        let stream = TcpStream::connect((host, port)).await?;
+        let (reader, writer): (
+            Box<dyn AsyncRead + Send + Unpin>,
+            Box<dyn AsyncWrite + Send + Unpin>,
+        ) = match tls {
+            true => {
+                let server_name = ServerName::try_from(host.to_string())
+                    .map_err(|e| CallError::General(Box::new(e)))?;
+                let stream = tls_connector()?.connect(server_name, stream).await?;
+                let (reader, writer) = tokio::io::split(stream);
+                (Box::new(reader), Box::new(writer))
+            }
+            false => {
+                let (reader, writer) = tokio::io::split(stream);
+                (Box::new(reader), Box::new(writer))
+            }
+        };
+
+        let pending = Arc::new(std::sync::Mutex::new(PendingCalls::new()));
+        let closed = Arc::new(AtomicBool::new(false));
+        tokio::spawn(read_messages(
+            reader,
+            Arc::clone(&pending),
+            Arc::clone(&closed),
+            notifications,
+        ));
+        Ok(Self {
+            writer: Mutex::new(writer),
+            pending,
+            closed,
+        })
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    async fn call(&self, id: String, method: &str, params: Value) -> Result<Value, CallError> {
+        let (sender, receiver) = oneshot::channel();
+        self.pending
+            .lock()
+            .expect("pending calls lock poisoned")
+            .insert(id.clone(), sender);
+
+        let mut message = serde_json::to_vec(&RpcRequest {
+            id: id.clone(),
+            jsonrpc: String::from("2.0"),
+            method: method.to_string(),
+            params,
+        })?;
+        message.push(b'\n');
+        let mut writer = self.writer.lock().await;
+        if let Err(e) = writer.write_all(&message).await {
+            self.closed.store(true, Ordering::SeqCst);
+            return Err(e.into());
+        }
+        drop(writer);
+
+        match tokio::time::timeout(CALL_TIMEOUT, receiver).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(CallError::Disconnected),
+            Err(_) => {
+                self.pending
+                    .lock()
+                    .expect("pending calls lock poisoned")
+                    .remove(&id);
+                Err(CallError::Timeout)
+            }
+        }
+    }
+}
+
+/// TLS connector that verifies the server certificate against the Mozilla
+/// root certificates.
+fn tls_connector() -> Result<TlsConnector, CallError> {
+    let roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let config = ClientConfig::builder_with_provider(Arc::new(default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(TlsConnector::from(Arc::new(config)))
+}
+
+async fn read_messages(
+    reader: Box<dyn AsyncRead + Send + Unpin>,
+    pending: Arc<std::sync::Mutex<PendingCalls>>,
+    closed: Arc<AtomicBool>,
+    notifications: broadcast::Sender<ElectrumNotification>,
+) {
+    let mut lines = BufReader::new(reader).lines();
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => {
+                debug!("electrum connection closed");
+                break;
+            }
+            Err(e) => {
+                warn!("failed to read from electrum connection: {:?}", e);
+                break;
+            }
+        };
+
+        let message: RpcServerMessage = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!(line, "got invalid message from electrum server: {:?}", e);
+                continue;
+            }
+        };
+        let (id, result) = match message.body {
+            RpcServerMessageBody::Notification { method, params } => {
+                match parse_notification(&method, params) {
+                    Some(notification) => {
+                        trace!("got electrum notification {:?}", notification);
+                        let _ = notifications.send(notification);
+                    }
+                    None => debug!(method, "ignoring unknown electrum notification"),
+                }
+                continue;
+            }
+            RpcServerMessageBody::Response { id, result } => (id, Ok(result)),
+            RpcServerMessageBody::Error { id, error } => (id, Err(CallError::RpcError(error))),
+        };
+
+        let sender = pending
+            .lock()
+            .expect("pending calls lock poisoned")
+            .remove(&id);
+        match sender {
+            Some(sender) => {
+                let _ = sender.send(result);
+            }
+            None => debug!(id, "got electrum response for unknown call"),
+        }
+    }
+
+    // Dropping the pending senders fails the outstanding calls.
+    closed.store(true, Ordering::SeqCst);
+    pending.lock().expect("pending calls lock poisoned").clear();
+    let _ = notifications.send(ElectrumNotification::Disconnected);
+}
+
+fn parse_notification(method: &str, params: Value) -> Option<ElectrumNotification> {
+    match method {
+        "blockchain.headers.subscribe" => {
+            let (header,): (HeaderNotification,) = serde_json::from_value(params).ok()?;
+            Some(ElectrumNotification::Header {
+                height: header.height,
+            })
+        }
+        "blockchain.scripthash.subscribe" => {
+            let (script_hash, status): (String, Option<String>) =
+                serde_json::from_value(params).ok()?;
+            Some(ElectrumNotification::ScriptHash {
+                script_hash,
+                status,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// The electrum script hash of `script`: the reversed sha256 of the script, hex
+/// encoded.
+pub fn script_hash(script: &Script) -> String {
+    let mut hash = sha256::Hash::hash(script.as_bytes()).to_byte_array();
+    hash.reverse();
+    hex::encode(hash)
+}
+
+/// Client for the electrum protocol. Connects lazily and reconnects on the
+/// next call after the connection dropped. Subscriptions don't survive a
+/// reconnect, subscribers are notified with `ElectrumNotification::Disconnected`.
+pub struct ElectrumClient {
+    host: String,
+    port: u16,
+    tls: bool,
+    network: Network,
+    connection: Mutex<Option<Arc<Connection>>>,
+    counter: AtomicU64,
+    notifications: broadcast::Sender<ElectrumNotification>,
+}
+
+impl std::fmt::Debug for ElectrumClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ElectrumClient")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("tls", &self.tls)
+            .field("network", &self.network)
+            .finish()
+    }
+}
+
+impl ElectrumClient {
+    pub fn new(url: &str, network: Network) -> Result<Self, InvalidElectrumUrl> {
+        let (tls, host_port) = if let Some(host_port) = url.strip_prefix("tcp://") {
+            (false, host_port)
+        } else if let Some(host_port) = url.strip_prefix("ssl://") {
+            (true, host_port)
+        } else {
+            return Err(InvalidElectrumUrl(url.to_string()));
+        };
+        let (host, port) = host_port
+            .rsplit_once(':')
+            .ok_or_else(|| InvalidElectrumUrl(url.to_string()))?;
+        let port = port
+            .parse()
+            .map_err(|_| InvalidElectrumUrl(url.to_string()))?;
+        let (notifications, _) = broadcast::channel(NOTIFICATION_BUFFER_SIZE);
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            tls,
+            network,
+            connection: Mutex::new(None),
+            counter: AtomicU64::new(0),
+            notifications,
+        })
+    }
+
+    /// Subscribes to notifications of all subscriptions made through this
+    /// client.
+    pub fn notifications(&self) -> broadcast::Receiver<ElectrumNotification> {
+        self.notifications.subscribe()
+    }
+
+    async fn connection(&self) -> Result<Arc<Connection>, CallError> {
+        let mut connection = self.connection.lock().await;
+        if let Some(connection) = &*connection {
+            if !connection.is_closed() {
+                return Ok(Arc::clone(connection));
+            }
+        }
+
+        debug!(
+            host = field::display(&self.host),
+            port = self.port,
+            "connecting to electrum server"
+        );
+        let new_connection = Arc::new(
+            Connection::connect(&self.host, self.port, self.tls, self.notifications.clone())
+                .await?,
+        );
+        let id = self.next_id();
+        new_connection
+            .call(id, "server.version", json!(["swapd", "1.4"]))
+            .await?;
+        *connection = Some(Arc::clone(&new_connection));
+        Ok(new_connection)
+    }
+
+    fn next_id(&self) -> String {
+        (self.counter.fetch_add(1, Ordering::SeqCst) + 1).to_string()
+    }
+
+    async fn call<T>(&self, method: &str, params: Value) -> Result<T, CallError>
+    where
+        T: DeserializeOwned,
+    {
+        trace!("calling {}", method);
+        let connection = self.connection().await?;
+        let result = connection.call(self.next_id(), method, params).await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Subscribes to new chain tips and returns the current tip.
+    pub async fn subscribe_headers(&self) -> Result<HeaderNotification, ChainError> {
+        Ok(self.call("blockchain.headers.subscribe", json!([])).await?)
+    }
+
+    pub async fn get_header(&self, height: u64) -> Result<Header, ChainError> {
+        let hex: String = self
+            .call("blockchain.block.header", json!([height]))
+            .await?;
+        Ok(deserialize(&hex::decode(hex).map_err(invalid_hex)?)?)
+    }
+
+    /// Returns up to `count` consecutive headers starting at `start_height`.
+    pub async fn get_headers(
+        &self,
+        start_height: u64,
+        count: u64,
+    ) -> Result<Vec<Header>, ChainError> {
+        let resp: HeadersResponse = self
+            .call("blockchain.block.headers", json!([start_height, count]))
+            .await?;
+        let raw = hex::decode(resp.hex).map_err(invalid_hex)?;
+        raw.chunks(80)
+            .map(|chunk| Ok(deserialize(chunk)?))
+            .collect()
+    }
+
+    /// Subscribes to changes in the history of `script_hash` and returns the
+    /// current status, which is `None` if the history is empty.
+    pub async fn subscribe_script_hash(
+        &self,
+        script_hash: &str,
+    ) -> Result<Option<String>, ChainError> {
+        Ok(self
+            .call("blockchain.scripthash.subscribe", json!([script_hash]))
+            .await?)
+    }
+
+    pub async fn get_history(&self, script_hash: &str) -> Result<Vec<HistoryItem>, ChainError> {
+        Ok(self
+            .call("blockchain.scripthash.get_history", json!([script_hash]))
+            .await?)
+    }
+
+    /// Returns the fee rate in btc/kb to confirm within `conf_target` blocks,
+    /// or a negative number if the server can't estimate.
+    pub(super) async fn estimate_fee(&self, conf_target: u32) -> Result<f64, CallError> {
+        self.call("blockchain.estimatefee", json!([conf_target]))
+            .await
+    }
+
+    async fn get_tx(&self, tx_id: &Txid) -> Result<Transaction, ChainError> {
+        let hex: String = self
+            .call("blockchain.transaction.get", json!([tx_id.to_string()]))
+            .await?;
+        Ok(deserialize(&hex::decode(hex).map_err(invalid_hex)?)?)
+    }
+}
+
+fn invalid_hex(e: hex::FromHexError) -> ChainError {
+    ChainError::General(format!("invalid hex: {:?}", e).into())
+}
+
+#[async_trait::async_trait]
+impl ChainClient for ElectrumClient {
+    async fn broadcast_tx(&self, tx: Transaction) -> Result<(), BroadcastError> {
+        let hex = serialize_hex(&tx);
+        trace!(tx = hex, "broadcasting tx");
+        let _: Value = self
+            .call("blockchain.transaction.broadcast", json!([hex]))
+            .await?;
+        Ok(())
+    }
+
+    async fn get_blockheight(&self) -> Result<u64, ChainError> {
+        Ok(self.subscribe_headers().await?.height)
+    }
+
+    async fn get_tip_hash(&self) -> Result<BlockHash, ChainError> {
+        let tip = self.subscribe_headers().await?;
+        let header: Header = deserialize(&hex::decode(tip.hex).map_err(invalid_hex)?)?;
+        Ok(header.block_hash())
+    }
+
+    async fn get_block(&self, _hash: &BlockHash) -> Result<Block, ChainError> {
+        Err(ChainError::General(
+            "electrum does not serve full blocks".into(),
+        ))
+    }
+
+    async fn get_block_hash(&self, height: u64) -> Result<BlockHash, ChainError> {
+        Ok(self.get_header(height).await?.block_hash())
+    }
+
+    async fn get_block_header(&self, _hash: &BlockHash) -> Result<BlockHeader, ChainError> {
+        Err(ChainError::General(
+            "electrum does not serve headers by hash".into(),
+        ))
+    }
+
+    async fn get_sender_addresses(&self, utxos: &[OutPoint]) -> Result<Vec<Address>, ChainError> {
+        let mut txs: HashMap<Txid, Transaction> = HashMap::new();
+        let mut addresses = Vec::new();
+        for utxo in utxos {
+            let tx = self.get_tx(&utxo.txid).await?;
+            for vin in tx.input {
+                let txin_id = vin.previous_output.txid;
+                if let Entry::Vacant(entry) = txs.entry(txin_id) {
+                    entry.insert(self.get_tx(&txin_id).await?);
+                }
+
+                let txin = &txs[&txin_id];
+                let txout = match txin.output.get(vin.previous_output.vout as usize) {
+                    Some(txout) => txout,
+                    None => return Err(ChainError::General("txin output does not exist".into())),
+                };
+                let address = Address::from_script(&txout.script_pubkey, self.network)?;
+                addresses.push(address);
+            }
+        }
+
+        Ok(addresses)
+    }
+
+    async fn get_transaction(&self, tx_id: &Txid) -> Result<Transaction, ChainError> {
+        self.get_tx(tx_id).await
+    }
+}
+
+impl From<std::io::Error> for CallError {
+    fn from(value: std::io::Error) -> Self {
+        CallError::General(Box::new(value))
+    }
+}
+
+impl From<tokio_rustls::rustls::Error> for CallError {
+    fn from(value: tokio_rustls::rustls::Error) -> Self {
+        CallError::General(Box::new(value))
+    }
+}
+
+impl From<serde_json::error::Error> for CallError {
+    fn from(value: serde_json::error::Error) -> Self {
+        CallError::Deserialize(value)
+    }
+}
+
+impl From<CallError> for ChainError {
+    fn from(value: CallError) -> Self {
+        match value {
+            CallError::RpcError(e) => ChainError::General(e.message.into()),
+            CallError::Deserialize(e) => ChainError::General(Box::new(e)),
+            CallError::General(e) => ChainError::General(e),
+            _ => ChainError::General(Box::new(value)),
+        }
+    }
+}
+
+impl From<CallError> for BroadcastError {
+    fn from(value: CallError) -> Self {
+        match value {
+            CallError::RpcError(rpc_error) => match &rpc_error.message {
+                x if x.contains("insufficient fee, rejecting replacement") => {
+                    BroadcastError::InsufficientFeeRejectingReplacement(rpc_error.message)
+                }
+                _ => BroadcastError::UnknownError(rpc_error.message),
+            },
+            _ => BroadcastError::Chain(value.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        blockdata::constants::genesis_block, consensus::encode::serialize_hex, Network, ScriptBuf,
+    };
+    use serde_json::Value;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+    };
+
+    use crate::chain::ChainClient;
+
+    use super::{script_hash, ElectrumClient, ElectrumNotification};
+
+    #[test]
+    fn test_script_hash() {
+        // Example from the electrum protocol documentation.
+        let script =
+            ScriptBuf::from_hex("76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac").unwrap();
+        assert_eq!(
+            script_hash(&script),
+            "8b01df4e368ea28f8dc0423bcf7a4923e3a12d307c875e47a0cfbf90b5c39161"
+        );
+    }
+
+    #[test]
+    fn test_invalid_url() {
+        assert!(ElectrumClient::new("127.0.0.1:50001", Network::Regtest).is_err());
+        assert!(ElectrumClient::new("tcp://127.0.0.1", Network::Regtest).is_err());
+        assert!(ElectrumClient::new("tcp://127.0.0.1:50001", Network::Regtest).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_call_and_notification() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let tx = genesis_block(Network::Regtest).txdata[0].clone();
+        let tx_hex = serialize_hex(&tx);
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            while let Some(line) = lines.next_line().await.unwrap() {
+                let request: Value = serde_json::from_str(&line).unwrap();
+                let result = match request["method"].as_str().unwrap() {
+                    "server.version" => serde_json::json!(["test", "1.4"]),
+                    "blockchain.transaction.get" => Value::String(tx_hex.clone()),
+                    _ => Value::Null,
+                };
+                let notification = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "method": "blockchain.scripthash.subscribe",
+                    "params": ["abcd", "status"],
+                });
+                let response = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "result": result,
+                });
+                writer
+                    .write_all(format!("{}\n{}\n", notification, response).as_bytes())
+                    .await
+                    .unwrap();
+            }
+        });
+
+        let client =
+            ElectrumClient::new(&format!("tcp://127.0.0.1:{}", port), Network::Regtest).unwrap();
+        let mut notifications = client.notifications();
+        let result = client.get_transaction(&tx.compute_txid()).await.unwrap();
+        assert_eq!(result, tx);
+        assert!(matches!(
+            notifications.recv().await.unwrap(),
+            ElectrumNotification::ScriptHash { script_hash, status: Some(status) }
+                if script_hash == "abcd" && status == "status"
+        ));
+    }
+}
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use tracing::trace;
+
+use crate::chain::{self, FeeEstimate, FeeEstimateError};
+
+use super::{client::CallError, ElectrumClient};
+
+#[derive(Debug)]
+pub struct FeeEstimator {
+    client: Arc<ElectrumClient>,
+}
+
+impl FeeEstimator {
+    pub fn new(client: Arc<ElectrumClient>) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait::async_trait]
+impl chain::FeeEstimator for FeeEstimator {
+    async fn estimate_fee(&self, conf_target: i32) -> Result<FeeEstimate, FeeEstimateError> {
+        let target = conf_target.clamp(1, 1008);
+        let fee = self.client.estimate_fee(target as u32).await?;
+        if fee <= 0.0 {
+            return Err(FeeEstimateError::Unavailable);
+        }
+
+        // feerate is btc/kb (multiply by 100_000_000 and divide by 4)
+        let sat_per_kw = (fee * 25_000_000.0).ceil() as u32;
+
+        trace!(
+            "fee estimate for {} blocks: {} sat/kw",
+            conf_target,
+            sat_per_kw
+        );
+        Ok(FeeEstimate { sat_per_kw })
+    }
+}
+
+impl From<CallError> for FeeEstimateError {
+    fn from(value: CallError) -> Self {
+        FeeEstimateError::General(Box::new(value))
+    }
+}
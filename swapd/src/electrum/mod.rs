@@ -0,0 +1,7 @@
+mod client;
+mod fee_estimator;
+mod monitor;
+
+pub use client::ElectrumClient;
+pub use fee_estimator::FeeEstimator;
+pub use monitor::{ElectrumMonitor, ElectrumMonitorParams};
@@ -0,0 +1,519 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use bitcoin::{Address, OutPoint};
+use tokio::sync::{broadcast::error::RecvError, Mutex};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, field, info, trace, warn};
+
+use crate::{
+    chain::{
+        publish_new_txos, AddressUtxo, BlockHeader, BlockNotifier, ChainClient, ChainError,
+        ChainRepository, RescanError, RescanRequests, RescanResult, SpentTxo, Txo,
+    },
+    event::EventBus,
+    metrics::Metrics,
+};
+
+use super::{
+    client::{script_hash, ElectrumNotification},
+    ElectrumClient,
+};
+
+/// Maximum number of headers requested at once.
+const MAX_HEADERS: u64 = 2016;
+
+pub struct ElectrumMonitorParams<R>
+where
+    R: ChainRepository,
+{
+    pub birthday_height: Option<u64>,
+    pub chain_repository: Arc<R>,
+    pub client: Arc<ElectrumClient>,
+    pub event_bus: Arc<EventBus>,
+    pub metrics: Arc<Metrics>,
+    pub poll_interval: Duration,
+    pub reorg_safety_window: u64,
+    pub rescan_requests: RescanRequests,
+    pub synced_blocks: Arc<BlockNotifier>,
+}
+
+/// Populates the chain repository from an electrum server, as an alternative
+/// to the `ChainMonitor`. Rather than downloading every block, it subscribes
+/// to the script hashes of the watched addresses and only fetches the
+/// transactions in their history. Reorgs are detected through header
+/// notifications. Only the headers within the reorg safety window are kept,
+/// headers of older transactions are fetched when needed.
+pub struct ElectrumMonitor<R>
+where
+    R: ChainRepository,
+{
+    birthday_height: Option<u64>,
+    chain_repository: Arc<R>,
+    client: Arc<ElectrumClient>,
+    event_bus: Arc<EventBus>,
+    metrics: Arc<Metrics>,
+    poll_interval: Duration,
+    reorg_safety_window: u64,
+    rescan_requests: Mutex<RescanRequests>,
+    synced_blocks: Arc<BlockNotifier>,
+}
+
+#[derive(Default)]
+struct SyncState {
+    /// The height of the block the chain was synced from.
+    birthday: u64,
+    /// The synced block headers by height, within the reorg safety window.
+    headers: BTreeMap<u64, BlockHeader>,
+    /// The subscribed script hashes and their addresses.
+    subscribed: HashMap<String, Address>,
+    /// Script hashes whose history has to be synced.
+    dirty: HashSet<String>,
+}
+
+impl<R> ElectrumMonitor<R>
+where
+    R: ChainRepository + Send + Sync,
+{
+    pub fn new(params: ElectrumMonitorParams<R>) -> Self {
+        Self {
+            birthday_height: params.birthday_height,
+            chain_repository: params.chain_repository,
+            client: params.client,
+            event_bus: params.event_bus,
+            metrics: params.metrics,
+            poll_interval: params.poll_interval,
+            reorg_safety_window: params.reorg_safety_window,
+            rescan_requests: Mutex::new(params.rescan_requests),
+            synced_blocks: params.synced_blocks,
+        }
+    }
+
+    pub async fn start(&self, token: CancellationToken) -> Result<(), ChainError> {
+        let mut notifications = self.client.notifications();
+        let mut rescan_requests = self.rescan_requests.lock().await;
+        let (birthday, headers) = self.load_headers().await?;
+        let mut state = SyncState {
+            birthday,
+            headers,
+            ..Default::default()
+        };
+        loop {
+            if token.is_cancelled() {
+                return Ok(());
+            }
+
+            if let Err(e) = self.sync(&mut state).await {
+                error!("electrum sync failed: {:?}", e);
+            }
+
+            tokio::select! {
+                _ = token.cancelled() => {
+                    debug!("electrum monitor shutting down");
+                    break;
+                }
+                notification = notifications.recv() => match notification {
+                    Ok(ElectrumNotification::Header { height }) => {
+                        debug!("got electrum header notification for height {}", height);
+                    }
+                    Ok(ElectrumNotification::ScriptHash { script_hash, status }) => {
+                        trace!("script hash {} changed status to {:?}", script_hash, status);
+                        state.dirty.insert(script_hash);
+                    }
+                    Ok(ElectrumNotification::Disconnected) => {
+                        warn!("electrum connection dropped, resubscribing");
+                        state.subscribed.clear();
+                    }
+                    Err(RecvError::Lagged(n)) => {
+                        warn!("missed {} electrum notifications, resyncing all addresses", n);
+                        state.dirty.extend(state.subscribed.keys().cloned());
+                    }
+                    Err(RecvError::Closed) => return Ok(()),
+                },
+                request = rescan_requests.recv() => {
+                    let result = self
+                        .rescan(&state, request.from_height, &request.addresses)
+                        .await;
+                    request.reply(result);
+                }
+                _ = tokio::time::sleep(self.poll_interval) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the birthday height and the headers within the reorg safety
+    /// window.
+    async fn load_headers(&self) -> Result<(u64, BTreeMap<u64, BlockHeader>), ChainError> {
+        if let (Some(birthday), Some(tip)) = (
+            self.chain_repository.get_birthday().await?,
+            self.chain_repository.get_tip().await?,
+        ) {
+            let headers: BTreeMap<_, _> = self
+                .chain_repository
+                .get_block_headers(tip.height.saturating_sub(self.reorg_safety_window))
+                .await?
+                .into_iter()
+                .map(|header| (header.height, header))
+                .collect();
+            // Moving the birthday back makes the initial sync of every
+            // address include the blocks since the new birthday.
+            let birthday = match self.birthday_height {
+                Some(height) if height < birthday.height => {
+                    let birthday = self.add_birthday(height).await?;
+                    info!(
+                        birthday_hash = field::display(birthday.hash),
+                        "moving birthday back to block {}", birthday.height
+                    );
+                    birthday
+                }
+                _ => birthday,
+            };
+            return Ok((birthday.height, headers));
+        }
+
+        // If no birthday is set, use the block 20 blocks ago.
+        let height = match self.birthday_height {
+            Some(height) => height,
+            None => self
+                .client
+                .subscribe_headers()
+                .await?
+                .height
+                .saturating_sub(20),
+        };
+        let birthday = self.add_birthday(height).await?;
+        info!(
+            birthday_hash = field::display(birthday.hash),
+            "using block {} as birthday", birthday.height
+        );
+        Ok((height, BTreeMap::from([(height, birthday)])))
+    }
+
+    async fn add_birthday(&self, height: u64) -> Result<BlockHeader, ChainError> {
+        let header = self.client.get_header(height).await?;
+        let birthday = BlockHeader {
+            hash: header.block_hash(),
+            height,
+            prev: header.prev_blockhash,
+        };
+        self.chain_repository
+            .add_block(&birthday, &Vec::new(), &Vec::new())
+            .await?;
+        Ok(birthday)
+    }
+
+    async fn sync(&self, state: &mut SyncState) -> Result<(), ChainError> {
+        // Subscribing to headers again is harmless, and needed after a
+        // reconnect.
+        let tip = self.client.subscribe_headers().await?;
+        let old_tip = state.headers.last_key_value().map(|(_, h)| h.hash);
+        self.metrics.chain_tip_height.set(tip.height as i64);
+        let reorged = self.sync_headers(&mut state.headers, tip.height).await?;
+        if reorged {
+            // Transactions in the reorged blocks have to be linked to their
+            // new blocks.
+            state.dirty.extend(state.subscribed.keys().cloned());
+        }
+
+        for address in self.chain_repository.get_watch_addresses().await? {
+            let script_hash = script_hash(&address.script_pubkey());
+            if state.subscribed.contains_key(&script_hash) {
+                continue;
+            }
+
+            let status = self.client.subscribe_script_hash(&script_hash).await?;
+            if status.is_some() {
+                state.dirty.insert(script_hash.clone());
+            }
+            state.subscribed.insert(script_hash, address);
+        }
+
+        let dirty: Vec<_> = state.dirty.drain().collect();
+        for script_hash in dirty {
+            let address = match state.subscribed.get(&script_hash) {
+                Some(address) => address.clone(),
+                None => continue,
+            };
+            match self
+                .sync_address(
+                    state,
+                    &script_hash,
+                    address,
+                    state.birthday,
+                    &mut RescanResult::default(),
+                )
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => {
+                    state.dirty.insert(script_hash);
+                }
+                Err(e) => {
+                    state.dirty.insert(script_hash);
+                    return Err(e);
+                }
+            }
+        }
+
+        if let Some((height, _)) = state.headers.last_key_value() {
+            self.metrics.chain_synced_height.set(*height as i64);
+        }
+
+        let new_tip = state.headers.last_key_value().map(|(_, h)| h.clone());
+        if let Some(new_tip) = new_tip {
+            if old_tip != Some(new_tip.hash) {
+                self.synced_blocks.notify(new_tip.hash);
+                self.prune(state, new_tip.height).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Brings the synced headers up to the electrum tip at `tip_height`,
+    /// undoing reorged blocks. Returns whether blocks were undone.
+    async fn sync_headers(
+        &self,
+        headers: &mut BTreeMap<u64, BlockHeader>,
+        tip_height: u64,
+    ) -> Result<bool, ChainError> {
+        let (base_height, our_height) = match (headers.first_key_value(), headers.last_key_value())
+        {
+            (Some((base, _)), Some((tip, _))) => (*base, *tip),
+            _ => return Err(ChainError::EmptyChain),
+        };
+
+        // Find the last block we have in common with the server.
+        let start_height = our_height.min(tip_height);
+        let mut fork_height = start_height;
+        loop {
+            let header = self.client.get_header(fork_height).await?;
+            if headers.get(&fork_height).map(|h| h.hash) == Some(header.block_hash()) {
+                break;
+            }
+
+            if fork_height <= base_height {
+                return Err(ChainError::InvalidChain);
+            }
+
+            fork_height -= 1;
+        }
+
+        // The server is behind, wait for it to catch up.
+        if fork_height == start_height && tip_height < our_height {
+            debug!(
+                "electrum tip {} is behind synced tip {}",
+                tip_height, our_height
+            );
+            return Ok(false);
+        }
+
+        let reorged = headers.split_off(&(fork_height + 1));
+        for header in reorged.values().rev() {
+            debug!(
+                "block {} ({}) was reorged out of the chain, undoing block",
+                header.height, header.hash
+            );
+            self.chain_repository.undo_block(header.hash).await?;
+        }
+
+        let mut prev = headers[&fork_height].hash;
+        let mut height = fork_height + 1;
+        while height <= tip_height {
+            let count = (tip_height - height + 1).min(MAX_HEADERS);
+            let new_headers = self.client.get_headers(height, count).await?;
+            if new_headers.is_empty() {
+                break;
+            }
+
+            for new_header in new_headers {
+                // The chain changed while syncing, the next sync fixes it up.
+                if new_header.prev_blockhash != prev {
+                    return Err(ChainError::InvalidChain);
+                }
+
+                let header = BlockHeader {
+                    hash: new_header.block_hash(),
+                    height,
+                    prev,
+                };
+                trace!("adding block {} ({})", header.height, header.hash);
+                self.chain_repository
+                    .add_block(&header, &Vec::new(), &Vec::new())
+                    .await?;
+                prev = header.hash;
+                headers.insert(height, header);
+                height += 1;
+            }
+        }
+
+        Ok(!reorged.is_empty())
+    }
+
+    async fn prune(&self, state: &mut SyncState, tip_height: u64) -> Result<(), ChainError> {
+        let min_height = tip_height.saturating_sub(self.reorg_safety_window);
+        state.headers = state.headers.split_off(&min_height);
+        let pruned = self.chain_repository.prune_blocks(min_height).await?;
+        if pruned > 0 {
+            debug!("pruned {} blocks below height {}", pruned, min_height);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the header at `height`. Headers below the reorg safety window
+    /// are fetched from the server, as they are no longer kept.
+    async fn get_header(
+        &self,
+        headers: &BTreeMap<u64, BlockHeader>,
+        height: u64,
+    ) -> Result<Option<BlockHeader>, ChainError> {
+        if let Some(header) = headers.get(&height) {
+            return Ok(Some(header.clone()));
+        }
+
+        match headers.first_key_value() {
+            Some((base_height, _)) if height < *base_height => {}
+            _ => return Ok(None),
+        }
+
+        let header = self.client.get_header(height).await?;
+        Ok(Some(BlockHeader {
+            hash: header.block_hash(),
+            height,
+            prev: header.prev_blockhash,
+        }))
+    }
+
+    /// Syncs the history of the subscribed addresses, or only `addresses` if
+    /// not empty, again from `from_height`.
+    async fn rescan(
+        &self,
+        state: &SyncState,
+        from_height: u64,
+        addresses: &[Address],
+    ) -> Result<RescanResult, RescanError> {
+        let tip_height = state.headers.last_key_value().map(|(h, _)| *h).unwrap_or(0);
+        if from_height > tip_height {
+            return Err(RescanError::InvalidHeight {
+                from_height,
+                tip_height,
+            });
+        }
+
+        info!(
+            addresses = addresses.len(),
+            "rescanning address history from block {}", from_height
+        );
+        let mut result = RescanResult::default();
+        for (script_hash, address) in &state.subscribed {
+            if !addresses.is_empty() && !addresses.contains(address) {
+                continue;
+            }
+
+            self.sync_address(
+                state,
+                script_hash,
+                address.clone(),
+                from_height,
+                &mut result,
+            )
+            .await?;
+        }
+
+        info!(
+            new_outputs = result.new_outputs,
+            new_spends = result.new_spends,
+            "rescanned address history from block {}",
+            from_height
+        );
+        Ok(result)
+    }
+
+    /// Adds the confirmed outputs to and spends from `address` since
+    /// `min_height` to the chain repository, counting the new ones in
+    /// `result`. Returns false if the history contains transactions in blocks
+    /// that are not synced yet, so the address should be synced again.
+    async fn sync_address(
+        &self,
+        state: &SyncState,
+        script_hash: &str,
+        address: Address,
+        min_height: u64,
+        result: &mut RescanResult,
+    ) -> Result<bool, ChainError> {
+        let history = self.client.get_history(script_hash).await?;
+        let mut complete = true;
+        let mut blocks: BTreeMap<u64, (BlockHeader, Vec<AddressUtxo>, Vec<SpentTxo>)> =
+            BTreeMap::new();
+        for item in history {
+            // Unconfirmed transactions have a height of 0 or -1.
+            if item.height <= 0 {
+                continue;
+            }
+
+            // Like the chain monitor, ignore transactions before the birthday.
+            let height = item.height as u64;
+            if height < min_height {
+                trace!(
+                    address = field::display(&address),
+                    "ignoring tx {} before birthday",
+                    item.tx_hash
+                );
+                continue;
+            }
+
+            let header = match self.get_header(&state.headers, height).await? {
+                Some(header) => header,
+                None => {
+                    complete = false;
+                    continue;
+                }
+            };
+            let block_hash = header.hash;
+            let tx = self.client.get_transaction(&item.tx_hash).await?;
+            let (_, utxos, spent_txos) = blocks
+                .entry(height)
+                .or_insert_with(|| (header, Vec::new(), Vec::new()));
+            for (vout, output) in tx.output.iter().enumerate() {
+                if output.script_pubkey != address.script_pubkey() {
+                    continue;
+                }
+
+                utxos.push(AddressUtxo {
+                    address: address.clone(),
+                    utxo: Txo {
+                        block_hash,
+                        block_height: height,
+                        outpoint: OutPoint::new(item.tx_hash, vout as u32),
+                        tx_out: output.clone(),
+                    },
+                });
+            }
+
+            for (vin, input) in tx.input.iter().enumerate() {
+                spent_txos.push(SpentTxo {
+                    outpoint: input.previous_output,
+                    spending_tx: item.tx_hash,
+                    spending_input_index: vin as u32,
+                });
+            }
+        }
+
+        for (height, (header, utxos, spent_txos)) in blocks {
+            let new_txos = self
+                .chain_repository
+                .add_block(&header, &utxos, &spent_txos)
+                .await?;
+            result.add(&new_txos);
+            publish_new_txos(&self.event_bus, height, utxos, &new_txos);
+        }
+
+        Ok(complete)
+    }
+}
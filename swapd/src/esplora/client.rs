@@ -0,0 +1,383 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    time::Duration,
+};
+
+use bitcoin::{
+    consensus::{deserialize, encode::serialize_hex},
+    hashes::Hash,
+    Address, Block, BlockHash, Network, OutPoint, Transaction, Txid,
+};
+use reqwest::{Response, StatusCode, Url};
+use serde::{de::DeserializeOwned, Deserialize};
+use thiserror::Error;
+use tracing::trace;
+
+use crate::chain::{BlockHeader, BroadcastError, ChainClient, ChainError};
+
+#[derive(Debug, Deserialize)]
+struct EsploraBlock {
+    id: String,
+    height: u64,
+    previousblockhash: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct EsploraClient {
+    base_url: String,
+    client: reqwest::Client,
+    network: Network,
+}
+
+#[derive(Debug, Error)]
+pub(super) enum CallError {
+    #[error("esplora returned status {status}: {message}")]
+    Status { status: StatusCode, message: String },
+
+    #[error("deserialize error: {0}")]
+    Deserialize(Box<dyn std::error::Error + Sync + Send>),
+
+    #[error("{0}")]
+    General(Box<dyn std::error::Error + Sync + Send>),
+}
+
+impl From<reqwest::Error> for CallError {
+    fn from(value: reqwest::Error) -> Self {
+        CallError::General(Box::new(value))
+    }
+}
+
+impl EsploraClient {
+    /// `url` is the base url of the esplora api, for example
+    /// `https://blockstream.info/testnet/api`. Calls fail if connecting takes
+    /// longer than `connect_timeout`, or the whole call longer than
+    /// `request_timeout`.
+    pub fn new(
+        url: Url,
+        network: Network,
+        connect_timeout: Duration,
+        request_timeout: Duration,
+    ) -> Result<Self, reqwest::Error> {
+        let client = reqwest::Client::builder()
+            .connect_timeout(connect_timeout)
+            .timeout(request_timeout)
+            .build()?;
+        Ok(Self {
+            base_url: url.as_str().trim_end_matches('/').to_string(),
+            client,
+            network,
+        })
+    }
+
+    async fn get(&self, path: &str) -> Result<Response, CallError> {
+        trace!("calling GET /{}", path);
+        let resp = self
+            .client
+            .get(format!("{}/{}", self.base_url, path))
+            .send()
+            .await?;
+        check_status(resp).await
+    }
+
+    async fn get_bytes(&self, path: &str) -> Result<Vec<u8>, CallError> {
+        Ok(self.get(path).await?.bytes().await?.to_vec())
+    }
+
+    async fn get_text(&self, path: &str) -> Result<String, CallError> {
+        Ok(self.get(path).await?.text().await?.trim().to_string())
+    }
+
+    pub(super) async fn get_json<T>(&self, path: &str) -> Result<T, CallError>
+    where
+        T: DeserializeOwned,
+    {
+        let body = self.get_bytes(path).await?;
+        serde_json::from_slice(&body).map_err(|e| CallError::Deserialize(Box::new(e)))
+    }
+
+    async fn post_tx(&self, hex: String) -> Result<String, CallError> {
+        trace!("calling POST /tx");
+        let resp = self
+            .client
+            .post(format!("{}/tx", self.base_url))
+            .body(hex)
+            .send()
+            .await?;
+        Ok(check_status(resp).await?.text().await?)
+    }
+
+    async fn get_tx(&self, tx_id: &Txid) -> Result<Transaction, ChainError> {
+        let raw = self.get_bytes(&format!("tx/{}/raw", tx_id)).await?;
+        Ok(deserialize(&raw)?)
+    }
+}
+
+async fn check_status(resp: Response) -> Result<Response, CallError> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+
+    let message = resp.text().await.unwrap_or_default();
+    Err(CallError::Status { status, message })
+}
+
+#[async_trait::async_trait]
+impl ChainClient for EsploraClient {
+    async fn broadcast_tx(&self, tx: Transaction) -> Result<(), BroadcastError> {
+        let hex = serialize_hex(&tx);
+        trace!(tx = hex, "broadcasting tx");
+        self.post_tx(hex).await?;
+        Ok(())
+    }
+
+    async fn get_blockheight(&self) -> Result<u64, ChainError> {
+        let height = self.get_text("blocks/tip/height").await?;
+        height
+            .parse()
+            .map_err(|e| ChainError::General(format!("invalid tip height: {:?}", e).into()))
+    }
+
+    async fn get_tip_hash(&self) -> Result<BlockHash, ChainError> {
+        Ok(self.get_text("blocks/tip/hash").await?.parse()?)
+    }
+
+    async fn get_block(&self, hash: &BlockHash) -> Result<Block, ChainError> {
+        let raw = match self.get_bytes(&format!("block/{}/raw", hash)).await {
+            Ok(raw) => raw,
+            Err(CallError::Status {
+                status: StatusCode::NOT_FOUND,
+                ..
+            }) => return Err(ChainError::BlockNotFound),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(deserialize(&raw)?)
+    }
+
+    async fn get_block_hash(&self, height: u64) -> Result<BlockHash, ChainError> {
+        match self.get_text(&format!("block-height/{}", height)).await {
+            Ok(hash) => Ok(hash.parse()?),
+            Err(CallError::Status {
+                status: StatusCode::NOT_FOUND,
+                ..
+            }) => Err(ChainError::BlockNotFound),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn get_block_header(&self, hash: &BlockHash) -> Result<BlockHeader, ChainError> {
+        let block: EsploraBlock = match self.get_json(&format!("block/{}", hash)).await {
+            Ok(block) => block,
+            Err(CallError::Status {
+                status: StatusCode::NOT_FOUND,
+                ..
+            }) => return Err(ChainError::BlockNotFound),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(BlockHeader {
+            hash: block.id.parse()?,
+            height: block.height,
+            prev: match block.previousblockhash {
+                Some(prev) => prev.parse()?,
+                None => BlockHash::all_zeros(),
+            },
+        })
+    }
+
+    async fn get_sender_addresses(&self, utxos: &[OutPoint]) -> Result<Vec<Address>, ChainError> {
+        let mut txs: HashMap<Txid, Transaction> = HashMap::new();
+        let mut addresses = Vec::new();
+        for utxo in utxos {
+            let tx = self.get_tx(&utxo.txid).await?;
+            for vin in tx.input {
+                let txin_id = vin.previous_output.txid;
+                if let Entry::Vacant(entry) = txs.entry(txin_id) {
+                    entry.insert(self.get_tx(&txin_id).await?);
+                }
+
+                let txin = &txs[&txin_id];
+                let txout = match txin.output.get(vin.previous_output.vout as usize) {
+                    Some(txout) => txout,
+                    None => return Err(ChainError::General("txin output does not exist".into())),
+                };
+                let address = Address::from_script(&txout.script_pubkey, self.network)?;
+                addresses.push(address);
+            }
+        }
+
+        Ok(addresses)
+    }
+
+    async fn get_transaction(&self, tx_id: &Txid) -> Result<Transaction, ChainError> {
+        self.get_tx(tx_id).await
+    }
+}
+
+impl From<CallError> for ChainError {
+    fn from(value: CallError) -> Self {
+        match value {
+            CallError::Status { status, message } => {
+                ChainError::General(format!("esplora returned {}: {}", status, message).into())
+            }
+            CallError::Deserialize(e) => ChainError::General(e),
+            CallError::General(e) => ChainError::General(e),
+        }
+    }
+}
+
+impl From<CallError> for BroadcastError {
+    fn from(value: CallError) -> Self {
+        match value {
+            CallError::Status { status: _, message } => match &message {
+                x if x.contains("insufficient fee, rejecting replacement") => {
+                    BroadcastError::InsufficientFeeRejectingReplacement(message)
+                }
+                _ => BroadcastError::UnknownError(message),
+            },
+            CallError::Deserialize(_) => BroadcastError::Chain(value.into()),
+            CallError::General(_) => BroadcastError::Chain(value.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bitcoin::{
+        blockdata::constants::genesis_block, consensus::serialize, hashes::Hash, BlockHash, Network,
+    };
+    use mockito::Server;
+
+    use crate::chain::{BroadcastError, ChainClient, ChainError};
+
+    use super::EsploraClient;
+
+    fn client(server: &Server) -> EsploraClient {
+        EsploraClient::new(
+            server.url().parse().unwrap(),
+            Network::Regtest,
+            Duration::from_secs(1),
+            Duration::from_secs(5),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_get_tip() {
+        let mut server = Server::new_async().await;
+        let hash = genesis_block(Network::Regtest).block_hash();
+        let height_mock = server
+            .mock("GET", "/blocks/tip/height")
+            .with_body("123")
+            .create_async()
+            .await;
+        let hash_mock = server
+            .mock("GET", "/blocks/tip/hash")
+            .with_body(hash.to_string())
+            .create_async()
+            .await;
+
+        let client = client(&server);
+        assert_eq!(client.get_blockheight().await.unwrap(), 123);
+        assert_eq!(client.get_tip_hash().await.unwrap(), hash);
+        height_mock.assert_async().await;
+        hash_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_block() {
+        let mut server = Server::new_async().await;
+        let block = genesis_block(Network::Regtest);
+        let hash = block.block_hash();
+        server
+            .mock("GET", format!("/block/{}/raw", hash).as_str())
+            .with_body(serialize(&block))
+            .create_async()
+            .await;
+        server
+            .mock("GET", format!("/block/{}", hash).as_str())
+            .with_body(format!(
+                r#"{{"id":"{}","height":0,"timestamp":1296688602}}"#,
+                hash
+            ))
+            .create_async()
+            .await;
+
+        let client = client(&server);
+        assert_eq!(client.get_block(&hash).await.unwrap(), block);
+        let header = client.get_block_header(&hash).await.unwrap();
+        assert_eq!(header.hash, hash);
+        assert_eq!(header.height, 0);
+        assert_eq!(header.prev, BlockHash::all_zeros());
+    }
+
+    #[tokio::test]
+    async fn test_get_block_not_found() {
+        let mut server = Server::new_async().await;
+        let hash = genesis_block(Network::Regtest).block_hash();
+        server
+            .mock("GET", format!("/block/{}", hash).as_str())
+            .with_status(404)
+            .with_body("Block not found")
+            .create_async()
+            .await;
+
+        let client = client(&server);
+        assert!(matches!(
+            client.get_block_header(&hash).await,
+            Err(ChainError::BlockNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_transaction() {
+        let mut server = Server::new_async().await;
+        let tx = genesis_block(Network::Regtest).txdata[0].clone();
+        let tx_id = tx.compute_txid();
+        server
+            .mock("GET", format!("/tx/{}/raw", tx_id).as_str())
+            .with_body(serialize(&tx))
+            .create_async()
+            .await;
+
+        let client = client(&server);
+        assert_eq!(client.get_transaction(&tx_id).await.unwrap(), tx);
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_tx() {
+        let mut server = Server::new_async().await;
+        let tx = genesis_block(Network::Regtest).txdata[0].clone();
+        let mock = server
+            .mock("POST", "/tx")
+            .match_body(bitcoin::consensus::encode::serialize_hex(&tx).as_str())
+            .with_body(tx.compute_txid().to_string())
+            .create_async()
+            .await;
+
+        let client = client(&server);
+        client.broadcast_tx(tx).await.unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_tx_insufficient_fee() {
+        let mut server = Server::new_async().await;
+        let tx = genesis_block(Network::Regtest).txdata[0].clone();
+        server
+            .mock("POST", "/tx")
+            .with_status(400)
+            .with_body(
+                r#"sendrawtransaction RPC error: {"code":-26,"message":"insufficient fee, rejecting replacement"}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = client(&server);
+        assert!(matches!(
+            client.broadcast_tx(tx).await,
+            Err(BroadcastError::InsufficientFeeRejectingReplacement(_))
+        ));
+    }
+}
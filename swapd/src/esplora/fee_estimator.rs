@@ -0,0 +1,103 @@
+use std::{collections::HashMap, sync::Arc};
+
+use tracing::trace;
+
+use crate::chain::{self, FeeEstimate, FeeEstimateError};
+
+use super::{client::CallError, EsploraClient};
+
+#[derive(Debug)]
+pub struct FeeEstimator {
+    client: Arc<EsploraClient>,
+}
+
+impl FeeEstimator {
+    pub fn new(client: Arc<EsploraClient>) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait::async_trait]
+impl chain::FeeEstimator for FeeEstimator {
+    async fn estimate_fee(&self, conf_target: i32) -> Result<FeeEstimate, FeeEstimateError> {
+        // Maps confirmation targets to fee rates in sat/vb. Only a subset of
+        // targets is returned, take the closest target below the requested one.
+        let estimates: HashMap<String, f64> = self.client.get_json("fee-estimates").await?;
+        let target = conf_target.clamp(1, 1008) as u32;
+        let sat_per_vb = estimates
+            .iter()
+            .filter_map(|(t, fee)| t.parse::<u32>().ok().map(|t| (t, *fee)))
+            .filter(|(t, _)| *t <= target)
+            .max_by_key(|(t, _)| *t)
+            .map(|(_, fee)| fee)
+            .ok_or(FeeEstimateError::Unavailable)?;
+        let sat_per_kw = (sat_per_vb * 250.0).ceil() as u32;
+
+        trace!(
+            "fee estimate for {} blocks: {} sat/kw",
+            conf_target,
+            sat_per_kw
+        );
+        Ok(FeeEstimate { sat_per_kw })
+    }
+}
+
+impl From<CallError> for FeeEstimateError {
+    fn from(value: CallError) -> Self {
+        FeeEstimateError::General(Box::new(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use bitcoin::Network;
+    use mockito::Server;
+
+    use crate::chain::{FeeEstimateError, FeeEstimator as _};
+
+    use super::{EsploraClient, FeeEstimator};
+
+    async fn estimator(server: &mut Server, body: &str) -> FeeEstimator {
+        server
+            .mock("GET", "/fee-estimates")
+            .with_body(body)
+            .create_async()
+            .await;
+        FeeEstimator::new(Arc::new(
+            EsploraClient::new(
+                server.url().parse().unwrap(),
+                Network::Regtest,
+                Duration::from_secs(1),
+                Duration::from_secs(5),
+            )
+            .unwrap(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_estimate_fee_closest_lower_target() {
+        let mut server = Server::new_async().await;
+        let estimator = estimator(
+            &mut server,
+            r#"{"1":20.5,"2":15.0,"6":8.0,"144":2.1,"1008":1.0}"#,
+        )
+        .await;
+        assert_eq!(estimator.estimate_fee(1).await.unwrap().sat_per_kw, 5125);
+        assert_eq!(estimator.estimate_fee(5).await.unwrap().sat_per_kw, 3750);
+        assert_eq!(estimator.estimate_fee(6).await.unwrap().sat_per_kw, 2000);
+        assert_eq!(estimator.estimate_fee(200).await.unwrap().sat_per_kw, 525);
+        assert_eq!(estimator.estimate_fee(5000).await.unwrap().sat_per_kw, 250);
+    }
+
+    #[tokio::test]
+    async fn test_estimate_fee_unavailable() {
+        let mut server = Server::new_async().await;
+        let estimator = estimator(&mut server, r#"{"144":2.1}"#).await;
+        assert!(matches!(
+            estimator.estimate_fee(6).await,
+            Err(FeeEstimateError::Unavailable)
+        ));
+    }
+}
@@ -0,0 +1,5 @@
+mod client;
+mod fee_estimator;
+
+pub use client::EsploraClient;
+pub use fee_estimator::FeeEstimator;
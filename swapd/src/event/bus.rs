@@ -0,0 +1,55 @@
+use futures::{Stream, StreamExt};
+use thiserror::Error;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_util::sync::CancellationToken;
+use tracing::{field, trace};
+
+use super::SwapEvent;
+
+#[derive(Debug, Error)]
+pub enum EventBusError {
+    #[error("subscriber lagged behind, dropped {0} events")]
+    Lagged(u64),
+}
+
+/// Fans out swap events to all subscribers. Events are not persisted,
+/// subscribers only see events published after they subscribed. Publishing
+/// never blocks, a subscriber that can't keep up gets an error instead.
+#[derive(Debug)]
+pub struct EventBus {
+    sender: broadcast::Sender<SwapEvent>,
+    token: CancellationToken,
+}
+
+impl EventBus {
+    /// `capacity` is the number of events buffered per subscriber. The
+    /// subscriptions end when `token` is cancelled, so they don't hold up
+    /// shutdown.
+    pub fn new(capacity: usize, token: CancellationToken) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender, token }
+    }
+
+    pub fn publish(&self, event: SwapEvent) {
+        trace!(
+            address = field::display(&event.address),
+            "publishing swap event {:?}",
+            event.kind
+        );
+
+        // Sending only fails if there are no subscribers.
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> impl Stream<Item = Result<SwapEvent, EventBusError>> + Send {
+        let receiver = self.sender.subscribe();
+        futures::stream::unfold(receiver, |mut receiver| async move {
+            match receiver.recv().await {
+                Ok(event) => Some((Ok(event), receiver)),
+                Err(RecvError::Lagged(n)) => Some((Err(EventBusError::Lagged(n)), receiver)),
+                Err(RecvError::Closed) => None,
+            }
+        })
+        .take_until(self.token.clone().cancelled_owned())
+    }
+}
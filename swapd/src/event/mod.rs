@@ -0,0 +1,5 @@
+mod bus;
+mod types;
+
+pub use bus::{EventBus, EventBusError};
+pub use types::{SwapEvent, SwapEventKind};
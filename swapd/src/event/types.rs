@@ -0,0 +1,61 @@
+use std::time::SystemTime;
+
+use bitcoin::{hashes::sha256, Address, Amount, OutPoint, Txid};
+
+/// Something happened to a swap. Events are keyed by the swap address, so
+/// subscribers can follow a swap without knowing anything else about it.
+#[derive(Clone, Debug)]
+pub struct SwapEvent {
+    pub address: Address,
+    pub timestamp: SystemTime,
+    pub kind: SwapEventKind,
+}
+
+impl SwapEvent {
+    pub fn new(address: Address, kind: SwapEventKind) -> Self {
+        Self {
+            address,
+            timestamp: SystemTime::now(),
+            kind,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum SwapEventKind {
+    /// An output to the swap address was confirmed in a block.
+    DepositConfirmed {
+        outpoint: OutPoint,
+        amount: Amount,
+        block_height: u64,
+    },
+    /// An output to the swap address appeared in the mempool.
+    DepositUnconfirmed {
+        outpoint: OutPoint,
+        amount: Amount,
+        replaceable: bool,
+    },
+    /// An unconfirmed output to the swap address left the mempool without
+    /// confirming. The deposit transaction was replaced, double spent or
+    /// evicted.
+    DepositDropped { outpoint: OutPoint },
+    /// The swap invoice was paid.
+    Paid { payment_hash: sha256::Hash },
+    /// Paying the swap invoice failed. The swap can be paid again.
+    PaymentFailed {
+        payment_hash: sha256::Hash,
+        error: String,
+    },
+    /// An output of a paid swap was not paid over lightning. The user can
+    /// refund it cooperatively.
+    RefundOffered { outpoint: OutPoint, amount: Amount },
+    /// A claim transaction spending the swap outputs was broadcast.
+    ClaimBroadcast { tx_id: Txid },
+    /// An output to the swap address was spent in a block, either by a claim
+    /// or a refund.
+    OutputSpent {
+        outpoint: OutPoint,
+        spending_tx_id: Txid,
+        block_height: u64,
+    },
+}
@@ -0,0 +1,9 @@
+mod monitor;
+mod server;
+
+pub use monitor::{HealthMonitor, HealthMonitorParams};
+pub use server::HealthService;
+
+pub mod health_api {
+    tonic::include_proto!("grpc.health.v1");
+}
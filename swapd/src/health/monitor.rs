@@ -0,0 +1,168 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::{join, sync::watch};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::{
+    chain::{ChainClient, ChainRepository, FeeEstimator},
+    lightning::LightningClient,
+    metrics::Metrics,
+    public_server::MIN_SWAP_AMOUNT_CONF_TARGET,
+};
+
+pub struct HealthMonitorParams<C, CR, F, L>
+where
+    C: ChainClient,
+    CR: ChainRepository,
+    F: FeeEstimator,
+    L: LightningClient,
+{
+    pub chain_client: Arc<C>,
+    pub chain_repository: Arc<CR>,
+    pub fee_estimator: Arc<F>,
+    pub lightning_client: Arc<L>,
+    /// Maximum number of blocks the synced chain may lag behind the chain
+    /// backend before swapd is no longer ready.
+    pub max_chain_lag_blocks: u64,
+    pub metrics: Arc<Metrics>,
+    pub poll_interval: Duration,
+}
+
+/// Checks every `poll_interval` whether the dependencies needed to serve
+/// swaps are available. Swapd is ready when the lightning node, the chain
+/// backend and the database are reachable, the synced chain is close to the
+/// chain tip and a fee estimate is available. The readiness is published to
+/// the `HealthService`s, and the connection state to the metrics.
+pub struct HealthMonitor<C, CR, F, L>
+where
+    C: ChainClient,
+    CR: ChainRepository,
+    F: FeeEstimator,
+    L: LightningClient,
+{
+    chain_client: Arc<C>,
+    chain_repository: Arc<CR>,
+    fee_estimator: Arc<F>,
+    lightning_client: Arc<L>,
+    max_chain_lag_blocks: u64,
+    metrics: Arc<Metrics>,
+    poll_interval: Duration,
+    ready: watch::Sender<bool>,
+}
+
+impl<C, CR, F, L> HealthMonitor<C, CR, F, L>
+where
+    C: ChainClient + Send + Sync,
+    CR: ChainRepository + Send + Sync,
+    F: FeeEstimator + Send + Sync,
+    L: LightningClient + Send + Sync,
+{
+    pub fn new(params: HealthMonitorParams<C, CR, F, L>) -> Self {
+        let (ready, _) = watch::channel(false);
+        Self {
+            chain_client: params.chain_client,
+            chain_repository: params.chain_repository,
+            fee_estimator: params.fee_estimator,
+            lightning_client: params.lightning_client,
+            max_chain_lag_blocks: params.max_chain_lag_blocks,
+            metrics: params.metrics,
+            poll_interval: params.poll_interval,
+            ready,
+        }
+    }
+
+    /// Readiness updates. Swapd is not ready until the first check passed,
+    /// and no longer ready once the monitor shuts down.
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.ready.subscribe()
+    }
+
+    pub async fn start(&self, token: CancellationToken) {
+        let mut last_reasons = None;
+        loop {
+            if token.is_cancelled() {
+                break;
+            }
+
+            let reasons = self.check().await;
+            if last_reasons.as_ref() != Some(&reasons) {
+                match reasons.is_empty() {
+                    true => info!("swapd is ready"),
+                    false => warn!("swapd is not ready: {}", reasons.join(", ")),
+                }
+            }
+
+            self.set_ready(reasons.is_empty());
+            last_reasons = Some(reasons);
+
+            tokio::select! {
+                _ = token.cancelled() => {
+                    debug!("health monitor shutting down");
+                    break;
+                }
+                _ = tokio::time::sleep(self.poll_interval) => {}
+            }
+        }
+
+        self.set_ready(false);
+    }
+
+    fn set_ready(&self, ready: bool) {
+        self.ready.send_if_modified(|current| {
+            let modified = *current != ready;
+            *current = ready;
+            modified
+        });
+    }
+
+    /// Returns the reasons swapd is not ready, if any.
+    async fn check(&self) -> Vec<String> {
+        let (lightning_res, tip_res, synced_res, fee_res) = join!(
+            self.lightning_client.ping(),
+            self.chain_client.get_blockheight(),
+            self.chain_repository.get_tip(),
+            self.fee_estimator.estimate_fee(MIN_SWAP_AMOUNT_CONF_TARGET)
+        );
+
+        let mut reasons = Vec::new();
+        match lightning_res {
+            Ok(_) => self.metrics.lightning_up.set(1),
+            Err(e) => {
+                self.metrics.lightning_up.set(0);
+                reasons.push(format!("lightning node unreachable: {}", e));
+            }
+        }
+
+        let tip_height = match tip_res {
+            Ok(height) => {
+                self.metrics.chain_client_up.set(1);
+                self.metrics.chain_tip_height.set(height as i64);
+                Some(height)
+            }
+            Err(e) => {
+                self.metrics.chain_client_up.set(0);
+                reasons.push(format!("chain backend unreachable: {}", e));
+                None
+            }
+        };
+
+        match (synced_res, tip_height) {
+            (Ok(Some(synced)), Some(tip_height)) => {
+                let lag = tip_height.saturating_sub(synced.height);
+                if lag > self.max_chain_lag_blocks {
+                    reasons.push(format!("chain sync is {} blocks behind", lag));
+                }
+            }
+            (Ok(Some(_)), None) => {}
+            (Ok(None), _) => reasons.push(String::from("chain is not synced yet")),
+            (Err(e), _) => reasons.push(format!("database unreachable: {}", e)),
+        }
+
+        if let Err(e) = fee_res {
+            reasons.push(format!("no fee estimate available: {}", e));
+        }
+
+        reasons
+    }
+}
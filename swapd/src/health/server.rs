@@ -0,0 +1,82 @@
+use std::pin::Pin;
+
+use futures::{Stream, StreamExt};
+use tokio::sync::watch;
+use tonic::{Request, Response, Status};
+
+use super::health_api::{
+    health_check_response::ServingStatus, health_server::Health, HealthCheckRequest,
+    HealthCheckResponse,
+};
+
+/// Implements `grpc.health.v1.Health` for the services of one grpc server.
+/// All services share the readiness published by the `HealthMonitor`. The
+/// empty service name stands for the server as a whole.
+#[derive(Debug)]
+pub struct HealthService {
+    services: Vec<&'static str>,
+    ready: watch::Receiver<bool>,
+}
+
+impl HealthService {
+    pub fn new(services: Vec<&'static str>, ready: watch::Receiver<bool>) -> Self {
+        Self { services, ready }
+    }
+
+    fn is_known(&self, service: &str) -> bool {
+        service.is_empty() || self.services.contains(&service)
+    }
+}
+
+fn serving_status(ready: bool) -> ServingStatus {
+    match ready {
+        true => ServingStatus::Serving,
+        false => ServingStatus::NotServing,
+    }
+}
+
+#[tonic::async_trait]
+impl Health for HealthService {
+    type WatchStream =
+        Pin<Box<dyn Stream<Item = Result<HealthCheckResponse, Status>> + Send + 'static>>;
+
+    async fn check(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<HealthCheckResponse>, Status> {
+        let req = request.into_inner();
+        if !self.is_known(&req.service) {
+            return Err(Status::not_found("unknown service"));
+        }
+
+        Ok(Response::new(HealthCheckResponse {
+            status: serving_status(*self.ready.borrow()).into(),
+        }))
+    }
+
+    async fn watch(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<Self::WatchStream>, Status> {
+        let req = request.into_inner();
+        let known = self.is_known(&req.service);
+
+        // Send the current status right away, then every change. The stream
+        // ends when the health monitor shuts down.
+        let mut receiver = self.ready.clone();
+        receiver.mark_changed();
+        let stream = futures::stream::unfold(receiver, move |mut receiver| async move {
+            receiver.changed().await.ok()?;
+            let status = match known {
+                true => serving_status(*receiver.borrow_and_update()),
+                false => ServingStatus::ServiceUnknown,
+            };
+            let resp = HealthCheckResponse {
+                status: status.into(),
+            };
+            Some((resp, receiver))
+        })
+        .map(Ok);
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
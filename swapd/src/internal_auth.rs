@@ -0,0 +1,181 @@
+use bitcoin::hashes::{sha256, Hash};
+use tonic::Request;
+
+const AUTHORIZATION_HEADER: &str = "authorization";
+const BEARER_PREFIX: &str = "Bearer ";
+
+/// Permission needed to call an rpc on the internal server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Permission {
+    /// Calls that only read swap state.
+    ReadOnly,
+    /// Calls that move funds, change configuration or stop swapd.
+    Admin,
+}
+
+/// Authorizes internal server calls by the bearer token in the
+/// `authorization` header. The admin token grants all permissions, the
+/// read-only token only `Permission::ReadOnly`. Without an admin token,
+/// authorization is disabled and every call is allowed.
+#[derive(Clone, Debug, Default)]
+pub struct InternalAuth {
+    // Only the hashes are kept, so comparing them doesn't leak the tokens
+    // through timing.
+    admin_token_hash: Option<sha256::Hash>,
+    readonly_token_hash: Option<sha256::Hash>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AuthorizationError {
+    #[error("missing bearer token")]
+    MissingToken,
+    #[error("invalid token")]
+    InvalidToken,
+    #[error("read-only token is not allowed to call this method")]
+    PermissionDenied,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InternalAuthError {
+    #[error("a read-only token requires an admin token")]
+    ReadOnlyWithoutAdmin,
+    #[error("the read-only token must differ from the admin token")]
+    SameTokens,
+    #[error("tokens must not be empty")]
+    EmptyToken,
+}
+
+impl InternalAuth {
+    pub fn new(
+        admin_token: Option<&str>,
+        readonly_token: Option<&str>,
+    ) -> Result<Self, InternalAuthError> {
+        let admin_token = admin_token.map(str::trim);
+        let readonly_token = readonly_token.map(str::trim);
+        if admin_token.is_some_and(str::is_empty) || readonly_token.is_some_and(str::is_empty) {
+            return Err(InternalAuthError::EmptyToken);
+        }
+
+        match (admin_token, readonly_token) {
+            (None, Some(_)) => return Err(InternalAuthError::ReadOnlyWithoutAdmin),
+            (Some(admin), Some(readonly)) if admin == readonly => {
+                return Err(InternalAuthError::SameTokens)
+            }
+            _ => {}
+        }
+
+        Ok(Self {
+            admin_token_hash: admin_token.map(|t| sha256::Hash::hash(t.as_bytes())),
+            readonly_token_hash: readonly_token.map(|t| sha256::Hash::hash(t.as_bytes())),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.admin_token_hash.is_some()
+    }
+
+    pub fn authorize<T>(
+        &self,
+        request: &Request<T>,
+        required: Permission,
+    ) -> Result<(), AuthorizationError> {
+        let admin_token_hash = match &self.admin_token_hash {
+            Some(admin_token_hash) => admin_token_hash,
+            None => return Ok(()),
+        };
+
+        let token = request
+            .metadata()
+            .get(AUTHORIZATION_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix(BEARER_PREFIX))
+            .ok_or(AuthorizationError::MissingToken)?;
+        let token_hash = sha256::Hash::hash(token.trim().as_bytes());
+
+        if token_hash == *admin_token_hash {
+            return Ok(());
+        }
+
+        if Some(token_hash) == self.readonly_token_hash {
+            return match required {
+                Permission::ReadOnly => Ok(()),
+                Permission::Admin => Err(AuthorizationError::PermissionDenied),
+            };
+        }
+
+        Err(AuthorizationError::InvalidToken)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tonic::Request;
+
+    use super::{AuthorizationError, InternalAuth, InternalAuthError, Permission};
+
+    fn request(token: Option<&str>) -> Request<()> {
+        let mut request = Request::new(());
+        if let Some(token) = token {
+            request.metadata_mut().insert(
+                "authorization",
+                format!("Bearer {}", token).parse().unwrap(),
+            );
+        }
+        request
+    }
+
+    #[test]
+    fn test_disabled_allows_everything() {
+        let auth = InternalAuth::new(None, None).unwrap();
+        assert!(!auth.is_enabled());
+        assert!(auth.authorize(&request(None), Permission::Admin).is_ok());
+    }
+
+    #[test]
+    fn test_admin_token_allows_everything() {
+        let auth = InternalAuth::new(Some("admin"), Some("read")).unwrap();
+        assert!(auth
+            .authorize(&request(Some("admin")), Permission::Admin)
+            .is_ok());
+        assert!(auth
+            .authorize(&request(Some("admin")), Permission::ReadOnly)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_readonly_token_denied_admin() {
+        let auth = InternalAuth::new(Some("admin"), Some("read")).unwrap();
+        assert!(auth
+            .authorize(&request(Some("read")), Permission::ReadOnly)
+            .is_ok());
+        assert!(matches!(
+            auth.authorize(&request(Some("read")), Permission::Admin),
+            Err(AuthorizationError::PermissionDenied)
+        ));
+    }
+
+    #[test]
+    fn test_missing_or_wrong_token_unauthenticated() {
+        let auth = InternalAuth::new(Some("admin"), None).unwrap();
+        assert!(matches!(
+            auth.authorize(&request(None), Permission::ReadOnly),
+            Err(AuthorizationError::MissingToken)
+        ));
+        assert!(matches!(
+            auth.authorize(&request(Some("wrong")), Permission::ReadOnly),
+            Err(AuthorizationError::InvalidToken)
+        ));
+    }
+
+    #[test]
+    fn test_readonly_requires_admin() {
+        assert!(matches!(
+            InternalAuth::new(None, Some("read")),
+            Err(InternalAuthError::ReadOnlyWithoutAdmin)
+        ));
+        assert!(matches!(
+            InternalAuth::new(Some("same"), Some("same")),
+            Err(InternalAuthError::SameTokens)
+        ));
+    }
+}
@@ -1,99 +1,136 @@
-use std::{sync::Arc, time::UNIX_EPOCH};
+use std::{pin::Pin, sync::Arc, time::UNIX_EPOCH};
 
 use bitcoin::{
     address::{NetworkChecked, NetworkUnchecked},
     hashes::{sha256, Hash},
     Address, Network, OutPoint,
 };
+use futures::{Stream, TryStreamExt};
 use tokio_util::sync::CancellationToken;
 use tonic::{Request, Response, Status};
-use tracing::{instrument, warn};
+use tracing::{field, info, instrument, warn};
 
 use crate::{
-    chain::{ChainClient, ChainRepository, FeeEstimate, FeeEstimator},
+    chain::{ChainClient, ChainRepository, FeeEstimate, FeeEstimator, RescanError, Rescanner},
     chain_filter::ChainFilterRepository,
-    claim::{ClaimError, ClaimRepository, ClaimService, ClaimServiceError},
+    claim::{
+        ClaimError, ClaimRepository, ClaimRepositoryError, ClaimService, ClaimServiceError,
+        OutputDisposition,
+    },
+    event::{self, EventBus, SwapEventKind},
+    internal_auth::{AuthorizationError, InternalAuth, Permission},
     lightning::PaymentResult,
-    swap::{GetSwapsError, PrivateKeyProvider, SwapRepository},
+    mempool::MempoolRepository,
+    reverse::{ReverseSwapRepository, ReverseSwapRepositoryError},
+    swap::{ClaimKey, GetSwapsError, PrivateKeyProvider, ReencryptClaimKeysError, SwapRepository},
     wallet::{Wallet, WalletError},
 };
 
 use internal_swap_api::{
-    swap_manager_server::SwapManager, AddAddressFiltersRequest, AddAddressFiltersResponse,
-    ClaimRequest, ClaimResponse, ClaimableUtxo, GetInfoRequest, GetInfoResponse, GetSwapRequest,
-    GetSwapResponse, ListClaimableRequest, ListClaimableResponse, PaymentAttempt, SpendType,
-    StopRequest, StopResponse, SwapLock, SwapOutput, SwapOutputSpend,
+    swap_event, swap_manager_server::SwapManager, AddAddressFiltersRequest,
+    AddAddressFiltersResponse, ClaimBroadcast, ClaimRequest, ClaimResponse, ClaimableUtxo,
+    DepositConfirmed, DepositDropped, DepositUnconfirmed, GetInfoRequest, GetInfoResponse,
+    GetReverseSwapRequest, GetReverseSwapResponse, GetSwapRequest, GetSwapResponse,
+    ListClaimableRequest, ListClaimableResponse, ListUnpaidOutputsRequest,
+    ListUnpaidOutputsResponse, OutputSpent, PaymentAttempt, ReencryptClaimKeysRequest,
+    ReencryptClaimKeysResponse, RefundOffered, RescanRequest, RescanResponse,
+    SetOutputDispositionRequest, SetOutputDispositionResponse, SpendType, StopRequest,
+    StopResponse, SubscribeSwapEventsRequest, SwapEvent, SwapLock, SwapOutput, SwapOutputSpend,
+    SwapPaid, SwapPaymentFailed, UnconfirmedSwapOutput, UnpaidOutput,
 };
 
 pub mod internal_swap_api {
     tonic::include_proto!("swap_internal");
 }
 
+const DEFAULT_REENCRYPT_BATCH_SIZE: u32 = 100;
+
 #[derive(Debug)]
-pub struct ServerParams<CC, CF, CR, F, P, RR, SR, W>
+pub struct ServerParams<CC, CF, CR, F, MR, P, RR, RS, SR, W>
 where
     CC: ChainClient,
     CF: ChainFilterRepository,
     CR: ChainRepository,
     F: FeeEstimator,
+    MR: MempoolRepository,
     P: PrivateKeyProvider,
     RR: ClaimRepository,
+    RS: ReverseSwapRepository,
     SR: SwapRepository,
     W: Wallet,
 {
+    pub auth: InternalAuth,
     pub chain_client: Arc<CC>,
     pub chain_filter_repository: Arc<CF>,
     pub chain_repository: Arc<CR>,
+    pub event_bus: Arc<EventBus>,
     pub fee_estimator: Arc<F>,
+    pub mempool_repository: Arc<MR>,
     pub network: Network,
     pub claim_service: Arc<ClaimService<CC, CR, RR, SR, P>>,
+    pub reverse_swap_repository: Arc<RS>,
+    pub rescanner: Rescanner,
     pub swap_repository: Arc<SR>,
     pub token: CancellationToken,
     pub wallet: Arc<W>,
 }
 
 #[derive(Debug)]
-pub struct Server<CC, CF, CR, F, P, RR, SR, W>
+pub struct Server<CC, CF, CR, F, MR, P, RR, RS, SR, W>
 where
     CC: ChainClient,
     CF: ChainFilterRepository,
     CR: ChainRepository,
     F: FeeEstimator,
+    MR: MempoolRepository,
     P: PrivateKeyProvider,
     RR: ClaimRepository,
+    RS: ReverseSwapRepository,
     SR: SwapRepository,
     W: Wallet,
 {
+    auth: InternalAuth,
     chain_client: Arc<CC>,
     chain_filter_repository: Arc<CF>,
     chain_repository: Arc<CR>,
+    event_bus: Arc<EventBus>,
     fee_estimator: Arc<F>,
+    mempool_repository: Arc<MR>,
     network: Network,
     claim_service: Arc<ClaimService<CC, CR, RR, SR, P>>,
+    reverse_swap_repository: Arc<RS>,
+    rescanner: Rescanner,
     swap_repository: Arc<SR>,
     token: CancellationToken,
     wallet: Arc<W>,
 }
 
-impl<CC, CF, CR, F, P, RR, SR, W> Server<CC, CF, CR, F, P, RR, SR, W>
+impl<CC, CF, CR, F, MR, P, RR, RS, SR, W> Server<CC, CF, CR, F, MR, P, RR, RS, SR, W>
 where
     CC: ChainClient,
     CF: ChainFilterRepository,
     CR: ChainRepository,
     F: FeeEstimator,
+    MR: MempoolRepository,
     P: PrivateKeyProvider,
     RR: ClaimRepository,
+    RS: ReverseSwapRepository,
     SR: SwapRepository,
     W: Wallet,
 {
-    pub fn new(params: ServerParams<CC, CF, CR, F, P, RR, SR, W>) -> Self {
+    pub fn new(params: ServerParams<CC, CF, CR, F, MR, P, RR, RS, SR, W>) -> Self {
         Self {
+            auth: params.auth,
             chain_client: params.chain_client,
             chain_filter_repository: params.chain_filter_repository,
             chain_repository: params.chain_repository,
+            event_bus: params.event_bus,
             fee_estimator: params.fee_estimator,
+            mempool_repository: params.mempool_repository,
             network: params.network,
             claim_service: params.claim_service,
+            reverse_swap_repository: params.reverse_swap_repository,
+            rescanner: params.rescanner,
             swap_repository: params.swap_repository,
             token: params.token,
             wallet: params.wallet,
@@ -102,22 +139,29 @@ where
 }
 
 #[tonic::async_trait]
-impl<CC, CF, CR, F, P, RR, SR, W> SwapManager for Server<CC, CF, CR, F, P, RR, SR, W>
+impl<CC, CF, CR, F, MR, P, RR, RS, SR, W> SwapManager
+    for Server<CC, CF, CR, F, MR, P, RR, RS, SR, W>
 where
     CC: ChainClient + Send + Sync + 'static,
     CF: ChainFilterRepository + Send + Sync + 'static,
     CR: ChainRepository + Send + Sync + 'static,
     F: FeeEstimator + Send + Sync + 'static,
+    MR: MempoolRepository + Send + Sync + 'static,
     P: PrivateKeyProvider + Send + Sync + 'static,
     RR: ClaimRepository + Send + Sync + 'static,
+    RS: ReverseSwapRepository + Send + Sync + 'static,
     SR: SwapRepository + Send + Sync + 'static,
     W: Wallet + Send + Sync + 'static,
 {
-    #[instrument(skip(self), level = "debug")]
+    type SubscribeSwapEventsStream =
+        Pin<Box<dyn Stream<Item = Result<SwapEvent, Status>> + Send + 'static>>;
+
+    #[instrument(skip(self, request), fields(request = ?request.get_ref()), level = "debug")]
     async fn add_address_filters(
         &self,
         request: Request<AddAddressFiltersRequest>,
     ) -> Result<Response<AddAddressFiltersResponse>, Status> {
+        self.auth.authorize(&request, Permission::Admin)?;
         let req = request.into_inner();
         let addresses: Vec<Address<NetworkChecked>> = req
             .addresses
@@ -156,11 +200,12 @@ where
         Ok(Response::new(AddAddressFiltersResponse {}))
     }
 
-    #[instrument(skip(self), level = "debug")]
+    #[instrument(skip(self, request), fields(request = ?request.get_ref()), level = "debug")]
     async fn get_info(
         &self,
-        _request: Request<GetInfoRequest>,
+        request: Request<GetInfoRequest>,
     ) -> Result<Response<GetInfoResponse>, Status> {
+        self.auth.authorize(&request, Permission::ReadOnly)?;
         let tip = self.chain_repository.get_tip().await?;
         Ok(Response::new(GetInfoResponse {
             block_height: tip.map(|tip| tip.height).unwrap_or(0u64),
@@ -168,11 +213,12 @@ where
         }))
     }
 
-    #[instrument(skip(self), level = "debug")]
+    #[instrument(skip(self, request), fields(request = ?request.get_ref()), level = "debug")]
     async fn get_swap(
         &self,
         request: Request<GetSwapRequest>,
     ) -> Result<Response<GetSwapResponse>, Status> {
+        self.auth.authorize(&request, Permission::ReadOnly)?;
         let request = request.into_inner();
         let swap = match (
             request.address,
@@ -237,6 +283,12 @@ where
             .await
             .map_err(|e| Status::internal(format!("{:?}", e)))?;
 
+        let mempool_txos = self
+            .mempool_repository
+            .get_txos_for_address(&swap.swap.public.address)
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+
         let locks = self
             .swap_repository
             .get_swap_locks(&swap.swap.public.hash)
@@ -271,8 +323,31 @@ where
                     }),
                 })
                 .collect(),
+            unconfirmed_outputs: mempool_txos
+                .into_iter()
+                .map(|txo| {
+                    Ok(UnconfirmedSwapOutput {
+                        outpoint: txo.outpoint.to_string(),
+                        amount_sat: txo.tx_out.value.to_sat(),
+                        replaceable: txo.replaceable,
+                        first_seen: txo
+                            .first_seen
+                            .duration_since(UNIX_EPOCH)
+                            .map_err(|_| Status::internal("invalid system time"))?
+                            .as_secs(),
+                    })
+                })
+                .collect::<Result<Vec<_>, Status>>()?,
             payment_hash: swap.swap.public.hash.to_string(),
             lock_time: swap.swap.public.lock_time.into(),
+            claim_key_index: match &swap.swap.private.claim_key {
+                ClaimKey::Local { index, .. } => *index,
+                ClaimKey::Remote(key) => Some(key.index),
+            },
+            claim_key_family: match &swap.swap.private.claim_key {
+                ClaimKey::Local { .. } => None,
+                ClaimKey::Remote(key) => Some(key.family),
+            },
             active_locks: locks
                 .into_iter()
                 .map(|l| SwapLock {
@@ -293,6 +368,7 @@ where
                         label: a.attempt.label,
                         outpoints: a.attempt.outputs.iter().map(|o| o.to_string()).collect(),
                         amount_msat: a.attempt.amount_msat,
+                        fee_sat: a.attempt.fee_sat,
                         payment_request: a.attempt.payment_request,
                         error: match &a.result {
                             Some(r) => match r {
@@ -316,11 +392,86 @@ where
         Ok(Response::new(reply))
     }
 
-    #[instrument(skip(self), level = "debug")]
+    #[instrument(skip(self, request), fields(request = ?request.get_ref()), level = "debug")]
+    async fn get_reverse_swap(
+        &self,
+        request: Request<GetReverseSwapRequest>,
+    ) -> Result<Response<GetReverseSwapResponse>, Status> {
+        self.auth.authorize(&request, Permission::ReadOnly)?;
+        let request = request.into_inner();
+        let payment_hash = sha256::Hash::from_slice(&request.payment_hash)
+            .map_err(|_| Status::invalid_argument("invalid payment hash"))?;
+        let state = match self
+            .reverse_swap_repository
+            .get_reverse_swap(&payment_hash)
+            .await
+        {
+            Ok(state) => state,
+            Err(e) => {
+                return Err(match e {
+                    ReverseSwapRepositoryError::NotFound => {
+                        Status::not_found("reverse swap not found")
+                    }
+                    _ => Status::internal(format!("{:?}", e)),
+                })
+            }
+        };
+
+        let public = &state.reverse_swap.public;
+        let txos = self
+            .chain_repository
+            .get_txos_for_address_with_spends(&public.address)
+            .await
+            .map_err(|e| Status::internal(format!("{:?}", e)))?;
+        let reply = GetReverseSwapResponse {
+            address: public.address.to_string(),
+            creation_time: state
+                .reverse_swap
+                .creation_time
+                .duration_since(UNIX_EPOCH)
+                .map_err(|_| Status::internal("invalid system time"))?
+                .as_secs(),
+            payment_hash: public.hash.to_string(),
+            payment_request: state.payment_request.clone(),
+            amount_sat: state.amount_sat,
+            invoice_amount_msat: state.invoice_amount_msat,
+            lock_height: public.lock_height,
+            status: state.status.as_str().to_string(),
+            funding_tx_id: state.funding_tx_id.map(|txid| txid.to_string()),
+            preimage: state.preimage.map(hex::encode),
+            outputs: txos
+                .into_iter()
+                .map(|txo| SwapOutput {
+                    confirmation_height: txo.txo.block_height,
+                    outpoint: txo.txo.outpoint.to_string(),
+                    block_hash: txo.txo.block_hash.to_string(),
+                    spend: txo.spend.map(|spend| SwapOutputSpend {
+                        block_hash: spend.block_hash.to_string(),
+                        confirmation_height: spend.block_height,
+                        input_index: spend.spending_input_index,
+                        spend_type: SpendType::Unknown.into(),
+                        txid: spend.spending_tx.to_string(),
+                    }),
+                })
+                .collect(),
+            refund_key_index: match &state.reverse_swap.private.refund_key {
+                ClaimKey::Local { index, .. } => *index,
+                ClaimKey::Remote(key) => Some(key.index),
+            },
+            refund_key_family: match &state.reverse_swap.private.refund_key {
+                ClaimKey::Local { .. } => None,
+                ClaimKey::Remote(key) => Some(key.family),
+            },
+        };
+        Ok(Response::new(reply))
+    }
+
+    #[instrument(skip(self, request), fields(request = ?request.get_ref()), level = "debug")]
     async fn list_claimable(
         &self,
-        _request: Request<ListClaimableRequest>,
+        request: Request<ListClaimableRequest>,
     ) -> Result<Response<ListClaimableResponse>, Status> {
+        self.auth.authorize(&request, Permission::ReadOnly)?;
         let current_height = self.chain_client.get_blockheight().await?;
         let claimables = self.claim_service.list_claimable().await?;
         Ok(Response::new(ListClaimableResponse {
@@ -339,11 +490,12 @@ where
         }))
     }
 
-    #[instrument(skip(self), level = "debug")]
+    #[instrument(skip(self, request), fields(request = ?request.get_ref()), level = "debug")]
     async fn claim(
         &self,
         request: Request<ClaimRequest>,
     ) -> Result<Response<ClaimResponse>, Status> {
+        self.auth.authorize(&request, Permission::Admin)?;
         let request = request.into_inner();
         let all_claimables = self.claim_service.list_claimable().await?;
         let mut claimables = Vec::new();
@@ -405,19 +557,299 @@ where
         }))
     }
 
-    #[instrument(skip(self), level = "debug")]
-    async fn stop(&self, _request: Request<StopRequest>) -> Result<Response<StopResponse>, Status> {
+    #[instrument(skip(self, request), fields(request = ?request.get_ref()), level = "debug")]
+    async fn reencrypt_claim_keys(
+        &self,
+        request: Request<ReencryptClaimKeysRequest>,
+    ) -> Result<Response<ReencryptClaimKeysResponse>, Status> {
+        self.auth.authorize(&request, Permission::Admin)?;
+        let request = request.into_inner();
+        let batch_size = request.batch_size.unwrap_or(DEFAULT_REENCRYPT_BATCH_SIZE);
+        if batch_size == 0 {
+            return Err(Status::invalid_argument("batch_size must be positive"));
+        }
+
+        let reencrypted = self
+            .swap_repository
+            .reencrypt_claim_keys(batch_size)
+            .await?;
+        info!(reencrypted, "re-encrypted claim keys");
+        Ok(Response::new(ReencryptClaimKeysResponse { reencrypted }))
+    }
+
+    #[instrument(skip(self, request), fields(request = ?request.get_ref()), level = "debug")]
+    async fn rescan(
+        &self,
+        request: Request<RescanRequest>,
+    ) -> Result<Response<RescanResponse>, Status> {
+        self.auth.authorize(&request, Permission::Admin)?;
+        let request = request.into_inner();
+        let mut addresses = Vec::new();
+        for address in request.addresses {
+            addresses.push(
+                address
+                    .parse::<Address<NetworkUnchecked>>()
+                    .map_err(|e| Status::invalid_argument(e.to_string()))?
+                    .require_network(self.network)
+                    .map_err(|e| Status::invalid_argument(e.to_string()))?,
+            );
+        }
+
+        let watched = self
+            .chain_repository
+            .filter_watch_addresses(&addresses)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let unwatched: Vec<_> = addresses
+            .iter()
+            .filter(|a| !watched.contains(a))
+            .cloned()
+            .collect();
+        if !unwatched.is_empty() {
+            // Expired swaps are no longer watched. Watch them again so the
+            // rescan can find late deposits. If it doesn't, the expiry
+            // monitor expires them again. Archived swaps can't be rescanned,
+            // their outputs were archived with them.
+            let archived = self
+                .swap_repository
+                .filter_archived_addresses(&unwatched)
+                .await?;
+            if let Some(address) = archived.first() {
+                return Err(Status::failed_precondition(format!(
+                    "swap for address {} is archived",
+                    address
+                )));
+            }
+
+            let swaps = self.swap_repository.get_swaps(&unwatched).await?;
+            if let Some(address) = unwatched
+                .iter()
+                .find(|a| !swaps.get(a).is_some_and(|swap_state| swap_state.expired))
+            {
+                return Err(Status::invalid_argument(format!(
+                    "address {} is not watched",
+                    address
+                )));
+            }
+
+            let unexpired = self
+                .swap_repository
+                .unexpire_swaps(&unwatched)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?;
+            info!(
+                addresses = unexpired.len(),
+                "watching expired swaps again for rescan"
+            );
+        }
+
+        let result = self
+            .rescanner
+            .rescan(request.from_height, addresses)
+            .await?;
+        Ok(Response::new(RescanResponse {
+            new_outputs: result.new_outputs,
+            new_spends: result.new_spends,
+        }))
+    }
+
+    #[instrument(skip(self, request), fields(request = ?request.get_ref()), level = "debug")]
+    async fn list_unpaid_outputs(
+        &self,
+        request: Request<ListUnpaidOutputsRequest>,
+    ) -> Result<Response<ListUnpaidOutputsResponse>, Status> {
+        self.auth.authorize(&request, Permission::ReadOnly)?;
+        let outputs = self.claim_service.get_unpaid_outputs().await?;
+        Ok(Response::new(ListUnpaidOutputsResponse {
+            outputs: outputs
+                .into_iter()
+                .map(|o| UnpaidOutput {
+                    outpoint: o.outpoint.to_string(),
+                    address: o.address.to_string(),
+                    payment_hash: o.swap_hash.to_string(),
+                    amount_sat: o.amount_sat,
+                    creation_time: o
+                        .creation_time
+                        .duration_since(UNIX_EPOCH)
+                        .map(|d| d.as_secs())
+                        .unwrap_or_default(),
+                    disposition: o.disposition.map(|d| d.to_string()),
+                })
+                .collect(),
+        }))
+    }
+
+    #[instrument(skip(self, request), fields(request = ?request.get_ref()), level = "debug")]
+    async fn set_output_disposition(
+        &self,
+        request: Request<SetOutputDispositionRequest>,
+    ) -> Result<Response<SetOutputDispositionResponse>, Status> {
+        self.auth.authorize(&request, Permission::Admin)?;
+        let request = request.into_inner();
+        let outpoint = request
+            .outpoint
+            .parse::<OutPoint>()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let disposition = match internal_swap_api::OutputDisposition::try_from(request.disposition)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?
+        {
+            internal_swap_api::OutputDisposition::Donated => OutputDisposition::Donated,
+            internal_swap_api::OutputDisposition::Abandoned => OutputDisposition::Abandoned,
+        };
+        self.claim_service
+            .set_output_disposition(&outpoint, disposition)
+            .await?;
+        info!(
+            outpoint = field::display(outpoint),
+            disposition = field::display(disposition),
+            "set unpaid output disposition"
+        );
+        Ok(Response::new(SetOutputDispositionResponse {}))
+    }
+
+    #[instrument(skip(self, request), fields(request = ?request.get_ref()), level = "debug")]
+    async fn subscribe_swap_events(
+        &self,
+        request: Request<SubscribeSwapEventsRequest>,
+    ) -> Result<Response<Self::SubscribeSwapEventsStream>, Status> {
+        self.auth.authorize(&request, Permission::ReadOnly)?;
+        let stream = self
+            .event_bus
+            .subscribe()
+            .map_ok(map_swap_event)
+            .map_err(Status::from);
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    #[instrument(skip(self, request), fields(request = ?request.get_ref()), level = "debug")]
+    async fn stop(&self, request: Request<StopRequest>) -> Result<Response<StopResponse>, Status> {
+        self.auth.authorize(&request, Permission::Admin)?;
         self.token.cancel();
         Ok(Response::new(StopResponse {}))
     }
 }
 
+fn map_swap_event(event: event::SwapEvent) -> SwapEvent {
+    let kind = match event.kind {
+        SwapEventKind::DepositConfirmed {
+            outpoint,
+            amount,
+            block_height,
+        } => swap_event::Event::DepositConfirmed(DepositConfirmed {
+            outpoint: outpoint.to_string(),
+            amount_sat: amount.to_sat(),
+            block_height,
+        }),
+        SwapEventKind::DepositUnconfirmed {
+            outpoint,
+            amount,
+            replaceable,
+        } => swap_event::Event::DepositUnconfirmed(DepositUnconfirmed {
+            outpoint: outpoint.to_string(),
+            amount_sat: amount.to_sat(),
+            replaceable,
+        }),
+        SwapEventKind::DepositDropped { outpoint } => {
+            swap_event::Event::DepositDropped(DepositDropped {
+                outpoint: outpoint.to_string(),
+            })
+        }
+        SwapEventKind::RefundOffered { outpoint, amount } => {
+            swap_event::Event::RefundOffered(RefundOffered {
+                outpoint: outpoint.to_string(),
+                amount_sat: amount.to_sat(),
+            })
+        }
+        SwapEventKind::Paid { payment_hash } => swap_event::Event::Paid(SwapPaid {
+            payment_hash: payment_hash.to_string(),
+        }),
+        SwapEventKind::PaymentFailed {
+            payment_hash,
+            error,
+        } => swap_event::Event::PaymentFailed(SwapPaymentFailed {
+            payment_hash: payment_hash.to_string(),
+            error,
+        }),
+        SwapEventKind::ClaimBroadcast { tx_id } => {
+            swap_event::Event::ClaimBroadcast(ClaimBroadcast {
+                tx_id: tx_id.to_string(),
+            })
+        }
+        SwapEventKind::OutputSpent {
+            outpoint,
+            spending_tx_id,
+            block_height,
+        } => swap_event::Event::OutputSpent(OutputSpent {
+            outpoint: outpoint.to_string(),
+            spending_tx_id: spending_tx_id.to_string(),
+            block_height,
+        }),
+    };
+
+    SwapEvent {
+        address: event.address.to_string(),
+        timestamp: event
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        event: Some(kind),
+    }
+}
+
+impl From<AuthorizationError> for Status {
+    fn from(value: AuthorizationError) -> Self {
+        match value {
+            AuthorizationError::MissingToken | AuthorizationError::InvalidToken => {
+                Status::unauthenticated(value.to_string())
+            }
+            AuthorizationError::PermissionDenied => Status::permission_denied(value.to_string()),
+        }
+    }
+}
+
 impl From<ClaimServiceError> for Status {
     fn from(value: ClaimServiceError) -> Self {
         Status::internal(value.to_string())
     }
 }
 
+impl From<ClaimRepositoryError> for Status {
+    fn from(value: ClaimRepositoryError) -> Self {
+        match value {
+            ClaimRepositoryError::UnpaidOutputNotFound => Status::not_found(value.to_string()),
+            ClaimRepositoryError::DispositionConflict(_) => {
+                Status::failed_precondition(value.to_string())
+            }
+            ClaimRepositoryError::InvalidDisposition(_) => {
+                Status::invalid_argument(value.to_string())
+            }
+            _ => Status::internal(value.to_string()),
+        }
+    }
+}
+
+impl From<ReencryptClaimKeysError> for Status {
+    fn from(value: ReencryptClaimKeysError) -> Self {
+        match value {
+            ReencryptClaimKeysError::NoEncryptionKey => {
+                Status::failed_precondition(value.to_string())
+            }
+            ReencryptClaimKeysError::General(e) => Status::internal(e.to_string()),
+        }
+    }
+}
+
+impl From<RescanError> for Status {
+    fn from(value: RescanError) -> Self {
+        match value {
+            RescanError::InvalidHeight { .. } => Status::invalid_argument(value.to_string()),
+            RescanError::Unavailable => Status::unavailable(value.to_string()),
+            RescanError::Chain(e) => Status::internal(e.to_string()),
+        }
+    }
+}
+
 impl From<WalletError> for Status {
     fn from(value: WalletError) -> Self {
         Status::internal(value.to_string())
@@ -59,4 +59,6 @@ pub trait LightningClient {
         hash: &sha256::Hash,
     ) -> Result<bool, LightningError>;
     async fn pay(&self, request: PaymentRequest) -> Result<PaymentResult, LightningError>;
+    /// Makes a cheap call to the node, to check it is reachable.
+    async fn ping(&self) -> Result<(), LightningError>;
 }
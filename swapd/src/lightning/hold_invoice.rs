@@ -0,0 +1,41 @@
+use bitcoin::hashes::sha256;
+
+use super::LightningError;
+
+#[derive(Debug)]
+pub struct HoldInvoiceRequest {
+    pub amount_msat: u64,
+    pub cltv_expiry_delta: u32,
+    pub description: String,
+    pub expiry_seconds: u64,
+    pub payment_hash: sha256::Hash,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum HoldInvoiceState {
+    /// The invoice has not been paid yet.
+    Open,
+    /// All htlcs for the invoice have arrived and are being held.
+    /// `min_htlc_expiry` is the lowest expiry height of the held htlcs. The
+    /// invoice has to be settled before that height.
+    Accepted {
+        min_htlc_expiry: u64,
+    },
+    Settled,
+    Canceled,
+}
+
+/// Creates and resolves invoices where the preimage is not known to the node
+/// at creation time. Incoming htlcs are held until the invoice is either
+/// settled with the preimage or canceled.
+#[async_trait::async_trait]
+pub trait HoldInvoiceClient {
+    async fn add_hold_invoice(&self, request: HoldInvoiceRequest)
+        -> Result<String, LightningError>;
+    async fn cancel_hold_invoice(&self, hash: &sha256::Hash) -> Result<(), LightningError>;
+    async fn get_hold_invoice_state(
+        &self,
+        hash: &sha256::Hash,
+    ) -> Result<HoldInvoiceState, LightningError>;
+    async fn settle_hold_invoice(&self, preimage: &[u8; 32]) -> Result<(), LightningError>;
+}
@@ -1,4 +1,6 @@
 mod client;
+mod hold_invoice;
 pub use client::{
     LightningClient, LightningError, PaymentRequest, PaymentResult, PaymentState, PreimageResult,
 };
+pub use hold_invoice::{HoldInvoiceClient, HoldInvoiceRequest, HoldInvoiceState};
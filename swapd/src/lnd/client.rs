@@ -19,11 +19,14 @@ use crate::{
 };
 
 use super::{
+    invoicesrpc::invoices_client::InvoicesClient,
     lnrpc::{
         htlc_attempt::HtlcStatus, lightning_client::LightningClient, payment::PaymentStatus, Hop,
         ListPaymentsRequest,
     },
     routerrpc::{router_client::RouterClient, SendPaymentRequest, TrackPaymentRequest},
+    signrpc::signer_client::SignerClient,
+    walletrpc::wallet_kit_client::WalletKitClient,
     Repository, RepositoryError,
 };
 
@@ -144,6 +147,60 @@ where
 
         Ok(client)
     }
+
+    pub(super) async fn get_invoices_client(
+        &self,
+    ) -> Result<
+        InvoicesClient<
+            InterceptedService<Channel, impl Fn(Request<()>) -> Result<Request<()>, Status>>,
+        >,
+        GetClientError,
+    > {
+        let channel = self.get_channel().await?;
+        let macaroon = self.macaroon.clone();
+        let client = InvoicesClient::with_interceptor(channel, move |mut req: Request<()>| {
+            req.metadata_mut().insert("macaroon", macaroon.clone());
+            Ok(req)
+        });
+
+        Ok(client)
+    }
+
+    pub(super) async fn get_signer_client(
+        &self,
+    ) -> Result<
+        SignerClient<
+            InterceptedService<Channel, impl Fn(Request<()>) -> Result<Request<()>, Status>>,
+        >,
+        GetClientError,
+    > {
+        let channel = self.get_channel().await?;
+        let macaroon = self.macaroon.clone();
+        let client = SignerClient::with_interceptor(channel, move |mut req: Request<()>| {
+            req.metadata_mut().insert("macaroon", macaroon.clone());
+            Ok(req)
+        });
+
+        Ok(client)
+    }
+
+    pub(super) async fn get_wallet_kit_client(
+        &self,
+    ) -> Result<
+        WalletKitClient<
+            InterceptedService<Channel, impl Fn(Request<()>) -> Result<Request<()>, Status>>,
+        >,
+        GetClientError,
+    > {
+        let channel = self.get_channel().await?;
+        let macaroon = self.macaroon.clone();
+        let client = WalletKitClient::with_interceptor(channel, move |mut req: Request<()>| {
+            req.metadata_mut().insert("macaroon", macaroon.clone());
+            Ok(req)
+        });
+
+        Ok(client)
+    }
 }
 
 #[async_trait::async_trait]
@@ -413,6 +470,20 @@ where
             "did not receive final update from payment",
         )))
     }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn ping(&self) -> Result<(), LightningError> {
+        // ListPayments only needs the offchain:read permission the macaroon
+        // already has, unlike GetInfo.
+        let mut client = self.get_client().await?;
+        client
+            .list_payments(ListPaymentsRequest {
+                max_payments: 1,
+                ..Default::default()
+            })
+            .await?;
+        Ok(())
+    }
 }
 
 fn hops_to_string(hops: &[Hop]) -> String {
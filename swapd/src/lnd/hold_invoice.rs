@@ -0,0 +1,91 @@
+use bitcoin::hashes::{sha256, Hash};
+use tracing::instrument;
+
+use crate::lightning::{HoldInvoiceClient, HoldInvoiceRequest, HoldInvoiceState, LightningError};
+
+use super::{
+    invoicesrpc::{AddHoldInvoiceRequest, CancelInvoiceMsg, SettleInvoiceMsg},
+    lnrpc::{invoice::InvoiceState, InvoiceHtlcState, PaymentHash},
+    Client, Repository,
+};
+
+#[async_trait::async_trait]
+impl<R> HoldInvoiceClient for Client<R>
+where
+    R: Repository + Send + Sync,
+{
+    #[instrument(level = "trace", skip(self))]
+    async fn add_hold_invoice(
+        &self,
+        request: HoldInvoiceRequest,
+    ) -> Result<String, LightningError> {
+        let mut client = self.get_invoices_client().await?;
+        let resp = client
+            .add_hold_invoice(AddHoldInvoiceRequest {
+                memo: request.description,
+                hash: request.payment_hash.as_byte_array().to_vec(),
+                value_msat: request.amount_msat as i64,
+                expiry: request.expiry_seconds as i64,
+                cltv_expiry: request.cltv_expiry_delta as u64,
+                ..Default::default()
+            })
+            .await
+            .map_err(LightningError::General)?
+            .into_inner();
+        Ok(resp.payment_request)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn cancel_hold_invoice(&self, hash: &sha256::Hash) -> Result<(), LightningError> {
+        let mut client = self.get_invoices_client().await?;
+        client
+            .cancel_invoice(CancelInvoiceMsg {
+                payment_hash: hash.as_byte_array().to_vec(),
+            })
+            .await
+            .map_err(LightningError::General)?;
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn get_hold_invoice_state(
+        &self,
+        hash: &sha256::Hash,
+    ) -> Result<HoldInvoiceState, LightningError> {
+        let mut client = self.get_client().await?;
+        let invoice = client
+            .lookup_invoice(PaymentHash {
+                r_hash: hash.as_byte_array().to_vec(),
+                ..Default::default()
+            })
+            .await
+            .map_err(LightningError::General)?
+            .into_inner();
+        Ok(match invoice.state() {
+            InvoiceState::Open => HoldInvoiceState::Open,
+            InvoiceState::Accepted => HoldInvoiceState::Accepted {
+                min_htlc_expiry: invoice
+                    .htlcs
+                    .iter()
+                    .filter(|htlc| htlc.state() == InvoiceHtlcState::Accepted)
+                    .map(|htlc| htlc.expiry_height.max(0) as u64)
+                    .min()
+                    .unwrap_or(0),
+            },
+            InvoiceState::Settled => HoldInvoiceState::Settled,
+            InvoiceState::Canceled => HoldInvoiceState::Canceled,
+        })
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn settle_hold_invoice(&self, preimage: &[u8; 32]) -> Result<(), LightningError> {
+        let mut client = self.get_invoices_client().await?;
+        client
+            .settle_invoice(SettleInvoiceMsg {
+                preimage: preimage.to_vec(),
+            })
+            .await
+            .map_err(LightningError::General)?;
+        Ok(())
+    }
+}
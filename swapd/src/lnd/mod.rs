@@ -1,9 +1,17 @@
 mod client;
+mod hold_invoice;
 mod repository;
+mod signer;
 mod wallet;
 
 pub use client::{Client, ClientConnection};
 pub use repository::{Repository, RepositoryError};
+pub use signer::RemoteSigner;
+
+pub mod invoicesrpc {
+    #![allow(clippy::all)]
+    tonic::include_proto!("invoicesrpc");
+}
 
 pub mod lnrpc {
     #![allow(clippy::all)]
@@ -14,3 +22,13 @@ pub mod routerrpc {
     #![allow(clippy::all)]
     tonic::include_proto!("routerrpc");
 }
+
+pub mod signrpc {
+    #![allow(clippy::all)]
+    tonic::include_proto!("signrpc");
+}
+
+pub mod walletrpc {
+    #![allow(clippy::all)]
+    tonic::include_proto!("walletrpc");
+}
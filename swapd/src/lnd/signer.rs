@@ -0,0 +1,244 @@
+use std::sync::Arc;
+
+use bitcoin::{
+    consensus::encode::serialize,
+    hashes::Hash,
+    secp256k1::{schnorr, PublicKey, SecretKey, XOnlyPublicKey},
+    Script, TapNodeHash, TapSighash, TapSighashType, Transaction, TxOut,
+};
+use secp256k1::musig::{MusigPartialSignature, MusigPubNonce};
+use tracing::{instrument, warn};
+
+use crate::swap::{
+    ClaimKey, KeyLocator, PrivateKeyError, PrivateKeyProvider, RandomProvider, RemoteMusigSession,
+};
+
+use super::{
+    client::GetClientError,
+    signrpc::{
+        self, KeyDescriptor, MuSig2CleanupRequest, MuSig2RegisterNoncesRequest,
+        MuSig2SessionRequest, MuSig2SignRequest, MuSig2Version, SignDescriptor, SignMethod,
+        SignReq, TaprootTweakDesc,
+    },
+    walletrpc::KeyReq,
+    Client, Repository,
+};
+
+/// Key family of swap claim keys in lnd's wallet.
+const CLAIM_KEY_FAMILY: u32 = 1984;
+
+/// Key family of reverse swap refund keys in lnd's wallet.
+const REVERSE_REFUND_KEY_FAMILY: u32 = 1985;
+
+/// Keeps swap claim keys and reverse swap refund keys in lnd's wallet. Claim
+/// and refund transactions and MuSig2 sessions are signed by lnd, so these
+/// private keys never leave lnd. One-off secrets are still random.
+#[derive(Debug)]
+pub struct RemoteSigner<R, RP>
+where
+    R: Repository,
+{
+    client: Arc<Client<R>>,
+    rnd: Arc<RP>,
+}
+
+impl<R, RP> RemoteSigner<R, RP>
+where
+    R: Repository,
+    RP: RandomProvider,
+{
+    pub fn new(client: Arc<Client<R>>, rnd: Arc<RP>) -> Self {
+        Self { client, rnd }
+    }
+
+    async fn derive_next_key(&self, family: u32) -> Result<(PublicKey, ClaimKey), PrivateKeyError> {
+        let mut client = self.client.get_wallet_kit_client().await?;
+        let resp = client
+            .derive_next_key(KeyReq {
+                key_family: family as i32,
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+        let key_loc = resp.key_loc.ok_or(PrivateKeyError::General(
+            "lnd returned no key locator".into(),
+        ))?;
+        Ok((
+            PublicKey::from_slice(&resp.raw_key_bytes)?,
+            ClaimKey::Remote(KeyLocator {
+                family: key_loc.key_family as u32,
+                index: key_loc.key_index as u32,
+            }),
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl<R, RP> PrivateKeyProvider for RemoteSigner<R, RP>
+where
+    R: Repository + Send + Sync,
+    RP: RandomProvider + Send + Sync,
+{
+    fn new_private_key(&self) -> Result<SecretKey, PrivateKeyError> {
+        let key = self.rnd.rnd_32()?;
+        Ok(SecretKey::from_slice(&key)?)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn new_claim_key(&self) -> Result<(PublicKey, ClaimKey), PrivateKeyError> {
+        self.derive_next_key(CLAIM_KEY_FAMILY).await
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn new_reverse_refund_key(&self) -> Result<(PublicKey, ClaimKey), PrivateKeyError> {
+        self.derive_next_key(REVERSE_REFUND_KEY_FAMILY).await
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn sign_script_spend(
+        &self,
+        key: &KeyLocator,
+        tx: &Transaction,
+        prevouts: &[TxOut],
+        input_index: usize,
+        leaf_script: &Script,
+    ) -> Result<schnorr::Signature, PrivateKeyError> {
+        let prev_outputs: Vec<_> = prevouts
+            .iter()
+            .map(|o| signrpc::TxOut {
+                value: o.value.to_sat() as i64,
+                pk_script: o.script_pubkey.to_bytes(),
+            })
+            .collect();
+        let output = prev_outputs
+            .get(input_index)
+            .cloned()
+            .ok_or(PrivateKeyError::General("missing prevout".into()))?;
+        let mut client = self.client.get_signer_client().await?;
+        let resp = client
+            .sign_output_raw(SignReq {
+                raw_tx_bytes: serialize(tx),
+                sign_descs: vec![SignDescriptor {
+                    key_desc: Some(KeyDescriptor {
+                        key_loc: Some(key_locator(key)),
+                        ..Default::default()
+                    }),
+                    witness_script: leaf_script.to_bytes(),
+                    output: Some(output),
+                    sighash: TapSighashType::Default as u32,
+                    input_index: input_index as i32,
+                    sign_method: SignMethod::TaprootScriptSpend as i32,
+                    ..Default::default()
+                }],
+                prev_outputs,
+            })
+            .await?
+            .into_inner();
+        let signature = resp
+            .raw_sigs
+            .first()
+            .ok_or(PrivateKeyError::General("lnd returned no signature".into()))?;
+        Ok(schnorr::Signature::from_slice(signature)?)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn create_musig_session(
+        &self,
+        key: &KeyLocator,
+        signer_pubkeys: &[PublicKey],
+        script_root: TapNodeHash,
+    ) -> Result<RemoteMusigSession, PrivateKeyError> {
+        let mut client = self.client.get_signer_client().await?;
+        let resp = client
+            .mu_sig2_create_session(MuSig2SessionRequest {
+                key_loc: Some(key_locator(key)),
+                all_signer_pubkeys: signer_pubkeys
+                    .iter()
+                    .map(|p| p.serialize().to_vec())
+                    .collect(),
+                taproot_tweak: Some(TaprootTweakDesc {
+                    script_root: script_root.to_byte_array().to_vec(),
+                    key_spend_only: false,
+                }),
+                version: MuSig2Version::Musig2VersionV100rc2 as i32,
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+        let pub_nonce = MusigPubNonce::from_slice(&resp.local_public_nonces)
+            .map_err(|e| PrivateKeyError::General(format!("invalid nonce: {:?}", e).into()))?;
+        Ok(RemoteMusigSession {
+            id: resp.session_id,
+            combined_key: XOnlyPublicKey::from_slice(&resp.combined_key)?,
+            pub_nonce,
+        })
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn musig_sign(
+        &self,
+        session_id: &[u8],
+        their_pub_nonce: &MusigPubNonce,
+        sighash: TapSighash,
+    ) -> Result<MusigPartialSignature, PrivateKeyError> {
+        let mut client = self.client.get_signer_client().await?;
+        let result: Result<_, PrivateKeyError> = async {
+            client
+                .mu_sig2_register_nonces(MuSig2RegisterNoncesRequest {
+                    session_id: session_id.to_vec(),
+                    other_signer_public_nonces: vec![their_pub_nonce.serialize().to_vec()],
+                })
+                .await?;
+            let resp = client
+                .mu_sig2_sign(MuSig2SignRequest {
+                    session_id: session_id.to_vec(),
+                    message_digest: sighash.to_byte_array().to_vec(),
+                    cleanup: true,
+                })
+                .await?
+                .into_inner();
+            MusigPartialSignature::from_slice(&resp.local_partial_signature).map_err(|e| {
+                PrivateKeyError::General(format!("invalid partial signature: {:?}", e).into())
+            })
+        }
+        .await;
+
+        // lnd only cleans up the session if signing succeeded.
+        if result.is_err() {
+            if let Err(e) = self.cleanup_musig_session(session_id).await {
+                warn!("failed to clean up musig session: {:?}", e);
+            }
+        }
+        result
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn cleanup_musig_session(&self, session_id: &[u8]) -> Result<(), PrivateKeyError> {
+        let mut client = self.client.get_signer_client().await?;
+        client
+            .mu_sig2_cleanup(MuSig2CleanupRequest {
+                session_id: session_id.to_vec(),
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+fn key_locator(key: &KeyLocator) -> signrpc::KeyLocator {
+    signrpc::KeyLocator {
+        key_family: key.family as i32,
+        key_index: key.index as i32,
+    }
+}
+
+impl From<GetClientError> for PrivateKeyError {
+    fn from(value: GetClientError) -> Self {
+        PrivateKeyError::General(Box::new(value))
+    }
+}
+
+impl From<tonic::Status> for PrivateKeyError {
+    fn from(value: tonic::Status) -> Self {
+        PrivateKeyError::General(Box::new(value))
+    }
+}
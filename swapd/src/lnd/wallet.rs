@@ -1,8 +1,16 @@
-use bitcoin::{address::NetworkUnchecked, Address};
+use std::str::FromStr;
+
+use bitcoin::{address::NetworkUnchecked, Address, OutPoint, Txid};
 
 use crate::wallet::{Wallet, WalletError};
 
-use super::{client::GetClientError, lnrpc::NewAddressRequest, Client, Repository};
+use super::{
+    client::GetClientError,
+    lnrpc::{
+        self, GetTransactionsRequest, NewAddressRequest, SendCoinsRequest, WalletBalanceRequest,
+    },
+    Client, Repository,
+};
 
 impl From<GetClientError> for WalletError {
     fn from(value: GetClientError) -> Self {
@@ -29,4 +37,87 @@ where
         let address = address.require_network(self.network)?;
         Ok(address)
     }
+
+    async fn balance_sat(&self) -> Result<u64, WalletError> {
+        let mut client = self.get_client().await?;
+        let resp = client
+            .wallet_balance(WalletBalanceRequest {
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+        Ok(resp.total_balance.max(0) as u64)
+    }
+
+    async fn send_to_address(
+        &self,
+        address: &Address,
+        amount_sat: u64,
+        fee_per_kw: u32,
+    ) -> Result<Txid, WalletError> {
+        let mut client = self.get_client().await?;
+        let resp = client
+            .send_coins(SendCoinsRequest {
+                addr: address.to_string(),
+                amount: amount_sat as i64,
+                // lnd takes a fee rate in sat/vbyte.
+                sat_per_vbyte: (fee_per_kw as u64 * 4).div_ceil(1000),
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+        let txid = Txid::from_str(&resp.txid).map_err(|e| WalletError::General(Box::new(e)))?;
+        Ok(txid)
+    }
+
+    async fn find_tx_to_address(&self, address: &Address) -> Result<Option<Txid>, WalletError> {
+        let mut client = self.get_client().await?;
+        let resp = client
+            .get_transactions(GetTransactionsRequest {
+                start_height: 0,
+                // Include unconfirmed transactions.
+                end_height: -1,
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+        let address = address.to_string();
+        let tx = match resp.transactions.into_iter().find(|tx| {
+            tx.output_details
+                .iter()
+                .any(|output| output.address == address)
+        }) {
+            Some(tx) => tx,
+            None => return Ok(None),
+        };
+        let txid = Txid::from_str(&tx.tx_hash).map_err(|e| WalletError::General(Box::new(e)))?;
+        Ok(Some(txid))
+    }
+
+    async fn sweep_output(
+        &self,
+        outpoint: &OutPoint,
+        address: &Address,
+        fee_per_kw: u32,
+    ) -> Result<Txid, WalletError> {
+        let mut client = self.get_client().await?;
+        let resp = client
+            .send_coins(SendCoinsRequest {
+                addr: address.to_string(),
+                send_all: true,
+                outpoints: vec![lnrpc::OutPoint {
+                    txid_str: outpoint.txid.to_string(),
+                    output_index: outpoint.vout,
+                    ..Default::default()
+                }],
+                spend_unconfirmed: true,
+                // lnd takes a fee rate in sat/vbyte.
+                sat_per_vbyte: (fee_per_kw as u64 * 4).div_ceil(1000),
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+        let txid = Txid::from_str(&resp.txid).map_err(|e| WalletError::General(Box::new(e)))?;
+        Ok(txid)
+    }
 }
@@ -1,30 +1,57 @@
 use std::{fmt::Debug, path::PathBuf, sync::Arc, time::Duration};
 
 use base64::{prelude::BASE64_STANDARD, Engine};
-use bitcoin::Network;
-use bitcoind::BitcoindClient;
-use chain::{ChainMonitor, FallbackFeeEstimator};
+use bitcoin::{address::NetworkUnchecked, bip32::Xpriv, Address, Network, NetworkKind};
+use bitcoind::{BitcoindClient, ZmqBlockListener, ZmqTxListener};
+use chain::{
+    rescan_channel, BlockNotifier, ChainClient, ChainMonitor, FallbackFeeEstimator, FeeEstimator,
+};
 use chain_filter::ChainFilterImpl;
-use claim::{ClaimMonitor, ClaimMonitorParams, ClaimService, PreimageMonitor};
+use claim::{
+    ClaimDescriptor, ClaimDestinationPolicy, ClaimMonitor, ClaimMonitorParams, ClaimService,
+    ColdDestination, FeeEscalation, PreimageMonitor, ReverseRefundMonitor,
+    ReverseRefundMonitorParams, UnpaidOutputMonitor, UnpaidOutputMonitorParams,
+};
 use clap::Parser;
+use electrum::{ElectrumClient, ElectrumMonitor, ElectrumMonitorParams};
+use esplora::EsploraClient;
+use event::EventBus;
 use figment::{
     providers::{Env, Format, Serialized, Toml},
     Figment,
 };
-use internal_server::internal_swap_api::swap_manager_server::SwapManagerServer;
-use lightning::LightningClient;
-use postgresql::LndRepository;
+use health::{
+    health_api::health_server::HealthServer, HealthMonitor, HealthMonitorParams, HealthService,
+};
+use internal_auth::InternalAuth;
+use internal_server::internal_swap_api::swap_manager_server::{self, SwapManagerServer};
+use lightning::{HoldInvoiceClient, LightningClient};
+use mempool::{MempoolClient, MempoolMonitor, MempoolMonitorParams};
+use metrics::{Metrics, MetricsServer};
+use postgresql::{KeyCipher, LndRepository};
 use public_server::{
-    swap_api::taproot_swapper_server::TaprootSwapperServer, SwapServer, SwapServerParams,
+    swap_api::{
+        reverse_swapper_server::{self, ReverseSwapperServer},
+        taproot_swapper_server::{self, TaprootSwapperServer},
+    },
+    CreateSwapLimits, SwapServer, SwapServerParams,
 };
 use reqwest::Url;
+use retention::{RetentionMonitor, RetentionMonitorParams};
+use reverse::{ReverseSwapMonitor, ReverseSwapMonitorParams};
+use reverse_server::{ReverseSwapServer, ReverseSwapServerParams};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use sqlx::{PgPool, Pool, Postgres};
-use swap::{HistoricalPaymentMonitor, RandomPrivateKeyProvider, RingRandomProvider, SwapService};
-use tokio::signal;
+use swap::{
+    Bip32PrivateKeyProvider, HistoricalPaymentMonitor, PrivateKeyProvider,
+    RandomPrivateKeyProvider, RingRandomProvider, SwapExpiryMonitor, SwapExpiryMonitorParams,
+    SwapFeePolicy, SwapService,
+};
+use tls::{CertReloader, CertReloaderParams};
+use tokio::{net::TcpListener, signal, sync::broadcast};
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
-use tonic::transport::{Certificate, Identity, Server, Uri};
+use tonic::transport::{Certificate, Identity, Server, ServerTlsConfig, Uri};
 use tracing::{field, info, trace, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 use wallet::Wallet;
@@ -35,12 +62,24 @@ mod chain;
 mod chain_filter;
 mod claim;
 mod cln;
+mod electrum;
+mod esplora;
+mod event;
+mod health;
+mod internal_auth;
 mod internal_server;
 mod lightning;
 mod lnd;
+mod mempool;
+mod metrics;
 mod postgresql;
 mod public_server;
+mod rate_limit;
+mod retention;
+mod reverse;
+mod reverse_server;
 mod swap;
+mod tls;
 mod wallet;
 mod whatthefee;
 
@@ -104,6 +143,69 @@ impl FileOrCert {
     }
 }
 
+/// Reads an xprv that is either given directly or as a path to a file
+/// containing it.
+/// Reads the file at `value` if it exists, otherwise returns `value` itself.
+async fn read_value_or_file(value: &str) -> String {
+    match tokio::fs::read_to_string(value).await {
+        Ok(content) => content,
+        Err(_) => value.to_string(),
+    }
+}
+
+async fn resolve_xprv(value: &str, network: Network) -> Result<Xpriv, Box<dyn std::error::Error>> {
+    let xprv: Xpriv = read_value_or_file(value)
+        .await
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid claim_key_xprv: {:?}", e))?;
+    if xprv.network != NetworkKind::from(network) {
+        return Err("claim_key_xprv is for the wrong network".into());
+    }
+
+    Ok(xprv)
+}
+
+fn claim_destination_policy(
+    args: &Args,
+) -> Result<ClaimDestinationPolicy, Box<dyn std::error::Error>> {
+    let cold = match (
+        &args.claim_destination_address,
+        &args.claim_destination_descriptor,
+    ) {
+        (Some(_), Some(_)) => {
+            return Err(
+                "claim_destination_address and claim_destination_descriptor cannot both be set"
+                    .into(),
+            )
+        }
+        (Some(address), None) => Some(ColdDestination::Address(
+            address
+                .parse::<Address<NetworkUnchecked>>()
+                .map_err(|e| format!("invalid claim_destination_address: {:?}", e))?
+                .require_network(args.network)
+                .map_err(|_| "claim_destination_address is for the wrong network")?,
+        )),
+        (None, Some(descriptor)) => Some(ColdDestination::Descriptor(
+            ClaimDescriptor::parse(descriptor, args.network)
+                .map_err(|e| format!("invalid claim_destination_descriptor: {}", e))?,
+        )),
+        (None, None) => None,
+    };
+    if cold.is_none() && args.claim_wallet_reserve_sat > 0 {
+        return Err(concat!(
+            "claim_wallet_reserve_sat requires claim_destination_address or ",
+            "claim_destination_descriptor"
+        )
+        .into());
+    }
+
+    Ok(ClaimDestinationPolicy {
+        cold,
+        wallet_reserve_sat: args.claim_wallet_reserve_sat,
+    })
+}
+
 #[serde_as]
 #[derive(Clone, Parser, Debug, Serialize, Deserialize)]
 #[command(version, about, long_about = None)]
@@ -115,10 +217,72 @@ struct Args {
     #[arg(long, default_value = "127.0.0.1:58049")]
     pub address: core::net::SocketAddr,
 
+    /// Certificate the grpc server presents to clients. Can either be a file
+    /// path or the cert contents. Together with tls_key this enables TLS on
+    /// the grpc server. Given as files, the certificate and key are reloaded
+    /// when the files change, so they can be rotated without a restart.
+    #[arg(long)]
+    pub tls_cert: Option<FileOrCert>,
+
+    /// Private key of tls_cert. Can either be a file path or the key contents.
+    #[arg(long)]
+    pub tls_key: Option<FileOrCert>,
+
+    /// Interval between checking tls_cert and tls_key for changes.
+    #[arg(long, default_value = "60")]
+    pub tls_reload_interval_seconds: u64,
+
     /// Address the internal grpc server will listen on.
     #[arg(long, default_value = "127.0.0.1:58050")]
     pub internal_address: core::net::SocketAddr,
 
+    /// Certificate the internal grpc server presents to clients. Can either be
+    /// a file path or the cert contents. Together with internal_tls_key this
+    /// enables TLS on the internal grpc server.
+    #[arg(long)]
+    pub internal_tls_cert: Option<FileOrCert>,
+
+    /// Private key of internal_tls_cert. Can either be a file path or the key
+    /// contents.
+    #[arg(long)]
+    pub internal_tls_key: Option<FileOrCert>,
+
+    /// CA cert to verify client certificates of the internal grpc server
+    /// with. Can either be a file path or the cert contents. If set, clients
+    /// without a certificate signed by this CA are rejected. Requires
+    /// internal_tls_cert and internal_tls_key.
+    #[arg(long)]
+    pub internal_tls_client_ca_cert: Option<FileOrCert>,
+
+    /// Token that grants access to all internal grpc methods. Can either be a
+    /// file path or the token itself. Clients pass it as
+    /// `authorization: Bearer <token>`. If not set, the internal grpc server
+    /// does not check authorization.
+    #[arg(long)]
+    pub internal_admin_token: Option<String>,
+
+    /// Token that grants access to the internal grpc methods that only read
+    /// swap state. Can either be a file path or the token itself. Requires
+    /// internal_admin_token.
+    #[arg(long)]
+    pub internal_readonly_token: Option<String>,
+
+    /// Address the prometheus metrics endpoint will listen on, for example
+    /// `127.0.0.1:9090`. Metrics are served on `/metrics`. If not set, metrics
+    /// are not exposed.
+    #[arg(long)]
+    pub metrics_address: Option<core::net::SocketAddr>,
+
+    /// Interval between checking whether the dependencies of swapd are
+    /// available, for the grpc health service and the metrics.
+    #[arg(long, default_value = "15")]
+    pub connection_check_interval_seconds: u64,
+
+    /// Maximum number of blocks the synced chain may lag behind the chain
+    /// backend before the grpc health service reports swapd as not serving.
+    #[arg(long, default_value = "3")]
+    pub max_chain_lag_blocks: u64,
+
     /// Maximum amount allowed for swaps.
     #[arg(long, default_value = "4000000")]
     pub max_swap_amount_sat: u64,
@@ -155,6 +319,23 @@ struct Args {
     #[arg(long, default_value = "546")]
     pub dust_limit_sat: u64,
 
+    /// Master extended private key to derive swap claim keys from, or a path
+    /// to a file containing it. If set, the claim key of every swap can be
+    /// recovered from this key and the swap's claim key index. If not set,
+    /// claim keys are random and only exist in the database.
+    #[arg(long)]
+    pub claim_key_xprv: Option<String>,
+
+    /// Keys to encrypt claim private keys and reverse swap refund private keys
+    /// in the database with, or a path to a file containing them. Formatted
+    /// as `<version>:<hex encoded 32 byte key>`, separated by commas or
+    /// newlines. New keys are encrypted with the highest version, older
+    /// versions are only used for decryption. Use
+    /// `swap-cli reencrypt-claim-keys` after adding a new version to rotate.
+    /// If not set, private keys are stored in plaintext.
+    #[arg(long)]
+    pub claim_key_encryption_keys: Option<String>,
+
     /// cln only: Address to the cln grpc api.
     #[arg(long)]
     #[serde_as(as = "Option<DisplayFromStr>")]
@@ -190,10 +371,18 @@ struct Args {
 
     /// lnd only: Macaroon for grpc access. Can either be a file path or the
     /// macaroon contents. The macaroon needs offchain:read, offchain:write and
-    /// address:write permissions.
+    /// address:write permissions. With lnd_remote_signing it also needs
+    /// signer:generate and address:read permissions.
     #[arg(long)]
     pub lnd_grpc_macaroon: Option<FileOrCert>,
 
+    /// lnd only: Keep claim keys in lnd's wallet and let lnd sign claims and
+    /// refunds, so swapd never holds claim private keys. Cannot be combined
+    /// with claim_key_xprv. Swaps created before enabling this keep using
+    /// their stored claim keys.
+    #[arg(long)]
+    pub lnd_remote_signing: bool,
+
     /// Loglevel to use. Can be used to filter loges through the env filter
     /// format.
     #[arg(long, default_value = "info")]
@@ -215,14 +404,221 @@ struct Args {
     #[arg(long, default_value = "")]
     pub bitcoind_rpc_password: String,
 
-    /// Polling interval between chain syncs.
+    /// Base url of an esplora api, for example
+    /// `https://blockstream.info/testnet/api`. If set, chain data, broadcasts
+    /// and fallback fee estimates come from esplora instead of bitcoind, and
+    /// the bitcoind_rpc options are ignored.
+    #[arg(long)]
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub esplora_url: Option<Url>,
+
+    /// Timeout for connecting to the esplora api.
+    #[arg(long, default_value = "10")]
+    pub esplora_connect_timeout_seconds: u64,
+
+    /// Timeout for a whole call to the esplora api, including reading the
+    /// response.
+    #[arg(long, default_value = "60")]
+    pub esplora_request_timeout_seconds: u64,
+
+    /// Address of an electrum server, either `tcp://host:port` or
+    /// `ssl://host:port`. If set, chain data, broadcasts and fallback fee
+    /// estimates come from electrum instead of bitcoind, and the chain is
+    /// synced by subscribing to the watched addresses rather than by
+    /// downloading blocks. Cannot be combined with esplora_url.
+    #[arg(long)]
+    pub electrum_url: Option<String>,
+
+    /// Address of bitcoind's zmqpubhashblock or zmqpubrawblock endpoint, for
+    /// example `tcp://127.0.0.1:28332`. If set, the chain is synced and claims
+    /// are attempted as soon as bitcoind notifies a new block, rather than only
+    /// on the poll intervals.
+    #[arg(long)]
+    pub bitcoind_zmq_block_address: Option<String>,
+
+    /// Polling interval between chain syncs. With bitcoind_zmq_block_address,
+    /// this is the fallback for missed block notifications.
     #[arg(long, default_value = "60")]
     pub chain_poll_interval_seconds: u64,
 
+    /// Number of blocks below the tip that are kept to handle reorgs. Older
+    /// blocks are pruned, unless they confirm outputs or spends of swaps that
+    /// are not archived yet. Reorgs deeper than this stop the chain sync.
+    #[arg(long, default_value = "144")]
+    pub reorg_safety_window_blocks: u64,
+
+    /// Height of the block to start syncing the chain from. When not set, a
+    /// new database starts 20 blocks below the tip. Setting it below the
+    /// current birthday processes the blocks since the new birthday again.
+    #[arg(long)]
+    pub birthday_height: Option<u64>,
+
+    /// Watch bitcoind's mempool for unconfirmed deposits to swap addresses.
+    /// Requires the bitcoind chain backend. Runs as part of the chain sync.
+    #[arg(long)]
+    pub watch_mempool: bool,
+
+    /// Address of bitcoind's zmqpubrawtx endpoint, for example
+    /// `tcp://127.0.0.1:28333`. If set with watch_mempool, deposits are seen
+    /// as soon as they enter the mempool, rather than only on the mempool poll
+    /// interval.
+    #[arg(long)]
+    pub bitcoind_zmq_tx_address: Option<String>,
+
+    /// Polling interval between mempool syncs, if watch_mempool is set. With
+    /// bitcoind_zmq_tx_address, this is the fallback for missed transactions.
+    #[arg(long, default_value = "30")]
+    pub mempool_poll_interval_seconds: u64,
+
+    /// Swaps whose outputs sum up to at most this amount can be paid before
+    /// the deposits confirm. Zero-conf payouts assume any unconfirmed deposit
+    /// can be double spent after the payout: most nodes relay replacements
+    /// whether or not the deposit signals replaceability (full-RBF), so
+    /// deposits not signaling BIP125 are no safer. Only enable this for
+    /// amounts you are willing to lose. Deposits that do signal
+    /// replaceability are never paid zero-conf. Only takes effect if the
+    /// mempool is watched, in this process or in the process running the
+    /// chain sync. 0 disables zero-conf payouts.
+    #[arg(long, default_value = "0")]
+    pub zero_conf_max_amount_sat: u64,
+
+    /// Maximum summed value of unconfirmed deposits paid out zero-conf at any
+    /// time, across all swaps. This bounds the total loss if deposits are
+    /// double spent. A swap that would exceed it waits for confirmations. 0
+    /// disables zero-conf payouts.
+    #[arg(long, default_value = "1000000")]
+    pub zero_conf_max_outstanding_sat: u64,
+
+    /// Maximum number of swaps a single client ip can create within
+    /// create_swap_rate_limit_window_seconds. 0 disables the limit.
+    #[arg(long, default_value = "20")]
+    pub create_swap_rate_limit_per_ip: u32,
+
+    /// Maximum number of swaps with the same refund pubkey that can be
+    /// created within create_swap_rate_limit_window_seconds. 0 disables the
+    /// limit.
+    #[arg(long, default_value = "5")]
+    pub create_swap_rate_limit_per_refund_pubkey: u32,
+
+    /// Window for the create swap rate limits.
+    #[arg(long, default_value = "3600")]
+    pub create_swap_rate_limit_window_seconds: u64,
+
+    /// Maximum number of concurrent SubscribeSwapEvents streams a single
+    /// client ip can hold open. 0 disables the limit.
+    #[arg(long, default_value = "10")]
+    pub max_event_subscriptions_per_ip: u32,
+
+    /// Header with the client ip, set by a trusted reverse proxy in front of
+    /// the public server, for example x-forwarded-for. Only set this if the
+    /// proxy overwrites or appends to the header, otherwise clients can pick
+    /// their own ip. Without it, or if a request doesn't carry a valid header,
+    /// the peer address is used.
+    #[arg(long)]
+    pub client_ip_header: Option<String>,
+
+    /// Maximum number of swaps that are not funded and not expired yet. New
+    /// swaps are rejected while the maximum is reached. 0 disables the limit.
+    #[arg(long, default_value = "100000")]
+    pub max_unfunded_swaps: u64,
+
+    /// Polling interval between expiring unfunded swaps. Swaps that are not
+    /// funded after their lock time passed since creation are expired and
+    /// their addresses are no longer watched. Deposits to an expired swap
+    /// address are not paid out, the user can only refund them after the
+    /// lock time. Runs along with the chain sync.
+    #[arg(long, default_value = "600")]
+    pub swap_expiry_poll_interval_seconds: u64,
+
+    /// Polling interval between completing and archiving swaps. Swaps are
+    /// completed when they expired unfunded, or when all their outputs are
+    /// claimed or refunded with retention_min_confirmations. Completed swaps
+    /// are no longer watched, so later deposits to their addresses are not
+    /// paid out. Runs along with the chain sync.
+    #[arg(long, default_value = "3600")]
+    pub retention_poll_interval_seconds: u64,
+
+    /// Confirmations of the transactions spending the swap outputs, before a
+    /// swap is completed.
+    #[arg(long, default_value = "6")]
+    pub retention_min_confirmations: u64,
+
+    /// Time after completion before a swap is moved to the archive tables,
+    /// along with its payment attempts, outputs and claims. Archived swaps
+    /// are not returned by the rpcs anymore. 0 disables archiving.
+    #[arg(long, default_value = "7776000")]
+    pub retention_period_seconds: u64,
+
+    /// Maximum number of swaps archived in a single database transaction.
+    #[arg(long, default_value = "1000")]
+    pub retention_batch_size: u32,
+
     /// Polling interval between claim runs.
     #[arg(long, default_value = "60")]
     pub claim_poll_interval_seconds: u64,
 
+    /// Polling interval between checks for outputs of paid swaps that were
+    /// not paid over lightning.
+    #[arg(long, default_value = "60")]
+    pub unpaid_output_poll_interval_seconds: u64,
+
+    /// Claims of different swaps are batched into a single transaction when
+    /// the blocks left before they can be refunded fall within the same
+    /// bucket of this many blocks. Set to 0 to batch regardless of the
+    /// blocks left.
+    #[arg(long, default_value = "48")]
+    pub claim_batch_bucket_blocks: u32,
+
+    /// Maximum number of inputs of a batched claim transaction.
+    #[arg(long, default_value = "200")]
+    pub claim_batch_max_inputs: usize,
+
+    /// Factor the estimated claim fee rate is multiplied with when a claim is
+    /// not confirmed by the time the user can refund. The fee rate is
+    /// escalated from the estimate once fewer than min_claim_blocks blocks
+    /// are left, rising faster the closer the deadline is. Set to 1 to only
+    /// use the estimate.
+    #[arg(long, default_value = "4")]
+    pub claim_fee_max_multiplier: f64,
+
+    /// Maximum fee of a claim transaction, as a percentage of the claimed
+    /// value.
+    #[arg(long, default_value = "50")]
+    pub claim_max_fee_percent: f64,
+
+    /// Paid swap outputs that are not claimed when this many blocks or less
+    /// are left before the user can refund them are reported as at risk.
+    #[arg(long, default_value = "6")]
+    pub claim_at_risk_blocks: u32,
+
+    /// Static address to claim to, e.g. of cold storage, instead of a new
+    /// address of the lightning node's wallet. Claims to this address can
+    /// only be bumped by replacement.
+    #[arg(long)]
+    pub claim_destination_address: Option<String>,
+
+    /// Watch-only descriptor to derive a new address from for every claim,
+    /// instead of using the lightning node's wallet. Supports wpkh() and tr()
+    /// descriptors with an extended public key ending in /*, or a bare
+    /// extended public key. Derived addresses are recorded in the database.
+    #[arg(long)]
+    pub claim_destination_descriptor: Option<String>,
+
+    /// Keeps claiming to the lightning node's wallet while its balance is
+    /// below this amount, e.g. to keep funds for channel opens, and to the
+    /// claim destination address or descriptor otherwise.
+    #[arg(long, default_value = "0")]
+    pub claim_wallet_reserve_sat: u64,
+
+    /// Time the user gets to co-sign the cheaper key path claim of a paid
+    /// swap, before it is claimed through the script path instead. Set to 0
+    /// to always claim through the script path. The user can only co-sign if
+    /// the claim logic runs in the same process as the servers. Swaps with
+    /// fewer than min_claim_blocks or at most claim_at_risk_blocks blocks
+    /// left before the refund are claimed through the script path right away.
+    #[arg(long, default_value = "120")]
+    pub cooperative_claim_window_seconds: u64,
+
     /// Polling interval between checking historical payment states.
     #[arg(long, default_value = "120")]
     pub payment_poll_interval_seconds: u64,
@@ -231,6 +627,11 @@ struct Args {
     #[arg(long, default_value = "60")]
     pub preimage_poll_interval_seconds: u64,
 
+    /// Number of swap events buffered per SubscribeSwapEvents stream. Streams
+    /// that fall further behind are closed.
+    #[arg(long, default_value = "1024")]
+    pub swap_event_buffer_size: usize,
+
     /// Polling interval between checking whatthefee.io fees.
     #[arg(long, default_value = "60")]
     pub whatthefee_poll_interval_seconds: u64,
@@ -264,6 +665,11 @@ struct Args {
     #[arg(long)]
     pub no_servers: bool,
 
+    /// If this flag is set, the reverse swap monitor will not run in this
+    /// process. It should then be run separately.
+    #[arg(long)]
+    pub no_reverse: bool,
+
     /// Base fee component of the maximum payment fee. The max fee is calculated
     /// as `pay_fee_limit_base + (amount_msat * pay_fee_limit_ppm / 1_000_000)`.
     #[arg(long, default_value = "5000")]
@@ -281,6 +687,65 @@ struct Args {
     /// Minimum viable cltv for payout.
     #[arg(long, default_value = "40")]
     pub min_viable_cltv: u32,
+
+    /// Base component of the swap fee charged on payout. The swap fee is
+    /// calculated as `swap_fee_base_sat + (amount_sat * swap_fee_ppm / 1_000_000)`,
+    /// plus the estimated claim fee if `swap_fee_include_claim_fee` is set.
+    /// Users are paid the swap output value minus the swap fee.
+    #[arg(long, default_value = "0")]
+    pub swap_fee_base_sat: u64,
+
+    /// Proportional component of the swap fee charged on payout, in parts per
+    /// million of the swap output value.
+    #[arg(long, default_value = "0")]
+    pub swap_fee_ppm: u64,
+
+    /// If this flag is set, the estimated onchain fee for claiming the swap
+    /// outputs is added to the swap fee. For reverse swaps, the estimated fee
+    /// for funding the swap output is added instead.
+    #[arg(long)]
+    pub swap_fee_include_claim_fee: bool,
+
+    /// If `swap_fee_include_claim_fee` is set, the claim fee rate in the
+    /// payable amount of a swap is honored for this many seconds, so the
+    /// amount shown in the swap status can still be paid out if the fee
+    /// estimate moves in the meantime. 0 always uses the current estimate.
+    #[arg(long, default_value = "600")]
+    pub claim_fee_quote_seconds: u64,
+
+    /// Locktime for reverse swaps. This is the number of blocks between
+    /// creation of the reverse swap and the height at which the swap output
+    /// can be refunded if the user doesn't claim it.
+    #[arg(long, default_value = "144")]
+    pub reverse_lock_time: u32,
+
+    /// Maximum amount allowed for reverse swaps.
+    #[arg(long, default_value = "4000000")]
+    pub reverse_max_amount_sat: u64,
+
+    /// Minimum amount allowed for reverse swaps.
+    #[arg(long, default_value = "10000")]
+    pub reverse_min_amount_sat: u64,
+
+    /// Minimum number of blocks the user should have to claim a reverse swap
+    /// output. Reverse swaps paid later than this are canceled, rather than
+    /// funded.
+    #[arg(long, default_value = "72")]
+    pub reverse_min_claim_blocks: u32,
+
+    /// Minimum number of blocks between the reverse swap lock height and the
+    /// expiry of the htlcs paying the hold invoice. This is the time there is
+    /// left to settle the invoice after the user claims at the last moment.
+    #[arg(long, default_value = "18")]
+    pub reverse_min_settle_blocks: u32,
+
+    /// Expiry of reverse swap hold invoices in seconds.
+    #[arg(long, default_value = "3600")]
+    pub reverse_invoice_expiry_seconds: u64,
+
+    /// Polling interval between checking reverse swap states.
+    #[arg(long, default_value = "60")]
+    pub reverse_poll_interval_seconds: u64,
 }
 
 #[tokio::main]
@@ -338,6 +803,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 ca_cert: cln_ca_cert,
                 identity: cln_identity,
             };
+            if args.lnd_remote_signing {
+                Err("lnd_remote_signing requires an lnd node")?
+            }
             let cln_client = Arc::new(cln::Client::new(cln_conn, args.network));
             run_with_client(cln_client, pgpool, args).await?;
         }
@@ -359,7 +827,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             };
             let lnd_repository = Arc::new(LndRepository::new(Arc::clone(&pgpool)));
             let lnd_client = Arc::new(lnd::Client::new(lnd_conn, args.network, lnd_repository)?);
-            run_with_client(lnd_client, pgpool, args).await?;
+            if args.lnd_remote_signing {
+                if args.claim_key_xprv.is_some() {
+                    Err("cannot combine lnd_remote_signing with claim_key_xprv")?
+                }
+                let random_provider = Arc::new(RingRandomProvider::new());
+                let swap_repository = new_swap_repository(&pgpool, &args).await?;
+                let privkey_provider =
+                    lnd::RemoteSigner::new(Arc::clone(&lnd_client), Arc::clone(&random_provider));
+                run_with_privkey_provider(
+                    lnd_client,
+                    privkey_provider,
+                    random_provider,
+                    swap_repository,
+                    pgpool,
+                    args,
+                )
+                .await?;
+            } else {
+                run_with_client(lnd_client, pgpool, args).await?;
+            }
         }
     };
 
@@ -372,10 +859,116 @@ async fn run_with_client<T>(
     args: Args,
 ) -> Result<(), Box<dyn std::error::Error>>
 where
-    T: LightningClient + Wallet + Send + Sync + Debug + 'static,
+    T: LightningClient + HoldInvoiceClient + Wallet + Send + Sync + Debug + 'static,
 {
     let random_provider = Arc::new(RingRandomProvider::new());
-    let privkey_provider = RandomPrivateKeyProvider::new(Arc::clone(&random_provider));
+    let swap_repository = new_swap_repository(&pgpool, &args).await?;
+    match &args.claim_key_xprv {
+        Some(claim_key_xprv) => {
+            let master_key = resolve_xprv(claim_key_xprv, args.network).await?;
+            let privkey_provider = Bip32PrivateKeyProvider::new(
+                master_key,
+                Arc::clone(&random_provider),
+                Arc::clone(&swap_repository),
+            );
+            run_with_privkey_provider(
+                lightning_client,
+                privkey_provider,
+                random_provider,
+                swap_repository,
+                pgpool,
+                args,
+            )
+            .await
+        }
+        None => {
+            warn!("no claim_key_xprv configured, claim keys only exist in the database");
+            let privkey_provider = RandomPrivateKeyProvider::new(Arc::clone(&random_provider));
+            run_with_privkey_provider(
+                lightning_client,
+                privkey_provider,
+                random_provider,
+                swap_repository,
+                pgpool,
+                args,
+            )
+            .await
+        }
+    }
+}
+
+async fn internal_auth(args: &Args) -> Result<InternalAuth, Box<dyn std::error::Error>> {
+    let admin_token = match &args.internal_admin_token {
+        Some(token) => Some(read_value_or_file(token).await),
+        None => None,
+    };
+    let readonly_token = match &args.internal_readonly_token {
+        Some(token) => Some(read_value_or_file(token).await),
+        None => None,
+    };
+    Ok(InternalAuth::new(
+        admin_token.as_deref(),
+        readonly_token.as_deref(),
+    )?)
+}
+
+async fn internal_tls_config(
+    args: &Args,
+) -> Result<Option<ServerTlsConfig>, Box<dyn std::error::Error>> {
+    let (cert, key) = match (&args.internal_tls_cert, &args.internal_tls_key) {
+        (Some(cert), Some(key)) => (cert, key),
+        (None, None) => {
+            if args.internal_tls_client_ca_cert.is_some() {
+                return Err(
+                    "internal_tls_client_ca_cert requires internal_tls_cert and internal_tls_key",
+                )?;
+            }
+            return Ok(None);
+        }
+        _ => return Err("internal_tls_cert and internal_tls_key must be set together")?,
+    };
+
+    let identity = Identity::from_pem(cert.resolve().await, key.resolve().await);
+    let mut tls_config = ServerTlsConfig::new().identity(identity);
+    if let Some(client_ca_cert) = &args.internal_tls_client_ca_cert {
+        tls_config =
+            tls_config.client_ca_root(Certificate::from_pem(client_ca_cert.resolve().await));
+    }
+
+    Ok(Some(tls_config))
+}
+
+async fn new_swap_repository(
+    pgpool: &Arc<Pool<Postgres>>,
+    args: &Args,
+) -> Result<Arc<postgresql::SwapRepository>, Box<dyn std::error::Error>> {
+    let key_cipher = match &args.claim_key_encryption_keys {
+        Some(keys) => KeyCipher::parse(&read_value_or_file(keys).await)
+            .map_err(|e| format!("invalid claim_key_encryption_keys: {}", e))?,
+        None => {
+            warn!("no claim_key_encryption_keys configured, private keys are stored in plaintext");
+            KeyCipher::new(Vec::new())?
+        }
+    };
+    Ok(Arc::new(postgresql::SwapRepository::new(
+        Arc::clone(pgpool),
+        args.network,
+        Arc::new(key_cipher),
+    )))
+}
+
+async fn run_with_privkey_provider<T, P>(
+    lightning_client: Arc<T>,
+    privkey_provider: P,
+    random_provider: Arc<RingRandomProvider>,
+    swap_repository: Arc<postgresql::SwapRepository>,
+    pgpool: Arc<Pool<Postgres>>,
+    args: Args,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    T: LightningClient + HoldInvoiceClient + Wallet + Send + Sync + Debug + 'static,
+    P: PrivateKeyProvider + Send + Sync + Debug + 'static,
+{
     let swap_service = Arc::new(SwapService::new(
         args.network,
         privkey_provider,
@@ -383,40 +976,146 @@ where
         args.dust_limit_sat,
     ));
 
-    let chain_client = Arc::new(BitcoindClient::new(
-        args.bitcoind_rpc_address,
-        args.bitcoind_rpc_user,
-        args.bitcoind_rpc_password,
-        args.network,
-    ));
+    let fee_estimator_1 = WhatTheFeeEstimator::new(
+        args.whatthefee_url.clone(),
+        args.lock_time,
+        Duration::from_secs(args.whatthefee_poll_interval_seconds),
+    );
+    fee_estimator_1.start().await?;
+    let metrics = Arc::new(Metrics::new());
+    if args.watch_mempool && (args.esplora_url.is_some() || args.electrum_url.is_some()) {
+        return Err("watch_mempool requires the bitcoind chain backend".into());
+    }
 
-    let swap_repository = Arc::new(postgresql::SwapRepository::new(
-        Arc::clone(&pgpool),
-        args.network,
-    ));
+    match (&args.esplora_url, &args.electrum_url) {
+        (Some(_), Some(_)) => Err("esplora_url and electrum_url cannot both be set".into()),
+        (Some(esplora_url), None) => {
+            let chain_client = Arc::new(EsploraClient::new(
+                esplora_url.clone(),
+                args.network,
+                Duration::from_secs(args.esplora_connect_timeout_seconds),
+                Duration::from_secs(args.esplora_request_timeout_seconds),
+            )?);
+            let fee_estimator_2 = esplora::FeeEstimator::new(Arc::clone(&chain_client));
+            let fee_estimator = Arc::new(FallbackFeeEstimator::new(
+                fee_estimator_1,
+                fee_estimator_2,
+                Arc::clone(&metrics),
+            ));
+            run_with_chain_client(
+                lightning_client,
+                swap_service,
+                random_provider,
+                swap_repository,
+                chain_client,
+                None::<Arc<BitcoindClient>>,
+                fee_estimator,
+                metrics,
+                pgpool,
+                args,
+            )
+            .await
+        }
+        (None, Some(electrum_url)) => {
+            let chain_client = Arc::new(ElectrumClient::new(electrum_url, args.network)?);
+            let fee_estimator_2 = electrum::FeeEstimator::new(Arc::clone(&chain_client));
+            let fee_estimator = Arc::new(FallbackFeeEstimator::new(
+                fee_estimator_1,
+                fee_estimator_2,
+                Arc::clone(&metrics),
+            ));
+            run_with_chain_client(
+                lightning_client,
+                swap_service,
+                random_provider,
+                swap_repository,
+                chain_client,
+                None::<Arc<BitcoindClient>>,
+                fee_estimator,
+                metrics,
+                pgpool,
+                args,
+            )
+            .await
+        }
+        (None, None) => {
+            let chain_client = Arc::new(BitcoindClient::new(
+                args.bitcoind_rpc_address.clone(),
+                args.bitcoind_rpc_user.clone(),
+                args.bitcoind_rpc_password.clone(),
+                args.network,
+            ));
+            let fee_estimator_2 = bitcoind::FeeEstimator::new(Arc::clone(&chain_client));
+            let fee_estimator = Arc::new(FallbackFeeEstimator::new(
+                fee_estimator_1,
+                fee_estimator_2,
+                Arc::clone(&metrics),
+            ));
+            let mempool_client = args.watch_mempool.then(|| Arc::clone(&chain_client));
+            run_with_chain_client(
+                lightning_client,
+                swap_service,
+                random_provider,
+                swap_repository,
+                chain_client,
+                mempool_client,
+                fee_estimator,
+                metrics,
+                pgpool,
+                args,
+            )
+            .await
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_with_chain_client<T, P, C, M, F>(
+    lightning_client: Arc<T>,
+    swap_service: Arc<SwapService<P>>,
+    random_provider: Arc<RingRandomProvider>,
+    swap_repository: Arc<postgresql::SwapRepository>,
+    chain_client: Arc<C>,
+    mempool_client: Option<Arc<M>>,
+    fee_estimator: Arc<F>,
+    metrics: Arc<Metrics>,
+    pgpool: Arc<Pool<Postgres>>,
+    args: Args,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    T: LightningClient + HoldInvoiceClient + Wallet + Send + Sync + Debug + 'static,
+    P: PrivateKeyProvider + Send + Sync + Debug + 'static,
+    C: ChainClient + Send + Sync + Debug + 'static,
+    M: MempoolClient + Send + Sync + 'static,
+    F: FeeEstimator + Send + Sync + Debug + 'static,
+{
     let chain_repository = Arc::new(postgresql::ChainRepository::new(
         Arc::clone(&pgpool),
         args.network,
     ));
     let chain_filter_repository =
         Arc::new(postgresql::ChainFilterRepository::new(Arc::clone(&pgpool)));
+    let mempool_repository = Arc::new(postgresql::MempoolRepository::new(
+        Arc::clone(&pgpool),
+        args.network,
+    ));
     let claim_repository = Arc::new(postgresql::ClaimRepository::new(
         Arc::clone(&pgpool),
         args.network,
     ));
+    let retention_repository = Arc::new(postgresql::RetentionRepository::new(
+        Arc::clone(&pgpool),
+        args.network,
+    ));
+    let reverse_swap_repository = Arc::new(postgresql::ReverseSwapRepository::new(
+        Arc::clone(&pgpool),
+        args.network,
+        swap_repository.key_cipher(),
+    ));
     let chain_filter = Arc::new(ChainFilterImpl::new(
         Arc::clone(&chain_client),
         Arc::clone(&chain_filter_repository),
     ));
-    let fee_estimator_1 = WhatTheFeeEstimator::new(
-        args.whatthefee_url,
-        args.lock_time,
-        Duration::from_secs(args.whatthefee_poll_interval_seconds),
-    );
-    fee_estimator_1.start().await?;
-    let fee_estimator_2 = bitcoind::FeeEstimator::new(Arc::clone(&chain_client));
-    let fee_estimator = Arc::new(FallbackFeeEstimator::new(fee_estimator_1, fee_estimator_2));
-
     let claim_service = Arc::new(ClaimService::new(
         Arc::clone(&chain_client),
         Arc::clone(&chain_repository),
@@ -437,8 +1136,33 @@ where
         }
         signal_token.cancel();
     });
+    let event_bus = Arc::new(EventBus::new(args.swap_event_buffer_size, token.clone()));
+
+    // Blocks notified by bitcoind, and blocks processed by the chain monitor.
+    // The claim monitor waits for the chain monitor if it runs in this process.
+    let new_blocks = Arc::new(BlockNotifier::new());
+    let synced_blocks = match args.no_chain {
+        true => Arc::clone(&new_blocks),
+        false => Arc::new(BlockNotifier::new()),
+    };
+    // Rescans requested on the internal server, handled by the chain sync.
+    let (rescanner, rescan_requests) = rescan_channel();
+    if let Some(zmq_block_address) = &args.bitcoind_zmq_block_address {
+        let zmq_listener_token = token.clone();
+        let zmq_listener =
+            ZmqBlockListener::new(zmq_block_address.clone(), Arc::clone(&new_blocks));
+        tracker.spawn(async move {
+            info!("Starting zmq block listener");
+            let res = zmq_listener.start(zmq_listener_token.child_token()).await;
+            match res {
+                Ok(_) => info!("zmq block listener exited"),
+                Err(e) => info!("zmq block listener exited with {:?}", e),
+            };
+        });
+    }
     let mut payment_monitor = HistoricalPaymentMonitor::new(
         Arc::clone(&lightning_client),
+        Arc::clone(&metrics),
         Duration::from_secs(args.payment_poll_interval_seconds),
         Arc::clone(&swap_repository),
     );
@@ -462,8 +1186,21 @@ where
     if !args.no_claim {
         let claim_monitor_token = token.clone();
         let claim_monitor = ClaimMonitor::new(ClaimMonitorParams {
+            batch_bucket_blocks: args.claim_batch_bucket_blocks,
+            batch_max_inputs: args.claim_batch_max_inputs,
             chain_client: Arc::clone(&chain_client),
+            cooperative_claim_window: Duration::from_secs(args.cooperative_claim_window_seconds),
+            destination_policy: claim_destination_policy(&args)?,
+            event_bus: Arc::clone(&event_bus),
+            fee_escalation: FeeEscalation {
+                escalation_blocks: args.min_claim_blocks,
+                max_multiplier: args.claim_fee_max_multiplier,
+                max_fee_percent: args.claim_max_fee_percent,
+                at_risk_blocks: args.claim_at_risk_blocks,
+            },
             fee_estimator: Arc::clone(&fee_estimator),
+            metrics: Arc::clone(&metrics),
+            new_blocks: Arc::clone(&synced_blocks),
             poll_interval: Duration::from_secs(args.claim_poll_interval_seconds),
             claim_repository: Arc::clone(&claim_repository),
             claim_service: Arc::clone(&claim_service),
@@ -478,11 +1215,84 @@ where
             };
             claim_monitor_token.cancel();
         });
+
+        let reverse_refund_monitor_token = token.clone();
+        let reverse_refund_monitor = ReverseRefundMonitor::new(ReverseRefundMonitorParams {
+            chain_client: Arc::clone(&chain_client),
+            chain_repository: Arc::clone(&chain_repository),
+            fee_estimator: Arc::clone(&fee_estimator),
+            poll_interval: Duration::from_secs(args.claim_poll_interval_seconds),
+            reverse_swap_repository: Arc::clone(&reverse_swap_repository),
+            swap_service: Arc::clone(&swap_service),
+            wallet: Arc::clone(&lightning_client),
+        });
+        tracker.spawn(async move {
+            info!("Starting reverse refund monitor");
+            let res = reverse_refund_monitor
+                .start(reverse_refund_monitor_token.child_token())
+                .await;
+            match res {
+                Ok(_) => info!("reverse refund monitor exited"),
+                Err(e) => info!("reverse refund monitor exited with {:?}", e),
+            };
+            reverse_refund_monitor_token.cancel();
+        });
+    }
+
+    let unpaid_output_monitor_token = token.clone();
+    let unpaid_output_monitor = UnpaidOutputMonitor::new(UnpaidOutputMonitorParams {
+        claim_repository: Arc::clone(&claim_repository),
+        claim_service: Arc::clone(&claim_service),
+        event_bus: Arc::clone(&event_bus),
+        lightning_client: Arc::clone(&lightning_client),
+        metrics: Arc::clone(&metrics),
+        poll_interval: Duration::from_secs(args.unpaid_output_poll_interval_seconds),
+        swap_repository: Arc::clone(&swap_repository),
+    });
+    tracker.spawn(async move {
+        info!("Starting unpaid output monitor");
+        let res = unpaid_output_monitor
+            .start(unpaid_output_monitor_token.child_token())
+            .await;
+        match res {
+            Ok(_) => info!("unpaid output monitor exited"),
+            Err(e) => info!("unpaid output monitor exited with {:?}", e),
+        };
+        unpaid_output_monitor_token.cancel();
+    });
+
+    if !args.no_reverse {
+        let reverse_swap_monitor_token = token.clone();
+        let reverse_swap_monitor = ReverseSwapMonitor::new(ReverseSwapMonitorParams {
+            chain_client: Arc::clone(&chain_client),
+            chain_repository: Arc::clone(&chain_repository),
+            fee_estimator: Arc::clone(&fee_estimator),
+            lightning_client: Arc::clone(&lightning_client),
+            mempool_repository: Arc::clone(&mempool_repository),
+            reverse_swap_repository: Arc::clone(&reverse_swap_repository),
+            wallet: Arc::clone(&lightning_client),
+            poll_interval: Duration::from_secs(args.reverse_poll_interval_seconds),
+            invoice_expiry: Duration::from_secs(args.reverse_invoice_expiry_seconds),
+            min_claim_blocks: args.reverse_min_claim_blocks,
+            min_settle_blocks: args.reverse_min_settle_blocks,
+        });
+        tracker.spawn(async move {
+            info!("Starting reverse swap monitor");
+            let res = reverse_swap_monitor
+                .start(reverse_swap_monitor_token.child_token())
+                .await;
+            match res {
+                Ok(_) => info!("reverse swap monitor exited"),
+                Err(e) => info!("reverse swap monitor exited with {:?}", e),
+            };
+            reverse_swap_monitor_token.cancel();
+        });
     }
     if !args.no_preimage {
         let preimage_monitor_token = token.clone();
         let preimage_monitor = PreimageMonitor::new(
             Arc::clone(&chain_repository),
+            Arc::clone(&event_bus),
             Arc::clone(&lightning_client),
             Duration::from_secs(args.preimage_poll_interval_seconds),
             Arc::clone(&swap_repository),
@@ -499,13 +1309,46 @@ where
             preimage_monitor_token.cancel();
         });
     }
-    if !args.no_chain {
+    if let (false, Some(electrum_url)) = (args.no_chain, &args.electrum_url) {
+        let electrum_monitor_token = token.clone();
+        // The monitor gets its own connection, so its subscriptions don't
+        // share a connection with the other chain calls.
+        let electrum_monitor = ElectrumMonitor::new(ElectrumMonitorParams {
+            birthday_height: args.birthday_height,
+            chain_repository: Arc::clone(&chain_repository),
+            client: Arc::new(ElectrumClient::new(electrum_url, args.network)?),
+            event_bus: Arc::clone(&event_bus),
+            metrics: Arc::clone(&metrics),
+            poll_interval: Duration::from_secs(args.chain_poll_interval_seconds),
+            reorg_safety_window: args.reorg_safety_window_blocks,
+            rescan_requests,
+            synced_blocks: Arc::clone(&synced_blocks),
+        });
+        tracker.spawn(async move {
+            info!("Starting electrum monitor");
+            let res = electrum_monitor
+                .start(electrum_monitor_token.child_token())
+                .await;
+            match res {
+                Ok(_) => info!("electrum monitor exited"),
+                Err(e) => info!("electrum monitor exited with {:?}", e),
+            };
+            electrum_monitor_token.cancel();
+        });
+    } else if !args.no_chain {
         let chain_monitor_token = token.clone();
         let chain_monitor = Arc::new(ChainMonitor::new(
             args.network,
             Arc::clone(&chain_client),
             Arc::clone(&chain_repository),
+            Arc::clone(&event_bus),
+            Arc::clone(&metrics),
+            Arc::clone(&new_blocks),
+            Arc::clone(&synced_blocks),
             Duration::from_secs(args.chain_poll_interval_seconds),
+            args.birthday_height,
+            args.reorg_safety_window_blocks,
+            rescan_requests,
         ));
         tracker.spawn(async move {
             info!("Starting chain monitor");
@@ -516,37 +1359,227 @@ where
             };
             chain_monitor_token.cancel();
         });
+    } else {
+        // Without a chain sync in this process, rescans fail right away.
+        drop(rescan_requests);
     }
+    if let (false, Some(mempool_client)) = (args.no_chain, mempool_client) {
+        // Transactions notified by bitcoind. If the monitor falls behind, it
+        // resyncs the whole mempool.
+        let (new_txs, _) = broadcast::channel(1024);
+        if let Some(zmq_tx_address) = &args.bitcoind_zmq_tx_address {
+            let zmq_listener_token = token.clone();
+            let zmq_listener = ZmqTxListener::new(zmq_tx_address.clone(), new_txs.clone());
+            tracker.spawn(async move {
+                info!("Starting zmq tx listener");
+                let res = zmq_listener.start(zmq_listener_token.child_token()).await;
+                match res {
+                    Ok(_) => info!("zmq tx listener exited"),
+                    Err(e) => info!("zmq tx listener exited with {:?}", e),
+                };
+            });
+        }
+
+        let mempool_monitor_token = token.clone();
+        let mempool_monitor = MempoolMonitor::new(MempoolMonitorParams {
+            chain_client: Arc::clone(&chain_client),
+            chain_repository: Arc::clone(&chain_repository),
+            event_bus: Arc::clone(&event_bus),
+            mempool_client,
+            mempool_repository: Arc::clone(&mempool_repository),
+            network: args.network,
+            new_txs,
+            poll_interval: Duration::from_secs(args.mempool_poll_interval_seconds),
+        });
+        tracker.spawn(async move {
+            info!("Starting mempool monitor");
+            let res = mempool_monitor
+                .start(mempool_monitor_token.child_token())
+                .await;
+            match res {
+                Ok(_) => info!("mempool monitor exited"),
+                Err(e) => info!("mempool monitor exited with {:?}", e),
+            };
+            mempool_monitor_token.cancel();
+        });
+    }
+
+    if !args.no_chain {
+        let swap_expiry_monitor_token = token.clone();
+        let swap_expiry_monitor = SwapExpiryMonitor::new(SwapExpiryMonitorParams {
+            chain_repository: Arc::clone(&chain_repository),
+            metrics: Arc::clone(&metrics),
+            poll_interval: Duration::from_secs(args.swap_expiry_poll_interval_seconds),
+            swap_repository: Arc::clone(&swap_repository),
+        });
+        tracker.spawn(async move {
+            info!("Starting swap expiry monitor");
+            let res = swap_expiry_monitor
+                .start(swap_expiry_monitor_token.child_token())
+                .await;
+            match res {
+                Ok(_) => info!("swap expiry monitor exited"),
+                Err(e) => info!("swap expiry monitor exited with {:?}", e),
+            };
+            swap_expiry_monitor_token.cancel();
+        });
+
+        let retention_monitor_token = token.clone();
+        let retention_monitor = RetentionMonitor::new(RetentionMonitorParams {
+            batch_size: args.retention_batch_size,
+            chain_repository: Arc::clone(&chain_repository),
+            metrics: Arc::clone(&metrics),
+            min_confirmations: args.retention_min_confirmations,
+            poll_interval: Duration::from_secs(args.retention_poll_interval_seconds),
+            retention_repository: Arc::clone(&retention_repository),
+            retention_period: match args.retention_period_seconds {
+                0 => None,
+                seconds => Some(Duration::from_secs(seconds)),
+            },
+        });
+        tracker.spawn(async move {
+            info!("Starting retention monitor");
+            let res = retention_monitor
+                .start(retention_monitor_token.child_token())
+                .await;
+            match res {
+                Ok(_) => info!("retention monitor exited"),
+                Err(e) => info!("retention monitor exited with {:?}", e),
+            };
+            retention_monitor_token.cancel();
+        });
+    }
+
+    let health_monitor_token = token.clone();
+    let health_monitor = HealthMonitor::new(HealthMonitorParams {
+        chain_client: Arc::clone(&chain_client),
+        chain_repository: Arc::clone(&chain_repository),
+        fee_estimator: Arc::clone(&fee_estimator),
+        lightning_client: Arc::clone(&lightning_client),
+        max_chain_lag_blocks: args.max_chain_lag_blocks,
+        metrics: Arc::clone(&metrics),
+        poll_interval: Duration::from_secs(args.connection_check_interval_seconds),
+    });
+    let ready = health_monitor.subscribe();
+    tracker.spawn(async move {
+        info!("Starting health monitor");
+        health_monitor
+            .start(health_monitor_token.child_token())
+            .await;
+        info!("health monitor exited");
+    });
+
     if !args.no_servers {
         let server_token = token.clone();
         let swapper_server = TaprootSwapperServer::new(SwapServer::new(SwapServerParams {
             network: args.network,
+            fee_policy: SwapFeePolicy {
+                base_sat: args.swap_fee_base_sat,
+                ppm: args.swap_fee_ppm,
+                include_claim_fee: args.swap_fee_include_claim_fee,
+            },
+            claim_fee_quote_validity: Duration::from_secs(args.claim_fee_quote_seconds),
             max_swap_amount_sat: args.max_swap_amount_sat,
             min_confirmations: args.min_confirmations,
             min_claim_blocks: args.min_claim_blocks,
             min_utxo_amount_sat: args.min_utxo_amount_sat,
             min_viable_cltv: args.min_viable_cltv,
+            zero_conf_max_amount_sat: args.zero_conf_max_amount_sat,
+            zero_conf_max_outstanding_sat: args.zero_conf_max_outstanding_sat,
+            create_swap_limits: CreateSwapLimits {
+                per_ip: args.create_swap_rate_limit_per_ip,
+                per_refund_pubkey: args.create_swap_rate_limit_per_refund_pubkey,
+                window: Duration::from_secs(args.create_swap_rate_limit_window_seconds),
+                max_unfunded_swaps: args.max_unfunded_swaps,
+                client_ip_header: args.client_ip_header.clone(),
+            },
+            max_event_subscriptions_per_ip: args.max_event_subscriptions_per_ip,
             pay_fee_limit_base_msat: args.pay_fee_limit_base_msat,
             pay_fee_limit_ppm: args.pay_fee_limit_ppm,
             pay_timeout_seconds: args.pay_timeout_seconds,
             chain_service: Arc::clone(&chain_client),
             chain_filter_service: Arc::clone(&chain_filter),
             chain_repository: Arc::clone(&chain_repository),
+            claim_service: Arc::clone(&claim_service),
+            event_bus: Arc::clone(&event_bus),
             lightning_client: Arc::clone(&lightning_client),
+            mempool_repository: Arc::clone(&mempool_repository),
+            metrics: Arc::clone(&metrics),
             random_provider: Arc::clone(&random_provider),
             swap_service: Arc::clone(&swap_service),
             swap_repository: Arc::clone(&swap_repository),
             fee_estimator: Arc::clone(&fee_estimator),
         }));
+        let reverse_swapper_server =
+            ReverseSwapperServer::new(ReverseSwapServer::new(ReverseSwapServerParams {
+                dust_limit_sat: args.dust_limit_sat,
+                fee_policy: SwapFeePolicy {
+                    base_sat: args.swap_fee_base_sat,
+                    ppm: args.swap_fee_ppm,
+                    include_claim_fee: args.swap_fee_include_claim_fee,
+                },
+                invoice_expiry_seconds: args.reverse_invoice_expiry_seconds,
+                lock_time: args.reverse_lock_time,
+                max_amount_sat: args.reverse_max_amount_sat,
+                min_amount_sat: args.reverse_min_amount_sat,
+                min_settle_blocks: args.reverse_min_settle_blocks,
+                chain_client: Arc::clone(&chain_client),
+                chain_repository: Arc::clone(&chain_repository),
+                fee_estimator: Arc::clone(&fee_estimator),
+                lightning_client: Arc::clone(&lightning_client),
+                reverse_swap_repository: Arc::clone(&reverse_swap_repository),
+                swap_service: Arc::clone(&swap_service),
+            }));
+        let swapper_health_server = HealthServer::new(HealthService::new(
+            vec![
+                taproot_swapper_server::SERVICE_NAME,
+                reverse_swapper_server::SERVICE_NAME,
+            ],
+            ready.clone(),
+        ));
+        let tls_incoming = match (&args.tls_cert, &args.tls_key) {
+            (Some(cert), Some(key)) => {
+                let cert_reloader = CertReloader::new(CertReloaderParams {
+                    cert: cert.clone(),
+                    key: key.clone(),
+                    poll_interval: Duration::from_secs(args.tls_reload_interval_seconds),
+                })
+                .await?;
+                let listener = TcpListener::bind(args.address).await?;
+                let incoming = tls::incoming(listener, cert_reloader.acceptor()?);
+                let cert_reloader_token = token.clone();
+                tracker.spawn(async move {
+                    info!("Starting tls certificate reloader");
+                    cert_reloader.start(cert_reloader_token.child_token()).await;
+                    info!("tls certificate reloader exited");
+                });
+                Some(incoming)
+            }
+            (None, None) => None,
+            _ => Err("tls_cert and tls_key must be set together")?,
+        };
         tracker.spawn(async move {
             info!(
                 address = field::display(&args.address),
+                tls = tls_incoming.is_some(),
                 "Starting swapper server"
             );
-            let res = Server::builder()
+            let router = Server::builder()
                 .add_service(swapper_server)
-                .serve_with_shutdown(args.address, server_token.cancelled())
-                .await;
+                .add_service(reverse_swapper_server)
+                .add_service(swapper_health_server);
+            let res = match tls_incoming {
+                Some(incoming) => {
+                    router
+                        .serve_with_incoming_shutdown(incoming, server_token.cancelled())
+                        .await
+                }
+                None => {
+                    router
+                        .serve_with_shutdown(args.address, server_token.cancelled())
+                        .await
+                }
+            };
             match res {
                 Ok(_) => info!("swapper server exited"),
                 Err(e) => info!("swapper server exited with {:?}", e),
@@ -556,26 +1589,44 @@ where
         });
 
         let internal_server_token = token.clone();
+        let internal_auth = internal_auth(&args).await?;
+        if !internal_auth.is_enabled() && !args.internal_address.ip().is_loopback() {
+            warn!("internal server listens on a public address without internal_admin_token");
+        }
+        let mut internal_server_builder = Server::builder();
+        if let Some(tls_config) = internal_tls_config(&args).await? {
+            internal_server_builder = internal_server_builder.tls_config(tls_config)?;
+        }
         let internal_server = SwapManagerServer::new(internal_server::Server::new(
             internal_server::ServerParams {
+                auth: internal_auth,
                 chain_client: Arc::clone(&chain_client),
                 chain_filter_repository: Arc::clone(&chain_filter_repository),
                 chain_repository: Arc::clone(&chain_repository),
+                event_bus: Arc::clone(&event_bus),
                 fee_estimator: Arc::clone(&fee_estimator),
+                mempool_repository: Arc::clone(&mempool_repository),
                 swap_repository: Arc::clone(&swap_repository),
                 wallet: Arc::clone(&lightning_client),
                 network: args.network,
                 claim_service: Arc::clone(&claim_service),
+                reverse_swap_repository: Arc::clone(&reverse_swap_repository),
+                rescanner,
                 token: token.clone(),
             },
         ));
+        let internal_health_server = HealthServer::new(HealthService::new(
+            vec![swap_manager_server::SERVICE_NAME],
+            ready,
+        ));
         tracker.spawn(async move {
             info!(
                 address = field::display(&args.internal_address),
                 "Starting internal server"
             );
-            let res = Server::builder()
+            let res = internal_server_builder
                 .add_service(internal_server)
+                .add_service(internal_health_server)
                 .serve_with_shutdown(args.internal_address, internal_server_token.cancelled())
                 .await;
             match res {
@@ -586,6 +1637,25 @@ where
         });
     }
 
+    if let Some(metrics_address) = args.metrics_address {
+        let metrics_server_token = token.clone();
+        let metrics_server = MetricsServer::new(metrics_address, Arc::clone(&metrics));
+        tracker.spawn(async move {
+            info!(
+                address = field::display(&metrics_address),
+                "Starting metrics server"
+            );
+            let res = metrics_server
+                .start(metrics_server_token.child_token())
+                .await;
+            match res {
+                Ok(_) => info!("metrics server exited"),
+                Err(e) => info!("metrics server exited with {:?}", e),
+            }
+            metrics_server_token.cancel();
+        });
+    }
+
     info!("swapd started");
 
     // Ensure the tracker completes when all tasks have completed.
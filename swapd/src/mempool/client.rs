@@ -0,0 +1,17 @@
+use bitcoin::{Transaction, Txid};
+
+use crate::chain::ChainError;
+
+#[async_trait::async_trait]
+pub trait MempoolClient {
+    async fn get_mempool_txids(&self) -> Result<Vec<Txid>, ChainError>;
+    /// Returns `None` if the transaction is not in the mempool.
+    async fn get_mempool_transaction(
+        &self,
+        tx_id: &Txid,
+    ) -> Result<Option<Transaction>, ChainError>;
+    /// Whether the mempool transaction, or one of its unconfirmed ancestors,
+    /// signals replaceability (BIP125). Returns `None` if the transaction is
+    /// not in the mempool.
+    async fn is_replaceable(&self, tx_id: &Txid) -> Result<Option<bool>, ChainError>;
+}
@@ -0,0 +1,7 @@
+mod client;
+mod monitor;
+mod repository;
+
+pub use client::MempoolClient;
+pub use monitor::{MempoolMonitor, MempoolMonitorParams};
+pub use repository::{MempoolRepository, MempoolRepositoryError, MempoolTxo};
@@ -0,0 +1,336 @@
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use bitcoin::{Address, Network, OutPoint, Transaction, Txid};
+use thiserror::Error;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, field, info, trace, warn};
+
+use crate::{
+    chain::{ChainClient, ChainError, ChainRepository, ChainRepositoryError},
+    event::{EventBus, SwapEvent, SwapEventKind},
+};
+
+use super::{MempoolClient, MempoolRepository, MempoolRepositoryError, MempoolTxo};
+
+/// Number of new mempool transactions fetched before matching them against
+/// the watched addresses.
+const TX_BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Error)]
+pub enum MempoolMonitorError {
+    #[error("{0}")]
+    Chain(ChainError),
+    #[error("{0}")]
+    Repository(MempoolRepositoryError),
+}
+
+pub struct MempoolMonitorParams<C, CR, M, MR>
+where
+    C: ChainClient,
+    CR: ChainRepository,
+    M: MempoolClient,
+    MR: MempoolRepository,
+{
+    pub chain_client: Arc<C>,
+    pub chain_repository: Arc<CR>,
+    pub event_bus: Arc<EventBus>,
+    pub mempool_client: Arc<M>,
+    pub mempool_repository: Arc<MR>,
+    pub network: Network,
+    pub new_txs: broadcast::Sender<Transaction>,
+    pub poll_interval: Duration,
+}
+
+/// Records unconfirmed outputs to watched addresses in the mempool
+/// repository. The whole mempool is synced every `poll_interval`. In between,
+/// transactions sent on `new_txs` are matched as they come in. Outputs that
+/// leave the mempool without confirming are removed again.
+pub struct MempoolMonitor<C, CR, M, MR>
+where
+    C: ChainClient,
+    CR: ChainRepository,
+    M: MempoolClient,
+    MR: MempoolRepository,
+{
+    chain_client: Arc<C>,
+    chain_repository: Arc<CR>,
+    event_bus: Arc<EventBus>,
+    mempool_client: Arc<M>,
+    mempool_repository: Arc<MR>,
+    network: Network,
+    new_txs: broadcast::Sender<Transaction>,
+    poll_interval: Duration,
+}
+
+impl<C, CR, M, MR> MempoolMonitor<C, CR, M, MR>
+where
+    C: ChainClient + Send + Sync,
+    CR: ChainRepository + Send + Sync,
+    M: MempoolClient + Send + Sync,
+    MR: MempoolRepository + Send + Sync,
+{
+    pub fn new(params: MempoolMonitorParams<C, CR, M, MR>) -> Self {
+        Self {
+            chain_client: params.chain_client,
+            chain_repository: params.chain_repository,
+            event_bus: params.event_bus,
+            mempool_client: params.mempool_client,
+            mempool_repository: params.mempool_repository,
+            network: params.network,
+            new_txs: params.new_txs,
+            poll_interval: params.poll_interval,
+        }
+    }
+
+    pub async fn start(&self, token: CancellationToken) -> Result<(), MempoolMonitorError> {
+        let mut new_txs = self.new_txs.subscribe();
+
+        // Mempool transactions that were already matched.
+        let mut known: HashSet<Txid> = HashSet::new();
+        loop {
+            if token.is_cancelled() {
+                return Ok(());
+            }
+
+            if let Err(e) = self.sync(&mut known).await {
+                error!("mempool sync failed: {:?}", e);
+            }
+
+            let next_sync = tokio::time::sleep(self.poll_interval);
+            tokio::pin!(next_sync);
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => {
+                        debug!("mempool monitor shutting down");
+                        return Ok(());
+                    }
+                    _ = &mut next_sync => break,
+                    tx = new_txs.recv() => match tx {
+                        Ok(tx) => {
+                            let tx_id = tx.compute_txid();
+                            if known.contains(&tx_id) {
+                                continue;
+                            }
+
+                            match self.process_txs(vec![tx]).await {
+                                Ok(_) => {
+                                    known.insert(tx_id);
+                                }
+                                Err(e) => error!("failed to process mempool tx {}: {:?}", tx_id, e),
+                            }
+                        }
+                        Err(RecvError::Lagged(n)) => {
+                            warn!("missed {} mempool transactions, syncing mempool", n);
+                            break;
+                        }
+                        Err(RecvError::Closed) => return Ok(()),
+                    },
+                }
+            }
+        }
+    }
+
+    async fn sync(&self, known: &mut HashSet<Txid>) -> Result<(), MempoolMonitorError> {
+        let synced_tip = self.chain_repository.get_tip().await?.map(|tip| tip.hash);
+        let mempool: HashSet<Txid> = self
+            .mempool_client
+            .get_mempool_txids()
+            .await?
+            .into_iter()
+            .collect();
+        let tip = self.chain_client.get_tip_hash().await?;
+
+        known.retain(|tx_id| mempool.contains(tx_id));
+        let new_tx_ids: Vec<Txid> = mempool
+            .iter()
+            .filter(|tx_id| !known.contains(*tx_id))
+            .cloned()
+            .collect();
+        if !new_tx_ids.is_empty() {
+            debug!("matching {} new mempool transactions", new_tx_ids.len());
+        }
+
+        for tx_ids in new_tx_ids.chunks(TX_BATCH_SIZE) {
+            let mut txs = Vec::new();
+            for tx_id in tx_ids {
+                if let Some(tx) = self.mempool_client.get_mempool_transaction(tx_id).await? {
+                    txs.push(tx);
+                }
+            }
+
+            self.process_txs(txs).await?;
+            known.extend(tx_ids.iter().cloned());
+        }
+
+        // Outputs that left the mempool were either confirmed, or replaced,
+        // double spent or evicted. That can only be told apart once the chain
+        // repository is synced to the tip the mempool snapshot was taken at.
+        if synced_tip != Some(tip) {
+            trace!("chain is not synced to the mempool tip, not removing outputs");
+            return Ok(());
+        }
+
+        self.remove_txos(&mempool).await
+    }
+
+    /// Adds the outputs of `txs` to watched addresses to the repository and
+    /// publishes the new ones.
+    async fn process_txs(&self, txs: Vec<Transaction>) -> Result<(), MempoolMonitorError> {
+        let mut outputs = Vec::new();
+        for tx in &txs {
+            let tx_id = tx.compute_txid();
+            for (vout, tx_out) in tx.output.iter().enumerate() {
+                let address = match Address::from_script(&tx_out.script_pubkey, self.network) {
+                    Ok(address) => address,
+                    Err(_) => continue,
+                };
+                outputs.push((address, OutPoint::new(tx_id, vout as u32), tx_out.clone()));
+            }
+        }
+
+        if outputs.is_empty() {
+            return Ok(());
+        }
+
+        let addresses: Vec<_> = outputs
+            .iter()
+            .map(|(address, _, _)| address.clone())
+            .collect();
+        let watched: HashSet<Address> = self
+            .chain_repository
+            .filter_watch_addresses(&addresses)
+            .await?
+            .into_iter()
+            .collect();
+
+        let now = SystemTime::now();
+        let mut replaceable: HashMap<Txid, Option<bool>> = HashMap::new();
+        let mut txos = Vec::new();
+        for (address, outpoint, tx_out) in outputs {
+            if !watched.contains(&address) {
+                continue;
+            }
+
+            let is_replaceable = match replaceable.entry(outpoint.txid) {
+                Entry::Occupied(entry) => *entry.get(),
+                Entry::Vacant(entry) => {
+                    *entry.insert(self.mempool_client.is_replaceable(&outpoint.txid).await?)
+                }
+            };
+
+            // The transaction was confirmed or left the mempool in the
+            // meantime.
+            let is_replaceable = match is_replaceable {
+                Some(is_replaceable) => is_replaceable,
+                None => continue,
+            };
+
+            txos.push(MempoolTxo {
+                address,
+                outpoint,
+                tx_out,
+                replaceable: is_replaceable,
+                first_seen: now,
+            });
+        }
+
+        if txos.is_empty() {
+            return Ok(());
+        }
+
+        let new_outpoints = self.mempool_repository.add_txos(&txos).await?;
+        for txo in txos
+            .into_iter()
+            .filter(|txo| new_outpoints.contains(&txo.outpoint))
+        {
+            info!(
+                address = field::display(&txo.address),
+                outpoint = field::display(txo.outpoint),
+                replaceable = txo.replaceable,
+                "new unconfirmed deposit"
+            );
+            self.event_bus.publish(SwapEvent::new(
+                txo.address,
+                SwapEventKind::DepositUnconfirmed {
+                    outpoint: txo.outpoint,
+                    amount: txo.tx_out.value,
+                    replaceable: txo.replaceable,
+                },
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Removes the outputs that are no longer in the mempool, and publishes
+    /// the ones that didn't confirm as dropped.
+    async fn remove_txos(&self, mempool: &HashSet<Txid>) -> Result<(), MempoolMonitorError> {
+        let txos: Vec<_> = self
+            .mempool_repository
+            .get_txos()
+            .await?
+            .into_iter()
+            .filter(|txo| !mempool.contains(&txo.outpoint.txid))
+            .collect();
+        if txos.is_empty() {
+            return Ok(());
+        }
+
+        for txo in &txos {
+            let confirmed = self
+                .chain_repository
+                .get_txos_for_address(&txo.address)
+                .await?
+                .iter()
+                .any(|confirmed| confirmed.outpoint == txo.outpoint);
+            if confirmed {
+                debug!(
+                    address = field::display(&txo.address),
+                    outpoint = field::display(txo.outpoint),
+                    "unconfirmed deposit confirmed"
+                );
+                continue;
+            }
+
+            warn!(
+                address = field::display(&txo.address),
+                outpoint = field::display(txo.outpoint),
+                replaceable = txo.replaceable,
+                "unconfirmed deposit left the mempool without confirming, it was replaced, double spent or evicted"
+            );
+            self.event_bus.publish(SwapEvent::new(
+                txo.address.clone(),
+                SwapEventKind::DepositDropped {
+                    outpoint: txo.outpoint,
+                },
+            ));
+        }
+
+        let outpoints: Vec<_> = txos.iter().map(|txo| txo.outpoint).collect();
+        self.mempool_repository.remove_txos(&outpoints).await?;
+        Ok(())
+    }
+}
+
+impl From<ChainError> for MempoolMonitorError {
+    fn from(value: ChainError) -> Self {
+        MempoolMonitorError::Chain(value)
+    }
+}
+
+impl From<ChainRepositoryError> for MempoolMonitorError {
+    fn from(value: ChainRepositoryError) -> Self {
+        MempoolMonitorError::Chain(value.into())
+    }
+}
+
+impl From<MempoolRepositoryError> for MempoolMonitorError {
+    fn from(value: MempoolRepositoryError) -> Self {
+        MempoolMonitorError::Repository(value)
+    }
+}
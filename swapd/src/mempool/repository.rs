@@ -0,0 +1,36 @@
+use std::time::SystemTime;
+
+use bitcoin::{Address, OutPoint, TxOut};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum MempoolRepositoryError {
+    #[error("{0}")]
+    General(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// An unconfirmed output to a watched address.
+#[derive(Clone, Debug)]
+pub struct MempoolTxo {
+    pub address: Address,
+    pub outpoint: OutPoint,
+    pub tx_out: TxOut,
+    /// Whether the transaction signals replaceability (BIP125).
+    pub replaceable: bool,
+    pub first_seen: SystemTime,
+}
+
+#[async_trait::async_trait]
+pub trait MempoolRepository {
+    /// Adds the outputs, returns the outpoints that were not known yet.
+    async fn add_txos(&self, txos: &[MempoolTxo]) -> Result<Vec<OutPoint>, MempoolRepositoryError>;
+    async fn get_txos(&self) -> Result<Vec<MempoolTxo>, MempoolRepositoryError>;
+    async fn get_txos_for_address(
+        &self,
+        address: &Address,
+    ) -> Result<Vec<MempoolTxo>, MempoolRepositoryError>;
+    /// Returns the summed value of the unconfirmed outputs that were paid
+    /// out, or are being paid out, before they confirmed.
+    async fn get_paid_amount(&self) -> Result<u64, MempoolRepositoryError>;
+    async fn remove_txos(&self, outpoints: &[OutPoint]) -> Result<(), MempoolRepositoryError>;
+}
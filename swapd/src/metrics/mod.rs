@@ -0,0 +1,183 @@
+mod registry;
+mod server;
+
+pub use registry::{Counter, CounterVec, Gauge, GaugeVec, Histogram};
+pub use server::MetricsServer;
+
+use registry::Encoder;
+
+/// Upper bounds of the payout latency buckets, in seconds.
+const PAYOUT_LATENCY_BUCKETS: [f64; 10] =
+    [0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0, 300.0];
+
+/// Fee estimate sources, see `FallbackFeeEstimator`.
+pub const FEE_SOURCE_PRIMARY: &str = "primary";
+pub const FEE_SOURCE_FALLBACK: &str = "fallback";
+pub const FEE_SOURCE_UNAVAILABLE: &str = "unavailable";
+
+/// Operational metrics, exposed in the prometheus format by the
+/// `MetricsServer`. Every process keeps its own metrics, so with the tasks
+/// split over multiple processes, each process only reports the metrics of
+/// the tasks it runs.
+#[derive(Debug)]
+pub struct Metrics {
+    pub swaps_created: Counter,
+    /// Rejected swap creations, by the limit that was hit.
+    pub swaps_rejected: CounterVec,
+    pub swaps_expired: Counter,
+    /// Swaps that need no more attention, by completion reason.
+    pub swaps_completed: CounterVec,
+    pub swaps_archived: Counter,
+    pub swaps_paid: Counter,
+    /// Failed swap payments, by the failure reason reported by the lightning
+    /// node.
+    pub payment_failures: CounterVec,
+    pub payout_latency: Histogram,
+    pub unclaimed_value_sat: Gauge,
+    /// Paid swap outputs that are close to being refundable by the user.
+    pub claim_outputs_at_risk: Gauge,
+    /// Swap outputs not paid over lightning without a disposition.
+    pub unpaid_outputs: Gauge,
+    pub unpaid_value_sat: Gauge,
+    pub claim_txs_pending: Gauge,
+    /// Fee rates of the pending claim transactions, by txid.
+    pub claim_tx_fee_rate: GaugeVec,
+    pub chain_synced_height: Gauge,
+    pub chain_tip_height: Gauge,
+    /// Fee estimates, by the estimator that provided them.
+    pub fee_estimates: CounterVec,
+    pub lightning_up: Gauge,
+    pub chain_client_up: Gauge,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self {
+            swaps_created: Counter::default(),
+            swaps_rejected: CounterVec::new("reason"),
+            swaps_expired: Counter::default(),
+            swaps_completed: CounterVec::new("reason"),
+            swaps_archived: Counter::default(),
+            swaps_paid: Counter::default(),
+            payment_failures: CounterVec::new("reason"),
+            payout_latency: Histogram::new(&PAYOUT_LATENCY_BUCKETS),
+            unclaimed_value_sat: Gauge::default(),
+            claim_outputs_at_risk: Gauge::default(),
+            unpaid_outputs: Gauge::default(),
+            unpaid_value_sat: Gauge::default(),
+            claim_txs_pending: Gauge::default(),
+            claim_tx_fee_rate: GaugeVec::new("tx_id"),
+            chain_synced_height: Gauge::default(),
+            chain_tip_height: Gauge::default(),
+            fee_estimates: CounterVec::new("source"),
+            lightning_up: Gauge::default(),
+            chain_client_up: Gauge::default(),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let mut e = Encoder::new();
+        e.counter(
+            "swapd_swaps_created_total",
+            "Number of swaps created.",
+            &self.swaps_created,
+        );
+        e.counter_vec(
+            "swapd_swaps_rejected_total",
+            "Number of rejected swap creations, by the limit that was hit.",
+            &self.swaps_rejected,
+        );
+        e.counter(
+            "swapd_swaps_expired_total",
+            "Number of unfunded swaps expired after their lock time.",
+            &self.swaps_expired,
+        );
+        e.counter_vec(
+            "swapd_swaps_completed_total",
+            "Number of swaps completed, by reason: claimed, refunded or expired.",
+            &self.swaps_completed,
+        );
+        e.counter(
+            "swapd_swaps_archived_total",
+            "Number of completed swaps moved to the archive tables.",
+            &self.swaps_archived,
+        );
+        e.counter(
+            "swapd_swaps_paid_total",
+            "Number of swaps paid out over lightning.",
+            &self.swaps_paid,
+        );
+        e.counter_vec(
+            "swapd_payment_failures_total",
+            "Number of failed swap payouts, by failure reason.",
+            &self.payment_failures,
+        );
+        e.histogram(
+            "swapd_payout_latency_seconds",
+            "Time taken by successful swap payouts over lightning.",
+            &self.payout_latency,
+        );
+        e.gauge(
+            "swapd_unclaimed_value_sat",
+            "Value of paid swap outputs that are not claimed yet.",
+            &self.unclaimed_value_sat,
+        );
+        e.gauge(
+            "swapd_claim_outputs_at_risk",
+            "Number of paid swap outputs not claimed yet, with few blocks left before the user can refund them.",
+            &self.claim_outputs_at_risk,
+        );
+        e.gauge(
+            "swapd_unpaid_outputs",
+            "Number of swap outputs that were not paid over lightning and have no disposition yet.",
+            &self.unpaid_outputs,
+        );
+        e.gauge(
+            "swapd_unpaid_value_sat",
+            "Value of swap outputs that were not paid over lightning and have no disposition yet.",
+            &self.unpaid_value_sat,
+        );
+        e.gauge(
+            "swapd_claim_txs_pending",
+            "Number of broadcast claim transactions that are not confirmed yet.",
+            &self.claim_txs_pending,
+        );
+        e.gauge_vec(
+            "swapd_claim_tx_fee_rate_sat_per_kw",
+            "Fee rate of pending claim transactions.",
+            &self.claim_tx_fee_rate,
+        );
+        e.gauge(
+            "swapd_chain_synced_height",
+            "Height of the synced chain tip.",
+            &self.chain_synced_height,
+        );
+        e.gauge(
+            "swapd_chain_tip_height",
+            "Height of the chain tip according to the chain backend.",
+            &self.chain_tip_height,
+        );
+        e.counter_vec(
+            "swapd_fee_estimates_total",
+            "Number of fee estimates, by source. 'fallback' means the primary estimator failed.",
+            &self.fee_estimates,
+        );
+        e.gauge(
+            "swapd_lightning_up",
+            "Whether the lightning node was reachable on the last check.",
+            &self.lightning_up,
+        );
+        e.gauge(
+            "swapd_chain_client_up",
+            "Whether the chain backend was reachable on the last check.",
+            &self.chain_client_up,
+        );
+        e.finish()
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,262 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicI64, AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+/// Monotonically increasing count.
+#[derive(Debug, Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    pub fn inc(&self) {
+        self.inc_by(1);
+    }
+
+    pub fn inc_by(&self, n: u64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Value that can go up and down.
+#[derive(Debug, Default)]
+pub struct Gauge(AtomicI64);
+
+impl Gauge {
+    pub fn set(&self, value: i64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Counters keyed by the value of a single label.
+#[derive(Debug)]
+pub struct CounterVec {
+    label: &'static str,
+    values: Mutex<BTreeMap<String, u64>>,
+}
+
+impl CounterVec {
+    pub fn new(label: &'static str) -> Self {
+        Self {
+            label,
+            values: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn inc(&self, label_value: &str) {
+        let mut values = self.values.lock().unwrap();
+        *values.entry(label_value.to_string()).or_default() += 1;
+    }
+}
+
+/// Gauges keyed by the value of a single label.
+#[derive(Debug)]
+pub struct GaugeVec {
+    label: &'static str,
+    values: Mutex<BTreeMap<String, i64>>,
+}
+
+impl GaugeVec {
+    pub fn new(label: &'static str) -> Self {
+        Self {
+            label,
+            values: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Replaces all values, so label values that are no longer reported
+    /// disappear.
+    pub fn replace(&self, values: impl IntoIterator<Item = (String, i64)>) {
+        *self.values.lock().unwrap() = values.into_iter().collect();
+    }
+}
+
+/// Distribution of observed values over fixed buckets.
+#[derive(Debug)]
+pub struct Histogram {
+    buckets: Vec<f64>,
+    state: Mutex<HistogramState>,
+}
+
+#[derive(Debug)]
+struct HistogramState {
+    counts: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    /// `buckets` are the upper bounds of the buckets, in ascending order.
+    pub fn new(buckets: &[f64]) -> Self {
+        Self {
+            buckets: buckets.to_vec(),
+            state: Mutex::new(HistogramState {
+                counts: vec![0; buckets.len()],
+                count: 0,
+                sum: 0.0,
+            }),
+        }
+    }
+
+    pub fn observe(&self, value: f64) {
+        let mut state = self.state.lock().unwrap();
+        for (i, bucket) in self.buckets.iter().enumerate() {
+            if value <= *bucket {
+                state.counts[i] += 1;
+            }
+        }
+        state.count += 1;
+        state.sum += value;
+    }
+
+    pub fn observe_duration(&self, duration: Duration) {
+        self.observe(duration.as_secs_f64());
+    }
+}
+
+/// Writes metrics in the prometheus text exposition format.
+pub(super) struct Encoder {
+    out: String,
+}
+
+impl Encoder {
+    pub(super) fn new() -> Self {
+        Self { out: String::new() }
+    }
+
+    pub(super) fn finish(self) -> String {
+        self.out
+    }
+
+    fn header(&mut self, name: &str, help: &str, kind: &str) {
+        let _ = writeln!(self.out, "# HELP {} {}", name, help);
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind);
+    }
+
+    pub(super) fn counter(&mut self, name: &str, help: &str, counter: &Counter) {
+        self.header(name, help, "counter");
+        let _ = writeln!(self.out, "{} {}", name, counter.get());
+    }
+
+    pub(super) fn gauge(&mut self, name: &str, help: &str, gauge: &Gauge) {
+        self.header(name, help, "gauge");
+        let _ = writeln!(self.out, "{} {}", name, gauge.get());
+    }
+
+    pub(super) fn counter_vec(&mut self, name: &str, help: &str, counter: &CounterVec) {
+        self.header(name, help, "counter");
+        for (label_value, value) in counter.values.lock().unwrap().iter() {
+            let _ = writeln!(
+                self.out,
+                "{}{{{}=\"{}\"}} {}",
+                name,
+                counter.label,
+                escape(label_value),
+                value
+            );
+        }
+    }
+
+    pub(super) fn gauge_vec(&mut self, name: &str, help: &str, gauge: &GaugeVec) {
+        self.header(name, help, "gauge");
+        for (label_value, value) in gauge.values.lock().unwrap().iter() {
+            let _ = writeln!(
+                self.out,
+                "{}{{{}=\"{}\"}} {}",
+                name,
+                gauge.label,
+                escape(label_value),
+                value
+            );
+        }
+    }
+
+    pub(super) fn histogram(&mut self, name: &str, help: &str, histogram: &Histogram) {
+        self.header(name, help, "histogram");
+        let state = histogram.state.lock().unwrap();
+        for (bucket, count) in histogram.buckets.iter().zip(state.counts.iter()) {
+            let _ = writeln!(self.out, "{}_bucket{{le=\"{}\"}} {}", name, bucket, count);
+        }
+        let _ = writeln!(self.out, "{}_bucket{{le=\"+Inf\"}} {}", name, state.count);
+        let _ = writeln!(self.out, "{}_sum {}", name, state.sum);
+        let _ = writeln!(self.out, "{}_count {}", name, state.count);
+    }
+}
+
+fn escape(label_value: &str) -> String {
+    label_value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Counter, CounterVec, Encoder, GaugeVec, Histogram};
+
+    #[test]
+    fn test_encode_counter() {
+        let counter = Counter::default();
+        counter.inc();
+        counter.inc_by(2);
+        let mut encoder = Encoder::new();
+        encoder.counter("swaps_total", "Swaps.", &counter);
+        assert_eq!(
+            encoder.finish(),
+            "# HELP swaps_total Swaps.\n# TYPE swaps_total counter\nswaps_total 3\n"
+        );
+    }
+
+    #[test]
+    fn test_encode_counter_vec_escapes_labels() {
+        let counter = CounterVec::new("reason");
+        counter.inc("timeout");
+        counter.inc("timeout");
+        counter.inc("say \"no\"");
+        let mut encoder = Encoder::new();
+        encoder.counter_vec("failures_total", "Failures.", &counter);
+        assert_eq!(
+            encoder.finish(),
+            "# HELP failures_total Failures.\n# TYPE failures_total counter\nfailures_total{reason=\"say \\\"no\\\"\"} 1\nfailures_total{reason=\"timeout\"} 2\n"
+        );
+    }
+
+    #[test]
+    fn test_gauge_vec_replace_drops_old_values() {
+        let gauge = GaugeVec::new("tx_id");
+        gauge.replace([("a".to_string(), 1), ("b".to_string(), 2)]);
+        gauge.replace([("b".to_string(), 3)]);
+        let mut encoder = Encoder::new();
+        encoder.gauge_vec("fee_rate", "Fee rate.", &gauge);
+        assert_eq!(
+            encoder.finish(),
+            "# HELP fee_rate Fee rate.\n# TYPE fee_rate gauge\nfee_rate{tx_id=\"b\"} 3\n"
+        );
+    }
+
+    #[test]
+    fn test_encode_histogram_cumulative_buckets() {
+        let histogram = Histogram::new(&[1.0, 5.0]);
+        histogram.observe(0.5);
+        histogram.observe(2.0);
+        histogram.observe(10.0);
+        let mut encoder = Encoder::new();
+        encoder.histogram("latency", "Latency.", &histogram);
+        assert_eq!(
+            encoder.finish(),
+            "# HELP latency Latency.\n# TYPE latency histogram\nlatency_bucket{le=\"1\"} 1\nlatency_bucket{le=\"5\"} 2\nlatency_bucket{le=\"+Inf\"} 3\nlatency_sum 12.5\nlatency_count 3\n"
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Router};
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+
+use super::Metrics;
+
+/// Serves the metrics on `/metrics` for prometheus to scrape.
+pub struct MetricsServer {
+    address: SocketAddr,
+    metrics: Arc<Metrics>,
+}
+
+impl MetricsServer {
+    pub fn new(address: SocketAddr, metrics: Arc<Metrics>) -> Self {
+        Self { address, metrics }
+    }
+
+    pub async fn start(&self, token: CancellationToken) -> Result<(), std::io::Error> {
+        let app = Router::new()
+            .route("/metrics", get(get_metrics))
+            .with_state(Arc::clone(&self.metrics));
+        let listener = TcpListener::bind(self.address).await?;
+        axum::serve(listener, app)
+            .with_graceful_shutdown(token.cancelled_owned())
+            .await
+    }
+}
+
+async fn get_metrics(State(metrics): State<Arc<Metrics>>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics.encode(),
+    )
+}
@@ -6,7 +6,8 @@ use sqlx::{postgres::PgRow, PgConnection, PgPool, Row};
 use tracing::instrument;
 
 use crate::chain::{
-    self, AddressUtxo, BlockHeader, ChainRepositoryError, SpentTxo, Txo, TxoSpend, TxoWithSpend,
+    self, AddressSpentTxo, AddressUtxo, BlockHeader, ChainRepositoryError, NewTxos, SpentTxo, Txo,
+    TxoSpend, TxoWithSpend,
 };
 
 #[derive(Debug)]
@@ -25,7 +26,7 @@ impl ChainRepository {
         &self,
         tx: &mut PgConnection,
         tx_outputs: &[AddressUtxo],
-    ) -> Result<(), ChainRepositoryError> {
+    ) -> Result<Vec<OutPoint>, ChainRepositoryError> {
         let tx_ids: Vec<_> = tx_outputs
             .iter()
             .map(|u| u.utxo.outpoint.txid.to_string())
@@ -39,7 +40,7 @@ impl ChainRepository {
             .iter()
             .map(|u| u.utxo.tx_out.value.to_sat() as i64)
             .collect();
-        sqlx::query(
+        let mut rows = sqlx::query(
             r#"INSERT INTO tx_outputs (
                    tx_id
                ,   output_index
@@ -53,16 +54,23 @@ impl ChainRepository {
                ,   $4::bigint[]
                ) AS t(tx_id, output_index, address, amount)
                INNER JOIN watch_addresses w ON w.address = t.address
-               ON CONFLICT DO NOTHING"#,
+               ON CONFLICT DO NOTHING
+               RETURNING tx_id, output_index"#,
         )
         .bind(&tx_ids)
         .bind(&output_indices)
         .bind(&addresses)
         .bind(&amounts)
-        .execute(tx)
-        .await?;
+        .fetch(tx);
 
-        Ok(())
+        let mut result = Vec::new();
+        while let Some(row) = rows.try_next().await? {
+            let tx_id: String = row.try_get("tx_id")?;
+            let output_index: i64 = row.try_get("output_index")?;
+            result.push(OutPoint::new(tx_id.parse()?, output_index as u32));
+        }
+
+        Ok(result)
     }
 
     async fn map_txo(&self, address: &Address, row: &PgRow) -> Result<Txo, ChainRepositoryError> {
@@ -87,7 +95,7 @@ impl ChainRepository {
         &self,
         tx: &mut PgConnection,
         txos: &[SpentTxo],
-    ) -> Result<Vec<SpentTxo>, ChainRepositoryError> {
+    ) -> Result<Vec<AddressSpentTxo>, ChainRepositoryError> {
         let tx_ids: Vec<_> = txos.iter().map(|u| u.outpoint.txid.to_string()).collect();
         let tx_output_indices: Vec<_> = txos.iter().map(|u| u.outpoint.vout as i64).collect();
         let spending_tx_ids: Vec<_> = txos.iter().map(|u| u.spending_tx.to_string()).collect();
@@ -95,29 +103,39 @@ impl ChainRepository {
             txos.iter().map(|u| u.spending_input_index as i64).collect();
 
         let mut rows = sqlx::query(
-            r#"INSERT INTO tx_inputs (
-                   tx_id
-               ,   output_index
-               ,   spending_tx_id
-               ,   spending_input_index)
+            r#"WITH inserted AS (
+                   INSERT INTO tx_inputs (
+                       tx_id
+                   ,   output_index
+                   ,   spending_tx_id
+                   ,   spending_input_index)
+                   SELECT i.tx_id
+                   ,      i.output_index
+                   ,      i.spending_tx_id
+                   ,      i.spending_input_index
+                   FROM UNNEST(
+                       $1::text[]
+                   ,   $2::bigint[]
+                   ,   $3::text[]
+                   ,   $4::bigint[]
+                   ) AS i (
+                       tx_id
+                   ,   output_index
+                   ,   spending_tx_id
+                   ,   spending_input_index)
+                   INNER JOIN tx_outputs o 
+                       ON i.tx_id = o.tx_id AND i.output_index = o.output_index
+                   ON CONFLICT DO NOTHING
+                   RETURNING tx_id, output_index, spending_tx_id, spending_input_index
+               )
                SELECT i.tx_id
                ,      i.output_index
                ,      i.spending_tx_id
                ,      i.spending_input_index
-               FROM UNNEST(
-                   $1::text[]
-               ,   $2::bigint[]
-               ,   $3::text[]
-               ,   $4::bigint[]
-               ) AS i (
-                   tx_id
-               ,   output_index
-               ,   spending_tx_id
-               ,   spending_input_index)
+               ,      o.address
+               FROM inserted i
                INNER JOIN tx_outputs o 
-                   ON i.tx_id = o.tx_id AND i.output_index = o.output_index
-               ON CONFLICT DO NOTHING
-               RETURNING tx_id, output_index, spending_tx_id, spending_input_index"#,
+                   ON i.tx_id = o.tx_id AND i.output_index = o.output_index"#,
         )
         .bind(&tx_ids)
         .bind(&tx_output_indices)
@@ -125,18 +143,25 @@ impl ChainRepository {
         .bind(&spending_tx_input_indices)
         .fetch(tx);
 
-        let mut result: Vec<SpentTxo> = Vec::new();
+        let mut result: Vec<AddressSpentTxo> = Vec::new();
         while let Some(row) = rows.try_next().await? {
             let tx_id: String = row.try_get("tx_id")?;
             let output_index: i64 = row.try_get("output_index")?;
             let spending_tx_id: String = row.try_get("spending_tx_id")?;
             let spending_input_index: i64 = row.try_get("spending_input_index")?;
+            let address: String = row.try_get("address")?;
+            let address = address
+                .parse::<Address<NetworkUnchecked>>()?
+                .require_network(self.network)?;
             let outpoint = OutPoint::new(tx_id.parse()?, output_index as u32);
 
-            result.push(SpentTxo {
-                outpoint,
-                spending_tx: spending_tx_id.parse()?,
-                spending_input_index: spending_input_index as u32,
+            result.push(AddressSpentTxo {
+                address,
+                spent_txo: SpentTxo {
+                    outpoint,
+                    spending_tx: spending_tx_id.parse()?,
+                    spending_input_index: spending_input_index as u32,
+                },
             });
         }
 
@@ -152,7 +177,7 @@ impl chain::ChainRepository for ChainRepository {
         block: &BlockHeader,
         tx_outputs: &[AddressUtxo],
         tx_inputs: &[SpentTxo],
-    ) -> Result<Vec<SpentTxo>, ChainRepositoryError> {
+    ) -> Result<NewTxos, ChainRepositoryError> {
         let mut tx = self.pool.begin().await?;
         sqlx::query(
             r#"INSERT INTO blocks (block_hash, prev_block_hash, height)
@@ -165,7 +190,7 @@ impl chain::ChainRepository for ChainRepository {
         .execute(&mut *tx)
         .await?;
 
-        self.add_utxos(&mut tx, tx_outputs).await?;
+        let utxos = self.add_utxos(&mut tx, tx_outputs).await?;
 
         // NOTE: This also marks outputs as spent that were added in the current
         // transaction (as long as the default transaction isolation level is
@@ -194,8 +219,8 @@ impl chain::ChainRepository for ChainRepository {
                              FROM tx_outputs o
                              WHERE o.tx_id = i.tx_id)
                    OR EXISTS (SELECT 1
-                              FROM tx_inputs i
-                              WHERE i.spending_tx_id = i.tx_id)
+                              FROM tx_inputs ti
+                              WHERE ti.spending_tx_id = i.tx_id)
                ON CONFLICT DO NOTHING"#,
         )
         .bind(&txns)
@@ -204,7 +229,7 @@ impl chain::ChainRepository for ChainRepository {
         .await?;
 
         tx.commit().await?;
-        Ok(spent_txos)
+        Ok(NewTxos { utxos, spent_txos })
     }
 
     #[instrument(level = "trace", skip(self))]
@@ -247,14 +272,19 @@ impl chain::ChainRepository for ChainRepository {
     }
 
     #[instrument(level = "trace", skip(self))]
-    async fn get_block_headers(&self) -> Result<Vec<BlockHeader>, ChainRepositoryError> {
+    async fn get_block_headers(
+        &self,
+        min_height: u64,
+    ) -> Result<Vec<BlockHeader>, ChainRepositoryError> {
         let mut rows = sqlx::query(
             r#"SELECT block_hash
                ,      prev_block_hash
                ,      height
                FROM blocks
+               WHERE height >= $1
                ORDER BY height DESC"#,
         )
+        .bind(min_height as i64)
         .fetch(&*self.pool);
 
         let mut result: Vec<BlockHeader> = Vec::new();
@@ -272,6 +302,52 @@ impl chain::ChainRepository for ChainRepository {
         Ok(result)
     }
 
+    #[instrument(level = "trace", skip(self))]
+    async fn get_watch_addresses(&self) -> Result<Vec<Address>, ChainRepositoryError> {
+        let mut rows = sqlx::query(
+            r#"SELECT address
+               FROM watch_addresses"#,
+        )
+        .fetch(&*self.pool);
+
+        let mut result: Vec<Address> = Vec::new();
+        while let Some(row) = rows.try_next().await? {
+            let address: String = row.try_get("address")?;
+            let address = address
+                .parse::<Address<NetworkUnchecked>>()?
+                .require_network(self.network)?;
+            result.push(address);
+        }
+        Ok(result)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn get_birthday(&self) -> Result<Option<BlockHeader>, ChainRepositoryError> {
+        let maybe_row = sqlx::query(
+            r#"SELECT block_hash
+               ,      prev_block_hash
+               ,      height
+               FROM blocks
+               ORDER BY height
+               LIMIT 1"#,
+        )
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        let row = match maybe_row {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+        let block_hash: String = row.try_get("block_hash")?;
+        let prev_block_hash: String = row.try_get("prev_block_hash")?;
+        let height: i64 = row.try_get("height")?;
+        Ok(Some(BlockHeader {
+            hash: block_hash.parse()?,
+            prev: prev_block_hash.parse()?,
+            height: height as u64,
+        }))
+    }
+
     #[instrument(level = "trace", skip(self))]
     async fn get_tip(&self) -> Result<Option<BlockHeader>, ChainRepositoryError> {
         let mut rows = sqlx::query(
@@ -422,6 +498,39 @@ impl chain::ChainRepository for ChainRepository {
         Ok(result)
     }
 
+    #[instrument(level = "trace", skip(self))]
+    async fn prune_blocks(&self, min_height: u64) -> Result<u64, ChainRepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Transactions whose outputs and spends were archived don't need to
+        // be linked to their blocks anymore.
+        sqlx::query(
+            r#"DELETE FROM tx_blocks tb
+               USING blocks b
+               WHERE tb.block_hash = b.block_hash
+                 AND b.height < $1
+                 AND NOT EXISTS (SELECT 1 FROM tx_outputs o WHERE o.tx_id = tb.tx_id)
+                 AND NOT EXISTS (SELECT 1 FROM tx_inputs i WHERE i.spending_tx_id = tb.tx_id)"#,
+        )
+        .bind(min_height as i64)
+        .execute(&mut *tx)
+        .await?;
+
+        // The birthday is kept, it's where a full sync starts.
+        let result = sqlx::query(
+            r#"DELETE FROM blocks b
+               WHERE b.height < $1
+                 AND b.height > (SELECT MIN(height) FROM blocks)
+                 AND NOT EXISTS (SELECT 1 FROM tx_blocks tb WHERE tb.block_hash = b.block_hash)"#,
+        )
+        .bind(min_height as i64)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
     #[instrument(level = "trace", skip(self))]
     async fn undo_block(&self, hash: BlockHash) -> Result<(), ChainRepositoryError> {
         sqlx::query("DELETE FROM blocks WHERE block_hash = $1")
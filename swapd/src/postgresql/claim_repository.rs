@@ -6,14 +6,19 @@ use std::{
 use bitcoin::{
     address::NetworkUnchecked,
     consensus::{Decodable, Encodable},
-    Address, Network, OutPoint, Transaction,
+    hashes::{sha256, Hash},
+    Address, Network, OutPoint, Transaction, Txid,
 };
 use futures::TryStreamExt;
 use sqlx::{PgPool, Row};
 use tracing::instrument;
 
-use crate::claim::{self, Claim, ClaimRepositoryError};
+use crate::claim::{
+    self, Claim, ClaimCpfp, ClaimRepositoryError, DestinationAddress, OutputDisposition,
+    UnpaidOutput,
+};
 
+#[derive(Debug)]
 pub struct ClaimRepository {
     network: Network,
     pool: Arc<PgPool>,
@@ -74,6 +79,26 @@ impl claim::ClaimRepository for ClaimRepository {
         Ok(())
     }
 
+    #[instrument(level = "trace", skip(self))]
+    async fn add_cpfp(&self, cpfp: &ClaimCpfp) -> Result<(), ClaimRepositoryError> {
+        sqlx::query(
+            r#"INSERT INTO claim_cpfps (tx_id, claim_tx_id, creation_time, destination_address, fee_per_kw, package_fee_per_kw, weight, descendants, descendants_weight)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"#,
+        )
+        .bind(cpfp.tx_id.to_string())
+        .bind(cpfp.claim_tx_id.to_string())
+        .bind(cpfp.creation_time.duration_since(UNIX_EPOCH)?.as_secs() as i64)
+        .bind(cpfp.destination_address.to_string())
+        .bind(cpfp.fee_per_kw as i64)
+        .bind(cpfp.package_fee_per_kw as i64)
+        .bind(cpfp.weight as i64)
+        .bind(cpfp.descendants as i64)
+        .bind(cpfp.descendants_weight as i64)
+        .execute(&*self.pool)
+        .await?;
+        Ok(())
+    }
+
     /// Get all claims where the inputs haven't been spent yet, sorted by fee
     /// rate desc, then creation time desc.
     #[instrument(level = "trace", skip(self))]
@@ -81,12 +106,28 @@ impl claim::ClaimRepository for ClaimRepository {
         // NOTE: This query violates the separation principle of separating
         // chain and claim logic.
         let mut rows = sqlx::query(
-            r#"SELECT r.creation_time
+            r#"SELECT r.tx_id
+               ,      r.creation_time
                ,      r.tx
                ,      r.destination_address
                ,      r.fee_per_kw
                ,      r.auto_bump
+               ,      c.tx_id AS cpfp_tx_id
+               ,      c.creation_time AS cpfp_creation_time
+               ,      c.destination_address AS cpfp_destination_address
+               ,      c.fee_per_kw AS cpfp_fee_per_kw
+               ,      c.package_fee_per_kw AS cpfp_package_fee_per_kw
+               ,      c.weight AS cpfp_weight
+               ,      c.descendants AS cpfp_descendants
+               ,      c.descendants_weight AS cpfp_descendants_weight
                FROM claims r
+               LEFT JOIN LATERAL (
+                   SELECT *
+                   FROM claim_cpfps c
+                   WHERE c.claim_tx_id = r.tx_id
+                   ORDER BY c.package_fee_per_kw DESC, c.creation_time DESC
+                   LIMIT 1
+               ) c ON true
                WHERE r.tx_id IN (
                    SELECT ri.claim_tx_id
                    FROM claim_inputs ri
@@ -112,6 +153,36 @@ impl claim::ClaimRepository for ClaimRepository {
             let creation_time = SystemTime::UNIX_EPOCH
                 .checked_add(Duration::from_secs(creation_time as u64))
                 .ok_or(ClaimRepositoryError::InvalidTimestamp)?;
+            let cpfp_tx_id: Option<String> = row.try_get("cpfp_tx_id")?;
+            let cpfp = match cpfp_tx_id {
+                Some(cpfp_tx_id) => {
+                    let claim_tx_id: String = row.try_get("tx_id")?;
+                    let cpfp_creation_time: i64 = row.try_get("cpfp_creation_time")?;
+                    let cpfp_destination_address: String =
+                        row.try_get("cpfp_destination_address")?;
+                    let cpfp_fee_per_kw: i64 = row.try_get("cpfp_fee_per_kw")?;
+                    let cpfp_package_fee_per_kw: i64 = row.try_get("cpfp_package_fee_per_kw")?;
+                    let cpfp_weight: i64 = row.try_get("cpfp_weight")?;
+                    let cpfp_descendants: i64 = row.try_get("cpfp_descendants")?;
+                    let cpfp_descendants_weight: i64 = row.try_get("cpfp_descendants_weight")?;
+                    Some(ClaimCpfp {
+                        creation_time: SystemTime::UNIX_EPOCH
+                            .checked_add(Duration::from_secs(cpfp_creation_time as u64))
+                            .ok_or(ClaimRepositoryError::InvalidTimestamp)?,
+                        claim_tx_id: claim_tx_id.parse::<Txid>()?,
+                        tx_id: cpfp_tx_id.parse::<Txid>()?,
+                        destination_address: cpfp_destination_address
+                            .parse::<Address<NetworkUnchecked>>()?
+                            .require_network(self.network)?,
+                        fee_per_kw: cpfp_fee_per_kw as u32,
+                        package_fee_per_kw: cpfp_package_fee_per_kw as u32,
+                        weight: cpfp_weight as u64,
+                        descendants: cpfp_descendants as u64,
+                        descendants_weight: cpfp_descendants_weight as u64,
+                    })
+                }
+                None => None,
+            };
             result.push(Claim {
                 creation_time,
                 destination_address: destination_address
@@ -120,11 +191,201 @@ impl claim::ClaimRepository for ClaimRepository {
                 fee_per_kw: fee_per_kw as u32,
                 tx: Transaction::consensus_decode(&mut tx)?,
                 auto_bump,
+                cpfp,
+            });
+        }
+
+        Ok(result)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn add_unpaid_outputs(
+        &self,
+        outputs: &[UnpaidOutput],
+    ) -> Result<Vec<OutPoint>, ClaimRepositoryError> {
+        let mut tx_ids = Vec::new();
+        let mut output_indices = Vec::new();
+        let mut hashes = Vec::new();
+        let mut creation_times = Vec::new();
+        for output in outputs {
+            tx_ids.push(output.outpoint.txid.to_string());
+            output_indices.push(output.outpoint.vout as i64);
+            hashes.push(output.swap_hash.as_byte_array().to_vec());
+            creation_times.push(output.creation_time.duration_since(UNIX_EPOCH)?.as_secs() as i64);
+        }
+
+        let rows = sqlx::query(
+            r#"INSERT INTO unpaid_outputs (tx_id, output_index, swap_payment_hash, creation_time)
+               SELECT t.tx_id, t.output_index, t.swap_payment_hash, t.creation_time
+               FROM UNNEST($1::text[], $2::bigint[], $3::bytea[], $4::bigint[])
+                   AS t(tx_id, output_index, swap_payment_hash, creation_time)
+               ON CONFLICT DO NOTHING
+               RETURNING tx_id, output_index"#,
+        )
+        .bind(tx_ids)
+        .bind(output_indices)
+        .bind(hashes)
+        .bind(creation_times)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            let tx_id: String = row.try_get("tx_id")?;
+            let output_index: i64 = row.try_get("output_index")?;
+            result.push(OutPoint::new(tx_id.parse()?, output_index as u32));
+        }
+
+        Ok(result)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn get_unpaid_outputs(&self) -> Result<Vec<UnpaidOutput>, ClaimRepositoryError> {
+        let mut rows = sqlx::query(
+            r#"SELECT u.tx_id
+               ,      u.output_index
+               ,      u.swap_payment_hash
+               ,      u.creation_time
+               ,      u.disposition
+               ,      o.address
+               ,      o.amount
+               FROM unpaid_outputs u
+               INNER JOIN tx_outputs o
+                   ON u.tx_id = o.tx_id AND u.output_index = o.output_index
+               WHERE NOT EXISTS (
+                   SELECT 1
+                   FROM tx_inputs ti
+                   INNER JOIN tx_blocks tb ON ti.spending_tx_id = tb.tx_id
+                   WHERE ti.tx_id = u.tx_id AND ti.output_index = u.output_index
+               )
+               ORDER BY u.creation_time"#,
+        )
+        .fetch(&*self.pool);
+
+        let mut result = Vec::new();
+        while let Some(row) = rows.try_next().await? {
+            let tx_id: String = row.try_get("tx_id")?;
+            let output_index: i64 = row.try_get("output_index")?;
+            let swap_hash: Vec<u8> = row.try_get("swap_payment_hash")?;
+            let creation_time: i64 = row.try_get("creation_time")?;
+            let disposition: Option<String> = row.try_get("disposition")?;
+            let address: String = row.try_get("address")?;
+            let amount: i64 = row.try_get("amount")?;
+            result.push(UnpaidOutput {
+                outpoint: OutPoint::new(tx_id.parse()?, output_index as u32),
+                swap_hash: sha256::Hash::from_slice(&swap_hash)
+                    .map_err(|e| ClaimRepositoryError::General(Box::new(e)))?,
+                address: address
+                    .parse::<Address<NetworkUnchecked>>()?
+                    .require_network(self.network)?,
+                amount_sat: amount as u64,
+                creation_time: SystemTime::UNIX_EPOCH
+                    .checked_add(Duration::from_secs(creation_time as u64))
+                    .ok_or(ClaimRepositoryError::InvalidTimestamp)?,
+                disposition: disposition.map(|d| d.parse()).transpose()?,
             });
         }
 
         Ok(result)
     }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn set_output_disposition(
+        &self,
+        outpoint: &OutPoint,
+        disposition: OutputDisposition,
+    ) -> Result<(), ClaimRepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        let current: Option<Option<String>> = sqlx::query_scalar(
+            r#"SELECT disposition
+               FROM unpaid_outputs
+               WHERE tx_id = $1 AND output_index = $2
+               FOR UPDATE"#,
+        )
+        .bind(outpoint.txid.to_string())
+        .bind(outpoint.vout as i64)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let current = match current {
+            Some(current) => current
+                .map(|d| d.parse::<OutputDisposition>())
+                .transpose()?,
+            None => return Err(ClaimRepositoryError::UnpaidOutputNotFound),
+        };
+        if let Some(current) = current {
+            if !disposition.can_replace(Some(current)) {
+                return Err(ClaimRepositoryError::DispositionConflict(current));
+            }
+        }
+
+        sqlx::query(
+            r#"UPDATE unpaid_outputs
+               SET disposition = $3, disposition_time = $4
+               WHERE tx_id = $1 AND output_index = $2"#,
+        )
+        .bind(outpoint.txid.to_string())
+        .bind(outpoint.vout as i64)
+        .bind(disposition.to_string())
+        .bind(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn reserve_destination_index(
+        &self,
+        descriptor: &str,
+    ) -> Result<u32, ClaimRepositoryError> {
+        let reserved: i64 = sqlx::query_scalar(
+            r#"INSERT INTO claim_destination_descriptors AS d (descriptor, next_index)
+               VALUES ($1, 1)
+               ON CONFLICT (descriptor) DO UPDATE
+               SET next_index = d.next_index + 1
+               RETURNING d.next_index - 1"#,
+        )
+        .bind(descriptor)
+        .fetch_one(&*self.pool)
+        .await?;
+        u32::try_from(reserved).map_err(|e| ClaimRepositoryError::General(Box::new(e)))
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn add_destination_address(
+        &self,
+        address: &DestinationAddress,
+    ) -> Result<(), ClaimRepositoryError> {
+        sqlx::query(
+            r#"INSERT INTO claim_destination_addresses
+               (address, descriptor, derivation_index, creation_time)
+               VALUES ($1, $2, $3, $4)"#,
+        )
+        .bind(address.address.to_string())
+        .bind(&address.descriptor)
+        .bind(address.derivation_index as i64)
+        .bind(address.creation_time.duration_since(UNIX_EPOCH)?.as_secs() as i64)
+        .execute(&*self.pool)
+        .await?;
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn is_destination_address(
+        &self,
+        address: &Address,
+    ) -> Result<bool, ClaimRepositoryError> {
+        let exists: bool = sqlx::query_scalar(
+            r#"SELECT EXISTS (
+                   SELECT 1 FROM claim_destination_addresses WHERE address = $1
+               )"#,
+        )
+        .bind(address.to_string())
+        .fetch_one(&*self.pool)
+        .await?;
+        Ok(exists)
+    }
 }
 
 impl From<bitcoin::address::ParseError> for ClaimRepositoryError {
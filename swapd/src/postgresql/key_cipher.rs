@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN},
+    rand::{SecureRandom, SystemRandom},
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum KeyCipherError {
+    #[error("invalid encryption key: {0}")]
+    InvalidKey(String),
+    #[error("no encryption key with version {0}")]
+    UnknownVersion(u32),
+    #[error("encryption failed")]
+    Encrypt,
+    #[error("decryption failed")]
+    Decrypt,
+}
+
+/// Encrypts private keys at rest with ChaCha20-Poly1305. Every key has a
+/// version, data is always encrypted with the highest version. Older versions
+/// are kept around to decrypt data that wasn't re-encrypted yet. Without any
+/// keys, data is stored in plaintext.
+pub struct KeyCipher {
+    keys: BTreeMap<u32, LessSafeKey>,
+    rng: SystemRandom,
+}
+
+impl KeyCipher {
+    pub fn new(keys: Vec<(u32, [u8; 32])>) -> Result<Self, KeyCipherError> {
+        let mut map = BTreeMap::new();
+        for (version, key) in keys {
+            let key = UnboundKey::new(&CHACHA20_POLY1305, &key)
+                .map_err(|_| KeyCipherError::InvalidKey(format!("version {}", version)))?;
+            if map.insert(version, LessSafeKey::new(key)).is_some() {
+                return Err(KeyCipherError::InvalidKey(format!(
+                    "duplicate version {}",
+                    version
+                )));
+            }
+        }
+
+        Ok(Self {
+            keys: map,
+            rng: SystemRandom::new(),
+        })
+    }
+
+    /// Parses keys formatted as `<version>:<hex encoded 32 byte key>`,
+    /// separated by commas or newlines.
+    pub fn parse(s: &str) -> Result<Self, KeyCipherError> {
+        let mut keys = Vec::new();
+        for entry in s
+            .split([',', '\n'])
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+        {
+            let (version, key) = entry.split_once(':').ok_or(KeyCipherError::InvalidKey(
+                "expected <version>:<key>".into(),
+            ))?;
+            let version: u32 = version
+                .parse()
+                .map_err(|_| KeyCipherError::InvalidKey(format!("invalid version {}", version)))?;
+            let key: [u8; 32] = hex::decode(key)
+                .ok()
+                .and_then(|key| key.try_into().ok())
+                .ok_or(KeyCipherError::InvalidKey(format!(
+                    "version {} is not 32 hex encoded bytes",
+                    version
+                )))?;
+            keys.push((version, key));
+        }
+
+        Self::new(keys)
+    }
+
+    /// The version new data is encrypted with, `None` if data is stored in
+    /// plaintext.
+    pub fn current_version(&self) -> Option<u32> {
+        self.keys.keys().next_back().copied()
+    }
+
+    /// Encrypts `plaintext` with the current key. `aad` is authenticated, but
+    /// not encrypted, and has to be passed again to decrypt. Returns the
+    /// ciphertext and the key version it was encrypted with.
+    pub fn encrypt(
+        &self,
+        plaintext: &[u8],
+        aad: &[u8],
+    ) -> Result<(Vec<u8>, Option<u32>), KeyCipherError> {
+        let (version, key) = match self.keys.iter().next_back() {
+            Some(entry) => entry,
+            None => return Ok((plaintext.to_vec(), None)),
+        };
+
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| KeyCipherError::Encrypt)?;
+        let mut in_out = plaintext.to_vec();
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(aad),
+            &mut in_out,
+        )
+        .map_err(|_| KeyCipherError::Encrypt)?;
+
+        let mut ciphertext = nonce.to_vec();
+        ciphertext.extend(in_out);
+        Ok((ciphertext, Some(*version)))
+    }
+
+    /// Decrypts data encrypted with key `version`, or returns it as is if
+    /// `version` is `None`.
+    pub fn decrypt(
+        &self,
+        ciphertext: &[u8],
+        aad: &[u8],
+        version: Option<u32>,
+    ) -> Result<Vec<u8>, KeyCipherError> {
+        let version = match version {
+            Some(version) => version,
+            None => return Ok(ciphertext.to_vec()),
+        };
+        let key = self
+            .keys
+            .get(&version)
+            .ok_or(KeyCipherError::UnknownVersion(version))?;
+        if ciphertext.len() < NONCE_LEN {
+            return Err(KeyCipherError::Decrypt);
+        }
+
+        let (nonce, ciphertext) = ciphertext.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| KeyCipherError::Decrypt)?;
+        let mut in_out = ciphertext.to_vec();
+        let plaintext = key
+            .open_in_place(nonce, Aad::from(aad), &mut in_out)
+            .map_err(|_| KeyCipherError::Decrypt)?;
+        Ok(plaintext.to_vec())
+    }
+}
+
+impl std::fmt::Debug for KeyCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyCipher")
+            .field("versions", &self.keys.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeyCipher, KeyCipherError};
+
+    const KEY_1: &str = "0101010101010101010101010101010101010101010101010101010101010101";
+    const KEY_2: &str = "0202020202020202020202020202020202020202020202020202020202020202";
+
+    #[test]
+    fn test_roundtrip() {
+        let cipher = KeyCipher::parse(&format!("1:{}", KEY_1)).unwrap();
+        let (ciphertext, version) = cipher.encrypt(b"secret", b"hash").unwrap();
+        assert_eq!(Some(1), version);
+        assert_ne!(b"secret".to_vec(), ciphertext);
+        assert_eq!(
+            b"secret".to_vec(),
+            cipher.decrypt(&ciphertext, b"hash", version).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_wrong_aad_fails() {
+        let cipher = KeyCipher::parse(&format!("1:{}", KEY_1)).unwrap();
+        let (ciphertext, version) = cipher.encrypt(b"secret", b"hash").unwrap();
+        assert!(matches!(
+            cipher.decrypt(&ciphertext, b"other hash", version),
+            Err(KeyCipherError::Decrypt)
+        ));
+    }
+
+    #[test]
+    fn test_encrypts_with_highest_version() {
+        let old = KeyCipher::parse(&format!("1:{}", KEY_1)).unwrap();
+        let (ciphertext, version) = old.encrypt(b"secret", b"hash").unwrap();
+
+        let rotated = KeyCipher::parse(&format!("2:{}\n1:{}", KEY_2, KEY_1)).unwrap();
+        assert_eq!(Some(2), rotated.current_version());
+        assert_eq!(
+            b"secret".to_vec(),
+            rotated.decrypt(&ciphertext, b"hash", version).unwrap()
+        );
+        let (_, version) = rotated.encrypt(b"secret", b"hash").unwrap();
+        assert_eq!(Some(2), version);
+    }
+
+    #[test]
+    fn test_without_keys_is_plaintext() {
+        let cipher = KeyCipher::new(Vec::new()).unwrap();
+        let (ciphertext, version) = cipher.encrypt(b"secret", b"hash").unwrap();
+        assert_eq!(None, version);
+        assert_eq!(b"secret".to_vec(), ciphertext);
+        assert_eq!(
+            b"secret".to_vec(),
+            cipher.decrypt(&ciphertext, b"hash", None).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unknown_version_fails() {
+        let cipher = KeyCipher::parse(&format!("1:{}", KEY_1)).unwrap();
+        assert!(matches!(
+            cipher.decrypt(&[0; 64], b"hash", Some(2)),
+            Err(KeyCipherError::UnknownVersion(2))
+        ));
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_keys() {
+        assert!(KeyCipher::parse("1:abcd").is_err());
+        assert!(KeyCipher::parse(KEY_1).is_err());
+        assert!(KeyCipher::parse(&format!("1:{},1:{}", KEY_1, KEY_2)).is_err());
+    }
+}
@@ -0,0 +1,215 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTimeError, UNIX_EPOCH},
+};
+
+use bitcoin::{address::NetworkUnchecked, Address, Amount, Network, OutPoint, TxOut};
+use futures::TryStreamExt;
+use sqlx::{postgres::PgRow, PgPool, Row};
+use tracing::instrument;
+
+use crate::mempool::{self, MempoolRepositoryError, MempoolTxo};
+
+#[derive(Debug)]
+pub struct MempoolRepository {
+    network: Network,
+    pool: Arc<PgPool>,
+}
+
+impl MempoolRepository {
+    pub fn new(pool: Arc<PgPool>, network: Network) -> Self {
+        Self { pool, network }
+    }
+
+    fn map_txo(&self, row: &PgRow) -> Result<MempoolTxo, MempoolRepositoryError> {
+        let tx_id: String = row.try_get("tx_id")?;
+        let output_index: i64 = row.try_get("output_index")?;
+        let address: String = row.try_get("address")?;
+        let amount: i64 = row.try_get("amount")?;
+        let replaceable: bool = row.try_get("replaceable")?;
+        let first_seen: i64 = row.try_get("first_seen")?;
+        let address = address
+            .parse::<Address<NetworkUnchecked>>()?
+            .require_network(self.network)?;
+        let first_seen = UNIX_EPOCH
+            .checked_add(Duration::from_secs(first_seen as u64))
+            .ok_or(MempoolRepositoryError::General("invalid timestamp".into()))?;
+        Ok(MempoolTxo {
+            outpoint: OutPoint::new(tx_id.parse()?, output_index as u32),
+            tx_out: TxOut {
+                value: Amount::from_sat(amount as u64),
+                script_pubkey: address.script_pubkey(),
+            },
+            address,
+            replaceable,
+            first_seen,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl mempool::MempoolRepository for MempoolRepository {
+    #[instrument(level = "trace", skip(self))]
+    async fn add_txos(&self, txos: &[MempoolTxo]) -> Result<Vec<OutPoint>, MempoolRepositoryError> {
+        let tx_ids: Vec<_> = txos.iter().map(|t| t.outpoint.txid.to_string()).collect();
+        let output_indices: Vec<_> = txos.iter().map(|t| t.outpoint.vout as i64).collect();
+        let addresses: Vec<_> = txos.iter().map(|t| t.address.to_string()).collect();
+        let amounts: Vec<_> = txos
+            .iter()
+            .map(|t| t.tx_out.value.to_sat() as i64)
+            .collect();
+        let replaceables: Vec<_> = txos.iter().map(|t| t.replaceable).collect();
+        let first_seens = txos
+            .iter()
+            .map(|t| Ok(t.first_seen.duration_since(UNIX_EPOCH)?.as_secs() as i64))
+            .collect::<Result<Vec<_>, SystemTimeError>>()?;
+        let mut rows = sqlx::query(
+            r#"INSERT INTO mempool_tx_outputs (
+                   tx_id
+               ,   output_index
+               ,   address
+               ,   amount
+               ,   replaceable
+               ,   first_seen)
+               SELECT t.tx_id
+               ,      t.output_index
+               ,      t.address
+               ,      t.amount
+               ,      t.replaceable
+               ,      t.first_seen
+               FROM UNNEST(
+                   $1::text[]
+               ,   $2::bigint[]
+               ,   $3::text[]
+               ,   $4::bigint[]
+               ,   $5::boolean[]
+               ,   $6::bigint[]
+               ) AS t(tx_id, output_index, address, amount, replaceable, first_seen)
+               ON CONFLICT DO NOTHING
+               RETURNING tx_id, output_index"#,
+        )
+        .bind(&tx_ids)
+        .bind(&output_indices)
+        .bind(&addresses)
+        .bind(&amounts)
+        .bind(&replaceables)
+        .bind(&first_seens)
+        .fetch(&*self.pool);
+
+        let mut result = Vec::new();
+        while let Some(row) = rows.try_next().await? {
+            let tx_id: String = row.try_get("tx_id")?;
+            let output_index: i64 = row.try_get("output_index")?;
+            result.push(OutPoint::new(tx_id.parse()?, output_index as u32));
+        }
+
+        Ok(result)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn get_txos(&self) -> Result<Vec<MempoolTxo>, MempoolRepositoryError> {
+        let mut rows = sqlx::query(
+            r#"SELECT tx_id
+               ,      output_index
+               ,      address
+               ,      amount
+               ,      replaceable
+               ,      first_seen
+               FROM mempool_tx_outputs
+               ORDER BY first_seen, tx_id, output_index"#,
+        )
+        .fetch(&*self.pool);
+
+        let mut result = Vec::new();
+        while let Some(row) = rows.try_next().await? {
+            result.push(self.map_txo(&row)?);
+        }
+        Ok(result)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn get_txos_for_address(
+        &self,
+        address: &Address,
+    ) -> Result<Vec<MempoolTxo>, MempoolRepositoryError> {
+        let mut rows = sqlx::query(
+            r#"SELECT tx_id
+               ,      output_index
+               ,      address
+               ,      amount
+               ,      replaceable
+               ,      first_seen
+               FROM mempool_tx_outputs
+               WHERE address = $1
+               ORDER BY first_seen, tx_id, output_index"#,
+        )
+        .bind(address.to_string())
+        .fetch(&*self.pool);
+
+        let mut result = Vec::new();
+        while let Some(row) = rows.try_next().await? {
+            result.push(self.map_txo(&row)?);
+        }
+        Ok(result)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn get_paid_amount(&self) -> Result<u64, MempoolRepositoryError> {
+        let amount: i64 = sqlx::query(
+            r#"SELECT COALESCE(SUM(o.amount), 0)::BIGINT
+               FROM mempool_tx_outputs o
+               WHERE EXISTS (
+                   SELECT 1
+                   FROM payment_attempt_tx_outputs patx
+                   INNER JOIN payment_attempts pa ON patx.payment_attempt_id = pa.id
+                   WHERE patx.tx_id = o.tx_id
+                     AND patx.output_index = o.output_index
+                     AND (pa.success IS NULL OR pa.success = true)
+               )"#,
+        )
+        .fetch_one(&*self.pool)
+        .await?
+        .try_get(0)?;
+        Ok(amount as u64)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn remove_txos(&self, outpoints: &[OutPoint]) -> Result<(), MempoolRepositoryError> {
+        let tx_ids: Vec<_> = outpoints.iter().map(|o| o.txid.to_string()).collect();
+        let output_indices: Vec<_> = outpoints.iter().map(|o| o.vout as i64).collect();
+        sqlx::query(
+            r#"DELETE FROM mempool_tx_outputs o
+               USING UNNEST($1::text[], $2::bigint[]) AS t(tx_id, output_index)
+               WHERE o.tx_id = t.tx_id AND o.output_index = t.output_index"#,
+        )
+        .bind(&tx_ids)
+        .bind(&output_indices)
+        .execute(&*self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+impl From<bitcoin::address::ParseError> for MempoolRepositoryError {
+    fn from(value: bitcoin::address::ParseError) -> Self {
+        MempoolRepositoryError::General(Box::new(value))
+    }
+}
+
+impl From<bitcoin::hashes::hex::HexToArrayError> for MempoolRepositoryError {
+    fn from(value: bitcoin::hashes::hex::HexToArrayError) -> Self {
+        MempoolRepositoryError::General(Box::new(value))
+    }
+}
+
+impl From<sqlx::Error> for MempoolRepositoryError {
+    fn from(value: sqlx::Error) -> Self {
+        MempoolRepositoryError::General(Box::new(value))
+    }
+}
+
+impl From<SystemTimeError> for MempoolRepositoryError {
+    fn from(value: SystemTimeError) -> Self {
+        MempoolRepositoryError::General(Box::new(value))
+    }
+}
@@ -0,0 +1 @@
+ALTER TABLE payment_attempts DROP COLUMN fee_sat;
@@ -0,0 +1 @@
+ALTER TABLE payment_attempts ADD COLUMN fee_sat BIGINT NOT NULL DEFAULT 0;
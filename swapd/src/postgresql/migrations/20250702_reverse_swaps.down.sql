@@ -0,0 +1,5 @@
+DROP INDEX reverse_swap_refunds_reverse_swap_payment_hash_idx;
+DROP TABLE reverse_swap_refunds;
+DROP INDEX reverse_swaps_status_idx;
+DROP INDEX reverse_swaps_address_idx;
+DROP TABLE reverse_swaps;
@@ -0,0 +1,36 @@
+/*
+    reverse swaps
+*/
+
+CREATE TABLE reverse_swaps (
+    payment_hash BYTEA NOT NULL PRIMARY KEY,
+    address VARCHAR NOT NULL,
+    amount_sat BIGINT NOT NULL,
+    claim_pubkey BYTEA NOT NULL,
+    claim_script BYTEA NOT NULL,
+    creation_time BIGINT NOT NULL,
+    funding_tx_id VARCHAR NULL,
+    invoice_amount_msat BIGINT NOT NULL,
+    lock_height BIGINT NOT NULL,
+    payment_request VARCHAR NOT NULL,
+    preimage BYTEA NULL,
+    refund_privkey BYTEA NOT NULL,
+    refund_pubkey BYTEA NOT NULL,
+    refund_script BYTEA NOT NULL,
+    status VARCHAR NOT NULL
+);
+
+CREATE UNIQUE INDEX reverse_swaps_address_idx ON reverse_swaps (address);
+CREATE INDEX reverse_swaps_status_idx ON reverse_swaps (status);
+
+CREATE TABLE reverse_swap_refunds (
+    tx_id VARCHAR PRIMARY KEY,
+    reverse_swap_payment_hash BYTEA NOT NULL REFERENCES reverse_swaps,
+    creation_time BIGINT NOT NULL,
+    tx BYTEA NOT NULL,
+    destination_address VARCHAR NOT NULL,
+    fee_per_kw BIGINT NOT NULL
+);
+
+CREATE INDEX reverse_swap_refunds_reverse_swap_payment_hash_idx
+ON reverse_swap_refunds (reverse_swap_payment_hash);
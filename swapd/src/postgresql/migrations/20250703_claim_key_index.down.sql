@@ -0,0 +1,7 @@
+DROP INDEX reverse_swaps_refund_key_index_idx;
+ALTER TABLE reverse_swaps DROP COLUMN refund_key_index;
+DROP SEQUENCE reverse_swap_refund_key_index;
+
+DROP INDEX swaps_claim_key_index_idx;
+ALTER TABLE swaps DROP COLUMN claim_key_index;
+DROP SEQUENCE swap_claim_key_index;
@@ -0,0 +1,8 @@
+CREATE SEQUENCE swap_claim_key_index AS INTEGER MINVALUE 0 START WITH 0;
+ALTER TABLE swaps ADD COLUMN claim_key_index INTEGER NULL;
+CREATE UNIQUE INDEX swaps_claim_key_index_idx ON swaps (claim_key_index);
+
+CREATE SEQUENCE reverse_swap_refund_key_index AS INTEGER MINVALUE 0 START WITH 0;
+-- NULL for random refund keys that cannot be derived from a master key.
+ALTER TABLE reverse_swaps ADD COLUMN refund_key_index INTEGER NULL;
+CREATE UNIQUE INDEX reverse_swaps_refund_key_index_idx ON reverse_swaps (refund_key_index);
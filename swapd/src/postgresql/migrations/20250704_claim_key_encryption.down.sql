@@ -0,0 +1,2 @@
+ALTER TABLE reverse_swaps DROP COLUMN refund_privkey_key_version;
+ALTER TABLE swaps DROP COLUMN claim_privkey_key_version;
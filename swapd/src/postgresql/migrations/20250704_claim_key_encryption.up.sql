@@ -0,0 +1,4 @@
+-- NULL means the claim private key is stored in plaintext.
+ALTER TABLE swaps ADD COLUMN claim_privkey_key_version INTEGER NULL;
+-- NULL means the refund private key is stored in plaintext.
+ALTER TABLE reverse_swaps ADD COLUMN refund_privkey_key_version INTEGER NULL;
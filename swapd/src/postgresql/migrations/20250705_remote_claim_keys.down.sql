@@ -0,0 +1,11 @@
+DROP INDEX reverse_swaps_remote_refund_key_idx;
+DROP INDEX reverse_swaps_refund_key_index_idx;
+CREATE UNIQUE INDEX reverse_swaps_refund_key_index_idx ON reverse_swaps (refund_key_index);
+ALTER TABLE reverse_swaps DROP COLUMN refund_key_family;
+ALTER TABLE reverse_swaps ALTER COLUMN refund_privkey SET NOT NULL;
+
+DROP INDEX swaps_remote_claim_key_idx;
+DROP INDEX swaps_claim_key_index_idx;
+CREATE UNIQUE INDEX swaps_claim_key_index_idx ON swaps (claim_key_index);
+ALTER TABLE swaps DROP COLUMN claim_key_family;
+ALTER TABLE swaps ALTER COLUMN claim_privkey SET NOT NULL;
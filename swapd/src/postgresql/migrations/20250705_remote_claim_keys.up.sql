@@ -0,0 +1,18 @@
+-- Claim keys held by lnd have no private key, only a key family and index.
+ALTER TABLE swaps ALTER COLUMN claim_privkey DROP NOT NULL;
+ALTER TABLE swaps ADD COLUMN claim_key_family INTEGER NULL;
+DROP INDEX swaps_claim_key_index_idx;
+CREATE UNIQUE INDEX swaps_claim_key_index_idx ON swaps (claim_key_index)
+    WHERE claim_key_family IS NULL;
+CREATE UNIQUE INDEX swaps_remote_claim_key_idx ON swaps (claim_key_family, claim_key_index)
+    WHERE claim_key_family IS NOT NULL;
+
+-- The same for reverse swap refund keys.
+ALTER TABLE reverse_swaps ALTER COLUMN refund_privkey DROP NOT NULL;
+ALTER TABLE reverse_swaps ADD COLUMN refund_key_family INTEGER NULL;
+DROP INDEX reverse_swaps_refund_key_index_idx;
+CREATE UNIQUE INDEX reverse_swaps_refund_key_index_idx ON reverse_swaps (refund_key_index)
+    WHERE refund_key_family IS NULL;
+CREATE UNIQUE INDEX reverse_swaps_remote_refund_key_idx
+    ON reverse_swaps (refund_key_family, refund_key_index)
+    WHERE refund_key_family IS NOT NULL;
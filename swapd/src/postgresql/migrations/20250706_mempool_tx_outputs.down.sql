@@ -0,0 +1 @@
+DROP TABLE mempool_tx_outputs;
@@ -0,0 +1,13 @@
+-- Unconfirmed outputs to watched addresses. Rows are removed once the
+-- transaction leaves the mempool, confirmed or not.
+CREATE TABLE mempool_tx_outputs (
+    tx_id VARCHAR NOT NULL,
+    output_index BIGINT NOT NULL,
+    address VARCHAR NOT NULL,
+    amount BIGINT NOT NULL,
+    replaceable BOOLEAN NOT NULL,
+    first_seen BIGINT NOT NULL,
+    PRIMARY KEY (tx_id, output_index)
+);
+
+CREATE INDEX mempool_tx_outputs_address_idx ON mempool_tx_outputs(address);
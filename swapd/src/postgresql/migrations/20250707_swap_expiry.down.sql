@@ -0,0 +1,3 @@
+DROP INDEX swaps_unexpired_idx;
+ALTER TABLE swaps DROP COLUMN expiry_time;
+ALTER TABLE swaps DROP COLUMN creation_height;
@@ -0,0 +1,6 @@
+-- The block height at creation, so unfunded swaps can be expired once their
+-- lock time passed. NULL for swaps created before it was recorded.
+ALTER TABLE swaps ADD COLUMN creation_height BIGINT NULL;
+-- Set when an unfunded swap expired. Expired swaps are no longer watched.
+ALTER TABLE swaps ADD COLUMN expiry_time BIGINT NULL;
+CREATE INDEX swaps_unexpired_idx ON swaps (creation_time) WHERE expiry_time IS NULL;
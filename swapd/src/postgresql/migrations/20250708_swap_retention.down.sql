@@ -0,0 +1,17 @@
+INSERT INTO swaps SELECT * FROM swaps_archive;
+INSERT INTO payment_attempts SELECT * FROM payment_attempts_archive;
+INSERT INTO payment_attempt_tx_outputs SELECT * FROM payment_attempt_tx_outputs_archive;
+INSERT INTO tx_outputs SELECT * FROM tx_outputs_archive;
+INSERT INTO tx_inputs SELECT * FROM tx_inputs_archive;
+INSERT INTO claims SELECT * FROM claims_archive;
+INSERT INTO claim_inputs SELECT * FROM claim_inputs_archive;
+DROP TABLE claim_inputs_archive;
+DROP TABLE claims_archive;
+DROP TABLE tx_inputs_archive;
+DROP TABLE tx_outputs_archive;
+DROP TABLE payment_attempt_tx_outputs_archive;
+DROP TABLE payment_attempts_archive;
+DROP TABLE swaps_archive;
+DROP INDEX swaps_completion_time_idx;
+ALTER TABLE swaps DROP COLUMN completion_reason;
+ALTER TABLE swaps DROP COLUMN completion_time;
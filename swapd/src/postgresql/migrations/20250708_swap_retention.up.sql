@@ -0,0 +1,40 @@
+-- Set once a swap is done: all its outputs are claimed or refunded with
+-- enough confirmations, or it expired unfunded. Completed swaps are no longer
+-- watched.
+ALTER TABLE swaps ADD COLUMN completion_time BIGINT NULL;
+ALTER TABLE swaps ADD COLUMN completion_reason VARCHAR NULL;
+CREATE INDEX swaps_completion_time_idx ON swaps (completion_time)
+    WHERE completion_time IS NOT NULL;
+
+/*
+    archive
+
+    Completed swaps are moved here after the retention period, together with
+    their payment attempts, chain outputs and claims, so the hot tables stay
+    small. The archive tables mirror the columns of the hot tables, so columns
+    added to a hot table must be added to its archive table as well.
+*/
+CREATE TABLE swaps_archive (LIKE swaps);
+CREATE UNIQUE INDEX swaps_archive_payment_hash_idx ON swaps_archive (payment_hash);
+CREATE INDEX swaps_archive_address_idx ON swaps_archive (address);
+
+CREATE TABLE payment_attempts_archive (LIKE payment_attempts);
+CREATE INDEX payment_attempts_archive_swap_payment_hash_idx
+ON payment_attempts_archive (swap_payment_hash);
+
+CREATE TABLE payment_attempt_tx_outputs_archive (LIKE payment_attempt_tx_outputs);
+CREATE INDEX payment_attempt_tx_outputs_archive_payment_attempt_id_idx
+ON payment_attempt_tx_outputs_archive (payment_attempt_id);
+
+CREATE TABLE tx_outputs_archive (LIKE tx_outputs);
+CREATE INDEX tx_outputs_archive_address_idx ON tx_outputs_archive (address);
+
+CREATE TABLE tx_inputs_archive (LIKE tx_inputs);
+CREATE INDEX tx_inputs_archive_tx_id_output_index_idx
+ON tx_inputs_archive (tx_id, output_index);
+
+CREATE TABLE claims_archive (LIKE claims);
+CREATE UNIQUE INDEX claims_archive_tx_id_idx ON claims_archive (tx_id);
+
+CREATE TABLE claim_inputs_archive (LIKE claim_inputs);
+CREATE INDEX claim_inputs_archive_claim_tx_id_idx ON claim_inputs_archive (claim_tx_id);
@@ -0,0 +1,2 @@
+DROP TABLE claim_cpfps_archive;
+DROP TABLE claim_cpfps;
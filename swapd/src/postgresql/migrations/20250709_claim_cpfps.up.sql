@@ -0,0 +1,22 @@
+/*
+    claim_cpfps
+
+    Child transactions from the node wallet spending the output of a claim
+    transaction, to bump the fee of a claim that could not be replaced. A claim
+    bumped again gets a child spending the output of the previous child.
+*/
+CREATE TABLE claim_cpfps (
+    tx_id VARCHAR NOT NULL PRIMARY KEY,
+    claim_tx_id VARCHAR NOT NULL REFERENCES claims,
+    creation_time BIGINT NOT NULL,
+    destination_address VARCHAR NOT NULL,
+    fee_per_kw BIGINT NOT NULL,
+    package_fee_per_kw BIGINT NOT NULL,
+    weight BIGINT NOT NULL,
+    descendants BIGINT NOT NULL,
+    descendants_weight BIGINT NOT NULL
+);
+CREATE INDEX claim_cpfps_claim_tx_id_idx ON claim_cpfps (claim_tx_id);
+
+CREATE TABLE claim_cpfps_archive (LIKE claim_cpfps);
+CREATE INDEX claim_cpfps_archive_claim_tx_id_idx ON claim_cpfps_archive (claim_tx_id);
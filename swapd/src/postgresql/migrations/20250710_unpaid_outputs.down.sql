@@ -0,0 +1,2 @@
+DROP TABLE unpaid_outputs_archive;
+DROP TABLE unpaid_outputs;
@@ -0,0 +1,23 @@
+/*
+    unpaid_outputs
+
+    Outputs to the address of a paid swap that were not paid over lightning,
+    because they were sent after the swap was paid. Their disposition decides
+    what happens to them: donated outputs are claimed, abandoned outputs are
+    left for the user to refund and refunded outputs got a cooperative refund.
+    Undecided outputs have a NULL disposition.
+*/
+CREATE TABLE unpaid_outputs (
+    tx_id VARCHAR NOT NULL,
+    output_index BIGINT NOT NULL,
+    swap_payment_hash BYTEA NOT NULL REFERENCES swaps(payment_hash),
+    creation_time BIGINT NOT NULL,
+    disposition VARCHAR NULL,
+    disposition_time BIGINT NULL,
+    PRIMARY KEY (tx_id, output_index)
+);
+CREATE INDEX unpaid_outputs_swap_payment_hash_idx ON unpaid_outputs (swap_payment_hash);
+
+CREATE TABLE unpaid_outputs_archive (LIKE unpaid_outputs);
+CREATE INDEX unpaid_outputs_archive_swap_payment_hash_idx
+ON unpaid_outputs_archive (swap_payment_hash);
@@ -0,0 +1,2 @@
+DROP TABLE claim_destination_descriptors;
+DROP TABLE claim_destination_addresses;
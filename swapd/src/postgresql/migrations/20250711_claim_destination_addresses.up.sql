@@ -0,0 +1,26 @@
+/*
+    claim_destination_addresses
+
+    Claim addresses derived from a watch-only descriptor, at indices reserved
+    in claim_destination_descriptors, so addresses are never reused. Addresses
+    are kept after their swaps are archived.
+*/
+CREATE TABLE claim_destination_addresses (
+    address VARCHAR NOT NULL PRIMARY KEY,
+    descriptor VARCHAR NOT NULL,
+    derivation_index BIGINT NOT NULL,
+    creation_time BIGINT NOT NULL,
+    UNIQUE (descriptor, derivation_index)
+);
+
+/*
+    claim_destination_descriptors
+
+    The next derivation index per claim descriptor. Indices are reserved by
+    incrementing next_index in a single statement, so concurrent claims never
+    derive the same address.
+*/
+CREATE TABLE claim_destination_descriptors (
+    descriptor VARCHAR NOT NULL PRIMARY KEY,
+    next_index BIGINT NOT NULL
+);
@@ -1,13 +1,21 @@
 mod chain_filter_repository;
 mod chain_repository;
 mod claim_repository;
+mod key_cipher;
 mod lnd_repository;
+mod mempool_repository;
+mod retention_repository;
+mod reverse_swap_repository;
 mod swap_repository;
 
 pub use chain_filter_repository::ChainFilterRepository;
 pub use chain_repository::ChainRepository;
 pub use claim_repository::ClaimRepository;
+pub use key_cipher::KeyCipher;
 pub use lnd_repository::LndRepository;
+pub use mempool_repository::MempoolRepository;
+pub use retention_repository::RetentionRepository;
+pub use reverse_swap_repository::ReverseSwapRepository;
 use sqlx::{Pool, Postgres};
 pub use swap_repository::SwapRepository;
 
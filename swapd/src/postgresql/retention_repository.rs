@@ -0,0 +1,331 @@
+use std::{
+    sync::Arc,
+    time::{SystemTime, SystemTimeError, UNIX_EPOCH},
+};
+
+use bitcoin::{
+    address::NetworkUnchecked,
+    hashes::{sha256, Hash},
+    Address, Network,
+};
+use sqlx::{PgPool, Row};
+use tracing::instrument;
+
+use crate::retention::{CompletedSwap, CompletionReason, RetentionRepositoryError};
+
+#[derive(Debug)]
+pub struct RetentionRepository {
+    network: Network,
+    pool: Arc<PgPool>,
+}
+
+impl RetentionRepository {
+    pub fn new(pool: Arc<PgPool>, network: Network) -> Self {
+        Self { network, pool }
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::retention::RetentionRepository for RetentionRepository {
+    #[instrument(level = "trace", skip(self))]
+    async fn complete_swaps(
+        &self,
+        max_spend_height: u64,
+    ) -> Result<Vec<CompletedSwap>, RetentionRepositoryError> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        // A funded swap is complete when every confirmed output is spent by a
+        // transaction confirmed at or below the max spend height. Outputs
+        // spent by one of our claims make it claimed, otherwise the user
+        // refunded. Swaps with outputs in the mempool or with a lock for an
+        // ongoing payment or refund are left alone.
+        let rows = sqlx::query(
+            r#"WITH completed AS (
+                   UPDATE swaps s
+                   SET completion_time = $1
+                   ,   completion_reason = c.reason
+                   FROM (
+                       SELECT s2.payment_hash
+                       ,      CASE
+                                  WHEN s2.expiry_time IS NOT NULL THEN 'expired'
+                                  WHEN EXISTS (
+                                      SELECT 1
+                                      FROM tx_outputs o
+                                      INNER JOIN tx_inputs i
+                                          ON i.tx_id = o.tx_id
+                                          AND i.output_index = o.output_index
+                                      INNER JOIN claim_inputs ci
+                                          ON ci.claim_tx_id = i.spending_tx_id
+                                          AND ci.tx_id = o.tx_id
+                                          AND ci.output_index = o.output_index
+                                      WHERE o.address = s2.address
+                                  ) THEN 'claimed'
+                                  ELSE 'refunded'
+                              END AS reason
+                       FROM swaps s2
+                       WHERE s2.completion_time IS NULL
+                         AND (s2.expiry_time IS NOT NULL OR (
+                             EXISTS (
+                                 SELECT 1
+                                 FROM tx_outputs o
+                                 INNER JOIN tx_blocks tb ON tb.tx_id = o.tx_id
+                                 WHERE o.address = s2.address
+                             )
+                             AND NOT EXISTS (
+                                 SELECT 1
+                                 FROM tx_outputs o
+                                 INNER JOIN tx_blocks tb ON tb.tx_id = o.tx_id
+                                 WHERE o.address = s2.address
+                                   AND NOT EXISTS (
+                                       SELECT 1
+                                       FROM tx_inputs i
+                                       INNER JOIN tx_blocks itb ON itb.tx_id = i.spending_tx_id
+                                       INNER JOIN blocks ib ON ib.block_hash = itb.block_hash
+                                       WHERE i.tx_id = o.tx_id
+                                         AND i.output_index = o.output_index
+                                         AND ib.height <= $2
+                                   )
+                             )
+                             AND NOT EXISTS (
+                                 SELECT 1
+                                 FROM mempool_tx_outputs m
+                                 WHERE m.address = s2.address
+                             )
+                             AND NOT EXISTS (
+                                 SELECT 1
+                                 FROM swap_locks l
+                                 WHERE l.swap_payment_hash = s2.payment_hash
+                             )
+                         ))
+                   ) c
+                   WHERE s.payment_hash = c.payment_hash
+                   RETURNING s.address, s.payment_hash, s.completion_reason
+               ), unwatched AS (
+                   DELETE FROM watch_addresses w
+                   USING completed c
+                   WHERE w.address = c.address
+               )
+               SELECT address, payment_hash, completion_reason FROM completed"#,
+        )
+        .bind(now)
+        .bind(max_spend_height as i64)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            let address: String = row.try_get("address")?;
+            let payment_hash: Vec<u8> = row.try_get("payment_hash")?;
+            let reason: String = row.try_get("completion_reason")?;
+            result.push(CompletedSwap {
+                address: address
+                    .parse::<Address<NetworkUnchecked>>()?
+                    .require_network(self.network)?,
+                hash: sha256::Hash::from_slice(&payment_hash)?,
+                reason: reason.parse::<CompletionReason>()?,
+            });
+        }
+
+        Ok(result)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn archive_swaps(
+        &self,
+        completed_before: SystemTime,
+        batch_size: u32,
+    ) -> Result<u64, RetentionRepositoryError> {
+        let completed_before = completed_before.duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        let mut tx = self.pool.begin().await?;
+        let rows = sqlx::query(
+            r#"SELECT payment_hash
+               ,      address
+               FROM swaps
+               WHERE completion_time < $1
+               ORDER BY completion_time
+               LIMIT $2
+               FOR UPDATE SKIP LOCKED"#,
+        )
+        .bind(completed_before)
+        .bind(batch_size as i64)
+        .fetch_all(&mut *tx)
+        .await?;
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        let mut hashes: Vec<Vec<u8>> = Vec::new();
+        let mut addresses: Vec<String> = Vec::new();
+        for row in &rows {
+            hashes.push(row.try_get("payment_hash")?);
+            addresses.push(row.try_get("address")?);
+        }
+
+        sqlx::query(
+            r#"WITH moved AS (
+                   DELETE FROM payment_attempt_tx_outputs pto
+                   USING payment_attempts pa
+                   WHERE pto.payment_attempt_id = pa.id
+                     AND pa.swap_payment_hash = ANY($1)
+                   RETURNING pto.*
+               )
+               INSERT INTO payment_attempt_tx_outputs_archive
+               SELECT * FROM moved"#,
+        )
+        .bind(&hashes)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"WITH moved AS (
+                   DELETE FROM payment_attempts
+                   WHERE swap_payment_hash = ANY($1)
+                   RETURNING *
+               )
+               INSERT INTO payment_attempts_archive
+               SELECT * FROM moved"#,
+        )
+        .bind(&hashes)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"WITH moved AS (
+                   DELETE FROM unpaid_outputs
+                   WHERE swap_payment_hash = ANY($1)
+                   RETURNING *
+               )
+               INSERT INTO unpaid_outputs_archive
+               SELECT * FROM moved"#,
+        )
+        .bind(&hashes)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM swap_locks WHERE swap_payment_hash = ANY($1)")
+            .bind(&hashes)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"WITH moved AS (
+                   DELETE FROM swaps
+                   WHERE payment_hash = ANY($1)
+                   RETURNING *
+               )
+               INSERT INTO swaps_archive
+               SELECT * FROM moved"#,
+        )
+        .bind(&hashes)
+        .execute(&mut *tx)
+        .await?;
+
+        // Claims spending the archived outputs are archived as a whole. The
+        // outputs are spent with enough confirmations, so these claims are
+        // either confirmed or conflicted and need no more attention.
+        let claim_tx_ids: Vec<String> = sqlx::query_scalar(
+            r#"SELECT DISTINCT ci.claim_tx_id
+               FROM claim_inputs ci
+               INNER JOIN tx_outputs o
+                   ON ci.tx_id = o.tx_id AND ci.output_index = o.output_index
+               WHERE o.address = ANY($1)"#,
+        )
+        .bind(&addresses)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"WITH moved AS (
+                   DELETE FROM claim_inputs
+                   WHERE claim_tx_id = ANY($1)
+                   RETURNING *
+               )
+               INSERT INTO claim_inputs_archive
+               SELECT * FROM moved"#,
+        )
+        .bind(&claim_tx_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"WITH moved AS (
+                   DELETE FROM claim_cpfps
+                   WHERE claim_tx_id = ANY($1)
+                   RETURNING *
+               )
+               INSERT INTO claim_cpfps_archive
+               SELECT * FROM moved"#,
+        )
+        .bind(&claim_tx_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"WITH moved AS (
+                   DELETE FROM claims
+                   WHERE tx_id = ANY($1)
+                   RETURNING *
+               )
+               INSERT INTO claims_archive
+               SELECT * FROM moved"#,
+        )
+        .bind(&claim_tx_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"WITH moved AS (
+                   DELETE FROM tx_inputs i
+                   USING tx_outputs o
+                   WHERE i.tx_id = o.tx_id
+                     AND i.output_index = o.output_index
+                     AND o.address = ANY($1)
+                   RETURNING i.*
+               )
+               INSERT INTO tx_inputs_archive
+               SELECT * FROM moved"#,
+        )
+        .bind(&addresses)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"WITH moved AS (
+                   DELETE FROM tx_outputs
+                   WHERE address = ANY($1)
+                   RETURNING *
+               )
+               INSERT INTO tx_outputs_archive
+               SELECT * FROM moved"#,
+        )
+        .bind(&addresses)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(rows.len() as u64)
+    }
+}
+
+impl From<sqlx::Error> for RetentionRepositoryError {
+    fn from(value: sqlx::Error) -> Self {
+        RetentionRepositoryError::General(Box::new(value))
+    }
+}
+
+impl From<SystemTimeError> for RetentionRepositoryError {
+    fn from(value: SystemTimeError) -> Self {
+        RetentionRepositoryError::General(Box::new(value))
+    }
+}
+
+impl From<bitcoin::address::ParseError> for RetentionRepositoryError {
+    fn from(value: bitcoin::address::ParseError) -> Self {
+        RetentionRepositoryError::General(Box::new(value))
+    }
+}
+
+impl From<bitcoin::hashes::FromSliceError> for RetentionRepositoryError {
+    fn from(value: bitcoin::hashes::FromSliceError) -> Self {
+        RetentionRepositoryError::General(Box::new(value))
+    }
+}
@@ -0,0 +1,475 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bitcoin::{
+    address::NetworkUnchecked,
+    consensus::{Decodable, Encodable},
+    hashes::{sha256, Hash},
+    secp256k1::{PublicKey, SecretKey},
+    Address, Network, ScriptBuf, Transaction, Txid,
+};
+use futures::TryStreamExt;
+use sqlx::{postgres::PgRow, PgPool, Row};
+use tracing::instrument;
+
+use super::key_cipher::{KeyCipher, KeyCipherError};
+use crate::{
+    reverse::{ReverseSwapRefund, ReverseSwapRepositoryError, ReverseSwapState, ReverseSwapStatus},
+    swap::{ClaimKey, KeyLocator, ReverseSwap, ReverseSwapPrivateData, ReverseSwapPublicData},
+};
+
+#[derive(Debug)]
+pub struct ReverseSwapRepository {
+    network: Network,
+    pool: Arc<PgPool>,
+    key_cipher: Arc<KeyCipher>,
+}
+
+impl ReverseSwapRepository {
+    pub fn new(pool: Arc<PgPool>, network: Network, key_cipher: Arc<KeyCipher>) -> Self {
+        Self {
+            pool,
+            network,
+            key_cipher,
+        }
+    }
+
+    fn map_reverse_swap_state(
+        &self,
+        row: &PgRow,
+    ) -> Result<ReverseSwapState, ReverseSwapRepositoryError> {
+        let address: &str = row.try_get("address")?;
+        let amount_sat: i64 = row.try_get("amount_sat")?;
+        let claim_pubkey: Vec<u8> = row.try_get("claim_pubkey")?;
+        let claim_script: Vec<u8> = row.try_get("claim_script")?;
+        let creation_time: i64 = row.try_get("creation_time")?;
+        let funding_tx_id: Option<&str> = row.try_get("funding_tx_id")?;
+        let invoice_amount_msat: i64 = row.try_get("invoice_amount_msat")?;
+        let lock_height: i64 = row.try_get("lock_height")?;
+        let payment_hash: Vec<u8> = row.try_get("payment_hash")?;
+        let payment_request: String = row.try_get("payment_request")?;
+        let preimage: Option<Vec<u8>> = row.try_get("preimage")?;
+        let refund_key_family: Option<i32> = row.try_get("refund_key_family")?;
+        let refund_key_index: Option<i32> = row.try_get("refund_key_index")?;
+        let refund_privkey: Option<Vec<u8>> = row.try_get("refund_privkey")?;
+        let refund_privkey_key_version: Option<i32> = row.try_get("refund_privkey_key_version")?;
+        let refund_pubkey: Vec<u8> = row.try_get("refund_pubkey")?;
+        let refund_script: Vec<u8> = row.try_get("refund_script")?;
+        let status: &str = row.try_get("status")?;
+
+        let creation_time = SystemTime::UNIX_EPOCH
+            .checked_add(Duration::from_secs(creation_time as u64))
+            .ok_or(ReverseSwapRepositoryError::InvalidTimestamp)?;
+        let address = address
+            .parse::<Address<NetworkUnchecked>>()?
+            .require_network(self.network)?;
+        let refund_key = match (refund_key_family, refund_privkey) {
+            (Some(family), _) => ClaimKey::Remote(KeyLocator {
+                family: family as u32,
+                index: refund_key_index.ok_or(ReverseSwapRepositoryError::General(
+                    "missing refund key index".into(),
+                ))? as u32,
+            }),
+            (None, Some(refund_privkey)) => {
+                let refund_privkey = self.key_cipher.decrypt(
+                    &refund_privkey,
+                    &payment_hash,
+                    refund_privkey_key_version.map(|v| v as u32),
+                )?;
+                ClaimKey::Local {
+                    privkey: SecretKey::from_slice(&refund_privkey)?,
+                    index: refund_key_index.map(|i| i as u32),
+                }
+            }
+            (None, None) => {
+                return Err(ReverseSwapRepositoryError::General(
+                    "missing refund key".into(),
+                ))
+            }
+        };
+        Ok(ReverseSwapState {
+            reverse_swap: ReverseSwap {
+                creation_time,
+                public: ReverseSwapPublicData {
+                    address,
+                    claim_pubkey: PublicKey::from_slice(&claim_pubkey)?,
+                    claim_script: ScriptBuf::from_bytes(claim_script),
+                    hash: sha256::Hash::from_slice(&payment_hash)?,
+                    lock_height: lock_height as u32,
+                    refund_pubkey: PublicKey::from_slice(&refund_pubkey)?,
+                    refund_script: ScriptBuf::from_bytes(refund_script),
+                },
+                private: ReverseSwapPrivateData { refund_key },
+            },
+            amount_sat: amount_sat as u64,
+            invoice_amount_msat: invoice_amount_msat as u64,
+            payment_request,
+            status: status.parse()?,
+            funding_tx_id: match funding_tx_id {
+                Some(funding_tx_id) => Some(funding_tx_id.parse::<Txid>()?),
+                None => None,
+            },
+            preimage: match preimage {
+                Some(preimage) => Some(
+                    preimage
+                        .try_into()
+                        .map_err(|_| ReverseSwapRepositoryError::InvalidPreimage)?,
+                ),
+                None => None,
+            },
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::reverse::ReverseSwapRepository for ReverseSwapRepository {
+    #[instrument(level = "trace", skip(self))]
+    async fn add_reverse_swap(
+        &self,
+        state: &ReverseSwapState,
+    ) -> Result<(), ReverseSwapRepositoryError> {
+        let reverse_swap = &state.reverse_swap;
+        let (refund_key_family, refund_key_index, refund_privkey, refund_privkey_key_version) =
+            match &reverse_swap.private.refund_key {
+                ClaimKey::Local { privkey, index } => {
+                    // The payment hash is authenticated along with the refund
+                    // key, so an encrypted refund key cannot be moved to
+                    // another reverse swap.
+                    let (refund_privkey, refund_privkey_key_version) = self.key_cipher.encrypt(
+                        &privkey.secret_bytes(),
+                        reverse_swap.public.hash.as_byte_array(),
+                    )?;
+                    (
+                        None,
+                        *index,
+                        Some(refund_privkey),
+                        refund_privkey_key_version,
+                    )
+                }
+                ClaimKey::Remote(key) => (Some(key.family), Some(key.index), None, None),
+            };
+        sqlx::query(
+            r#"INSERT INTO reverse_swaps (address
+               ,                          amount_sat
+               ,                          claim_pubkey
+               ,                          claim_script
+               ,                          creation_time
+               ,                          invoice_amount_msat
+               ,                          lock_height
+               ,                          payment_hash
+               ,                          payment_request
+               ,                          refund_key_family
+               ,                          refund_key_index
+               ,                          refund_privkey
+               ,                          refund_privkey_key_version
+               ,                          refund_pubkey
+               ,                          refund_script
+               ,                          status
+               ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)"#,
+        )
+        .bind(reverse_swap.public.address.to_string())
+        .bind(state.amount_sat as i64)
+        .bind(reverse_swap.public.claim_pubkey.serialize())
+        .bind(reverse_swap.public.claim_script.as_bytes())
+        .bind(
+            reverse_swap
+                .creation_time
+                .duration_since(UNIX_EPOCH)?
+                .as_secs() as i64,
+        )
+        .bind(state.invoice_amount_msat as i64)
+        .bind(reverse_swap.public.lock_height as i64)
+        .bind(reverse_swap.public.hash.as_byte_array().to_vec())
+        .bind(&state.payment_request)
+        .bind(refund_key_family.map(|f| f as i32))
+        .bind(refund_key_index.map(|i| i as i32))
+        .bind(refund_privkey)
+        .bind(refund_privkey_key_version.map(|v| v as i32))
+        .bind(reverse_swap.public.refund_pubkey.serialize())
+        .bind(reverse_swap.public.refund_script.as_bytes())
+        .bind(state.status.as_str())
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn add_refund(
+        &self,
+        hash: &sha256::Hash,
+        refund: &ReverseSwapRefund,
+    ) -> Result<(), ReverseSwapRepositoryError> {
+        let mut tx: Vec<u8> = Vec::new();
+        refund.tx.consensus_encode(&mut tx)?;
+        sqlx::query(
+            r#"INSERT INTO reverse_swap_refunds (tx_id
+               ,                                 reverse_swap_payment_hash
+               ,                                 creation_time
+               ,                                 tx
+               ,                                 destination_address
+               ,                                 fee_per_kw
+               ) VALUES ($1, $2, $3, $4, $5, $6)"#,
+        )
+        .bind(refund.tx.compute_txid().to_string())
+        .bind(hash.as_byte_array().to_vec())
+        .bind(refund.creation_time.duration_since(UNIX_EPOCH)?.as_secs() as i64)
+        .bind(tx)
+        .bind(refund.destination_address.to_string())
+        .bind(refund.fee_per_kw as i64)
+        .execute(&*self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn get_reverse_swap(
+        &self,
+        hash: &sha256::Hash,
+    ) -> Result<ReverseSwapState, ReverseSwapRepositoryError> {
+        let maybe_row = sqlx::query(&format!(
+            r#"SELECT {}
+               FROM reverse_swaps r
+               WHERE r.payment_hash = $1"#,
+            reverse_swap_state_fields("r")
+        ))
+        .bind(hash.as_byte_array().to_vec())
+        .fetch_optional(&*self.pool)
+        .await?;
+
+        let row = match maybe_row {
+            Some(row) => row,
+            None => return Err(ReverseSwapRepositoryError::NotFound),
+        };
+
+        self.map_reverse_swap_state(&row)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn get_reverse_swaps_by_status(
+        &self,
+        statuses: &[ReverseSwapStatus],
+    ) -> Result<Vec<ReverseSwapState>, ReverseSwapRepositoryError> {
+        let statuses: Vec<_> = statuses.iter().map(|s| s.as_str()).collect();
+        let query = format!(
+            r#"SELECT {}
+               FROM reverse_swaps r
+               WHERE r.status = ANY($1)
+               ORDER BY r.creation_time"#,
+            reverse_swap_state_fields("r")
+        );
+        let mut rows = sqlx::query(&query).bind(statuses).fetch(&*self.pool);
+
+        let mut result = Vec::new();
+        while let Some(row) = rows.try_next().await? {
+            result.push(self.map_reverse_swap_state(&row)?);
+        }
+
+        Ok(result)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn get_refunds(
+        &self,
+        hash: &sha256::Hash,
+    ) -> Result<Vec<ReverseSwapRefund>, ReverseSwapRepositoryError> {
+        let mut rows = sqlx::query(
+            r#"SELECT r.creation_time
+               ,      r.tx
+               ,      r.destination_address
+               ,      r.fee_per_kw
+               FROM reverse_swap_refunds r
+               WHERE r.reverse_swap_payment_hash = $1
+               ORDER BY r.fee_per_kw DESC, r.creation_time DESC"#,
+        )
+        .bind(hash.as_byte_array().to_vec())
+        .fetch(&*self.pool);
+
+        let mut result = Vec::new();
+        while let Some(row) = rows.try_next().await? {
+            let creation_time: i64 = row.try_get("creation_time")?;
+            let mut tx: &[u8] = row.try_get("tx")?;
+            let destination_address: String = row.try_get("destination_address")?;
+            let fee_per_kw: i64 = row.try_get("fee_per_kw")?;
+
+            let creation_time = SystemTime::UNIX_EPOCH
+                .checked_add(Duration::from_secs(creation_time as u64))
+                .ok_or(ReverseSwapRepositoryError::InvalidTimestamp)?;
+            result.push(ReverseSwapRefund {
+                creation_time,
+                destination_address: destination_address
+                    .parse::<Address<NetworkUnchecked>>()?
+                    .require_network(self.network)?,
+                fee_per_kw: fee_per_kw as u32,
+                tx: Transaction::consensus_decode(&mut tx)?,
+            });
+        }
+
+        Ok(result)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn set_funded(
+        &self,
+        hash: &sha256::Hash,
+        funding_tx_id: &Txid,
+    ) -> Result<(), ReverseSwapRepositoryError> {
+        let result = sqlx::query(
+            r#"UPDATE reverse_swaps
+               SET funding_tx_id = $2
+               ,   status = $3
+               WHERE payment_hash = $1 AND status = $4"#,
+        )
+        .bind(hash.as_byte_array().to_vec())
+        .bind(funding_tx_id.to_string())
+        .bind(ReverseSwapStatus::Funded.as_str())
+        .bind(ReverseSwapStatus::Funding.as_str())
+        .execute(&*self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ReverseSwapRepositoryError::StatusChanged);
+        }
+
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn set_settled(
+        &self,
+        hash: &sha256::Hash,
+        preimage: &[u8; 32],
+    ) -> Result<(), ReverseSwapRepositoryError> {
+        let result = sqlx::query(
+            r#"UPDATE reverse_swaps
+               SET preimage = $2
+               ,   status = $3
+               WHERE payment_hash = $1 AND status = $4"#,
+        )
+        .bind(hash.as_byte_array().to_vec())
+        .bind(preimage.to_vec())
+        .bind(ReverseSwapStatus::Settled.as_str())
+        .bind(ReverseSwapStatus::Funded.as_str())
+        .execute(&*self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ReverseSwapRepositoryError::StatusChanged);
+        }
+
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn update_status(
+        &self,
+        hash: &sha256::Hash,
+        from: ReverseSwapStatus,
+        to: ReverseSwapStatus,
+    ) -> Result<(), ReverseSwapRepositoryError> {
+        let result = sqlx::query(
+            r#"UPDATE reverse_swaps
+               SET status = $2
+               WHERE payment_hash = $1 AND status = $3"#,
+        )
+        .bind(hash.as_byte_array().to_vec())
+        .bind(to.as_str())
+        .bind(from.as_str())
+        .execute(&*self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ReverseSwapRepositoryError::StatusChanged);
+        }
+
+        Ok(())
+    }
+}
+
+fn reverse_swap_state_fields(prefix: &str) -> String {
+    format!(
+        r#"{0}.address
+         , {0}.amount_sat
+         , {0}.claim_pubkey
+         , {0}.claim_script
+         , {0}.creation_time
+         , {0}.funding_tx_id
+         , {0}.invoice_amount_msat
+         , {0}.lock_height
+         , {0}.payment_hash
+         , {0}.payment_request
+         , {0}.preimage
+         , {0}.refund_key_family
+         , {0}.refund_key_index
+         , {0}.refund_privkey
+         , {0}.refund_privkey_key_version
+         , {0}.refund_pubkey
+         , {0}.refund_script
+         , {0}.status
+         "#,
+        prefix
+    )
+}
+
+impl From<sqlx::Error> for ReverseSwapRepositoryError {
+    fn from(value: sqlx::Error) -> Self {
+        match value {
+            sqlx::Error::Database(e) => match e.constraint() {
+                Some(_) => ReverseSwapRepositoryError::AlreadyExists,
+                None => ReverseSwapRepositoryError::General(Box::new(e)),
+            },
+            e => ReverseSwapRepositoryError::General(Box::new(e)),
+        }
+    }
+}
+
+impl From<bitcoin::address::ParseError> for ReverseSwapRepositoryError {
+    fn from(value: bitcoin::address::ParseError) -> Self {
+        ReverseSwapRepositoryError::General(Box::new(value))
+    }
+}
+
+impl From<bitcoin::hashes::FromSliceError> for ReverseSwapRepositoryError {
+    fn from(value: bitcoin::hashes::FromSliceError) -> Self {
+        ReverseSwapRepositoryError::General(Box::new(value))
+    }
+}
+
+impl From<bitcoin::hashes::hex::HexToArrayError> for ReverseSwapRepositoryError {
+    fn from(value: bitcoin::hashes::hex::HexToArrayError) -> Self {
+        ReverseSwapRepositoryError::General(Box::new(value))
+    }
+}
+
+impl From<bitcoin::secp256k1::Error> for ReverseSwapRepositoryError {
+    fn from(value: bitcoin::secp256k1::Error) -> Self {
+        ReverseSwapRepositoryError::General(Box::new(value))
+    }
+}
+
+impl From<std::time::SystemTimeError> for ReverseSwapRepositoryError {
+    fn from(value: std::time::SystemTimeError) -> Self {
+        ReverseSwapRepositoryError::General(Box::new(value))
+    }
+}
+
+impl From<bitcoin::io::Error> for ReverseSwapRepositoryError {
+    fn from(value: bitcoin::io::Error) -> Self {
+        ReverseSwapRepositoryError::General(Box::new(value))
+    }
+}
+
+impl From<bitcoin::consensus::encode::Error> for ReverseSwapRepositoryError {
+    fn from(value: bitcoin::consensus::encode::Error) -> Self {
+        ReverseSwapRepositoryError::General(Box::new(value))
+    }
+}
+
+impl From<KeyCipherError> for ReverseSwapRepositoryError {
+    fn from(value: KeyCipherError) -> Self {
+        ReverseSwapRepositoryError::General(Box::new(value))
+    }
+}
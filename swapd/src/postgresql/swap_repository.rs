@@ -17,29 +17,49 @@ use tracing::instrument;
 use crate::{
     lightning::PaymentResult,
     swap::{
-        AddPaymentResultError, GetPaidUtxosError, GetPaymentAttemptsError, GetSwapsError,
-        LockSwapError, PaidOutpoint, PaymentAttempt, PaymentAttemptWithResult, Swap, SwapLock,
-        SwapPersistenceError, SwapPrivateData, SwapPublicData, SwapState, SwapStatePaidOutpoints,
+        AddPaymentResultError, ClaimKey, ClaimKeyIndexRepository, ExpireSwapsError,
+        GetPaidUtxosError, GetPaymentAttemptsError, GetSwapsError, KeyLocator, LockSwapError,
+        PaidOutpoint, PaymentAttempt, PaymentAttemptWithResult, PrivateKeyError,
+        ReencryptClaimKeysError, Swap, SwapLock, SwapPersistenceError, SwapPrivateData,
+        SwapPublicData, SwapState, SwapStatePaidOutpoints,
     },
 };
 
+use super::key_cipher::{KeyCipher, KeyCipherError};
+
 #[derive(Debug)]
 pub struct SwapRepository {
     network: Network,
     pool: Arc<PgPool>,
+    key_cipher: Arc<KeyCipher>,
 }
 
 impl SwapRepository {
-    pub fn new(pool: Arc<PgPool>, network: Network) -> Self {
-        Self { pool, network }
+    pub fn new(pool: Arc<PgPool>, network: Network, key_cipher: Arc<KeyCipher>) -> Self {
+        Self {
+            pool,
+            network,
+            key_cipher,
+        }
+    }
+
+    /// The cipher for private keys in the database, shared with the reverse
+    /// swap repository.
+    pub fn key_cipher(&self) -> Arc<KeyCipher> {
+        Arc::clone(&self.key_cipher)
     }
 
     fn map_swap_state(&self, row: &PgRow) -> Result<SwapState, GetSwapsError> {
         let address: &str = row.try_get("address")?;
-        let claim_privkey: Vec<u8> = row.try_get("claim_privkey")?;
+        let claim_key_family: Option<i32> = row.try_get("claim_key_family")?;
+        let claim_key_index: Option<i32> = row.try_get("claim_key_index")?;
+        let claim_privkey: Option<Vec<u8>> = row.try_get("claim_privkey")?;
+        let claim_privkey_key_version: Option<i32> = row.try_get("claim_privkey_key_version")?;
         let claim_pubkey: Vec<u8> = row.try_get("claim_pubkey")?;
         let claim_script: Vec<u8> = row.try_get("claim_script")?;
+        let creation_height: Option<i64> = row.try_get("creation_height")?;
         let creation_time: i64 = row.try_get("creation_time")?;
+        let expiry_time: Option<i64> = row.try_get("expiry_time")?;
         let lock_time: i32 = row.try_get("lock_time")?;
         let payment_hash: Vec<u8> = row.try_get("payment_hash")?;
         let refund_pubkey: Vec<u8> = row.try_get("refund_pubkey")?;
@@ -48,10 +68,31 @@ impl SwapRepository {
         let creation_time = SystemTime::UNIX_EPOCH
             .checked_add(Duration::from_secs(creation_time as u64))
             .ok_or(GetSwapsError::General("invalid timestamp".into()))?;
+        let claim_key = match (claim_key_family, claim_privkey) {
+            (Some(family), _) => ClaimKey::Remote(KeyLocator {
+                family: family as u32,
+                index: claim_key_index
+                    .ok_or(GetSwapsError::General("missing claim key index".into()))?
+                    as u32,
+            }),
+            (None, Some(claim_privkey)) => {
+                let claim_privkey = self.key_cipher.decrypt(
+                    &claim_privkey,
+                    &payment_hash,
+                    claim_privkey_key_version.map(|v| v as u32),
+                )?;
+                ClaimKey::Local {
+                    privkey: SecretKey::from_slice(&claim_privkey)?,
+                    index: claim_key_index.map(|i| i as u32),
+                }
+            }
+            (None, None) => return Err(GetSwapsError::General("missing claim key".into())),
+        };
         let address = address
             .parse::<Address<NetworkUnchecked>>()?
             .require_network(self.network)?;
         let swap = Swap {
+            creation_height: creation_height.map(|h| h as u64),
             creation_time,
             public: SwapPublicData {
                 address: address.clone(),
@@ -62,9 +103,7 @@ impl SwapRepository {
                 refund_pubkey: PublicKey::from_slice(&refund_pubkey)?,
                 refund_script: ScriptBuf::from_bytes(refund_script),
             },
-            private: SwapPrivateData {
-                claim_privkey: SecretKey::from_slice(&claim_privkey)?,
-            },
+            private: SwapPrivateData { claim_key },
         };
         let preimage: Option<Vec<u8>> = row.try_get("preimage")?;
         Ok(SwapState {
@@ -77,30 +116,121 @@ impl SwapRepository {
                 ),
                 None => None,
             },
+            expired: expiry_time.is_some(),
         })
     }
+
+    /// Re-encrypts the private keys in `column` of `table` that are not
+    /// encrypted with `current_version`. Keys are authenticated with the
+    /// payment hash of their row.
+    async fn reencrypt_keys(
+        &self,
+        table: &str,
+        column: &str,
+        version_column: &str,
+        current_version: i32,
+        batch_size: u32,
+    ) -> Result<u64, ReencryptClaimKeysError> {
+        let mut reencrypted = 0;
+        loop {
+            let rows = sqlx::query(&format!(
+                r#"SELECT t.payment_hash
+                   ,      t.{1} AS privkey
+                   ,      t.{2} AS key_version
+                   FROM {0} t
+                   WHERE t.{1} IS NOT NULL
+                     AND t.{2} IS DISTINCT FROM $1
+                   LIMIT $2"#,
+                table, column, version_column
+            ))
+            .bind(current_version)
+            .bind(batch_size as i64)
+            .fetch_all(&*self.pool)
+            .await?;
+            if rows.is_empty() {
+                break;
+            }
+
+            for row in rows {
+                let payment_hash: Vec<u8> = row.try_get("payment_hash")?;
+                let privkey: Vec<u8> = row.try_get("privkey")?;
+                let key_version: Option<i32> = row.try_get("key_version")?;
+                let plaintext = self.key_cipher.decrypt(
+                    &privkey,
+                    &payment_hash,
+                    key_version.map(|v| v as u32),
+                )?;
+                let (ciphertext, version) = self.key_cipher.encrypt(&plaintext, &payment_hash)?;
+
+                // Only update if the row wasn't changed in the meantime, so
+                // this can run while swapd is running.
+                let result = sqlx::query(&format!(
+                    r#"UPDATE {0}
+                       SET {1} = $1
+                       ,   {2} = $2
+                       WHERE payment_hash = $3
+                         AND {1} = $4"#,
+                    table, column, version_column
+                ))
+                .bind(ciphertext)
+                .bind(version.map(|v| v as i32))
+                .bind(&payment_hash)
+                .bind(&privkey)
+                .execute(&*self.pool)
+                .await?;
+                reencrypted += result.rows_affected();
+            }
+        }
+
+        Ok(reencrypted)
+    }
 }
 
 #[async_trait::async_trait]
 impl crate::swap::SwapRepository for SwapRepository {
     #[instrument(level = "trace", skip(self))]
     async fn add_swap(&self, swap: &Swap) -> Result<(), SwapPersistenceError> {
-        sqlx::query(
+        let (claim_key_family, claim_key_index, claim_privkey, claim_privkey_key_version) =
+            match &swap.private.claim_key {
+                ClaimKey::Local { privkey, index } => {
+                    // The payment hash is authenticated along with the claim
+                    // key, so an encrypted claim key cannot be moved to
+                    // another swap.
+                    let (claim_privkey, claim_privkey_key_version) = self
+                        .key_cipher
+                        .encrypt(&privkey.secret_bytes(), swap.public.hash.as_byte_array())?;
+                    (None, *index, Some(claim_privkey), claim_privkey_key_version)
+                }
+                ClaimKey::Remote(key) => (Some(key.family), Some(key.index), None, None),
+            };
+        let result = sqlx::query(
             r#"INSERT INTO swaps (address
+               ,                  claim_key_family
+               ,                  claim_key_index
                ,                  claim_privkey
+               ,                  claim_privkey_key_version
                ,                  claim_pubkey
                ,                  claim_script
+               ,                  creation_height
                ,                  creation_time
                ,                  lock_time
                ,                  payment_hash
                ,                  refund_pubkey
                ,                  refund_script
-               ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"#,
+               )
+               SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13
+               WHERE NOT EXISTS (
+                   SELECT 1 FROM swaps_archive WHERE payment_hash = $11
+               )"#,
         )
         .bind(swap.public.address.to_string())
-        .bind(swap.private.claim_privkey.secret_bytes().to_vec())
+        .bind(claim_key_family.map(|f| f as i32))
+        .bind(claim_key_index.map(|i| i as i32))
+        .bind(claim_privkey)
+        .bind(claim_privkey_key_version.map(|v| v as i32))
         .bind(swap.public.claim_pubkey.serialize())
         .bind(swap.public.claim_script.as_bytes())
+        .bind(swap.creation_height.map(|h| h as i64))
         .bind(swap.creation_time.duration_since(UNIX_EPOCH)?.as_secs() as i64)
         .bind(swap.public.lock_time as i32)
         .bind(swap.public.hash.as_byte_array().to_vec())
@@ -109,9 +239,135 @@ impl crate::swap::SwapRepository for SwapRepository {
         .execute(&*self.pool)
         .await?;
 
+        // Payment hashes of archived swaps can't be reused either.
+        if result.rows_affected() == 0 {
+            return Err(SwapPersistenceError::AlreadyExists);
+        }
+
         Ok(())
     }
 
+    #[instrument(level = "trace", skip(self))]
+    async fn count_unfunded_swaps(&self) -> Result<u64, GetSwapsError> {
+        let count: i64 = sqlx::query_scalar(
+            r#"SELECT COUNT(*)
+               FROM swaps s
+               WHERE s.expiry_time IS NULL
+                 AND NOT EXISTS (SELECT 1 FROM tx_outputs o WHERE o.address = s.address)
+                 AND NOT EXISTS (
+                     SELECT 1 FROM mempool_tx_outputs m WHERE m.address = s.address
+                 )"#,
+        )
+        .fetch_one(&*self.pool)
+        .await?;
+        Ok(count as u64)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn expire_unfunded_swaps(
+        &self,
+        current_height: u64,
+        block_time: Duration,
+    ) -> Result<Vec<Address>, ExpireSwapsError> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        // Expiring the swap and removing its watch address is a single
+        // statement, so a swap is never left expired while still watched.
+        let addresses: Vec<String> = sqlx::query_scalar(
+            r#"WITH expired AS (
+                   UPDATE swaps s
+                   SET expiry_time = $1
+                   WHERE s.expiry_time IS NULL
+                     AND s.preimage IS NULL
+                     AND CASE WHEN s.creation_height IS NULL
+                              THEN s.creation_time + s.lock_time * $3 < $1
+                              ELSE s.creation_height + s.lock_time < $2
+                         END
+                     AND NOT EXISTS (SELECT 1 FROM tx_outputs o WHERE o.address = s.address)
+                     AND NOT EXISTS (
+                         SELECT 1 FROM mempool_tx_outputs m WHERE m.address = s.address
+                     )
+                   RETURNING s.address
+               ), unwatched AS (
+                   DELETE FROM watch_addresses w
+                   USING expired e
+                   WHERE w.address = e.address
+               )
+               SELECT address FROM expired"#,
+        )
+        .bind(now)
+        .bind(current_height as i64)
+        .bind(block_time.as_secs() as i64)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        addresses
+            .iter()
+            .map(|a| {
+                Ok(a.parse::<Address<NetworkUnchecked>>()?
+                    .require_network(self.network)?)
+            })
+            .collect()
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn unexpire_swaps(
+        &self,
+        addresses: &[Address],
+    ) -> Result<Vec<Address>, ExpireSwapsError> {
+        let addresses: Vec<String> = addresses.iter().map(|a| a.to_string()).collect();
+        let addresses: Vec<String> = sqlx::query_scalar(
+            r#"WITH unexpired AS (
+                   UPDATE swaps s
+                   SET expiry_time = NULL
+                   ,   completion_time = NULL
+                   ,   completion_reason = NULL
+                   WHERE s.address = ANY($1)
+                     AND s.expiry_time IS NOT NULL
+                   RETURNING s.address
+               ), watched AS (
+                   INSERT INTO watch_addresses (address)
+                   SELECT address FROM unexpired
+                   ON CONFLICT DO NOTHING
+               )
+               SELECT address FROM unexpired"#,
+        )
+        .bind(addresses)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        addresses
+            .iter()
+            .map(|a| {
+                Ok(a.parse::<Address<NetworkUnchecked>>()?
+                    .require_network(self.network)?)
+            })
+            .collect()
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn filter_archived_addresses(
+        &self,
+        addresses: &[Address],
+    ) -> Result<Vec<Address>, GetSwapsError> {
+        let addresses: Vec<String> = addresses.iter().map(|a| a.to_string()).collect();
+        let addresses: Vec<String> = sqlx::query_scalar(
+            r#"SELECT address
+               FROM swaps_archive
+               WHERE address = ANY($1)"#,
+        )
+        .bind(addresses)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        addresses
+            .iter()
+            .map(|a| {
+                Ok(a.parse::<Address<NetworkUnchecked>>()?
+                    .require_network(self.network)?)
+            })
+            .collect()
+    }
+
     #[instrument(level = "trace", skip(self))]
     async fn get_swap_by_hash(&self, hash: &sha256::Hash) -> Result<SwapState, GetSwapsError> {
         let maybe_row = sqlx::query(&format!(
@@ -212,6 +468,7 @@ impl crate::swap::SwapRepository for SwapRepository {
                ,      pa.label
                ,      pa.creation_time
                ,      pa.amount_msat
+               ,      pa.fee_sat
                ,      pa.payment_request
                ,      pa.destination
                ,      pa.success
@@ -234,6 +491,7 @@ impl crate::swap::SwapRepository for SwapRepository {
             let label: String = row.try_get("label")?;
             let creation_time: i64 = row.try_get("creation_time")?;
             let amount_msat: i64 = row.try_get("amount_msat")?;
+            let fee_sat: i64 = row.try_get("fee_sat")?;
             let payment_request: String = row.try_get("payment_request")?;
             let destination: Vec<u8> = row.try_get("destination")?;
             let success: Option<bool> = row.try_get("success")?;
@@ -272,6 +530,7 @@ impl crate::swap::SwapRepository for SwapRepository {
                             label: label.clone(),
                             creation_time,
                             amount_msat: amount_msat as u64,
+                            fee_sat: fee_sat as u64,
                             payment_request: payment_request.clone(),
                             destination,
                             outputs: Vec::new(),
@@ -386,6 +645,7 @@ impl crate::swap::SwapRepository for SwapRepository {
                ,      pa.label
                ,      pa.creation_time
                ,      pa.amount_msat
+               ,      pa.fee_sat
                ,      pa.payment_request
                ,      pa.destination
                ,      patx.tx_id
@@ -403,6 +663,7 @@ impl crate::swap::SwapRepository for SwapRepository {
             let label: String = row.try_get("label")?;
             let creation_time: i64 = row.try_get("creation_time")?;
             let amount_msat: i64 = row.try_get("amount_msat")?;
+            let fee_sat: i64 = row.try_get("fee_sat")?;
             let payment_request: String = row.try_get("payment_request")?;
             let destination: Vec<u8> = row.try_get("destination")?;
             let tx_id: Option<String> = row.try_get("tx_id")?;
@@ -421,6 +682,7 @@ impl crate::swap::SwapRepository for SwapRepository {
                     label: label.clone(),
                     creation_time,
                     amount_msat: amount_msat as u64,
+                    fee_sat: fee_sat as u64,
                     payment_request: payment_request.clone(),
                     destination,
                     outputs: Vec::new(),
@@ -473,9 +735,10 @@ impl crate::swap::SwapRepository for SwapRepository {
             ,                             label
             ,                             creation_time
             ,                             amount_msat
+            ,                             fee_sat
             ,                             payment_request
             ,                             destination)
-            VALUES($1, $2, $3, $4, $5, $6)
+            VALUES($1, $2, $3, $4, $5, $6, $7)
             RETURNING id
             "#,
         )
@@ -483,6 +746,7 @@ impl crate::swap::SwapRepository for SwapRepository {
         .bind(&attempt.label)
         .bind(attempt.creation_time.duration_since(UNIX_EPOCH)?.as_secs() as i64)
         .bind(attempt.amount_msat as i64)
+        .bind(attempt.fee_sat as i64)
         .bind(&attempt.payment_request)
         .bind(attempt.destination.serialize().to_vec())
         .fetch_one(&mut *tx)
@@ -610,6 +874,55 @@ impl crate::swap::SwapRepository for SwapRepository {
         tx.commit().await?;
         Ok(())
     }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn reencrypt_claim_keys(&self, batch_size: u32) -> Result<u64, ReencryptClaimKeysError> {
+        let current_version = match self.key_cipher.current_version() {
+            Some(version) => version as i32,
+            None => return Err(ReencryptClaimKeysError::NoEncryptionKey),
+        };
+
+        let claim_keys = self
+            .reencrypt_keys(
+                "swaps",
+                "claim_privkey",
+                "claim_privkey_key_version",
+                current_version,
+                batch_size,
+            )
+            .await?;
+        let refund_keys = self
+            .reencrypt_keys(
+                "reverse_swaps",
+                "refund_privkey",
+                "refund_privkey_key_version",
+                current_version,
+                batch_size,
+            )
+            .await?;
+        Ok(claim_keys + refund_keys)
+    }
+}
+
+#[async_trait::async_trait]
+impl ClaimKeyIndexRepository for SwapRepository {
+    #[instrument(level = "trace", skip(self))]
+    async fn next_claim_key_index(&self) -> Result<u32, PrivateKeyError> {
+        let index: i64 = sqlx::query_scalar("SELECT nextval('swap_claim_key_index')")
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| PrivateKeyError::General(Box::new(e)))?;
+        u32::try_from(index).map_err(|e| PrivateKeyError::General(Box::new(e)))
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    async fn next_reverse_refund_key_index(&self) -> Result<u32, PrivateKeyError> {
+        let index: i64 = sqlx::query_scalar("SELECT nextval('reverse_swap_refund_key_index')")
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(|e| PrivateKeyError::General(Box::new(e)))?;
+        u32::try_from(index).map_err(|e| PrivateKeyError::General(Box::new(e)))
+    }
 }
 
 async fn lock_swap_for_update<'c, E>(hash: &sha256::Hash, executor: E) -> Result<(), LockSwapError>
@@ -631,10 +944,15 @@ where
 fn swap_state_fields(prefix: &str) -> String {
     format!(
         r#"{0}.address
+         , {0}.claim_key_family
+         , {0}.claim_key_index
          , {0}.claim_privkey
+         , {0}.claim_privkey_key_version
          , {0}.claim_pubkey
          , {0}.claim_script
+         , {0}.creation_height
          , {0}.creation_time
+         , {0}.expiry_time
          , {0}.lock_time
          , {0}.payment_hash
          , {0}.preimage
@@ -657,6 +975,12 @@ impl From<sqlx::Error> for SwapPersistenceError {
     }
 }
 
+impl From<KeyCipherError> for SwapPersistenceError {
+    fn from(value: KeyCipherError) -> Self {
+        SwapPersistenceError::General(Box::new(value))
+    }
+}
+
 impl From<SystemTimeError> for SwapPersistenceError {
     fn from(value: SystemTimeError) -> Self {
         SwapPersistenceError::General(Box::new(value))
@@ -669,6 +993,42 @@ impl From<bitcoin::address::ParseError> for GetSwapsError {
     }
 }
 
+impl From<KeyCipherError> for GetSwapsError {
+    fn from(value: KeyCipherError) -> Self {
+        GetSwapsError::General(Box::new(value))
+    }
+}
+
+impl From<sqlx::Error> for ExpireSwapsError {
+    fn from(value: sqlx::Error) -> Self {
+        ExpireSwapsError::General(Box::new(value))
+    }
+}
+
+impl From<SystemTimeError> for ExpireSwapsError {
+    fn from(value: SystemTimeError) -> Self {
+        ExpireSwapsError::General(Box::new(value))
+    }
+}
+
+impl From<bitcoin::address::ParseError> for ExpireSwapsError {
+    fn from(value: bitcoin::address::ParseError) -> Self {
+        ExpireSwapsError::General(Box::new(value))
+    }
+}
+
+impl From<KeyCipherError> for ReencryptClaimKeysError {
+    fn from(value: KeyCipherError) -> Self {
+        ReencryptClaimKeysError::General(Box::new(value))
+    }
+}
+
+impl From<sqlx::Error> for ReencryptClaimKeysError {
+    fn from(value: sqlx::Error) -> Self {
+        ReencryptClaimKeysError::General(Box::new(value))
+    }
+}
+
 impl From<sqlx::Error> for GetSwapsError {
     fn from(value: sqlx::Error) -> Self {
         GetSwapsError::General(Box::new(value))
@@ -1,38 +1,56 @@
 use bitcoin::{
     address::NetworkUnchecked,
-    consensus::Decodable,
+    consensus::{Decodable, Encodable},
     hashes::{sha256::Hash, Hash as _},
-    secp256k1::PublicKey,
-    Address, CompressedPublicKey, Network, Transaction, TxOut,
+    secp256k1::{PublicKey, Secp256k1, VerifyOnly},
+    Address, BlockHash, CompressedPublicKey, Network, Transaction, TxOut,
 };
-use futures::future::join_all;
+use futures::{future::join_all, Stream, StreamExt};
 use lightning_invoice::Bolt11Invoice;
-use secp256k1::musig::MusigPubNonce;
+use secp256k1::musig::{MusigPartialSignature, MusigPubNonce};
 use std::sync::Arc;
 use std::{
     fmt::Debug,
-    time::{SystemTime, UNIX_EPOCH},
+    net::IpAddr,
+    pin::Pin,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
+use tokio::sync::Mutex;
 use tonic::{Request, Response, Status};
 use tracing::{debug, error, field, info, instrument, trace, warn};
 
 use crate::{
     chain::{
-        ChainClient, ChainError, ChainRepository, ChainRepositoryError, FeeEstimateError,
-        FeeEstimator, Txo,
+        ChainClient, ChainError, ChainRepository, ChainRepositoryError, FeeEstimate,
+        FeeEstimateError, FeeEstimator, Txo,
     },
     chain_filter::ChainFilterService,
+    claim::{
+        ClaimRepository, ClaimRepositoryError, ClaimService, CooperativeClaimError,
+        OutputDisposition,
+    },
+    event::{self, EventBus, EventBusError, SwapEventKind},
     lightning::{LightningClient, LightningError, PaymentRequest, PaymentResult},
-    swap::{ClaimableUtxo, LockSwapError, PaymentAttempt, RandomError, RandomProvider},
+    mempool::{MempoolRepository, MempoolRepositoryError},
+    metrics::Metrics,
+    rate_limit::{ConcurrencyLimiter, RateLimiter},
+    swap::{
+        verify_ownership_proof, ClaimFeeQuotes, ClaimableUtxo, LockSwapError, PaymentAttempt,
+        RandomError, RandomProvider, SwapFeePolicy, SwapState,
+    },
 };
 
 use crate::swap::{
     GetSwapsError, PrivateKeyProvider, SwapPersistenceError, SwapRepository, SwapService,
 };
 use swap_api::{
-    taproot_swapper_server::TaprootSwapper, CreateSwapRequest, CreateSwapResponse, PaySwapRequest,
-    PaySwapResponse, RefundSwapRequest, RefundSwapResponse, SwapParameters, SwapParametersRequest,
-    SwapParametersResponse,
+    swap_event, taproot_swapper_server::TaprootSwapper, ClaimBroadcast, ClaimSwapDetailsRequest,
+    ClaimSwapDetailsResponse, ClaimSwapRequest, ClaimSwapResponse, CreateSwapRequest,
+    CreateSwapResponse, DepositConfirmed, DepositDropped, DepositUnconfirmed, GetSwapStatusRequest,
+    GetSwapStatusResponse, NotPayableReason, OutputSpent, PaySwapRequest, PaySwapResponse,
+    PayoutState, RefundOffered, RefundSwapRequest, RefundSwapResponse, SubscribeSwapEventsRequest,
+    SwapEvent, SwapPaid, SwapParameters, SwapParametersRequest, SwapParametersResponse,
+    SwapPaymentFailed, SwapStatusOutput,
 };
 
 pub mod swap_api {
@@ -40,31 +58,60 @@ pub mod swap_api {
 }
 
 const FAKE_PREIMAGE: [u8; 32] = [0; 32];
-const MIN_SWAP_AMOUNT_CONF_TARGET: i32 = 12;
-pub struct SwapServerParams<C, CF, CR, L, P, R, RP, F>
+pub(crate) const MIN_SWAP_AMOUNT_CONF_TARGET: i32 = 12;
+
+/// Limits on CreateSwap, so clients can't grow the swaps and watched addresses
+/// without bound. A limit of 0 disables it.
+#[derive(Clone, Debug)]
+pub struct CreateSwapLimits {
+    /// Swaps created per client ip within `window`.
+    pub per_ip: u32,
+    /// Swaps created per refund pubkey within `window`.
+    pub per_refund_pubkey: u32,
+    pub window: Duration,
+    /// Swaps that are not funded and not expired yet, over all clients.
+    pub max_unfunded_swaps: u64,
+    /// Header set by a trusted reverse proxy with the client ip. The last ip
+    /// in the header is used, because that's the one added by the proxy.
+    pub client_ip_header: Option<String>,
+}
+
+pub struct SwapServerParams<C, CF, CL, CR, L, MR, P, R, RP, F>
 where
     C: ChainClient,
     CF: ChainFilterService,
+    CL: ClaimRepository,
     CR: ChainRepository,
     L: LightningClient,
+    MR: MempoolRepository,
     P: PrivateKeyProvider,
     R: SwapRepository,
     RP: RandomProvider,
     F: FeeEstimator,
 {
     pub network: Network,
+    pub fee_policy: SwapFeePolicy,
+    pub claim_fee_quote_validity: Duration,
     pub max_swap_amount_sat: u64,
     pub min_confirmations: u64,
     pub min_claim_blocks: u32,
     pub min_utxo_amount_sat: u64,
     pub min_viable_cltv: u32,
+    pub zero_conf_max_amount_sat: u64,
+    pub zero_conf_max_outstanding_sat: u64,
+    pub create_swap_limits: CreateSwapLimits,
+    pub max_event_subscriptions_per_ip: u32,
     pub pay_fee_limit_base_msat: u64,
     pub pay_fee_limit_ppm: u64,
     pub pay_timeout_seconds: u16,
     pub chain_service: Arc<C>,
     pub chain_filter_service: Arc<CF>,
     pub chain_repository: Arc<CR>,
+    pub claim_service: Arc<ClaimService<C, CR, CL, R, P>>,
+    pub event_bus: Arc<EventBus>,
     pub lightning_client: Arc<L>,
+    pub mempool_repository: Arc<MR>,
+    pub metrics: Arc<Metrics>,
     pub random_provider: Arc<RP>,
     pub swap_service: Arc<SwapService<P>>,
     pub swap_repository: Arc<R>,
@@ -72,69 +119,234 @@ where
 }
 
 #[derive(Debug)]
-pub struct SwapServer<C, CF, CR, L, P, R, RP, F>
+pub struct SwapServer<C, CF, CL, CR, L, MR, P, R, RP, F>
 where
     C: ChainClient,
     CF: ChainFilterService,
+    CL: ClaimRepository,
     CR: ChainRepository,
     L: LightningClient,
+    MR: MempoolRepository,
     P: PrivateKeyProvider,
     R: SwapRepository,
     RP: RandomProvider,
     F: FeeEstimator,
 {
     network: Network,
+    fee_policy: SwapFeePolicy,
+    /// Claim fee rates quoted per swap hash, if the fee includes the claim
+    /// fee.
+    claim_fee_quotes: ClaimFeeQuotes<Hash>,
     max_swap_amount_sat: u64,
     min_confirmations: u64,
     min_claim_blocks: u32,
     min_utxo_amount_sat: u64,
     min_viable_cltv: u32,
+    zero_conf_max_amount_sat: u64,
+    zero_conf_max_outstanding_sat: u64,
+    /// Held from deciding on a zero-conf payout until its payment attempt is
+    /// stored, so concurrent payouts can't exceed
+    /// `zero_conf_max_outstanding_sat` together.
+    zero_conf_lock: Mutex<()>,
+    client_ip_header: Option<String>,
+    ip_rate_limiter: RateLimiter<IpAddr>,
+    refund_pubkey_rate_limiter: RateLimiter<PublicKey>,
+    max_unfunded_swaps: u64,
+    event_subscription_limiter: ConcurrencyLimiter<IpAddr>,
     pay_fee_limit_base_msat: u64,
     pay_fee_limit_ppm: u64,
     pay_timeout_seconds: u16,
     chain_client: Arc<C>,
     chain_filter_service: Arc<CF>,
     chain_repository: Arc<CR>,
+    claim_service: Arc<ClaimService<C, CR, CL, R, P>>,
+    event_bus: Arc<EventBus>,
     lightning_client: Arc<L>,
+    mempool_repository: Arc<MR>,
+    metrics: Arc<Metrics>,
     random_provider: Arc<RP>,
+    secp: Secp256k1<VerifyOnly>,
     swap_service: Arc<SwapService<P>>,
     swap_repository: Arc<R>,
     fee_estimator: Arc<F>,
 }
 
-impl<C, CF, CR, L, P, R, RP, F> SwapServer<C, CF, CR, L, P, R, RP, F>
+impl<C, CF, CL, CR, L, MR, P, R, RP, F> SwapServer<C, CF, CL, CR, L, MR, P, R, RP, F>
 where
     C: ChainClient,
     CF: ChainFilterService,
+    CL: ClaimRepository,
     CR: ChainRepository,
     L: LightningClient,
+    MR: MempoolRepository,
     P: PrivateKeyProvider,
     R: SwapRepository,
     RP: RandomProvider,
     F: FeeEstimator,
 {
-    pub fn new(params: SwapServerParams<C, CF, CR, L, P, R, RP, F>) -> Self {
+    pub fn new(params: SwapServerParams<C, CF, CL, CR, L, MR, P, R, RP, F>) -> Self {
         SwapServer {
             network: params.network,
+            fee_policy: params.fee_policy,
+            claim_fee_quotes: ClaimFeeQuotes::new(params.claim_fee_quote_validity),
             max_swap_amount_sat: params.max_swap_amount_sat,
             min_confirmations: params.min_confirmations,
             min_claim_blocks: params.min_claim_blocks,
             min_utxo_amount_sat: params.min_utxo_amount_sat,
             min_viable_cltv: params.min_viable_cltv,
+            zero_conf_max_amount_sat: params.zero_conf_max_amount_sat,
+            zero_conf_max_outstanding_sat: params.zero_conf_max_outstanding_sat,
+            zero_conf_lock: Mutex::new(()),
+            client_ip_header: params
+                .create_swap_limits
+                .client_ip_header
+                .map(|h| h.to_ascii_lowercase()),
+            ip_rate_limiter: RateLimiter::new(
+                params.create_swap_limits.per_ip,
+                params.create_swap_limits.window,
+            ),
+            refund_pubkey_rate_limiter: RateLimiter::new(
+                params.create_swap_limits.per_refund_pubkey,
+                params.create_swap_limits.window,
+            ),
+            max_unfunded_swaps: params.create_swap_limits.max_unfunded_swaps,
+            event_subscription_limiter: ConcurrencyLimiter::new(
+                params.max_event_subscriptions_per_ip,
+            ),
             pay_fee_limit_base_msat: params.pay_fee_limit_base_msat,
             pay_fee_limit_ppm: params.pay_fee_limit_ppm,
             pay_timeout_seconds: params.pay_timeout_seconds,
             chain_client: params.chain_service,
             chain_filter_service: params.chain_filter_service,
             chain_repository: params.chain_repository,
+            claim_service: params.claim_service,
+            event_bus: params.event_bus,
             lightning_client: params.lightning_client,
+            mempool_repository: params.mempool_repository,
+            metrics: params.metrics,
             random_provider: params.random_provider,
+            secp: Secp256k1::verification_only(),
             swap_service: params.swap_service,
             swap_repository: params.swap_repository,
             fee_estimator: params.fee_estimator,
         }
     }
 
+    /// Returns the ip from `client_ip_header` if it is configured and valid,
+    /// otherwise the peer address. Falling back keeps requests without the
+    /// header, for example ones that bypass the proxy, under the per-ip limits.
+    fn client_ip<T>(&self, request: &Request<T>) -> Option<IpAddr> {
+        if let Some(header) = &self.client_ip_header {
+            let ip = request
+                .metadata()
+                .get(header.as_str())
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.rsplit(',').next())
+                .and_then(|ip| ip.trim().parse().ok());
+            if ip.is_some() {
+                return ip;
+            }
+            trace!(
+                header = header.as_str(),
+                "missing or invalid client ip header, using peer address"
+            );
+        }
+
+        request.remote_addr().map(|addr| addr.ip())
+    }
+
+    /// Returns the swap at `address` if the request carries a valid ownership
+    /// proof for it. Unknown swaps fail the same way as invalid proofs, so
+    /// callers don't reveal which addresses belong to swaps.
+    async fn authenticate_swap(
+        &self,
+        address: &str,
+        timestamp: u64,
+        signature: &[u8],
+    ) -> Result<(Address, SwapState), Status> {
+        let address = address
+            .parse::<Address<NetworkUnchecked>>()
+            .map_err(|e| {
+                trace!("could not parse address: {:?}", e);
+                Status::invalid_argument("invalid address")
+            })?
+            .require_network(self.network)
+            .map_err(|e| {
+                trace!("address for wrong network: {:?}", e);
+                Status::invalid_argument("invalid address")
+            })?;
+
+        let swap_state = match self.swap_repository.get_swap_by_address(&address).await {
+            Ok(swap_state) => swap_state,
+            Err(GetSwapsError::NotFound) => {
+                trace!("swap not found");
+                return Err(Status::unauthenticated("invalid ownership proof"));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        verify_ownership_proof(
+            &self.secp,
+            &swap_state.swap.public.refund_pubkey,
+            &address,
+            timestamp,
+            signature,
+            SystemTime::now(),
+        )
+        .map_err(|e| {
+            trace!("invalid ownership proof: {:?}", e);
+            Status::unauthenticated("invalid ownership proof")
+        })?;
+
+        Ok((address, swap_state))
+    }
+
+    /// Enforces the CreateSwap limits. A request rejected by a limit is not
+    /// counted towards the limits checked after it.
+    async fn check_create_swap_limits(
+        &self,
+        client_ip: Option<IpAddr>,
+        refund_pubkey: PublicKey,
+    ) -> Result<(), Status> {
+        let now = Instant::now();
+        if let Some(client_ip) = client_ip {
+            if !self.ip_rate_limiter.check(client_ip, now) {
+                debug!(client_ip = field::display(client_ip), "ip rate limited");
+                self.metrics.swaps_rejected.inc("ip_rate_limit");
+                return Err(Status::resource_exhausted(
+                    "too many swaps created, try again later",
+                ));
+            }
+        }
+
+        if !self.refund_pubkey_rate_limiter.check(refund_pubkey, now) {
+            debug!(
+                refund_pubkey = field::display(refund_pubkey),
+                "refund pubkey rate limited"
+            );
+            self.metrics.swaps_rejected.inc("refund_pubkey_rate_limit");
+            return Err(Status::resource_exhausted(
+                "too many swaps created, try again later",
+            ));
+        }
+
+        if self.max_unfunded_swaps > 0 {
+            let unfunded_swaps = self.swap_repository.count_unfunded_swaps().await?;
+            if unfunded_swaps >= self.max_unfunded_swaps {
+                warn!(
+                    unfunded_swaps,
+                    max_unfunded_swaps = self.max_unfunded_swaps,
+                    "max unfunded swaps reached, rejecting new swaps"
+                );
+                self.metrics.swaps_rejected.inc("max_unfunded_swaps");
+                return Err(Status::resource_exhausted(
+                    "swap service is busy, try again later",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     async fn get_swap_parameters(&self) -> Result<SwapParameters, Status> {
         let fee_estimate = self
             .fee_estimator
@@ -148,27 +360,369 @@ where
             max_swap_amount_sat: self.max_swap_amount_sat,
             min_swap_amount_sat: min_utxo_amount_sat,
             min_utxo_amount_sat,
+            fee_base_sat: self.fee_policy.base_sat,
+            fee_ppm: self.fee_policy.ppm,
+            fee_includes_claim_fee: self.fee_policy.include_claim_fee,
+            claim_fee_quote_seconds: match self.fee_policy.include_claim_fee {
+                true => self.claim_fee_quotes.validity().as_secs(),
+                false => 0,
+            },
         })
     }
+
+    /// Fee estimate for the claim of the swap with the given hash. If the swap
+    /// fee includes the claim fee, the payout amount depends on it. The fee
+    /// rate is then quoted per swap, so the amount GetSwapStatus returns still
+    /// matches in PaySwap while the quote is valid. Returns the quote expiry.
+    async fn claim_fee_estimate(
+        &self,
+        hash: &Hash,
+    ) -> Result<(FeeEstimate, Option<SystemTime>), Status> {
+        // Do a fee estimation with 6 blocks in order to check whether the swap
+        // is claimable within reasonable time.
+        let fee_estimate = self.fee_estimator.estimate_fee(6).await?;
+        if !self.fee_policy.include_claim_fee {
+            return Ok((fee_estimate, None));
+        }
+
+        Ok(
+            match self
+                .claim_fee_quotes
+                .quote(*hash, fee_estimate.sat_per_kw, SystemTime::now())
+            {
+                Some(quote) => (
+                    FeeEstimate {
+                        sat_per_kw: quote.sat_per_kw,
+                    },
+                    Some(quote.expires_at),
+                ),
+                None => (fee_estimate, None),
+            },
+        )
+    }
+
+    /// Determines which outputs of the swap would be paid out right now, and
+    /// for which amount. Shared by PaySwap and GetSwapStatus, so they agree on
+    /// whether a swap is payable. The outer error is an internal error, the
+    /// inner error is the reason the swap is not payable.
+    async fn swap_payout(
+        &self,
+        swap_state: &SwapState,
+        parameters: &SwapParameters,
+        current_height: u64,
+    ) -> Result<Result<SwapPayout, NotPayableReason>, Status> {
+        if swap_state.preimage.is_some() {
+            trace!("swap already had preimage");
+            return Ok(Err(NotPayableReason::AlreadyPaid));
+        }
+
+        if swap_state.expired {
+            trace!("swap expired unfunded");
+            return Ok(Err(NotPayableReason::Expired));
+        }
+
+        let txos = self
+            .chain_repository
+            .get_txos_for_address(&swap_state.swap.public.address)
+            .await?;
+        let unconfirmed_txos = self
+            .zero_conf_txos(swap_state, &txos, current_height)
+            .await?;
+
+        // Small swaps are paid out without waiting for confirmations.
+        let zero_conf = !unconfirmed_txos.is_empty();
+        let min_confirmations = match zero_conf {
+            true => 0,
+            false => self.min_confirmations,
+        };
+        let txos: Vec<Txo> = txos.into_iter().chain(unconfirmed_txos).collect();
+        if txos.is_empty() {
+            trace!("swap has no utxos");
+            return Ok(Err(NotPayableReason::NoOutputs));
+        }
+
+        let min_confirmation_height = match txos.iter().map(|txo| txo.block_height).min() {
+            Some(m) => m,
+            None => {
+                error!("swap had txos but no confirmations");
+                return Ok(Err(NotPayableReason::NoOutputs));
+            }
+        };
+
+        let blocks_left = match swap_state.blocks_left(min_confirmation_height, current_height) {
+            blocks_left if blocks_left < 0 => return Ok(Err(NotPayableReason::Expired)),
+            blocks_left => blocks_left as u32,
+        }
+        .saturating_sub(self.min_claim_blocks);
+
+        if blocks_left == 0 || blocks_left < self.min_viable_cltv {
+            trace!(
+                blocks_left,
+                min_viable_cltv = self.min_viable_cltv,
+                "payout blocks left too low"
+            );
+            return Ok(Err(NotPayableReason::Expired));
+        }
+
+        let mut insufficient_confirmations = false;
+        let txos = txos
+            .into_iter()
+            .filter(|txo| {
+                let confirmations = txo.confirmations(current_height);
+                if confirmations < min_confirmations {
+                    debug!(
+                        outpoint = field::display(txo.outpoint),
+                        confirmations, min_confirmations, "utxo has less than min confirmations"
+                    );
+                    insufficient_confirmations = true;
+                    return false;
+                }
+
+                if txo.tx_out.value.to_sat() < parameters.min_utxo_amount_sat {
+                    debug!(
+                        outpoint = field::display(txo.outpoint),
+                        utxo_amount_sat = txo.tx_out.value.to_sat(),
+                        min_utxo_amount_sat = parameters.min_utxo_amount_sat,
+                        "utxo value is below min_utxo_amount_sat"
+                    );
+                    return false;
+                }
+
+                trace!(
+                    outpoint = field::display(txo.outpoint),
+                    confirmations,
+                    min_confirmations,
+                    "utxo has correct amount of confirmations"
+                );
+                true
+            })
+            .collect::<Vec<Txo>>();
+
+        if txos.is_empty() {
+            return Ok(Err(match insufficient_confirmations {
+                true => NotPayableReason::InsufficientConfirmations,
+                false => NotPayableReason::ValueTooLow,
+            }));
+        }
+
+        // TODO: Filter utxos on sync?
+        let txos = match self.chain_filter_service.filter_txos(txos.clone()).await {
+            Ok(txos) => txos,
+            Err(e) => {
+                error!("failed to filter utxos: {:?}", e);
+                txos
+            }
+        };
+        if txos.is_empty() {
+            trace!("all utxos were filtered");
+            return Ok(Err(NotPayableReason::OutputsFiltered));
+        }
+
+        // Sum the utxo amounts.
+        let amount_sum_sat = txos
+            .iter()
+            .fold(0u64, |sum, utxo| sum + utxo.tx_out.value.to_sat());
+
+        let (fee_estimate, claim_fee_quote_expiry) = self
+            .claim_fee_estimate(&swap_state.swap.public.hash)
+            .await?;
+        let fake_address = Address::p2wpkh(
+            &CompressedPublicKey::from_slice(&[0x02; 33]).map_err(|e| {
+                error!("failed to create fake pubkey: {:?}", e);
+                Status::internal("internal error")
+            })?,
+            self.network,
+        );
+
+        // If the claim tx can be created, this is a valid swap.
+        let fake_claim_tx = match self.swap_service.estimate_claim_tx(
+            &txos
+                .iter()
+                .map(|utxo| ClaimableUtxo {
+                    swap: swap_state.swap.clone(),
+                    utxo: utxo.clone(),
+                    paid_with_request: None,
+                    preimage: FAKE_PREIMAGE,
+                })
+                .collect::<Vec<_>>(),
+            &fee_estimate,
+            current_height,
+            fake_address,
+        ) {
+            Ok(tx) => tx,
+            Err(e) => {
+                debug!("could not create valid fake claim tx: {:?}", e);
+                return Ok(Err(NotPayableReason::ValueTooLow));
+            }
+        };
+
+        // The user pays the swap fee by requesting less than the utxo value.
+        let claim_fee_sat = amount_sum_sat.saturating_sub(
+            fake_claim_tx
+                .output
+                .iter()
+                .fold(0u64, |sum, o| sum + o.value.to_sat()),
+        );
+        let fee_sat = self.fee_policy.fee_sat(amount_sum_sat, claim_fee_sat);
+        if fee_sat >= amount_sum_sat {
+            trace!(amount_sum_sat, fee_sat, "swap fee exceeds utxo values");
+            return Ok(Err(NotPayableReason::ValueTooLow));
+        }
+
+        Ok(Ok(SwapPayout {
+            txos,
+            amount_sat: amount_sum_sat - fee_sat,
+            fee_sat,
+            cltv_limit: blocks_left,
+            claim_fee_quote_expiry,
+        }))
+    }
+
+    fn zero_conf_enabled(&self) -> bool {
+        self.zero_conf_max_amount_sat > 0 && self.zero_conf_max_outstanding_sat > 0
+    }
+
+    /// Returns the unconfirmed outputs of the swap if the swap can be paid
+    /// out without confirmations. That is the case if zero-conf is enabled,
+    /// the swap outputs together don't exceed `zero_conf_max_amount_sat` and
+    /// paying them keeps all unconfirmed outputs paid out within
+    /// `zero_conf_max_outstanding_sat`. Any unconfirmed deposit can be double
+    /// spent, these limits bound what is lost if that happens. The outputs are
+    /// returned as if they confirm in the next block.
+    async fn zero_conf_txos(
+        &self,
+        swap_state: &SwapState,
+        confirmed: &[Txo],
+        current_height: u64,
+    ) -> Result<Vec<Txo>, Status> {
+        if !self.zero_conf_enabled() {
+            return Ok(Vec::new());
+        }
+
+        // Outputs that confirmed in the meantime are already in `confirmed`.
+        let mempool_txos: Vec<_> = self
+            .mempool_repository
+            .get_txos_for_address(&swap_state.swap.public.address)
+            .await?
+            .into_iter()
+            .filter(|txo| !confirmed.iter().any(|c| c.outpoint == txo.outpoint))
+            .collect();
+        if mempool_txos.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Nodes running full-RBF relay replacements regardless of signaling, so
+        // this is not a protection against double spends. Deposits that
+        // announce they may be replaced are just not worth the risk.
+        if let Some(txo) = mempool_txos.iter().find(|txo| txo.replaceable) {
+            trace!(
+                outpoint = field::display(txo.outpoint),
+                "unconfirmed utxo signals replaceability, not paying zero-conf"
+            );
+            return Ok(Vec::new());
+        }
+
+        let amount_sat = confirmed
+            .iter()
+            .map(|txo| txo.tx_out.value.to_sat())
+            .chain(mempool_txos.iter().map(|txo| txo.tx_out.value.to_sat()))
+            .sum::<u64>();
+        if amount_sat > self.zero_conf_max_amount_sat {
+            trace!(
+                amount_sat,
+                zero_conf_max_amount_sat = self.zero_conf_max_amount_sat,
+                "swap amount exceeds zero-conf max amount"
+            );
+            return Ok(Vec::new());
+        }
+
+        let unconfirmed_sat = mempool_txos
+            .iter()
+            .map(|txo| txo.tx_out.value.to_sat())
+            .sum::<u64>();
+        let outstanding_sat = self.mempool_repository.get_paid_amount().await?;
+        if outstanding_sat + unconfirmed_sat > self.zero_conf_max_outstanding_sat {
+            debug!(
+                outstanding_sat,
+                unconfirmed_sat,
+                zero_conf_max_outstanding_sat = self.zero_conf_max_outstanding_sat,
+                "outstanding zero-conf payouts would exceed the max, not paying zero-conf"
+            );
+            return Ok(Vec::new());
+        }
+
+        Ok(mempool_txos
+            .into_iter()
+            .map(|txo| Txo {
+                block_hash: BlockHash::all_zeros(),
+                block_height: current_height + 1,
+                outpoint: txo.outpoint,
+                tx_out: txo.tx_out,
+            })
+            .collect())
+    }
+}
+
+/// The outputs a swap payout pays for.
+struct SwapPayout {
+    txos: Vec<Txo>,
+    /// The amount to pay the user.
+    amount_sat: u64,
+    fee_sat: u64,
+    /// Blocks left to complete the lightning payment.
+    cltv_limit: u32,
+    /// Until when the claim fee included in `fee_sat` is honored.
+    claim_fee_quote_expiry: Option<SystemTime>,
+}
+
+fn not_payable_status(reason: NotPayableReason) -> Status {
+    match reason {
+        NotPayableReason::NoOutputs => Status::failed_precondition("no utxos found"),
+        NotPayableReason::InsufficientConfirmations => {
+            Status::failed_precondition("utxos have too few confirmations")
+        }
+        NotPayableReason::Expired => Status::failed_precondition("swap expired"),
+        NotPayableReason::ValueTooLow => Status::failed_precondition("value too low"),
+        NotPayableReason::OutputsFiltered => {
+            Status::failed_precondition("confirmed utxo values don't match invoice value")
+        }
+        NotPayableReason::AmountTooHigh => {
+            Status::invalid_argument("amount exceeds max swap amount")
+        }
+        NotPayableReason::AmountTooLow => {
+            Status::invalid_argument("amount is below min swap amount")
+        }
+        NotPayableReason::AlreadyPaid => Status::failed_precondition("swap already paid"),
+        NotPayableReason::PaymentInProgress | NotPayableReason::RefundInProgress => {
+            Status::failed_precondition("swap is locked")
+        }
+    }
 }
 #[tonic::async_trait]
-impl<C, CF, CR, L, P, R, RP, F> TaprootSwapper for SwapServer<C, CF, CR, L, P, R, RP, F>
+impl<C, CF, CL, CR, L, MR, P, R, RP, F> TaprootSwapper
+    for SwapServer<C, CF, CL, CR, L, MR, P, R, RP, F>
 where
     C: ChainClient + Debug + Send + Sync + 'static,
     CF: ChainFilterService + Debug + Send + Sync + 'static,
+    CL: ClaimRepository + Debug + Send + Sync + 'static,
     CR: ChainRepository + Debug + Send + Sync + 'static,
     L: LightningClient + Debug + Send + Sync + 'static,
+    MR: MempoolRepository + Debug + Send + Sync + 'static,
     P: PrivateKeyProvider + Debug + Send + Sync + 'static,
     R: SwapRepository + Debug + Send + Sync + 'static,
     RP: RandomProvider + Debug + Send + Sync + 'static,
     F: FeeEstimator + Debug + Send + Sync + 'static,
 {
+    type SubscribeSwapEventsStream =
+        Pin<Box<dyn Stream<Item = Result<SwapEvent, Status>> + Send + 'static>>;
+
     #[instrument(skip(self), level = "debug")]
     async fn create_swap(
         &self,
         request: Request<CreateSwapRequest>,
     ) -> Result<Response<CreateSwapResponse>, Status> {
         debug!("create_swap request");
+        let client_ip = self.client_ip(&request);
         let req = request.into_inner();
         let payer_pubkey = PublicKey::from_slice(&req.refund_pubkey).map_err(|_| {
             trace!("got invalid refund_pubkey");
@@ -178,6 +732,8 @@ where
             trace!("got invalid hash");
             Status::invalid_argument("invalid hash")
         })?;
+        self.check_create_swap_limits(client_ip, payer_pubkey)
+            .await?;
 
         // Get a fee estimate for the next block to account for worst case fees.
         let current_height = self.chain_client.get_blockheight().await?;
@@ -185,6 +741,7 @@ where
         let swap = self
             .swap_service
             .create_swap(payer_pubkey, hash, current_height)
+            .await
             .map_err(|e| {
                 error!("failed to create swap: {:?}", e);
                 Status::internal("internal error")
@@ -200,6 +757,7 @@ where
             address = field::display(&swap.public.address),
             "new swap created"
         );
+        self.metrics.swaps_created.inc();
 
         let parameters = self.get_swap_parameters().await?;
         Ok(Response::new(CreateSwapResponse {
@@ -261,11 +819,6 @@ where
 
         let hash = invoice.payment_hash();
         let swap_state = self.swap_repository.get_swap_by_hash(hash).await?;
-        if swap_state.preimage.is_some() {
-            trace!("swap already had preimage");
-            return Err(Status::failed_precondition("swap already paid"));
-        }
-
         let min_final_cltv_expiry_delta: u32 = invoice
             .min_final_cltv_expiry_delta()
             .try_into()
@@ -274,36 +827,23 @@ where
                 Status::invalid_argument("min_final_cltv_expiry_delta too high")
             })?;
 
-        let txos = self
-            .chain_repository
-            .get_txos_for_address(&swap_state.swap.public.address)
-            .await?;
-
-        if txos.is_empty() {
-            trace!("swap has no utxos");
-            return Err(Status::failed_precondition("no utxos found"));
-        }
-
-        let min_confirmation_height = match txos.iter().map(|txo| txo.block_height).min() {
-            Some(m) => m,
-            None => {
-                error!("swap had txos but no confirmations");
-                return Err(Status::failed_precondition("no utxos found"));
-            }
+        let zero_conf_guard = match self.zero_conf_enabled() {
+            true => Some(self.zero_conf_lock.lock().await),
+            false => None,
         };
-
         let current_height = self.chain_client.get_blockheight().await?;
-        let blocks_left = match swap_state.blocks_left(min_confirmation_height, current_height) {
-            blocks_left if blocks_left < 0 => {
-                return Err(Status::failed_precondition("swap expired"))
-            }
-            blocks_left => blocks_left as u32,
-        }
-        .saturating_sub(self.min_claim_blocks);
+        let SwapPayout {
+            txos,
+            amount_sat: payout_amount_sat,
+            fee_sat,
+            cltv_limit: blocks_left,
+            ..
+        } = self
+            .swap_payout(&swap_state, &parameters, current_height)
+            .await?
+            .map_err(not_payable_status)?;
 
-        if blocks_left == 0
-            || blocks_left.saturating_sub(min_final_cltv_expiry_delta) < self.min_viable_cltv
-        {
+        if blocks_left.saturating_sub(min_final_cltv_expiry_delta) < self.min_viable_cltv {
             trace!(
                 blocks_left,
                 min_viable_cltv = self.min_viable_cltv,
@@ -312,97 +852,18 @@ where
             return Err(Status::failed_precondition("swap expired"));
         }
 
-        let txos = txos
-            .into_iter()
-            .filter(|txo| {
-                let confirmations = txo.confirmations(current_height);
-                if confirmations < self.min_confirmations {
-                    debug!(
-                        outpoint = field::display(txo.outpoint),
-                        confirmations,
-                        min_confirmations = self.min_confirmations,
-                        "utxo has less than min confirmations"
-                    );
-                    return false;
-                }
-
-                if txo.tx_out.value.to_sat() < parameters.min_utxo_amount_sat {
-                    debug!(
-                        outpoint = field::display(txo.outpoint),
-                        utxo_amount_sat = txo.tx_out.value.to_sat(),
-                        min_utxo_amount_sat = parameters.min_utxo_amount_sat,
-                        "utxo value is below min_utxo_amount_sat"
-                    );
-                    return false;
-                }
-
-                trace!(
-                    outpoint = field::display(txo.outpoint),
-                    confirmations,
-                    min_confirmations = self.min_confirmations,
-                    "utxo has correct amount of confirmations"
-                );
-                true
-            })
-            .collect::<Vec<Txo>>();
-
-        // TODO: Filter utxos on sync?
-        let txos = match self.chain_filter_service.filter_txos(txos.clone()).await {
-            Ok(txos) => txos,
-            Err(e) => {
-                error!("failed to filter utxos: {:?}", e);
-                txos
-            }
-        };
-
-        // TODO: Add ability to charge a fee?
-        // Sum the utxo amounts.
-        let amount_sum_sat = txos
-            .iter()
-            .fold(0u64, |sum, utxo| sum + utxo.tx_out.value.to_sat());
-        if amount_sum_sat != amount_sat {
+        if payout_amount_sat != amount_sat {
             trace!(
-                amount_sum_sat,
+                payout_amount_sat,
+                fee_sat,
                 amount_sat,
-                "utxo values don't match invoice value"
+                "utxo values minus fee don't match invoice value"
             );
             return Err(Status::failed_precondition(
                 "confirmed utxo values don't match invoice value",
             ));
         }
 
-        // Do a fee estimation with 6 blocks in order to check whether the swap
-        // is claimable within reasonable time.
-        let fee_estimate = self.fee_estimator.estimate_fee(6).await?;
-        let fake_address = Address::p2wpkh(
-            &CompressedPublicKey::from_slice(&[0x02; 33]).map_err(|e| {
-                error!("failed to create fake pubkey: {:?}", e);
-                Status::internal("internal error")
-            })?,
-            self.network,
-        );
-
-        // If the claim tx can be created, this is a valid swap.
-        self.swap_service
-            .create_claim_tx(
-                &txos
-                    .iter()
-                    .map(|utxo| ClaimableUtxo {
-                        swap: swap_state.swap.clone(),
-                        utxo: utxo.clone(),
-                        paid_with_request: None,
-                        preimage: FAKE_PREIMAGE,
-                    })
-                    .collect::<Vec<_>>(),
-                &fee_estimate,
-                current_height,
-                fake_address,
-            )
-            .map_err(|e| {
-                debug!("could not create valid fake claim tx: {:?}", e);
-                Status::failed_precondition("value too low")
-            })?;
-
         // Store the payment attempt to ensure not 'too many' utxos are claimed
         // on claim if a user accidentally sends multiple utxos to the same
         // address.
@@ -419,6 +880,7 @@ where
             .swap_repository
             .lock_add_payment_attempt(&PaymentAttempt {
                 amount_msat,
+                fee_sat,
                 creation_time: now,
                 label: label.clone(),
                 destination: invoice.get_payee_pub_key(),
@@ -438,6 +900,7 @@ where
                 return Err(Status::internal("internal error"));
             }
         };
+        drop(zero_conf_guard);
 
         // Pay the user. After the payment succeeds, we will have paid the
         // funds, but not claimed anything onchain yet. That will happen in the
@@ -448,7 +911,8 @@ where
                 .saturating_mul(self.pay_fee_limit_ppm)
                 .saturating_div(1_000_000);
         debug!("about to pay");
-        let pay_result = self
+        let pay_start = Instant::now();
+        let pay_result = match self
             .lightning_client
             .pay(PaymentRequest {
                 bolt11: req.payment_request,
@@ -458,7 +922,14 @@ where
                 fee_limit_msat,
                 timeout_seconds: self.pay_timeout_seconds,
             })
-            .await?;
+            .await
+        {
+            Ok(pay_result) => pay_result,
+            Err(e) => {
+                self.metrics.payment_failures.inc("lightning_error");
+                return Err(e.into());
+            }
+        };
 
         // Persist the preimage right away. There's also a background service
         // checking for preimages, in case the `pay` call failed, but the
@@ -484,13 +955,32 @@ where
                 info!(
                     label = field::display(&label),
                     hash = field::display(hash),
-                    address = field::display(swap_state.swap.public.address),
+                    fee_sat,
+                    address = field::display(&swap_state.swap.public.address),
                     "successfully paid"
                 );
+                self.metrics.swaps_paid.inc();
+                self.metrics
+                    .payout_latency
+                    .observe_duration(pay_start.elapsed());
+                self.event_bus.publish(event::SwapEvent::new(
+                    swap_state.swap.public.address,
+                    SwapEventKind::Paid {
+                        payment_hash: *hash,
+                    },
+                ));
                 PaySwapResponse::default()
             }
             PaymentResult::Failure { error } => {
                 info!("payment failed with: {}", error);
+                self.metrics.payment_failures.inc(&error);
+                self.event_bus.publish(event::SwapEvent::new(
+                    swap_state.swap.public.address,
+                    SwapEventKind::PaymentFailed {
+                        payment_hash: *hash,
+                        error,
+                    },
+                ));
                 return Err(Status::unknown("payment failed"));
             }
         };
@@ -569,9 +1059,11 @@ where
             return Err(Status::invalid_argument("invalid transaction input"));
         }
 
+        let refund_outpoint = tx.input[input_index].previous_output;
         let (partial_signature, our_pub_nonce) = self
             .swap_service
             .partial_sign_refund_tx(&swap.swap, tx, prevouts, input_index, their_pub_nonce)
+            .await
             .map_err(|e| {
                 error!("failed to sign refund transaction: {:?}", e);
                 Status::internal("internal error")
@@ -605,11 +1097,34 @@ where
         {
             Ok(false) => {}
             Ok(true) => {
-                let _ = self
-                    .swap_repository
-                    .unlock_swap_refund(&swap.swap.public.hash, &refund_id)
-                    .await;
-                return Err(Status::failed_precondition("swap is locked"));
+                // Outputs of a paid swap that were not paid over lightning can
+                // still be refunded, as long as they were not donated. Marking
+                // them refunded ensures they are never claimed.
+                if let Err(e) = self
+                    .claim_service
+                    .set_output_disposition(&refund_outpoint, OutputDisposition::Refunded)
+                    .await
+                {
+                    let _ = self
+                        .swap_repository
+                        .unlock_swap_refund(&swap.swap.public.hash, &refund_id)
+                        .await;
+                    return match e {
+                        ClaimRepositoryError::UnpaidOutputNotFound
+                        | ClaimRepositoryError::DispositionConflict(_) => {
+                            Err(Status::failed_precondition("swap is locked"))
+                        }
+                        e => {
+                            error!("failed to mark unpaid output refunded: {:?}", e);
+                            Err(Status::internal("internal error"))
+                        }
+                    };
+                }
+
+                info!(
+                    outpoint = field::display(refund_outpoint),
+                    "signed cooperative refund for unpaid output"
+                );
             }
             Err(e) => {
                 error!("failed to check for pending or complete payment: {:?}", e);
@@ -627,6 +1142,88 @@ where
         }))
     }
 
+    #[instrument(skip(self), level = "debug")]
+    async fn claim_swap_details(
+        &self,
+        request: Request<ClaimSwapDetailsRequest>,
+    ) -> Result<Response<ClaimSwapDetailsResponse>, Status> {
+        debug!("claim_swap_details request");
+        let req = request.into_inner();
+        let (_, swap) = self
+            .authenticate_swap(&req.address, req.timestamp, &req.signature)
+            .await?;
+        let (tx, pub_nonces) = self
+            .claim_service
+            .get_cooperative_claim(&swap.swap.public.hash)?;
+        let mut transaction = Vec::new();
+        tx.consensus_encode(&mut transaction).map_err(|e| {
+            error!("failed to encode claim transaction: {:?}", e);
+            Status::internal("internal error")
+        })?;
+
+        Ok(Response::new(ClaimSwapDetailsResponse {
+            transaction,
+            pub_nonces: pub_nonces
+                .iter()
+                .map(|nonce| nonce.serialize().to_vec())
+                .collect(),
+        }))
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn claim_swap(
+        &self,
+        request: Request<ClaimSwapRequest>,
+    ) -> Result<Response<ClaimSwapResponse>, Status> {
+        debug!("claim_swap request");
+        let req = request.into_inner();
+        let their_pub_nonces = req
+            .pub_nonces
+            .iter()
+            .map(|nonce| MusigPubNonce::from_slice(nonce))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| {
+                trace!("got invalid pub nonce: {:?}", e);
+                Status::invalid_argument("invalid pub_nonces")
+            })?;
+        let their_partial_sigs = req
+            .partial_signatures
+            .iter()
+            .map(|sig| MusigPartialSignature::from_slice(sig))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| {
+                trace!("got invalid partial signature: {:?}", e);
+                Status::invalid_argument("invalid partial_signatures")
+            })?;
+
+        let (address, swap) = self
+            .authenticate_swap(&req.address, req.timestamp, &req.signature)
+            .await?;
+        let tx = self
+            .claim_service
+            .complete_cooperative_claim(
+                &swap.swap.public.hash,
+                &their_pub_nonces,
+                &their_partial_sigs,
+            )
+            .await?;
+        info!(
+            address = field::display(&address),
+            tx_id = field::display(tx.compute_txid()),
+            "broadcast cooperative claim tx"
+        );
+        self.event_bus.publish(event::SwapEvent::new(
+            address,
+            SwapEventKind::ClaimBroadcast {
+                tx_id: tx.compute_txid(),
+            },
+        ));
+
+        Ok(Response::new(ClaimSwapResponse {
+            tx_id: tx.compute_txid().to_string(),
+        }))
+    }
+
     async fn swap_parameters(
         &self,
         _request: Request<SwapParametersRequest>,
@@ -636,6 +1233,243 @@ where
             parameters: Some(parameters),
         }))
     }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn get_swap_status(
+        &self,
+        request: Request<GetSwapStatusRequest>,
+    ) -> Result<Response<GetSwapStatusResponse>, Status> {
+        debug!("get_swap_status request");
+        let req = request.into_inner();
+        let (address, swap_state) = self
+            .authenticate_swap(&req.address, req.timestamp, &req.signature)
+            .await?;
+
+        let hash = swap_state.swap.public.hash;
+        let current_height = self.chain_client.get_blockheight().await?;
+        let parameters = self.get_swap_parameters().await?;
+        let txos = self
+            .chain_repository
+            .get_txos_for_address_with_spends(&address)
+            .await?;
+        let mempool_txos: Vec<_> = self
+            .mempool_repository
+            .get_txos_for_address(&address)
+            .await?
+            .into_iter()
+            .filter(|txo| !txos.iter().any(|t| t.txo.outpoint == txo.outpoint))
+            .collect();
+        let locks = self
+            .swap_repository
+            .get_swap_locks(&hash)
+            .await
+            .map_err(|e| {
+                error!("failed to get swap locks: {:?}", e);
+                Status::internal("internal error")
+            })?;
+        let payment_attempts = self
+            .swap_repository
+            .get_swap_payment_attempts(&hash)
+            .await
+            .map_err(|e| {
+                error!("failed to get payment attempts: {:?}", e);
+                Status::internal("internal error")
+            })?;
+
+        let payout = match self
+            .swap_payout(&swap_state, &parameters, current_height)
+            .await?
+        {
+            Ok(payout) if payout.amount_sat > parameters.max_swap_amount_sat => {
+                Err(NotPayableReason::AmountTooHigh)
+            }
+            Ok(payout) if payout.amount_sat < parameters.min_swap_amount_sat => {
+                Err(NotPayableReason::AmountTooLow)
+            }
+            Ok(_) if locks.iter().any(|l| l.refund_id.is_some()) => {
+                Err(NotPayableReason::RefundInProgress)
+            }
+            Ok(_) if !locks.is_empty() => Err(NotPayableReason::PaymentInProgress),
+            payout => payout,
+        };
+
+        let payout_state = if swap_state.preimage.is_some() {
+            PayoutState::Succeeded
+        } else if payment_attempts.iter().any(|a| a.result.is_none()) {
+            PayoutState::Pending
+        } else if payment_attempts.is_empty() {
+            PayoutState::NotStarted
+        } else {
+            PayoutState::Failed
+        };
+
+        let blocks_until_refund = txos
+            .iter()
+            .map(|txo| txo.txo.block_height)
+            .min()
+            .map(|height| swap_state.blocks_left(height, current_height));
+
+        Ok(Response::new(GetSwapStatusResponse {
+            outputs: txos
+                .into_iter()
+                .map(|txo| SwapStatusOutput {
+                    outpoint: txo.txo.outpoint.to_string(),
+                    amount_sat: txo.txo.tx_out.value.to_sat(),
+                    confirmations: txo.txo.confirmations(current_height),
+                    spending_tx_id: txo.spend.map(|spend| spend.spending_tx.to_string()),
+                })
+                .chain(mempool_txos.into_iter().map(|txo| SwapStatusOutput {
+                    outpoint: txo.outpoint.to_string(),
+                    amount_sat: txo.tx_out.value.to_sat(),
+                    confirmations: 0,
+                    spending_tx_id: None,
+                }))
+                .collect(),
+            current_height,
+            blocks_until_refund,
+            payable: payout.is_ok(),
+            not_payable_reason: payout.as_ref().err().map(|reason| *reason as i32),
+            payable_amount_sat: payout.as_ref().ok().map(|payout| payout.amount_sat),
+            fee_sat: payout.as_ref().ok().map(|payout| payout.fee_sat),
+            payout_state: payout_state.into(),
+            claim_fee_quote_expiry: payout
+                .as_ref()
+                .ok()
+                .and_then(|payout| payout.claim_fee_quote_expiry)
+                .and_then(|expiry| expiry.duration_since(UNIX_EPOCH).ok())
+                .map(|expiry| expiry.as_secs()),
+        }))
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn subscribe_swap_events(
+        &self,
+        request: Request<SubscribeSwapEventsRequest>,
+    ) -> Result<Response<Self::SubscribeSwapEventsStream>, Status> {
+        debug!("subscribe_swap_events request");
+        let client_ip = self.client_ip(&request);
+        let req = request.into_inner();
+        let (address, _) = self
+            .authenticate_swap(&req.address, req.timestamp, &req.signature)
+            .await?;
+
+        // The permit is released when the client disconnects and the stream
+        // is dropped.
+        let permit = match client_ip {
+            Some(client_ip) => match self.event_subscription_limiter.acquire(client_ip) {
+                Some(permit) => Some(permit),
+                None => {
+                    debug!(
+                        client_ip = field::display(client_ip),
+                        "too many event subscriptions"
+                    );
+                    return Err(Status::resource_exhausted("too many subscriptions"));
+                }
+            },
+            None => None,
+        };
+
+        let stream = self.event_bus.subscribe().filter_map(move |event| {
+            let _permit = &permit;
+            let event = match event {
+                Ok(event) if event.address == address => Some(Ok(map_swap_event(event))),
+                Ok(_) => None,
+                Err(e) => Some(Err(e.into())),
+            };
+            futures::future::ready(event)
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+fn map_swap_event(event: event::SwapEvent) -> SwapEvent {
+    let kind = match event.kind {
+        SwapEventKind::DepositConfirmed {
+            outpoint,
+            amount,
+            block_height,
+        } => swap_event::Event::DepositConfirmed(DepositConfirmed {
+            outpoint: outpoint.to_string(),
+            amount_sat: amount.to_sat(),
+            block_height,
+        }),
+        SwapEventKind::DepositUnconfirmed {
+            outpoint,
+            amount,
+            replaceable,
+        } => swap_event::Event::DepositUnconfirmed(DepositUnconfirmed {
+            outpoint: outpoint.to_string(),
+            amount_sat: amount.to_sat(),
+            replaceable,
+        }),
+        SwapEventKind::DepositDropped { outpoint } => {
+            swap_event::Event::DepositDropped(DepositDropped {
+                outpoint: outpoint.to_string(),
+            })
+        }
+        SwapEventKind::RefundOffered { outpoint, amount } => {
+            swap_event::Event::RefundOffered(RefundOffered {
+                outpoint: outpoint.to_string(),
+                amount_sat: amount.to_sat(),
+            })
+        }
+        SwapEventKind::Paid { .. } => swap_event::Event::Paid(SwapPaid {}),
+        SwapEventKind::PaymentFailed { .. } => {
+            swap_event::Event::PaymentFailed(SwapPaymentFailed {})
+        }
+        SwapEventKind::ClaimBroadcast { tx_id } => {
+            swap_event::Event::ClaimBroadcast(ClaimBroadcast {
+                tx_id: tx_id.to_string(),
+            })
+        }
+        SwapEventKind::OutputSpent {
+            outpoint,
+            spending_tx_id,
+            block_height,
+        } => swap_event::Event::OutputSpent(OutputSpent {
+            outpoint: outpoint.to_string(),
+            spending_tx_id: spending_tx_id.to_string(),
+            block_height,
+        }),
+    };
+
+    SwapEvent {
+        address: event.address.to_string(),
+        timestamp: event
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        event: Some(kind),
+    }
+}
+
+impl From<EventBusError> for Status {
+    fn from(value: EventBusError) -> Self {
+        match value {
+            EventBusError::Lagged(n) => {
+                debug!(dropped = n, "event subscriber lagged behind");
+                Status::resource_exhausted("subscriber lagged behind, resubscribe")
+            }
+        }
+    }
+}
+
+impl From<CooperativeClaimError> for Status {
+    fn from(value: CooperativeClaimError) -> Self {
+        match value {
+            CooperativeClaimError::NotFound => {
+                Status::failed_precondition("no cooperative claim pending")
+            }
+            CooperativeClaimError::InvalidSignature => {
+                Status::invalid_argument("invalid partial signature")
+            }
+            CooperativeClaimError::General(e) => {
+                error!("failed to complete cooperative claim: {:?}", e);
+                Status::internal("internal error")
+            }
+        }
+    }
 }
 
 impl From<SwapPersistenceError> for Status {
@@ -736,6 +1570,13 @@ impl From<ChainRepositoryError> for Status {
     }
 }
 
+impl From<MempoolRepositoryError> for Status {
+    fn from(value: MempoolRepositoryError) -> Self {
+        error!("failed to get mempool data: {:?}", value);
+        Status::internal("internal error")
+    }
+}
+
 impl From<RandomError> for Status {
     fn from(value: RandomError) -> Self {
         error!("random error: {:?}", value);
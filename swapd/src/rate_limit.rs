@@ -0,0 +1,222 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Limits the number of requests per key within a fixed time window. A limit
+/// of 0 disables the limiter. Keys whose window passed are dropped once per
+/// window, so memory is bounded by the keys seen in the last window.
+#[derive(Debug)]
+pub struct RateLimiter<K> {
+    limit: u32,
+    window: Duration,
+    state: Mutex<RateLimiterState<K>>,
+}
+
+#[derive(Debug)]
+struct RateLimiterState<K> {
+    last_prune: Option<Instant>,
+    windows: HashMap<K, (Instant, u32)>,
+}
+
+impl<K> RateLimiter<K>
+where
+    K: Eq + Hash,
+{
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            state: Mutex::new(RateLimiterState {
+                last_prune: None,
+                windows: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Records a request for `key` at `now`. Returns false if the key already
+    /// reached its limit in the current window, in which case the request is
+    /// not counted.
+    pub fn check(&self, key: K, now: Instant) -> bool {
+        if self.limit == 0 {
+            return true;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let prune = match state.last_prune {
+            Some(last_prune) => now.saturating_duration_since(last_prune) >= self.window,
+            None => true,
+        };
+        if prune {
+            let window = self.window;
+            state
+                .windows
+                .retain(|_, (start, _)| now.saturating_duration_since(*start) < window);
+            state.last_prune = Some(now);
+        }
+
+        let (start, count) = state.windows.entry(key).or_insert((now, 0));
+        if now.saturating_duration_since(*start) >= self.window {
+            *start = now;
+            *count = 0;
+        }
+
+        if *count >= self.limit {
+            return false;
+        }
+
+        *count += 1;
+        true
+    }
+}
+
+/// Limits the number of concurrently held permits per key. A limit of 0
+/// disables the limiter. Keys without permits are dropped, so memory is bounded
+/// by the permits held.
+#[derive(Debug)]
+pub struct ConcurrencyLimiter<K> {
+    limit: u32,
+    active: Arc<Mutex<HashMap<K, u32>>>,
+}
+
+/// Counts towards the limit of its key until dropped.
+#[derive(Debug)]
+pub struct ConcurrencyPermit<K>
+where
+    K: Eq + Hash,
+{
+    key: Option<K>,
+    active: Arc<Mutex<HashMap<K, u32>>>,
+}
+
+impl<K> ConcurrencyLimiter<K>
+where
+    K: Eq + Hash + Clone,
+{
+    pub fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            active: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Returns a permit for `key`, or None if the key already holds `limit`
+    /// permits.
+    pub fn acquire(&self, key: K) -> Option<ConcurrencyPermit<K>> {
+        if self.limit == 0 {
+            return Some(ConcurrencyPermit {
+                key: None,
+                active: Arc::clone(&self.active),
+            });
+        }
+
+        let mut active = self.active.lock().unwrap();
+        let count = active.entry(key.clone()).or_insert(0);
+        if *count >= self.limit {
+            return None;
+        }
+
+        *count += 1;
+        Some(ConcurrencyPermit {
+            key: Some(key),
+            active: Arc::clone(&self.active),
+        })
+    }
+}
+
+impl<K> Drop for ConcurrencyPermit<K>
+where
+    K: Eq + Hash,
+{
+    fn drop(&mut self) {
+        let key = match self.key.take() {
+            Some(key) => key,
+            None => return,
+        };
+        let mut active = self.active.lock().unwrap();
+        if let Some(count) = active.get_mut(&key) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                active.remove(&key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{ConcurrencyLimiter, RateLimiter};
+
+    #[test]
+    fn test_limit_per_key() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let now = Instant::now();
+        assert!(limiter.check("a", now));
+        assert!(limiter.check("a", now));
+        assert!(!limiter.check("a", now));
+        assert!(limiter.check("b", now));
+    }
+
+    #[test]
+    fn test_window_resets() {
+        let limiter = RateLimiter::new(1, Duration::from_secs(60));
+        let now = Instant::now();
+        assert!(limiter.check("a", now));
+        assert!(!limiter.check("a", now + Duration::from_secs(59)));
+        assert!(limiter.check("a", now + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_zero_disables() {
+        let limiter = RateLimiter::new(0, Duration::from_secs(60));
+        let now = Instant::now();
+        for _ in 0..100 {
+            assert!(limiter.check("a", now));
+        }
+    }
+
+    #[test]
+    fn test_stale_keys_pruned() {
+        let limiter = RateLimiter::new(1, Duration::from_secs(60));
+        let now = Instant::now();
+        assert!(limiter.check("a", now));
+        assert!(limiter.check("b", now + Duration::from_secs(30)));
+        assert!(limiter.check("c", now + Duration::from_secs(61)));
+        let windows = &limiter.state.lock().unwrap().windows;
+        assert!(!windows.contains_key("a"));
+        assert!(windows.contains_key("b"));
+        assert!(windows.contains_key("c"));
+    }
+
+    #[test]
+    fn test_concurrency_limit_per_key() {
+        let limiter = ConcurrencyLimiter::new(2);
+        let first = limiter.acquire("a").unwrap();
+        let _second = limiter.acquire("a").unwrap();
+        assert!(limiter.acquire("a").is_none());
+        assert!(limiter.acquire("b").is_some());
+
+        drop(first);
+        assert!(limiter.acquire("a").is_some());
+    }
+
+    #[test]
+    fn test_concurrency_released_keys_removed() {
+        let limiter = ConcurrencyLimiter::new(1);
+        let permit = limiter.acquire("a").unwrap();
+        drop(permit);
+        assert!(limiter.active.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_concurrency_zero_disables() {
+        let limiter = ConcurrencyLimiter::new(0);
+        let permits: Vec<_> = (0..100).map(|_| limiter.acquire("a").unwrap()).collect();
+        assert_eq!(permits.len(), 100);
+        assert!(limiter.active.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,7 @@
+mod monitor;
+mod repository;
+
+pub use monitor::{RetentionMonitor, RetentionMonitorParams};
+pub use repository::{
+    CompletedSwap, CompletionReason, RetentionRepository, RetentionRepositoryError,
+};
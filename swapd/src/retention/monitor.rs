@@ -0,0 +1,154 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, field, info};
+
+use crate::{chain::ChainRepository, metrics::Metrics};
+
+use super::RetentionRepository;
+
+pub struct RetentionMonitorParams<CR, RR>
+where
+    CR: ChainRepository,
+    RR: RetentionRepository,
+{
+    pub batch_size: u32,
+    pub chain_repository: Arc<CR>,
+    pub metrics: Arc<Metrics>,
+    pub min_confirmations: u64,
+    pub poll_interval: Duration,
+    pub retention_repository: Arc<RR>,
+    pub retention_period: Option<Duration>,
+}
+
+/// Completes swaps that need no more attention and stops watching their
+/// addresses. A swap is completed when it expired unfunded, or when all its
+/// outputs are spent with at least `min_confirmations`, so a reorg won't bring
+/// them back. Completed swaps are moved to the archive tables once they are
+/// older than `retention_period`, if set.
+pub struct RetentionMonitor<CR, RR>
+where
+    CR: ChainRepository,
+    RR: RetentionRepository,
+{
+    batch_size: u32,
+    chain_repository: Arc<CR>,
+    metrics: Arc<Metrics>,
+    min_confirmations: u64,
+    poll_interval: Duration,
+    retention_repository: Arc<RR>,
+    retention_period: Option<Duration>,
+}
+
+impl<CR, RR> RetentionMonitor<CR, RR>
+where
+    CR: ChainRepository,
+    RR: RetentionRepository,
+{
+    pub fn new(params: RetentionMonitorParams<CR, RR>) -> Self {
+        Self {
+            batch_size: params.batch_size,
+            chain_repository: params.chain_repository,
+            metrics: params.metrics,
+            min_confirmations: params.min_confirmations,
+            poll_interval: params.poll_interval,
+            retention_repository: params.retention_repository,
+            retention_period: params.retention_period,
+        }
+    }
+
+    pub async fn start(&self, token: CancellationToken) -> Result<(), Box<dyn std::error::Error>> {
+        loop {
+            if let Err(e) = self.complete_swaps().await {
+                error!("failed to complete swaps: {:?}", e);
+            }
+
+            if let Err(e) = self.archive_swaps(&token).await {
+                error!("failed to archive swaps: {:?}", e);
+            }
+
+            tokio::select! {
+                _ = token.cancelled() => {
+                    debug!("retention monitor shutting down");
+                    break;
+                }
+                _ = tokio::time::sleep(self.poll_interval) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn complete_swaps(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let tip = match self.chain_repository.get_tip().await? {
+            Some(tip) => tip,
+            None => {
+                debug!("chain not synced yet, not completing swaps");
+                return Ok(());
+            }
+        };
+
+        // A spend in the tip block has 1 confirmation.
+        let max_spend_height = match (tip.height + 1).checked_sub(self.min_confirmations) {
+            Some(max_spend_height) => max_spend_height,
+            None => return Ok(()),
+        };
+        let completed = self
+            .retention_repository
+            .complete_swaps(max_spend_height)
+            .await?;
+        for swap in &completed {
+            self.metrics.swaps_completed.inc(swap.reason.as_str());
+            debug!(
+                hash = field::display(swap.hash),
+                address = field::display(&swap.address),
+                reason = swap.reason.as_str(),
+                "swap completed"
+            );
+        }
+
+        if !completed.is_empty() {
+            info!(count = completed.len(), "completed swaps");
+        }
+
+        Ok(())
+    }
+
+    async fn archive_swaps(
+        &self,
+        token: &CancellationToken,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let retention_period = match self.retention_period {
+            Some(retention_period) => retention_period,
+            None => return Ok(()),
+        };
+        let completed_before = match SystemTime::now().checked_sub(retention_period) {
+            Some(completed_before) => completed_before,
+            None => return Ok(()),
+        };
+
+        // Archive in batches, so a large backlog doesn't end up in a single
+        // huge transaction.
+        let mut total = 0;
+        while !token.is_cancelled() {
+            let archived = self
+                .retention_repository
+                .archive_swaps(completed_before, self.batch_size)
+                .await?;
+            self.metrics.swaps_archived.inc_by(archived);
+            total += archived;
+            if archived == 0 || archived < self.batch_size as u64 {
+                break;
+            }
+        }
+
+        if total > 0 {
+            info!(count = total, "archived completed swaps");
+        }
+
+        Ok(())
+    }
+}
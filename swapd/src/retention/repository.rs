@@ -0,0 +1,80 @@
+use std::time::SystemTime;
+
+use bitcoin::{hashes::sha256, Address};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RetentionRepositoryError {
+    #[error("invalid completion reason: {0}")]
+    InvalidCompletionReason(String),
+    #[error("{0}")]
+    General(Box<dyn std::error::Error + Sync + Send>),
+}
+
+/// Why a swap no longer needs to be watched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompletionReason {
+    /// The swap outputs were claimed by the swapper.
+    Claimed,
+    /// The swap outputs were spent by the user, through a cooperative or
+    /// unilateral refund.
+    Refunded,
+    /// The swap was never funded and expired.
+    Expired,
+}
+
+impl CompletionReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompletionReason::Claimed => "claimed",
+            CompletionReason::Refunded => "refunded",
+            CompletionReason::Expired => "expired",
+        }
+    }
+}
+
+impl std::str::FromStr for CompletionReason {
+    type Err = RetentionRepositoryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "claimed" => CompletionReason::Claimed,
+            "refunded" => CompletionReason::Refunded,
+            "expired" => CompletionReason::Expired,
+            _ => {
+                return Err(RetentionRepositoryError::InvalidCompletionReason(
+                    s.to_string(),
+                ))
+            }
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CompletedSwap {
+    pub address: Address,
+    pub hash: sha256::Hash,
+    pub reason: CompletionReason,
+}
+
+#[async_trait::async_trait]
+pub trait RetentionRepository {
+    /// Marks swaps as completed that expired unfunded, or whose outputs are
+    /// all spent by transactions confirmed at or below `max_spend_height`.
+    /// Removes the addresses of the completed swaps from the watch addresses
+    /// in the same transaction.
+    async fn complete_swaps(
+        &self,
+        max_spend_height: u64,
+    ) -> Result<Vec<CompletedSwap>, RetentionRepositoryError>;
+
+    /// Moves up to `batch_size` swaps completed before `completed_before` to
+    /// the archive tables, together with their payment attempts, outputs and
+    /// the claims spending those outputs. Returns the number of archived
+    /// swaps.
+    async fn archive_swaps(
+        &self,
+        completed_before: SystemTime,
+        batch_size: u32,
+    ) -> Result<u64, RetentionRepositoryError>;
+}
@@ -0,0 +1,8 @@
+mod monitor;
+mod repository;
+
+pub use monitor::{ReverseSwapMonitor, ReverseSwapMonitorParams};
+pub use repository::{
+    ReverseSwapRefund, ReverseSwapRepository, ReverseSwapRepositoryError, ReverseSwapState,
+    ReverseSwapStatus,
+};
@@ -0,0 +1,508 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use bitcoin::{
+    hashes::{sha256, Hash},
+    Address, Txid, Witness,
+};
+use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, field, info, instrument, warn};
+
+use crate::{
+    chain::{
+        ChainClient, ChainError, ChainRepository, ChainRepositoryError, FeeEstimateError,
+        FeeEstimator,
+    },
+    lightning::{HoldInvoiceClient, HoldInvoiceState, LightningError},
+    mempool::{MempoolRepository, MempoolRepositoryError},
+    wallet::{Wallet, WalletError},
+};
+
+use super::{
+    ReverseSwapRepository, ReverseSwapRepositoryError, ReverseSwapState, ReverseSwapStatus,
+};
+
+const FUNDING_CONF_TARGET: i32 = 6;
+
+#[derive(Debug, Error)]
+pub enum ReverseSwapMonitorError {
+    #[error("{0}")]
+    General(Box<dyn std::error::Error + Sync + Send>),
+}
+
+pub struct ReverseSwapMonitorParams<CC, CR, FE, L, MR, RR, W>
+where
+    CC: ChainClient,
+    CR: ChainRepository,
+    FE: FeeEstimator,
+    L: HoldInvoiceClient,
+    MR: MempoolRepository,
+    RR: ReverseSwapRepository,
+    W: Wallet,
+{
+    pub chain_client: Arc<CC>,
+    pub chain_repository: Arc<CR>,
+    pub fee_estimator: Arc<FE>,
+    pub lightning_client: Arc<L>,
+    pub mempool_repository: Arc<MR>,
+    pub reverse_swap_repository: Arc<RR>,
+    pub wallet: Arc<W>,
+    pub poll_interval: Duration,
+    pub invoice_expiry: Duration,
+    pub min_claim_blocks: u32,
+    pub min_settle_blocks: u32,
+}
+
+/// Drives reverse swaps from invoice payment to settlement. Once the hold
+/// invoice is accepted the swap output is funded. Once the user claims the
+/// swap output onchain, the preimage is taken from the claim witness and the
+/// invoice is settled.
+pub struct ReverseSwapMonitor<CC, CR, FE, L, MR, RR, W>
+where
+    CC: ChainClient,
+    CR: ChainRepository,
+    FE: FeeEstimator,
+    L: HoldInvoiceClient,
+    MR: MempoolRepository,
+    RR: ReverseSwapRepository,
+    W: Wallet,
+{
+    chain_client: Arc<CC>,
+    chain_repository: Arc<CR>,
+    fee_estimator: Arc<FE>,
+    lightning_client: Arc<L>,
+    mempool_repository: Arc<MR>,
+    reverse_swap_repository: Arc<RR>,
+    wallet: Arc<W>,
+    poll_interval: Duration,
+    invoice_expiry: Duration,
+    min_claim_blocks: u32,
+    min_settle_blocks: u32,
+}
+
+impl<CC, CR, FE, L, MR, RR, W> ReverseSwapMonitor<CC, CR, FE, L, MR, RR, W>
+where
+    CC: ChainClient + Send + Sync,
+    CR: ChainRepository + Send + Sync,
+    FE: FeeEstimator + Send + Sync,
+    L: HoldInvoiceClient + Send + Sync,
+    MR: MempoolRepository + Send + Sync,
+    RR: ReverseSwapRepository + Send + Sync,
+    W: Wallet + Send + Sync,
+{
+    pub fn new(params: ReverseSwapMonitorParams<CC, CR, FE, L, MR, RR, W>) -> Self {
+        Self {
+            chain_client: params.chain_client,
+            chain_repository: params.chain_repository,
+            fee_estimator: params.fee_estimator,
+            lightning_client: params.lightning_client,
+            mempool_repository: params.mempool_repository,
+            reverse_swap_repository: params.reverse_swap_repository,
+            wallet: params.wallet,
+            poll_interval: params.poll_interval,
+            invoice_expiry: params.invoice_expiry,
+            min_claim_blocks: params.min_claim_blocks,
+            min_settle_blocks: params.min_settle_blocks,
+        }
+    }
+
+    pub async fn start(&self, token: CancellationToken) -> Result<(), ReverseSwapMonitorError> {
+        loop {
+            if token.is_cancelled() {
+                return Ok(());
+            }
+
+            if let Err(e) = self.do_monitor().await {
+                error!("failed to monitor reverse swaps: {:?}", e);
+            }
+
+            tokio::select! {
+                _ = token.cancelled() => {
+                    debug!("reverse swap monitor shutting down");
+                    break;
+                }
+                _ = tokio::time::sleep(self.poll_interval) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn do_monitor(&self) -> Result<(), ReverseSwapMonitorError> {
+        let current_height = self.chain_client.get_blockheight().await?;
+        let reverse_swaps = self
+            .reverse_swap_repository
+            .get_reverse_swaps_by_status(&[
+                ReverseSwapStatus::Created,
+                ReverseSwapStatus::Funding,
+                ReverseSwapStatus::Funded,
+            ])
+            .await?;
+        for reverse_swap in reverse_swaps {
+            let hash = reverse_swap.reverse_swap.public.hash;
+            let res = match reverse_swap.status {
+                ReverseSwapStatus::Created => {
+                    self.handle_created(current_height, &reverse_swap).await
+                }
+                ReverseSwapStatus::Funding => {
+                    self.handle_funding(current_height, &reverse_swap).await
+                }
+                ReverseSwapStatus::Funded => self.handle_funded(&reverse_swap).await,
+                _ => Ok(()),
+            };
+            if let Err(e) = res {
+                error!(
+                    hash = field::display(hash),
+                    "failed to process reverse swap: {:?}", e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self), level = "trace")]
+    async fn handle_created(
+        &self,
+        current_height: u64,
+        reverse_swap: &ReverseSwapState,
+    ) -> Result<(), ReverseSwapMonitorError> {
+        let public = &reverse_swap.reverse_swap.public;
+        let state = self
+            .lightning_client
+            .get_hold_invoice_state(&public.hash)
+            .await?;
+        match state {
+            HoldInvoiceState::Open => {
+                let expired = match reverse_swap
+                    .reverse_swap
+                    .creation_time
+                    .checked_add(self.invoice_expiry)
+                {
+                    Some(expiry) => expiry < SystemTime::now(),
+                    None => false,
+                };
+                if expired {
+                    debug!(hash = field::display(public.hash), "reverse swap expired");
+                    self.cancel(&public.hash, ReverseSwapStatus::Created)
+                        .await?;
+                }
+            }
+            HoldInvoiceState::Accepted { min_htlc_expiry } => {
+                // The invoice has to be settled with the preimage the user
+                // reveals onchain. The user can claim up until the swap output
+                // is refunded, so the htlcs should outlive the lock height.
+                let min_htlc_expiry_required =
+                    public.lock_height as u64 + self.min_settle_blocks as u64;
+                if min_htlc_expiry < min_htlc_expiry_required {
+                    warn!(
+                        hash = field::display(public.hash),
+                        min_htlc_expiry,
+                        min_htlc_expiry_required,
+                        "htlc expiry too low, canceling reverse swap"
+                    );
+                    self.cancel(&public.hash, ReverseSwapStatus::Created)
+                        .await?;
+                    return Ok(());
+                }
+
+                // Leave the user enough time to claim the swap output.
+                if current_height + self.min_claim_blocks as u64 > public.lock_height as u64 {
+                    warn!(
+                        hash = field::display(public.hash),
+                        current_height,
+                        lock_height = public.lock_height,
+                        "reverse swap paid too late, canceling reverse swap"
+                    );
+                    self.cancel(&public.hash, ReverseSwapStatus::Created)
+                        .await?;
+                    return Ok(());
+                }
+
+                self.fund(reverse_swap).await?;
+            }
+            HoldInvoiceState::Settled => {
+                error!(
+                    hash = field::display(public.hash),
+                    "hold invoice for unfunded reverse swap is settled"
+                );
+            }
+            HoldInvoiceState::Canceled => {
+                self.reverse_swap_repository
+                    .update_status(
+                        &public.hash,
+                        ReverseSwapStatus::Created,
+                        ReverseSwapStatus::Canceled,
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn fund(&self, reverse_swap: &ReverseSwapState) -> Result<(), ReverseSwapMonitorError> {
+        let public = &reverse_swap.reverse_swap.public;
+        let fee_estimate = self.fee_estimator.estimate_fee(FUNDING_CONF_TARGET).await?;
+
+        // Mark the swap as funding before sending, so the output is never
+        // funded twice.
+        self.reverse_swap_repository
+            .update_status(
+                &public.hash,
+                ReverseSwapStatus::Created,
+                ReverseSwapStatus::Funding,
+            )
+            .await?;
+        let funding_tx_id = match self
+            .wallet
+            .send_to_address(
+                &public.address,
+                reverse_swap.amount_sat,
+                fee_estimate.sat_per_kw,
+            )
+            .await
+        {
+            Ok(funding_tx_id) => funding_tx_id,
+            Err(e) => {
+                // The send may have been published after all, so the swap
+                // stays funding until the funding tx is found, and is never
+                // funded again.
+                error!(
+                    hash = field::display(public.hash),
+                    "failed to fund reverse swap, looking for the funding tx: {:?}", e
+                );
+                return Ok(());
+            }
+        };
+        info!(
+            hash = field::display(public.hash),
+            address = field::display(&public.address),
+            tx_id = field::display(funding_tx_id),
+            amount_sat = reverse_swap.amount_sat,
+            "funded reverse swap"
+        );
+        self.reverse_swap_repository
+            .set_funded(&public.hash, &funding_tx_id)
+            .await?;
+        Ok(())
+    }
+
+    #[instrument(skip(self), level = "trace")]
+    async fn handle_funding(
+        &self,
+        current_height: u64,
+        reverse_swap: &ReverseSwapState,
+    ) -> Result<(), ReverseSwapMonitorError> {
+        // The process stopped between funding and storing the funding tx, or
+        // the send failed. Once the funding tx is found, confirmed or not, the
+        // swap is funded so the refund covers it. The invoice is only canceled
+        // if no funding tx exists, meaning it was never published, or its
+        // inputs were double spent or abandoned and the wallet dropped it.
+        // That is only assumed once the user no longer has time to claim, long
+        // before the htlcs expire.
+        let public = &reverse_swap.reverse_swap.public;
+        let funding_tx_id = match self.find_funding_tx(&public.address).await? {
+            Some(funding_tx_id) => funding_tx_id,
+            None if current_height + self.min_claim_blocks as u64 > public.lock_height as u64 => {
+                error!(
+                    hash = field::display(public.hash),
+                    current_height,
+                    lock_height = public.lock_height,
+                    "no funding tx exists for reverse swap, canceling reverse swap"
+                );
+                self.cancel(&public.hash, ReverseSwapStatus::Funding)
+                    .await?;
+                return Ok(());
+            }
+            None => {
+                warn!(
+                    hash = field::display(public.hash),
+                    "reverse swap is funding, but no funding tx was found yet"
+                );
+                return Ok(());
+            }
+        };
+
+        info!(
+            hash = field::display(public.hash),
+            tx_id = field::display(funding_tx_id),
+            "found funding tx for reverse swap"
+        );
+        self.reverse_swap_repository
+            .set_funded(&public.hash, &funding_tx_id)
+            .await?;
+        Ok(())
+    }
+
+    /// Looks for a transaction paying to the swap address in the chain, the
+    /// mempool and the wallet.
+    async fn find_funding_tx(
+        &self,
+        address: &Address,
+    ) -> Result<Option<Txid>, ReverseSwapMonitorError> {
+        if let Some(txo) = self
+            .chain_repository
+            .get_txos_for_address(address)
+            .await?
+            .first()
+        {
+            return Ok(Some(txo.outpoint.txid));
+        }
+
+        if let Some(txo) = self
+            .mempool_repository
+            .get_txos_for_address(address)
+            .await?
+            .first()
+        {
+            return Ok(Some(txo.outpoint.txid));
+        }
+
+        Ok(self.wallet.find_tx_to_address(address).await?)
+    }
+
+    #[instrument(skip(self), level = "trace")]
+    async fn handle_funded(
+        &self,
+        reverse_swap: &ReverseSwapState,
+    ) -> Result<(), ReverseSwapMonitorError> {
+        let public = &reverse_swap.reverse_swap.public;
+        let txos = self
+            .chain_repository
+            .get_txos_for_address_with_spends(&public.address)
+            .await?;
+        for txo in txos {
+            if Some(txo.txo.outpoint.txid) != reverse_swap.funding_tx_id {
+                continue;
+            }
+
+            let spend = match txo.spend {
+                Some(spend) => spend,
+                None => continue,
+            };
+
+            let tx = self
+                .chain_client
+                .get_transaction(&spend.spending_tx)
+                .await?;
+            let input = match tx.input.get(spend.spending_input_index as usize) {
+                Some(input) => input,
+                None => {
+                    return Err(ReverseSwapMonitorError::General(
+                        "spending input not found".into(),
+                    ))
+                }
+            };
+
+            if let Some(preimage) = extract_preimage(&input.witness, &public.hash) {
+                info!(
+                    hash = field::display(public.hash),
+                    tx_id = field::display(spend.spending_tx),
+                    "reverse swap claimed, settling invoice"
+                );
+                self.lightning_client.settle_hold_invoice(&preimage).await?;
+                self.reverse_swap_repository
+                    .set_settled(&public.hash, &preimage)
+                    .await?;
+                return Ok(());
+            }
+
+            if input.witness.len() == 3
+                && input.witness.nth(1) == Some(public.refund_script.as_bytes())
+            {
+                info!(
+                    hash = field::display(public.hash),
+                    tx_id = field::display(spend.spending_tx),
+                    "reverse swap refunded, canceling invoice"
+                );
+                self.cancel(&public.hash, ReverseSwapStatus::Funded).await?;
+                return Ok(());
+            }
+
+            error!(
+                hash = field::display(public.hash),
+                tx_id = field::display(spend.spending_tx),
+                "reverse swap output spent without revealing preimage"
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn cancel(
+        &self,
+        hash: &sha256::Hash,
+        from: ReverseSwapStatus,
+    ) -> Result<(), ReverseSwapMonitorError> {
+        let to = match from {
+            ReverseSwapStatus::Funded => ReverseSwapStatus::Refunded,
+            _ => ReverseSwapStatus::Canceled,
+        };
+        self.lightning_client.cancel_hold_invoice(hash).await?;
+        self.reverse_swap_repository
+            .update_status(hash, from, to)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Extracts the preimage from a claim leaf spend. The claim witness is
+/// `<signature> <preimage> <claim script> <control block>`.
+fn extract_preimage(witness: &Witness, hash: &sha256::Hash) -> Option<[u8; 32]> {
+    if witness.len() != 4 {
+        return None;
+    }
+
+    let preimage: [u8; 32] = witness.nth(1)?.try_into().ok()?;
+    if sha256::Hash::hash(&preimage) != *hash {
+        return None;
+    }
+
+    Some(preimage)
+}
+
+impl From<ChainError> for ReverseSwapMonitorError {
+    fn from(value: ChainError) -> Self {
+        ReverseSwapMonitorError::General(Box::new(value))
+    }
+}
+
+impl From<ChainRepositoryError> for ReverseSwapMonitorError {
+    fn from(value: ChainRepositoryError) -> Self {
+        ReverseSwapMonitorError::General(Box::new(value))
+    }
+}
+
+impl From<FeeEstimateError> for ReverseSwapMonitorError {
+    fn from(value: FeeEstimateError) -> Self {
+        ReverseSwapMonitorError::General(Box::new(value))
+    }
+}
+
+impl From<LightningError> for ReverseSwapMonitorError {
+    fn from(value: LightningError) -> Self {
+        ReverseSwapMonitorError::General(Box::new(value))
+    }
+}
+
+impl From<MempoolRepositoryError> for ReverseSwapMonitorError {
+    fn from(value: MempoolRepositoryError) -> Self {
+        ReverseSwapMonitorError::General(Box::new(value))
+    }
+}
+
+impl From<ReverseSwapRepositoryError> for ReverseSwapMonitorError {
+    fn from(value: ReverseSwapRepositoryError) -> Self {
+        ReverseSwapMonitorError::General(Box::new(value))
+    }
+}
+
+impl From<WalletError> for ReverseSwapMonitorError {
+    fn from(value: WalletError) -> Self {
+        ReverseSwapMonitorError::General(Box::new(value))
+    }
+}
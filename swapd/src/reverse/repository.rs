@@ -0,0 +1,148 @@
+use std::time::SystemTime;
+
+use bitcoin::{hashes::sha256, Address, Transaction, Txid};
+use thiserror::Error;
+
+use crate::swap::ReverseSwap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReverseSwapStatus {
+    /// The hold invoice was created, but not paid yet.
+    Created,
+    /// The hold invoice is accepted and the swap output is being funded.
+    Funding,
+    /// The swap output was funded, waiting for the user to claim it.
+    Funded,
+    /// The user claimed the swap output and the invoice was settled.
+    Settled,
+    /// The swap output was refunded to the swapper and the invoice canceled.
+    Refunded,
+    /// The invoice was canceled before the swap output was funded.
+    Canceled,
+}
+
+impl ReverseSwapStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReverseSwapStatus::Created => "created",
+            ReverseSwapStatus::Funding => "funding",
+            ReverseSwapStatus::Funded => "funded",
+            ReverseSwapStatus::Settled => "settled",
+            ReverseSwapStatus::Refunded => "refunded",
+            ReverseSwapStatus::Canceled => "canceled",
+        }
+    }
+}
+
+impl std::str::FromStr for ReverseSwapStatus {
+    type Err = ReverseSwapRepositoryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "created" => ReverseSwapStatus::Created,
+            "funding" => ReverseSwapStatus::Funding,
+            "funded" => ReverseSwapStatus::Funded,
+            "settled" => ReverseSwapStatus::Settled,
+            "refunded" => ReverseSwapStatus::Refunded,
+            "canceled" => ReverseSwapStatus::Canceled,
+            _ => return Err(ReverseSwapRepositoryError::InvalidStatus(s.to_string())),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ReverseSwapState {
+    pub reverse_swap: ReverseSwap,
+    /// Value of the swap output the user can claim.
+    pub amount_sat: u64,
+    /// Amount of the hold invoice the user pays, including the swap fee.
+    pub invoice_amount_msat: u64,
+    pub payment_request: String,
+    pub status: ReverseSwapStatus,
+    pub funding_tx_id: Option<Txid>,
+    pub preimage: Option<[u8; 32]>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReverseSwapRefund {
+    pub creation_time: SystemTime,
+    pub tx: Transaction,
+    pub destination_address: Address,
+    pub fee_per_kw: u32,
+}
+
+#[derive(Debug, Error)]
+pub enum ReverseSwapRepositoryError {
+    #[error("reverse swap already exists")]
+    AlreadyExists,
+    #[error("reverse swap not found")]
+    NotFound,
+    #[error("reverse swap status changed")]
+    StatusChanged,
+    #[error("invalid status: {0}")]
+    InvalidStatus(String),
+    #[error("invalid preimage")]
+    InvalidPreimage,
+    #[error("invalid timestamp")]
+    InvalidTimestamp,
+    #[error("{0}")]
+    General(Box<dyn std::error::Error + Sync + Send>),
+}
+
+#[async_trait::async_trait]
+pub trait ReverseSwapRepository {
+    async fn add_reverse_swap(
+        &self,
+        state: &ReverseSwapState,
+    ) -> Result<(), ReverseSwapRepositoryError>;
+
+    /// Adds a refund transaction for the reverse swap with the given hash.
+    async fn add_refund(
+        &self,
+        hash: &sha256::Hash,
+        refund: &ReverseSwapRefund,
+    ) -> Result<(), ReverseSwapRepositoryError>;
+
+    async fn get_reverse_swap(
+        &self,
+        hash: &sha256::Hash,
+    ) -> Result<ReverseSwapState, ReverseSwapRepositoryError>;
+
+    async fn get_reverse_swaps_by_status(
+        &self,
+        statuses: &[ReverseSwapStatus],
+    ) -> Result<Vec<ReverseSwapState>, ReverseSwapRepositoryError>;
+
+    /// Get all refunds for the reverse swap, sorted by fee rate desc, then
+    /// creation time desc.
+    async fn get_refunds(
+        &self,
+        hash: &sha256::Hash,
+    ) -> Result<Vec<ReverseSwapRefund>, ReverseSwapRepositoryError>;
+
+    /// Marks the reverse swap as funded by the given transaction. The reverse
+    /// swap has to be in the `Funding` state.
+    async fn set_funded(
+        &self,
+        hash: &sha256::Hash,
+        funding_tx_id: &Txid,
+    ) -> Result<(), ReverseSwapRepositoryError>;
+
+    /// Stores the preimage revealed by the user and marks the reverse swap as
+    /// settled. The reverse swap has to be in the `Funded` state.
+    async fn set_settled(
+        &self,
+        hash: &sha256::Hash,
+        preimage: &[u8; 32],
+    ) -> Result<(), ReverseSwapRepositoryError>;
+
+    /// Moves the reverse swap from status `from` to status `to`. Returns
+    /// `StatusChanged` if the reverse swap is no longer in status `from`, so
+    /// concurrent processes can't make the same transition twice.
+    async fn update_status(
+        &self,
+        hash: &sha256::Hash,
+        from: ReverseSwapStatus,
+        to: ReverseSwapStatus,
+    ) -> Result<(), ReverseSwapRepositoryError>;
+}
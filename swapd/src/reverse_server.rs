@@ -0,0 +1,282 @@
+use std::{fmt::Debug, sync::Arc};
+
+use bitcoin::{
+    hashes::{sha256::Hash, Hash as _},
+    secp256k1::PublicKey,
+};
+use tonic::{Request, Response, Status};
+use tracing::{debug, error, field, info, instrument, trace};
+
+use crate::{
+    chain::{ChainClient, ChainRepository, FeeEstimator},
+    lightning::{HoldInvoiceClient, HoldInvoiceRequest},
+    public_server::swap_api::{
+        reverse_swapper_server::ReverseSwapper, CreateReverseSwapRequest,
+        CreateReverseSwapResponse, ReverseSwapParameters, ReverseSwapParametersRequest,
+        ReverseSwapParametersResponse,
+    },
+    reverse::{
+        ReverseSwapRepository, ReverseSwapRepositoryError, ReverseSwapState, ReverseSwapStatus,
+    },
+    swap::{PrivateKeyProvider, SwapFeePolicy, SwapService},
+};
+
+const FUNDING_FEE_CONF_TARGET: i32 = 6;
+
+// Rough weight of a funding transaction with a single input and change.
+const FUNDING_TX_WEIGHT: u64 = 700;
+
+pub struct ReverseSwapServerParams<C, CR, L, P, RR, F>
+where
+    C: ChainClient,
+    CR: ChainRepository,
+    L: HoldInvoiceClient,
+    P: PrivateKeyProvider,
+    RR: ReverseSwapRepository,
+    F: FeeEstimator,
+{
+    pub dust_limit_sat: u64,
+    pub fee_policy: SwapFeePolicy,
+    pub invoice_expiry_seconds: u64,
+    pub lock_time: u32,
+    pub max_amount_sat: u64,
+    pub min_amount_sat: u64,
+    pub min_settle_blocks: u32,
+    pub chain_client: Arc<C>,
+    pub chain_repository: Arc<CR>,
+    pub fee_estimator: Arc<F>,
+    pub lightning_client: Arc<L>,
+    pub reverse_swap_repository: Arc<RR>,
+    pub swap_service: Arc<SwapService<P>>,
+}
+
+#[derive(Debug)]
+pub struct ReverseSwapServer<C, CR, L, P, RR, F>
+where
+    C: ChainClient,
+    CR: ChainRepository,
+    L: HoldInvoiceClient,
+    P: PrivateKeyProvider,
+    RR: ReverseSwapRepository,
+    F: FeeEstimator,
+{
+    dust_limit_sat: u64,
+    fee_policy: SwapFeePolicy,
+    invoice_expiry_seconds: u64,
+    lock_time: u32,
+    max_amount_sat: u64,
+    min_amount_sat: u64,
+    min_settle_blocks: u32,
+    chain_client: Arc<C>,
+    chain_repository: Arc<CR>,
+    fee_estimator: Arc<F>,
+    lightning_client: Arc<L>,
+    reverse_swap_repository: Arc<RR>,
+    swap_service: Arc<SwapService<P>>,
+}
+
+impl<C, CR, L, P, RR, F> ReverseSwapServer<C, CR, L, P, RR, F>
+where
+    C: ChainClient,
+    CR: ChainRepository,
+    L: HoldInvoiceClient,
+    P: PrivateKeyProvider,
+    RR: ReverseSwapRepository,
+    F: FeeEstimator,
+{
+    pub fn new(params: ReverseSwapServerParams<C, CR, L, P, RR, F>) -> Self {
+        ReverseSwapServer {
+            dust_limit_sat: params.dust_limit_sat,
+            fee_policy: params.fee_policy,
+            invoice_expiry_seconds: params.invoice_expiry_seconds,
+            lock_time: params.lock_time,
+            max_amount_sat: params.max_amount_sat,
+            min_amount_sat: params.min_amount_sat,
+            min_settle_blocks: params.min_settle_blocks,
+            chain_client: params.chain_client,
+            chain_repository: params.chain_repository,
+            fee_estimator: params.fee_estimator,
+            lightning_client: params.lightning_client,
+            reverse_swap_repository: params.reverse_swap_repository,
+            swap_service: params.swap_service,
+        }
+    }
+
+    async fn get_reverse_swap_parameters(&self) -> Result<ReverseSwapParameters, Status> {
+        // The funding fee is only passed on to the user if the fee policy says
+        // so.
+        let funding_fee_sat = match self.fee_policy.include_claim_fee {
+            true => {
+                let fee_estimate = self
+                    .fee_estimator
+                    .estimate_fee(FUNDING_FEE_CONF_TARGET)
+                    .await?;
+                (fee_estimate.sat_per_kw as u64 * FUNDING_TX_WEIGHT).div_ceil(1000)
+            }
+            false => 0,
+        };
+
+        Ok(ReverseSwapParameters {
+            max_amount_sat: self.max_amount_sat,
+            min_amount_sat: self.min_amount_sat.max(self.dust_limit_sat),
+            fee_base_sat: self.fee_policy.base_sat,
+            fee_ppm: self.fee_policy.ppm,
+            funding_fee_sat,
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl<C, CR, L, P, RR, F> ReverseSwapper for ReverseSwapServer<C, CR, L, P, RR, F>
+where
+    C: ChainClient + Debug + Send + Sync + 'static,
+    CR: ChainRepository + Debug + Send + Sync + 'static,
+    L: HoldInvoiceClient + Debug + Send + Sync + 'static,
+    P: PrivateKeyProvider + Debug + Send + Sync + 'static,
+    RR: ReverseSwapRepository + Debug + Send + Sync + 'static,
+    F: FeeEstimator + Debug + Send + Sync + 'static,
+{
+    #[instrument(skip(self), level = "debug")]
+    async fn create_reverse_swap(
+        &self,
+        request: Request<CreateReverseSwapRequest>,
+    ) -> Result<Response<CreateReverseSwapResponse>, Status> {
+        debug!("create_reverse_swap request");
+        let req = request.into_inner();
+        let claim_pubkey = PublicKey::from_slice(&req.claim_pubkey).map_err(|_| {
+            trace!("got invalid claim_pubkey");
+            Status::invalid_argument("invalid claim_pubkey")
+        })?;
+        let hash = Hash::from_slice(&req.hash).map_err(|_| {
+            trace!("got invalid hash");
+            Status::invalid_argument("invalid hash")
+        })?;
+
+        let parameters = self.get_reverse_swap_parameters().await?;
+        if req.amount_sat > parameters.max_amount_sat {
+            trace!(
+                amount_sat = req.amount_sat,
+                max_amount_sat = parameters.max_amount_sat,
+                "amount exceeds max reverse swap amount"
+            );
+            return Err(Status::invalid_argument("amount exceeds max swap amount"));
+        }
+
+        if req.amount_sat < parameters.min_amount_sat {
+            trace!(
+                amount_sat = req.amount_sat,
+                min_amount_sat = parameters.min_amount_sat,
+                "amount is below min reverse swap amount"
+            );
+            return Err(Status::invalid_argument("amount is below min swap amount"));
+        }
+
+        let current_height = self.chain_client.get_blockheight().await?;
+        let lock_height: u32 = current_height
+            .saturating_add(self.lock_time as u64)
+            .try_into()
+            .map_err(|_| {
+                error!(current_height, "lock height exceeds u32::MAX");
+                Status::internal("internal error")
+            })?;
+        let reverse_swap = self
+            .swap_service
+            .create_reverse_swap(claim_pubkey, hash, lock_height)
+            .await
+            .map_err(|e| {
+                error!("failed to create reverse swap: {:?}", e);
+                Status::internal("internal error")
+            })?;
+
+        let fee_sat = self
+            .fee_policy
+            .fee_sat(req.amount_sat, parameters.funding_fee_sat);
+        let invoice_amount_msat = req.amount_sat.saturating_add(fee_sat).saturating_mul(1000);
+
+        // The htlcs paying the invoice have to outlive the lock height of the
+        // swap output, so the invoice can be settled when the user claims
+        // just before the lock height.
+        let payment_request = self
+            .lightning_client
+            .add_hold_invoice(HoldInvoiceRequest {
+                amount_msat: invoice_amount_msat,
+                cltv_expiry_delta: self.lock_time.saturating_add(self.min_settle_blocks),
+                description: format!("reverse swap to {}", reverse_swap.public.address),
+                expiry_seconds: self.invoice_expiry_seconds,
+                payment_hash: hash,
+            })
+            .await
+            .map_err(|e| {
+                error!("failed to create hold invoice: {:?}", e);
+                Status::internal("internal error")
+            })?;
+
+        if let Err(e) = self
+            .reverse_swap_repository
+            .add_reverse_swap(&ReverseSwapState {
+                reverse_swap: reverse_swap.clone(),
+                amount_sat: req.amount_sat,
+                invoice_amount_msat,
+                payment_request: payment_request.clone(),
+                status: ReverseSwapStatus::Created,
+                funding_tx_id: None,
+                preimage: None,
+            })
+            .await
+        {
+            if let Err(e) = self.lightning_client.cancel_hold_invoice(&hash).await {
+                error!("failed to cancel hold invoice: {:?}", e);
+            }
+            return Err(e.into());
+        }
+        self.chain_repository
+            .add_watch_address(&reverse_swap.public.address)
+            .await?;
+
+        info!(
+            hash = field::display(&hash),
+            address = field::display(&reverse_swap.public.address),
+            amount_sat = req.amount_sat,
+            fee_sat,
+            "new reverse swap created"
+        );
+
+        Ok(Response::new(CreateReverseSwapResponse {
+            payment_request,
+            address: reverse_swap.public.address.to_string(),
+            refund_pubkey: reverse_swap.public.refund_pubkey.serialize().to_vec(),
+            lock_height,
+            parameters: Some(parameters),
+        }))
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    async fn reverse_swap_parameters(
+        &self,
+        _request: Request<ReverseSwapParametersRequest>,
+    ) -> Result<Response<ReverseSwapParametersResponse>, Status> {
+        let parameters = self.get_reverse_swap_parameters().await?;
+        Ok(Response::new(ReverseSwapParametersResponse {
+            parameters: Some(parameters),
+        }))
+    }
+}
+
+impl From<ReverseSwapRepositoryError> for Status {
+    fn from(value: ReverseSwapRepositoryError) -> Self {
+        match value {
+            ReverseSwapRepositoryError::AlreadyExists => {
+                trace!("reverse swap already exists");
+                Status::already_exists("Hash already exists")
+            }
+            ReverseSwapRepositoryError::NotFound => {
+                trace!("reverse swap not found");
+                Status::not_found("reverse swap not found")
+            }
+            e => {
+                error!("failed to persist reverse swap: {:?}", e);
+                Status::internal("internal error")
+            }
+        }
+    }
+}
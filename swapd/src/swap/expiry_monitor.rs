@@ -0,0 +1,100 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, field, info};
+
+use crate::{chain::ChainRepository, metrics::Metrics};
+
+use super::SwapRepository;
+
+/// Used to expire swaps created before the creation height was recorded.
+const AVERAGE_BLOCK_TIME: Duration = Duration::from_secs(600);
+
+pub struct SwapExpiryMonitorParams<CR, R>
+where
+    CR: ChainRepository,
+    R: SwapRepository,
+{
+    pub chain_repository: Arc<CR>,
+    pub metrics: Arc<Metrics>,
+    pub poll_interval: Duration,
+    pub swap_repository: Arc<R>,
+}
+
+/// Expires swaps that were never funded once their lock time passed since
+/// creation, and stops watching their addresses. This keeps the watched
+/// addresses from growing with swaps that are never used. The synced chain
+/// tip is used as the current height, so outputs in blocks that are not
+/// synced yet can't be missed.
+pub struct SwapExpiryMonitor<CR, R>
+where
+    CR: ChainRepository,
+    R: SwapRepository,
+{
+    chain_repository: Arc<CR>,
+    metrics: Arc<Metrics>,
+    poll_interval: Duration,
+    swap_repository: Arc<R>,
+}
+
+impl<CR, R> SwapExpiryMonitor<CR, R>
+where
+    CR: ChainRepository,
+    R: SwapRepository,
+{
+    pub fn new(params: SwapExpiryMonitorParams<CR, R>) -> Self {
+        Self {
+            chain_repository: params.chain_repository,
+            metrics: params.metrics,
+            poll_interval: params.poll_interval,
+            swap_repository: params.swap_repository,
+        }
+    }
+
+    pub async fn start(&self, token: CancellationToken) -> Result<(), Box<dyn std::error::Error>> {
+        loop {
+            if let Err(e) = self.expire_swaps().await {
+                error!("failed to expire unfunded swaps: {:?}", e);
+            }
+
+            tokio::select! {
+                _ = token.cancelled() => {
+                    debug!("swap expiry monitor shutting down");
+                    break;
+                }
+                _ = tokio::time::sleep(self.poll_interval) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn expire_swaps(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let tip = match self.chain_repository.get_tip().await? {
+            Some(tip) => tip,
+            None => {
+                debug!("chain not synced yet, not expiring swaps");
+                return Ok(());
+            }
+        };
+
+        let addresses = self
+            .swap_repository
+            .expire_unfunded_swaps(tip.height, AVERAGE_BLOCK_TIME)
+            .await?;
+        if addresses.is_empty() {
+            return Ok(());
+        }
+
+        self.metrics.swaps_expired.inc_by(addresses.len() as u64);
+        for address in &addresses {
+            debug!(address = field::display(address), "unfunded swap expired");
+        }
+        info!(
+            count = addresses.len(),
+            height = tip.height,
+            "expired unfunded swaps"
+        );
+        Ok(())
+    }
+}
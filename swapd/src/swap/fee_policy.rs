@@ -0,0 +1,69 @@
+/// Fee charged by the swap service for paying out a swap. The fee is deducted
+/// from the value of the swap outputs, so the user is expected to provide an
+/// invoice for `utxo_sum - fee`.
+#[derive(Clone, Debug, Default)]
+pub struct SwapFeePolicy {
+    /// Fixed fee component charged per payout.
+    pub base_sat: u64,
+    /// Proportional fee component, in parts per million of the swapped amount.
+    pub ppm: u64,
+    /// Whether to pass the estimated onchain claim fee on to the user.
+    pub include_claim_fee: bool,
+}
+
+impl SwapFeePolicy {
+    /// Calculates the swap fee for the given sum of swap outputs.
+    /// `claim_fee_sat` is the estimated fee for claiming those outputs
+    /// onchain. It is only charged if `include_claim_fee` is set.
+    pub fn fee_sat(&self, amount_sat: u64, claim_fee_sat: u64) -> u64 {
+        let proportional_sat = amount_sat.saturating_mul(self.ppm) / 1_000_000;
+        let claim_fee_sat = match self.include_claim_fee {
+            true => claim_fee_sat,
+            false => 0,
+        };
+        self.base_sat
+            .saturating_add(proportional_sat)
+            .saturating_add(claim_fee_sat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SwapFeePolicy;
+
+    #[test]
+    fn test_default_policy_is_free() {
+        let policy = SwapFeePolicy::default();
+        assert_eq!(0, policy.fee_sat(100_000, 500));
+    }
+
+    #[test]
+    fn test_base_and_ppm() {
+        let policy = SwapFeePolicy {
+            base_sat: 1_000,
+            ppm: 10_000,
+            include_claim_fee: false,
+        };
+        assert_eq!(2_000, policy.fee_sat(100_000, 500));
+    }
+
+    #[test]
+    fn test_includes_claim_fee() {
+        let policy = SwapFeePolicy {
+            base_sat: 1_000,
+            ppm: 10_000,
+            include_claim_fee: true,
+        };
+        assert_eq!(2_500, policy.fee_sat(100_000, 500));
+    }
+
+    #[test]
+    fn test_saturates() {
+        let policy = SwapFeePolicy {
+            base_sat: u64::MAX,
+            ppm: 10_000,
+            include_claim_fee: true,
+        };
+        assert_eq!(u64::MAX, policy.fee_sat(u64::MAX, u64::MAX));
+    }
+}
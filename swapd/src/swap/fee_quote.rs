@@ -0,0 +1,137 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+/// A claim fee rate that is honored until `expires_at`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClaimFeeQuote {
+    pub sat_per_kw: u32,
+    pub expires_at: SystemTime,
+}
+
+/// Claim fee rates quoted per key. While a quote is valid, the payout amount
+/// derived from it stays the same, even if the fee estimate moves. A validity
+/// of 0 disables quoting. Expired quotes are dropped once per validity period,
+/// so memory is bounded by the keys quoted in the last period.
+#[derive(Debug)]
+pub struct ClaimFeeQuotes<K> {
+    validity: Duration,
+    state: Mutex<ClaimFeeQuotesState<K>>,
+}
+
+#[derive(Debug)]
+struct ClaimFeeQuotesState<K> {
+    last_prune: Option<SystemTime>,
+    quotes: HashMap<K, ClaimFeeQuote>,
+}
+
+impl<K> ClaimFeeQuotes<K>
+where
+    K: Eq + Hash,
+{
+    pub fn new(validity: Duration) -> Self {
+        Self {
+            validity,
+            state: Mutex::new(ClaimFeeQuotesState {
+                last_prune: None,
+                quotes: HashMap::new(),
+            }),
+        }
+    }
+
+    pub fn validity(&self) -> Duration {
+        self.validity
+    }
+
+    /// Quotes `sat_per_kw` for `key` at `now`. If a quote for `key` is still
+    /// valid, that one is kept and returned instead. Returns None if quoting
+    /// is disabled.
+    pub fn quote(&self, key: K, sat_per_kw: u32, now: SystemTime) -> Option<ClaimFeeQuote> {
+        if self.validity.is_zero() {
+            return None;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let prune = match state.last_prune {
+            Some(last_prune) => now.duration_since(last_prune).unwrap_or_default() >= self.validity,
+            None => true,
+        };
+        if prune {
+            state.quotes.retain(|_, quote| quote.expires_at > now);
+            state.last_prune = Some(now);
+        }
+
+        if let Some(quote) = state.quotes.get(&key) {
+            if quote.expires_at > now {
+                return Some(*quote);
+            }
+        }
+
+        let quote = ClaimFeeQuote {
+            sat_per_kw,
+            expires_at: now + self.validity,
+        };
+        state.quotes.insert(key, quote);
+        Some(quote)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::ClaimFeeQuotes;
+
+    #[test]
+    fn test_keeps_valid_quote() {
+        let quotes = ClaimFeeQuotes::new(Duration::from_secs(60));
+        let now = SystemTime::now();
+        let quote = quotes.quote(1, 1000, now).unwrap();
+        assert_eq!(1000, quote.sat_per_kw);
+        assert_eq!(now + Duration::from_secs(60), quote.expires_at);
+
+        let later = now + Duration::from_secs(59);
+        assert_eq!(Some(quote), quotes.quote(1, 2000, later));
+    }
+
+    #[test]
+    fn test_requotes_after_expiry() {
+        let quotes = ClaimFeeQuotes::new(Duration::from_secs(60));
+        let now = SystemTime::now();
+        quotes.quote(1, 1000, now);
+
+        let later = now + Duration::from_secs(60);
+        let quote = quotes.quote(1, 2000, later).unwrap();
+        assert_eq!(2000, quote.sat_per_kw);
+        assert_eq!(later + Duration::from_secs(60), quote.expires_at);
+    }
+
+    #[test]
+    fn test_keys_are_independent() {
+        let quotes = ClaimFeeQuotes::new(Duration::from_secs(60));
+        let now = SystemTime::now();
+        quotes.quote(1, 1000, now);
+        assert_eq!(2000, quotes.quote(2, 2000, now).unwrap().sat_per_kw);
+        assert_eq!(1000, quotes.quote(1, 3000, now).unwrap().sat_per_kw);
+    }
+
+    #[test]
+    fn test_zero_validity_disables() {
+        let quotes = ClaimFeeQuotes::new(Duration::ZERO);
+        let now = SystemTime::now();
+        assert_eq!(None, quotes.quote(1, 1000, now));
+        assert!(quotes.state.lock().unwrap().quotes.is_empty());
+    }
+
+    #[test]
+    fn test_prunes_expired_quotes() {
+        let quotes = ClaimFeeQuotes::new(Duration::from_secs(60));
+        let now = SystemTime::now();
+        quotes.quote(1, 1000, now);
+        quotes.quote(2, 1000, now + Duration::from_secs(120));
+        assert_eq!(1, quotes.state.lock().unwrap().quotes.len());
+    }
+}
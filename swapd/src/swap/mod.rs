@@ -1,11 +1,22 @@
+mod expiry_monitor;
+mod fee_policy;
+mod fee_quote;
+mod ownership_proof;
 mod payment_monitor;
 mod privkey_provider;
 mod random_provider;
 mod swap_repository;
 mod swap_service;
 
+pub use expiry_monitor::{SwapExpiryMonitor, SwapExpiryMonitorParams};
+pub use fee_policy::SwapFeePolicy;
+pub use fee_quote::ClaimFeeQuotes;
+pub use ownership_proof::verify_ownership_proof;
 pub use payment_monitor::HistoricalPaymentMonitor;
-pub use privkey_provider::{PrivateKeyProvider, RandomPrivateKeyProvider};
+pub use privkey_provider::{
+    Bip32PrivateKeyProvider, ClaimKey, ClaimKeyIndexRepository, KeyLocator, PrivateKeyError,
+    PrivateKeyProvider, RandomPrivateKeyProvider, RemoteMusigSession,
+};
 pub use random_provider::{RandomError, RandomProvider, RingRandomProvider};
 pub use swap_repository::*;
 pub use swap_service::*;
@@ -0,0 +1,159 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bitcoin::{
+    hashes::{sha256, Hash, HashEngine},
+    secp256k1::{schnorr, Message, PublicKey, Secp256k1, Verification},
+    Address,
+};
+use thiserror::Error;
+
+/// Tag of the BIP340 tagged hash the ownership proof signs.
+const OWNERSHIP_PROOF_TAG: &[u8] = b"swapd/SwapOwnershipProof";
+
+/// How far the timestamp of an ownership proof may be off from the current
+/// time, in either direction.
+pub const OWNERSHIP_PROOF_MAX_CLOCK_DIFF: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Error)]
+pub enum OwnershipProofError {
+    #[error("timestamp out of range")]
+    TimestampOutOfRange,
+    #[error("invalid signature")]
+    InvalidSignature,
+}
+
+/// The message a swap owner signs with their refund key to prove ownership of
+/// the swap at `address`: the tagged hash of the address string followed by
+/// the 8 byte big endian unix `timestamp` in seconds.
+pub fn ownership_proof_message(address: &Address, timestamp: u64) -> Message {
+    let tag = sha256::Hash::hash(OWNERSHIP_PROOF_TAG);
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag.as_ref());
+    engine.input(tag.as_ref());
+    engine.input(address.to_string().as_bytes());
+    engine.input(&timestamp.to_be_bytes());
+    Message::from_digest(sha256::Hash::from_engine(engine).to_byte_array())
+}
+
+/// Verifies `signature` is a BIP340 signature of the ownership proof message
+/// by `refund_pubkey`, created around `now`.
+pub fn verify_ownership_proof<C: Verification>(
+    secp: &Secp256k1<C>,
+    refund_pubkey: &PublicKey,
+    address: &Address,
+    timestamp: u64,
+    signature: &[u8],
+    now: SystemTime,
+) -> Result<(), OwnershipProofError> {
+    let now = now
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| OwnershipProofError::TimestampOutOfRange)?
+        .as_secs();
+    if now.abs_diff(timestamp) > OWNERSHIP_PROOF_MAX_CLOCK_DIFF.as_secs() {
+        return Err(OwnershipProofError::TimestampOutOfRange);
+    }
+
+    let signature = schnorr::Signature::from_slice(signature)
+        .map_err(|_| OwnershipProofError::InvalidSignature)?;
+    secp.verify_schnorr(
+        &signature,
+        &ownership_proof_message(address, timestamp),
+        &refund_pubkey.x_only_public_key().0,
+    )
+    .map_err(|_| OwnershipProofError::InvalidSignature)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use bitcoin::{
+        key::Keypair,
+        secp256k1::{Secp256k1, SecretKey},
+        Address, CompressedPublicKey, Network,
+    };
+
+    use super::{ownership_proof_message, verify_ownership_proof, OwnershipProofError};
+
+    fn setup() -> (Secp256k1<bitcoin::secp256k1::All>, Keypair, Address, u64) {
+        let secp = Secp256k1::new();
+        let keypair = Keypair::from_secret_key(&secp, &SecretKey::from_slice(&[0x42; 32]).unwrap());
+        let address = Address::p2wpkh(&CompressedPublicKey(keypair.public_key()), Network::Regtest);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        (secp, keypair, address, timestamp)
+    }
+
+    #[test]
+    fn test_valid_proof() {
+        let (secp, keypair, address, timestamp) = setup();
+        let signature =
+            secp.sign_schnorr_no_aux_rand(&ownership_proof_message(&address, timestamp), &keypair);
+        assert!(verify_ownership_proof(
+            &secp,
+            &keypair.public_key(),
+            &address,
+            timestamp,
+            signature.as_ref(),
+            SystemTime::now(),
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_wrong_key_fails() {
+        let (secp, keypair, address, timestamp) = setup();
+        let other = Keypair::from_secret_key(&secp, &SecretKey::from_slice(&[0x43; 32]).unwrap());
+        let signature =
+            secp.sign_schnorr_no_aux_rand(&ownership_proof_message(&address, timestamp), &other);
+        assert!(matches!(
+            verify_ownership_proof(
+                &secp,
+                &keypair.public_key(),
+                &address,
+                timestamp,
+                signature.as_ref(),
+                SystemTime::now(),
+            ),
+            Err(OwnershipProofError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_signature_is_bound_to_timestamp() {
+        let (secp, keypair, address, timestamp) = setup();
+        let signature =
+            secp.sign_schnorr_no_aux_rand(&ownership_proof_message(&address, timestamp), &keypair);
+        assert!(matches!(
+            verify_ownership_proof(
+                &secp,
+                &keypair.public_key(),
+                &address,
+                timestamp + 1,
+                signature.as_ref(),
+                SystemTime::now(),
+            ),
+            Err(OwnershipProofError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_old_proof_fails() {
+        let (secp, keypair, address, timestamp) = setup();
+        let signature =
+            secp.sign_schnorr_no_aux_rand(&ownership_proof_message(&address, timestamp), &keypair);
+        assert!(matches!(
+            verify_ownership_proof(
+                &secp,
+                &keypair.public_key(),
+                &address,
+                timestamp,
+                signature.as_ref(),
+                SystemTime::now() + Duration::from_secs(6 * 60),
+            ),
+            Err(OwnershipProofError::TimestampOutOfRange)
+        ));
+    }
+}
@@ -5,11 +5,13 @@ use tracing::{debug, error, field};
 
 use crate::{
     lightning::{LightningClient, LightningError, PaymentResult, PaymentState},
+    metrics::Metrics,
     swap::{PaymentAttempt, SwapRepository},
 };
 
 pub struct HistoricalPaymentMonitor<S, L> {
     lightning_client: Arc<L>,
+    metrics: Arc<Metrics>,
     payment_attempts: Vec<PaymentAttempt>,
     poll_interval: Duration,
     swap_repository: Arc<S>,
@@ -20,9 +22,15 @@ where
     S: SwapRepository,
     L: LightningClient,
 {
-    pub fn new(lightning_client: Arc<L>, poll_interval: Duration, swap_repository: Arc<S>) -> Self {
+    pub fn new(
+        lightning_client: Arc<L>,
+        metrics: Arc<Metrics>,
+        poll_interval: Duration,
+        swap_repository: Arc<S>,
+    ) -> Self {
         HistoricalPaymentMonitor {
             lightning_client,
+            metrics,
             payment_attempts: Vec::new(),
             poll_interval,
             swap_repository,
@@ -103,6 +111,7 @@ where
                             },
                         )
                         .await?;
+                    self.metrics.payment_failures.inc("cancelled");
 
                     continue;
                 }
@@ -122,12 +131,14 @@ where
                             &PaymentResult::Success { preimage },
                         )
                         .await?;
+                    self.metrics.swaps_paid.inc();
                 }
                 PaymentState::Failure { error } => {
                     debug!(
                         payment_hash = field::display(&attempt.payment_hash),
                         "historical swap payment failed with error: {}", error
                     );
+                    self.metrics.payment_failures.inc(&error);
                     self.swap_repository
                         .unlock_add_payment_result(
                             &attempt.payment_hash,
@@ -1,16 +1,136 @@
 use std::sync::Arc;
 
-use bitcoin::secp256k1::SecretKey;
+use bitcoin::{
+    bip32::{ChildNumber, DerivationPath, Xpriv},
+    secp256k1::{schnorr, All, PublicKey, Secp256k1, SecretKey, XOnlyPublicKey},
+    Script, TapNodeHash, TapSighash, Transaction, TxOut,
+};
+use secp256k1::musig::{MusigPartialSignature, MusigPubNonce};
 use thiserror::Error;
 
 use super::random_provider::{RandomError, RandomProvider};
 
-pub trait PrivateKeyProvider {
+/// Hardened derivation path prefix for swap claim keys. The claim key of a
+/// swap is derived at `m/1984'/0'/<index>'`.
+pub const CLAIM_KEY_DERIVATION_PATH: [u32; 2] = [1984, 0];
+
+/// Hardened derivation path prefix for reverse swap refund keys. The refund
+/// key of a reverse swap is derived at `m/1984'/1'/<index>'`.
+pub const REVERSE_REFUND_KEY_DERIVATION_PATH: [u32; 2] = [1984, 1];
+
+/// The claim key of a swap, or the refund key of a reverse swap.
+#[derive(Clone)]
+pub enum ClaimKey {
+    /// The private key is held by swapd. `index` is the derivation index of
+    /// the key if it was derived from a master key, so it can be recovered
+    /// later.
+    Local {
+        privkey: SecretKey,
+        index: Option<u32>,
+    },
+    /// The private key is held by a remote signer, swapd only knows where to
+    /// find it.
+    Remote(KeyLocator),
+}
+
+impl std::fmt::Debug for ClaimKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClaimKey::Local { index, .. } => f
+                .debug_struct("Local")
+                .field("privkey", &"redacted")
+                .field("index", index)
+                .finish(),
+            ClaimKey::Remote(locator) => f.debug_tuple("Remote").field(locator).finish(),
+        }
+    }
+}
+
+/// Location of a key in the wallet of a remote signer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyLocator {
+    pub family: u32,
+    pub index: u32,
+}
+
+/// A MuSig2 signing session held by a remote signer. The session can sign a
+/// single message.
+#[derive(Clone, Debug)]
+pub struct RemoteMusigSession {
+    pub id: Vec<u8>,
+    /// The tweaked aggregate key the session signs for.
+    pub combined_key: XOnlyPublicKey,
+    pub pub_nonce: MusigPubNonce,
+}
+
+/// Provides the keys of swaps. Claim keys are either held in memory, or by a
+/// remote signer. Providers that hold keys in memory can rely on the default
+/// implementations of the remote signing functions, which fail for remote
+/// keys.
+#[async_trait::async_trait]
+pub trait PrivateKeyProvider: Send + Sync {
+    /// A new random private key, used for one-off secrets.
     fn new_private_key(&self) -> Result<SecretKey, PrivateKeyError>;
+
+    /// A new key for the claim leaf of a swap.
+    async fn new_claim_key(&self) -> Result<(PublicKey, ClaimKey), PrivateKeyError>;
+
+    /// A new key for the refund leaf of a reverse swap.
+    async fn new_reverse_refund_key(&self) -> Result<(PublicKey, ClaimKey), PrivateKeyError>;
+
+    /// Signs input `input_index` of `tx` for the script path `leaf_script`
+    /// with a remote key.
+    async fn sign_script_spend(
+        &self,
+        _key: &KeyLocator,
+        _tx: &Transaction,
+        _prevouts: &[TxOut],
+        _input_index: usize,
+        _leaf_script: &Script,
+    ) -> Result<schnorr::Signature, PrivateKeyError> {
+        Err(PrivateKeyError::RemoteKeyUnavailable)
+    }
+
+    /// Starts a MuSig2 session with a remote key for the aggregate of
+    /// `signer_pubkeys`, tweaked with the taptree `script_root`.
+    async fn create_musig_session(
+        &self,
+        _key: &KeyLocator,
+        _signer_pubkeys: &[PublicKey],
+        _script_root: TapNodeHash,
+    ) -> Result<RemoteMusigSession, PrivateKeyError> {
+        Err(PrivateKeyError::RemoteKeyUnavailable)
+    }
+
+    /// Signs `sighash` in a remote MuSig2 session, once the other signer's
+    /// nonce is known. The session cannot be used afterwards.
+    async fn musig_sign(
+        &self,
+        _session_id: &[u8],
+        _their_pub_nonce: &MusigPubNonce,
+        _sighash: TapSighash,
+    ) -> Result<MusigPartialSignature, PrivateKeyError> {
+        Err(PrivateKeyError::RemoteKeyUnavailable)
+    }
+
+    /// Drops a remote MuSig2 session that will not be signed.
+    async fn cleanup_musig_session(&self, _session_id: &[u8]) -> Result<(), PrivateKeyError> {
+        Err(PrivateKeyError::RemoteKeyUnavailable)
+    }
+}
+
+/// Persists the derivation index of the last handed out claim key and
+/// reverse swap refund key, so an index is never used twice.
+#[async_trait::async_trait]
+pub trait ClaimKeyIndexRepository {
+    async fn next_claim_key_index(&self) -> Result<u32, PrivateKeyError>;
+    async fn next_reverse_refund_key_index(&self) -> Result<u32, PrivateKeyError>;
 }
 
 #[derive(Debug, Error)]
 pub enum PrivateKeyError {
+    #[error("remote key unavailable, swapd is not configured for remote signing")]
+    RemoteKeyUnavailable,
     #[error("general: {0}")]
     General(Box<dyn std::error::Error + Send + Sync>),
 }
@@ -18,6 +138,7 @@ pub enum PrivateKeyError {
 #[derive(Debug)]
 pub struct RandomPrivateKeyProvider<RP> {
     rnd: Arc<RP>,
+    secp: Secp256k1<All>,
 }
 
 impl<RP> RandomPrivateKeyProvider<RP>
@@ -25,18 +146,142 @@ where
     RP: RandomProvider,
 {
     pub fn new(rnd: Arc<RP>) -> Self {
-        Self { rnd }
+        Self {
+            rnd,
+            secp: Secp256k1::new(),
+        }
     }
 }
 
+#[async_trait::async_trait]
 impl<RP> PrivateKeyProvider for RandomPrivateKeyProvider<RP>
+where
+    RP: RandomProvider + Send + Sync,
+{
+    fn new_private_key(&self) -> Result<SecretKey, PrivateKeyError> {
+        let key = self.rnd.rnd_32()?;
+        Ok(SecretKey::from_slice(&key)?)
+    }
+
+    async fn new_claim_key(&self) -> Result<(PublicKey, ClaimKey), PrivateKeyError> {
+        let privkey = self.new_private_key()?;
+        Ok((
+            privkey.public_key(&self.secp),
+            ClaimKey::Local {
+                privkey,
+                index: None,
+            },
+        ))
+    }
+
+    async fn new_reverse_refund_key(&self) -> Result<(PublicKey, ClaimKey), PrivateKeyError> {
+        self.new_claim_key().await
+    }
+}
+
+/// Derives claim keys and reverse swap refund keys from a master key, so they
+/// can be recovered from the master key and the index stored with the swap.
+/// One-off secrets are still random.
+pub struct Bip32PrivateKeyProvider<RP, KR> {
+    master_key: Xpriv,
+    secp: Secp256k1<All>,
+    rnd: Arc<RP>,
+    key_index_repository: Arc<KR>,
+}
+
+impl<RP, KR> Bip32PrivateKeyProvider<RP, KR>
 where
     RP: RandomProvider,
+    KR: ClaimKeyIndexRepository,
+{
+    pub fn new(master_key: Xpriv, rnd: Arc<RP>, key_index_repository: Arc<KR>) -> Self {
+        Self {
+            master_key,
+            secp: Secp256k1::new(),
+            rnd,
+            key_index_repository,
+        }
+    }
+}
+
+impl<RP, KR> std::fmt::Debug for Bip32PrivateKeyProvider<RP, KR> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Bip32PrivateKeyProvider")
+            .field("master_key", &"redacted")
+            .finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl<RP, KR> PrivateKeyProvider for Bip32PrivateKeyProvider<RP, KR>
+where
+    RP: RandomProvider + Send + Sync,
+    KR: ClaimKeyIndexRepository + Send + Sync,
 {
     fn new_private_key(&self) -> Result<SecretKey, PrivateKeyError> {
         let key = self.rnd.rnd_32()?;
         Ok(SecretKey::from_slice(&key)?)
     }
+
+    async fn new_claim_key(&self) -> Result<(PublicKey, ClaimKey), PrivateKeyError> {
+        let index = self.key_index_repository.next_claim_key_index().await?;
+        let privkey = derive_claim_key(&self.secp, &self.master_key, index)?;
+        Ok((
+            privkey.public_key(&self.secp),
+            ClaimKey::Local {
+                privkey,
+                index: Some(index),
+            },
+        ))
+    }
+
+    async fn new_reverse_refund_key(&self) -> Result<(PublicKey, ClaimKey), PrivateKeyError> {
+        let index = self
+            .key_index_repository
+            .next_reverse_refund_key_index()
+            .await?;
+        let privkey = derive_reverse_refund_key(&self.secp, &self.master_key, index)?;
+        Ok((
+            privkey.public_key(&self.secp),
+            ClaimKey::Local {
+                privkey,
+                index: Some(index),
+            },
+        ))
+    }
+}
+
+/// Derives the claim key at `index` from the master key.
+pub fn derive_claim_key(
+    secp: &Secp256k1<All>,
+    master_key: &Xpriv,
+    index: u32,
+) -> Result<SecretKey, PrivateKeyError> {
+    derive_key(secp, master_key, &CLAIM_KEY_DERIVATION_PATH, index)
+}
+
+/// Derives the reverse swap refund key at `index` from the master key.
+pub fn derive_reverse_refund_key(
+    secp: &Secp256k1<All>,
+    master_key: &Xpriv,
+    index: u32,
+) -> Result<SecretKey, PrivateKeyError> {
+    derive_key(secp, master_key, &REVERSE_REFUND_KEY_DERIVATION_PATH, index)
+}
+
+fn derive_key(
+    secp: &Secp256k1<All>,
+    master_key: &Xpriv,
+    prefix: &[u32],
+    index: u32,
+) -> Result<SecretKey, PrivateKeyError> {
+    let path = prefix
+        .iter()
+        .chain(std::iter::once(&index))
+        .map(|i| ChildNumber::from_hardened_idx(*i))
+        .collect::<Result<Vec<_>, _>>()?;
+    let key = master_key.derive_priv(secp, &DerivationPath::from(path))?;
+    Ok(key.private_key)
 }
 
 impl From<RandomError> for PrivateKeyError {
@@ -50,3 +295,72 @@ impl From<bitcoin::secp256k1::Error> for PrivateKeyError {
         PrivateKeyError::General(Box::new(value))
     }
 }
+
+impl From<bitcoin::bip32::Error> for PrivateKeyError {
+    fn from(value: bitcoin::bip32::Error) -> Self {
+        PrivateKeyError::General(Box::new(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bitcoin::{
+        bip32::{DerivationPath, Xpriv},
+        secp256k1::Secp256k1,
+        NetworkKind,
+    };
+
+    use super::{derive_claim_key, derive_reverse_refund_key};
+
+    fn master_key() -> Xpriv {
+        Xpriv::new_master(NetworkKind::Test, &[0x42; 32]).unwrap()
+    }
+
+    #[test]
+    fn test_derive_claim_key_is_hardened_path() {
+        let secp = Secp256k1::new();
+        let master_key = master_key();
+        let expected = master_key
+            .derive_priv(&secp, &DerivationPath::from_str("m/1984'/0'/7'").unwrap())
+            .unwrap()
+            .private_key;
+        assert_eq!(expected, derive_claim_key(&secp, &master_key, 7).unwrap());
+    }
+
+    #[test]
+    fn test_derive_reverse_refund_key_is_separate_account() {
+        let secp = Secp256k1::new();
+        let master_key = master_key();
+        let expected = master_key
+            .derive_priv(&secp, &DerivationPath::from_str("m/1984'/1'/7'").unwrap())
+            .unwrap()
+            .private_key;
+        assert_eq!(
+            expected,
+            derive_reverse_refund_key(&secp, &master_key, 7).unwrap()
+        );
+        assert_ne!(
+            derive_claim_key(&secp, &master_key, 7).unwrap(),
+            derive_reverse_refund_key(&secp, &master_key, 7).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_derive_claim_key_differs_per_index() {
+        let secp = Secp256k1::new();
+        let master_key = master_key();
+        assert_ne!(
+            derive_claim_key(&secp, &master_key, 0).unwrap(),
+            derive_claim_key(&secp, &master_key, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_derive_claim_key_rejects_non_hardenable_index() {
+        let secp = Secp256k1::new();
+        let master_key = master_key();
+        assert!(derive_claim_key(&secp, &master_key, 1 << 31).is_err());
+    }
+}
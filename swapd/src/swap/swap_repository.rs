@@ -1,4 +1,7 @@
-use std::{collections::HashMap, time::SystemTime};
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
 
 use bitcoin::{hashes::sha256, secp256k1, Address, OutPoint};
 use thiserror::Error;
@@ -45,6 +48,12 @@ pub enum GetSwapsError {
     General(Box<dyn std::error::Error + Sync + Send>),
 }
 
+#[derive(Debug, Error)]
+pub enum ExpireSwapsError {
+    #[error("{0}")]
+    General(Box<dyn std::error::Error + Sync + Send>),
+}
+
 #[derive(Debug, Error)]
 pub enum GetPaymentAttemptsError {
     #[error("invalid preimage")]
@@ -53,6 +62,14 @@ pub enum GetPaymentAttemptsError {
     General(Box<dyn std::error::Error + Sync + Send>),
 }
 
+#[derive(Debug, Error)]
+pub enum ReencryptClaimKeysError {
+    #[error("no encryption key configured")]
+    NoEncryptionKey,
+    #[error("{0}")]
+    General(Box<dyn std::error::Error + Sync + Send>),
+}
+
 #[derive(Clone, Debug)]
 pub struct PaymentAttempt {
     pub creation_time: SystemTime,
@@ -60,6 +77,7 @@ pub struct PaymentAttempt {
     pub payment_hash: sha256::Hash,
     pub outputs: Vec<OutPoint>,
     pub amount_msat: u64,
+    pub fee_sat: u64,
     pub destination: secp256k1::PublicKey,
     pub payment_request: String,
 }
@@ -91,6 +109,34 @@ pub struct SwapLock {
 #[async_trait::async_trait]
 pub trait SwapRepository {
     async fn add_swap(&self, swap: &Swap) -> Result<(), SwapPersistenceError>;
+
+    /// Counts the swaps that are not expired and have no outputs, confirmed
+    /// or in the mempool.
+    async fn count_unfunded_swaps(&self) -> Result<u64, GetSwapsError>;
+
+    /// Marks the unfunded swaps whose lock time passed at `current_height` as
+    /// expired, and removes their watch addresses in the same transaction.
+    /// Swaps without a creation height are expired by their creation time,
+    /// assuming `block_time` per block. Returns the expired addresses.
+    async fn expire_unfunded_swaps(
+        &self,
+        current_height: u64,
+        block_time: Duration,
+    ) -> Result<Vec<Address>, ExpireSwapsError>;
+
+    /// Clears the expiry, and the completion the retention job derived from
+    /// it, of the expired swaps among `addresses`, and watches their
+    /// addresses again in the same transaction. Swaps that are still unfunded
+    /// expire again with the next `expire_unfunded_swaps`. Archived swaps are
+    /// not touched. Returns the addresses that were expired.
+    async fn unexpire_swaps(&self, addresses: &[Address])
+        -> Result<Vec<Address>, ExpireSwapsError>;
+
+    /// Returns the addresses among `addresses` of swaps that were archived.
+    async fn filter_archived_addresses(
+        &self,
+        addresses: &[Address],
+    ) -> Result<Vec<Address>, GetSwapsError>;
     async fn get_swap_by_hash(&self, hash: &sha256::Hash) -> Result<SwapState, GetSwapsError>;
     async fn get_swap_by_address(&self, address: &Address) -> Result<SwapState, GetSwapsError>;
     async fn get_swap_by_payment_request(
@@ -131,4 +177,9 @@ pub trait SwapRepository {
         hash: &sha256::Hash,
         refund_id: &str,
     ) -> Result<(), LockSwapError>;
+
+    /// Re-encrypts claim keys and reverse swap refund keys that are not
+    /// encrypted with the current encryption key, in batches of
+    /// `batch_size`. Returns the number of re-encrypted keys.
+    async fn reencrypt_claim_keys(&self, batch_size: u32) -> Result<u64, ReencryptClaimKeysError>;
 }
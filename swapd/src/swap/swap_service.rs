@@ -1,26 +1,28 @@
 use std::time::SystemTime;
 
-use super::privkey_provider::{PrivateKeyError, PrivateKeyProvider};
+use super::privkey_provider::{ClaimKey, PrivateKeyError, PrivateKeyProvider};
 use crate::chain::{FeeEstimate, Txo};
 use bitcoin::{
     absolute::LockTime,
     hashes::{ripemd160, sha256, Hash},
-    opcodes::all::{OP_CHECKSIG, OP_CHECKSIGVERIFY, OP_CSV, OP_EQUALVERIFY, OP_HASH160},
-    secp256k1::{All, Message, PublicKey, Secp256k1, SecretKey},
-    sighash::{self, Prevouts, SighashCache},
+    opcodes::all::{OP_CHECKSIG, OP_CHECKSIGVERIFY, OP_CLTV, OP_CSV, OP_EQUALVERIFY, OP_HASH160},
+    secp256k1::{schnorr, All, Message, PublicKey, Secp256k1, SecretKey},
+    sighash::{Prevouts, SighashCache},
     taproot::{LeafVersion, Signature, TaprootBuilder, TaprootSpendInfo},
     transaction::Version,
     Address, Amount, CompressedPublicKey, Network, Script, ScriptBuf, Sequence, TapLeafHash,
-    TapSighashType, Transaction, TxIn, TxOut, Weight, Witness, XOnlyPublicKey,
+    TapSighash, TapSighashType, Transaction, TxIn, TxOut, Weight, Witness, XOnlyPublicKey,
 };
 use secp256k1::musig::{
-    MusigAggNonce, MusigKeyAggCache, MusigPartialSignature, MusigPubNonce, MusigSecRand,
-    MusigSession,
+    MusigAggNonce, MusigKeyAggCache, MusigPartialSignature, MusigPubNonce, MusigSecNonce,
+    MusigSecRand, MusigSession,
 };
 use thiserror::Error;
-use tracing::{error, instrument, trace};
+use tracing::{error, instrument, trace, warn};
 
 const CLAIM_INPUT_WITNESS_SIZE: usize = 222;
+const COOPERATIVE_CLAIM_INPUT_WITNESS_SIZE: usize = 66;
+const REVERSE_REFUND_INPUT_WITNESS_SIZE: usize = 173;
 
 #[derive(Clone, Debug)]
 pub struct ClaimableUtxo {
@@ -42,6 +44,9 @@ impl ClaimableUtxo {
 pub struct SwapState {
     pub swap: Swap,
     pub preimage: Option<[u8; 32]>,
+    /// Whether the swap expired without being funded. Expired swaps are no
+    /// longer watched and cannot be paid out.
+    pub expired: bool,
 }
 
 impl SwapState {
@@ -60,6 +65,9 @@ impl SwapState {
 
 #[derive(Clone, Debug)]
 pub struct Swap {
+    /// Block height at creation. None for swaps created before the height
+    /// was recorded.
+    pub creation_height: Option<u64>,
     pub creation_time: SystemTime,
     pub public: SwapPublicData,
     pub private: SwapPrivateData,
@@ -76,19 +84,65 @@ pub struct SwapPublicData {
     pub refund_script: ScriptBuf,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct SwapPrivateData {
-    pub claim_privkey: SecretKey,
+    pub claim_key: ClaimKey,
+}
+
+/// A reverse swap output. The user can claim the output with the preimage
+/// before `lock_height`, after that the swapper can take the funds back.
+#[derive(Clone, Debug)]
+pub struct ReverseSwap {
+    pub creation_time: SystemTime,
+    pub public: ReverseSwapPublicData,
+    pub private: ReverseSwapPrivateData,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReverseSwapPublicData {
+    pub address: Address,
+    pub claim_pubkey: PublicKey,
+    pub claim_script: ScriptBuf,
+    pub hash: sha256::Hash,
+    pub lock_height: u32,
+    pub refund_pubkey: PublicKey,
+    pub refund_script: ScriptBuf,
 }
 
-impl std::fmt::Debug for SwapPrivateData {
+#[derive(Clone, Debug)]
+pub struct ReverseSwapPrivateData {
+    pub refund_key: ClaimKey,
+}
+
+/// An unsigned claim transaction spending swap outputs through the key path,
+/// together with our MuSig2 nonces for every input. Inputs are in the same
+/// order as `claimables`. Release it with `release_cooperative_claim_tx` if it
+/// will not be signed.
+pub struct CooperativeClaimTx {
+    pub tx: Transaction,
+    pub claimables: Vec<ClaimableUtxo>,
+    pub our_pub_nonces: Vec<MusigPubNonce>,
+    our_sessions: Vec<SigningSession>,
+}
+
+impl std::fmt::Debug for CooperativeClaimTx {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("SwapPrivateData")
-            .field("swapper_privkey", &"redacted")
+        f.debug_struct("CooperativeClaimTx")
+            .field("tx", &self.tx)
+            .field("claimables", &self.claimables)
+            .field("our_sessions", &"redacted")
             .finish()
     }
 }
 
+/// Our side of a MuSig2 signing session. For local claim keys the secret
+/// nonce is held in memory, for remote claim keys the remote signer holds the
+/// session.
+enum SigningSession {
+    Local(MusigSecNonce),
+    Remote(Vec<u8>),
+}
+
 #[derive(Debug, Error)]
 pub enum SwapError {
     #[error("private key: {0}")]
@@ -111,6 +165,8 @@ pub enum SwapError {
     NonceGen(secp256k1::musig::MusigNonceGenError),
     #[error("sign: {0}")]
     Sign(secp256k1::musig::MusigSignError),
+    #[error("invalid cooperative signature")]
+    InvalidCooperativeSignature,
 }
 
 #[derive(Debug)]
@@ -148,25 +204,16 @@ where
     }
 
     #[instrument(level = "trace", skip(self))]
-    pub fn create_swap(
+    pub async fn create_swap(
         &self,
         refund_pubkey: PublicKey,
         hash: sha256::Hash,
         current_height: u64,
     ) -> Result<Swap, SwapError> {
         let creation_time = SystemTime::now();
-        let claim_privkey = self.privkey_provider.new_private_key()?;
-        let claim_pubkey = claim_privkey.public_key(&self.secp);
-        let (x_only_claim_pubkey, _) = claim_pubkey.x_only_public_key();
+        let (claim_pubkey, claim_key) = self.privkey_provider.new_claim_key().await?;
         let (x_only_refund_pubkey, _) = refund_pubkey.x_only_public_key();
-        let claim_script = Script::builder()
-            .push_opcode(OP_HASH160)
-            .push_slice(ripemd160::Hash::hash(hash.as_byte_array()).as_byte_array())
-            .push_opcode(OP_EQUALVERIFY)
-            .push_x_only_key(&x_only_claim_pubkey)
-            .push_opcode(OP_CHECKSIG)
-            .into_script();
-
+        let claim_script = claim_script(&hash, &claim_pubkey);
         let refund_script = Script::builder()
             .push_x_only_key(&x_only_refund_pubkey)
             .push_opcode(OP_CHECKSIGVERIFY)
@@ -174,14 +221,9 @@ where
             .push_opcode(OP_CSV)
             .into_script();
 
-        let fake_address = Address::p2wpkh(
-            &CompressedPublicKey::from_slice(&[0x02; 33]).map_err(|e| {
-                error!("failed to create fake pubkey: {:?}", e);
-                SwapError::FakeAddress
-            })?,
-            self.network,
-        );
+        let fake_address = self.fake_address()?;
         let mut swap = Swap {
+            creation_height: Some(current_height),
             creation_time,
             public: SwapPublicData {
                 address: fake_address,
@@ -192,41 +234,76 @@ where
                 refund_pubkey,
                 refund_script,
             },
-            private: SwapPrivateData { claim_privkey },
+            private: SwapPrivateData { claim_key },
         };
 
-        let taproot_spend_info = self.taproot_spend_info(&swap)?;
+        let taproot_spend_info = self.swap_spend_info(&swap)?;
         swap.public.address = Address::p2tr_tweaked(taproot_spend_info.output_key(), self.network);
 
         Ok(swap)
     }
 
+    /// Creates a reverse swap output. The claim leaf belongs to the user, the
+    /// refund leaf to the swapper. The refund is locked until the absolute
+    /// block height `lock_height`.
     #[instrument(level = "trace", skip(self))]
-    pub fn create_claim_tx(
+    pub async fn create_reverse_swap(
         &self,
-        claimables: &[ClaimableUtxo],
+        claim_pubkey: PublicKey,
+        hash: sha256::Hash,
+        lock_height: u32,
+    ) -> Result<ReverseSwap, SwapError> {
+        let creation_time = SystemTime::now();
+        let lock_time = LockTime::from_height(lock_height)?;
+        let (refund_pubkey, refund_key) = self.privkey_provider.new_reverse_refund_key().await?;
+        let (x_only_refund_pubkey, _) = refund_pubkey.x_only_public_key();
+        let claim_script = claim_script(&hash, &claim_pubkey);
+        let refund_script = Script::builder()
+            .push_x_only_key(&x_only_refund_pubkey)
+            .push_opcode(OP_CHECKSIGVERIFY)
+            .push_lock_time(lock_time)
+            .push_opcode(OP_CLTV)
+            .into_script();
+
+        let taproot_spend_info =
+            self.taproot_spend_info(&claim_pubkey, &refund_pubkey, &claim_script, &refund_script)?;
+        Ok(ReverseSwap {
+            creation_time,
+            public: ReverseSwapPublicData {
+                address: Address::p2tr_tweaked(taproot_spend_info.output_key(), self.network),
+                claim_pubkey,
+                claim_script,
+                hash,
+                lock_height,
+                refund_pubkey,
+                refund_script,
+            },
+            private: ReverseSwapPrivateData { refund_key },
+        })
+    }
+
+    /// Creates a transaction spending the reverse swap output `utxo` back to
+    /// the swapper through the refund leaf. Only valid once `current_height`
+    /// is at or above the lock height of the reverse swap.
+    #[instrument(level = "trace", skip(self))]
+    pub async fn create_reverse_refund_tx(
+        &self,
+        reverse_swap: &ReverseSwap,
+        utxo: &Txo,
         fee: &FeeEstimate,
         current_height: u64,
         destination_address: Address,
     ) -> Result<Transaction, SwapError> {
-        // Sort by outpoint to reproducibly craft the same tx.
-        let mut claimables = claimables.to_vec();
-        claimables.sort_by(|a, b| a.utxo.outpoint.cmp(&b.utxo.outpoint));
-        let total_value = claimables
-            .iter()
-            .fold(0u64, |sum, r| sum + r.utxo.tx_out.value.to_sat());
+        let total_value = utxo.tx_out.value.to_sat();
         let mut tx = Transaction {
             version: Version::TWO,
             lock_time: LockTime::from_height(current_height as u32)?,
-            input: claimables
-                .iter()
-                .map(|r| TxIn {
-                    previous_output: r.utxo.outpoint,
-                    script_sig: ScriptBuf::default(),
-                    sequence: Sequence::ZERO,
-                    witness: Witness::default(),
-                })
-                .collect(),
+            input: vec![TxIn {
+                previous_output: utxo.outpoint,
+                script_sig: ScriptBuf::default(),
+                sequence: Sequence::ZERO,
+                witness: Witness::default(),
+            }],
             output: vec![TxOut {
                 script_pubkey: destination_address.into(),
                 value: Amount::from_sat(total_value),
@@ -235,9 +312,7 @@ where
 
         let weight = tx
             .weight()
-            .checked_add(Weight::from_wu(
-                (CLAIM_INPUT_WITNESS_SIZE * tx.input.len()) as u64,
-            ))
+            .checked_add(Weight::from_wu(REVERSE_REFUND_INPUT_WITNESS_SIZE as u64))
             .ok_or(SwapError::InvalidWeight)?;
         let fee_msat = weight.to_wu() * fee.sat_per_kw as u64;
         let fee_sat = fee_msat.div_ceil(1000);
@@ -253,35 +328,118 @@ where
         }
         tx.output[0].value = Amount::from_sat(value_after_fees_sat);
 
+        let public = &reverse_swap.public;
+        let prevouts = [utxo.tx_out.clone()];
+        let signature = match &reverse_swap.private.refund_key {
+            ClaimKey::Local { privkey, .. } => {
+                self.sign_script_spend(privkey, &tx, &prevouts, 0, &public.refund_script)?
+            }
+            ClaimKey::Remote(key) => {
+                self.privkey_provider
+                    .sign_script_spend(key, &tx, &prevouts, 0, &public.refund_script)
+                    .await?
+            }
+        };
+        let signature = Signature {
+            signature,
+            sighash_type: TapSighashType::Default,
+        };
+        let control_block = self
+            .taproot_spend_info(
+                &public.claim_pubkey,
+                &public.refund_pubkey,
+                &public.claim_script,
+                &public.refund_script,
+            )?
+            .control_block(&(public.refund_script.clone(), LeafVersion::TapScript))
+            .ok_or(TaprootError::MissingControlBlock)?;
+        let witness = vec![
+            signature.to_vec(),
+            public.refund_script.to_bytes(),
+            control_block.serialize(),
+        ];
+        tx.input[0].witness = witness.into();
+
+        Ok(tx)
+    }
+
+    /// Builds the claim transaction without signing it. The outputs are the
+    /// same as those of the signed claim transaction, so this can be used to
+    /// determine the claim fee.
+    #[instrument(level = "trace", skip(self))]
+    pub fn estimate_claim_tx(
+        &self,
+        claimables: &[ClaimableUtxo],
+        fee: &FeeEstimate,
+        current_height: u64,
+        destination_address: Address,
+    ) -> Result<Transaction, SwapError> {
+        let (tx, _) = self.unsigned_claim_tx(
+            claimables,
+            fee,
+            current_height,
+            destination_address,
+            CLAIM_INPUT_WITNESS_SIZE,
+        )?;
+        Ok(tx)
+    }
+
+    /// Weight of the signed script path claim transaction of the claimables.
+    #[instrument(level = "trace", skip(self))]
+    pub fn estimate_claim_weight(
+        &self,
+        claimables: &[ClaimableUtxo],
+        current_height: u64,
+        destination_address: Address,
+    ) -> Result<Weight, SwapError> {
+        let (tx, _) = self.unsigned_claim_tx(
+            claimables,
+            &FeeEstimate { sat_per_kw: 0 },
+            current_height,
+            destination_address,
+            CLAIM_INPUT_WITNESS_SIZE,
+        )?;
+        tx.weight()
+            .checked_add(Weight::from_wu(
+                (CLAIM_INPUT_WITNESS_SIZE * tx.input.len()) as u64,
+            ))
+            .ok_or(SwapError::InvalidWeight)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    pub async fn create_claim_tx(
+        &self,
+        claimables: &[ClaimableUtxo],
+        fee: &FeeEstimate,
+        current_height: u64,
+        destination_address: Address,
+    ) -> Result<Transaction, SwapError> {
+        let (mut tx, claimables) = self.unsigned_claim_tx(
+            claimables,
+            fee,
+            current_height,
+            destination_address,
+            CLAIM_INPUT_WITNESS_SIZE,
+        )?;
+
         let prevouts: Vec<TxOut> = claimables.iter().map(|u| u.utxo.tx_out.clone()).collect();
-        let prevouts = Prevouts::All(&prevouts);
         for (n, c) in claimables.iter().enumerate() {
-            let leaf_hash =
-                TapLeafHash::from_script(&c.swap.public.claim_script, LeafVersion::TapScript);
-
-            let mut sighasher = sighash::SighashCache::new(&tx);
-            let sighash = sighasher
-                .taproot_script_spend_signature_hash(
-                    n,
-                    &prevouts,
-                    leaf_hash,
-                    TapSighashType::Default,
-                )
-                .map_err(TaprootError::TaprootSighash)?;
-
-            let rnd = self.privkey_provider.new_private_key()?.secret_bytes();
-            let msg = Message::from(sighash);
-            let signature = self.secp.sign_schnorr_with_aux_rand(
-                &msg,
-                &c.swap.private.claim_privkey.keypair(&self.secp),
-                &rnd,
-            );
+            let signature = match &c.swap.private.claim_key {
+                ClaimKey::Local { privkey, .. } => {
+                    self.sign_script_spend(privkey, &tx, &prevouts, n, &c.swap.public.claim_script)?
+                }
+                ClaimKey::Remote(key) => {
+                    self.privkey_provider
+                        .sign_script_spend(key, &tx, &prevouts, n, &c.swap.public.claim_script)
+                        .await?
+                }
+            };
             let signature = Signature {
                 signature,
                 sighash_type: TapSighashType::Default,
             };
             let control_block = self
-                .taproot_spend_info(&c.swap)?
+                .swap_spend_info(&c.swap)?
                 .control_block(&(c.swap.public.claim_script.clone(), LeafVersion::TapScript))
                 .ok_or(TaprootError::MissingControlBlock)?;
             let witness = vec![
@@ -296,7 +454,160 @@ where
         Ok(tx)
     }
 
-    pub fn partial_sign_refund_tx(
+    /// Creates a claim transaction that spends the swap outputs through the
+    /// key path. The transaction is unsigned, it needs a partial signature
+    /// from the user for every input, see `sign_cooperative_claim_tx`.
+    #[instrument(level = "trace", skip(self))]
+    pub async fn create_cooperative_claim_tx(
+        &self,
+        claimables: &[ClaimableUtxo],
+        fee: &FeeEstimate,
+        current_height: u64,
+        destination_address: Address,
+    ) -> Result<CooperativeClaimTx, SwapError> {
+        let (tx, claimables) = self.unsigned_claim_tx(
+            claimables,
+            fee,
+            current_height,
+            destination_address,
+            COOPERATIVE_CLAIM_INPUT_WITNESS_SIZE,
+        )?;
+
+        let prevouts: Vec<TxOut> = claimables.iter().map(|u| u.utxo.tx_out.clone()).collect();
+        let mut our_sessions = Vec::new();
+        let mut our_pub_nonces = Vec::new();
+        for (n, c) in claimables.iter().enumerate() {
+            let result: Result<_, SwapError> = async {
+                let key_agg_cache = self.tweaked_key_agg_cache(&c.swap)?;
+                let sighash = key_spend_sighash(&tx, &prevouts, n)?;
+                self.signing_session(&c.swap, &key_agg_cache, sighash).await
+            }
+            .await;
+            match result {
+                Ok((session, pub_nonce)) => {
+                    our_sessions.push(session);
+                    our_pub_nonces.push(pub_nonce);
+                }
+                Err(e) => {
+                    self.release_signing_sessions(our_sessions).await;
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(CooperativeClaimTx {
+            tx,
+            claimables,
+            our_pub_nonces,
+            our_sessions,
+        })
+    }
+
+    /// Releases the signing sessions of a cooperative claim transaction that
+    /// will not be signed.
+    pub async fn release_cooperative_claim_tx(&self, claim: CooperativeClaimTx) {
+        self.release_signing_sessions(claim.our_sessions).await;
+    }
+
+    /// Verifies the user's partial signatures for every input of a
+    /// cooperative claim transaction, without using our signing sessions.
+    pub fn verify_cooperative_claim_signatures(
+        &self,
+        claim: &CooperativeClaimTx,
+        their_pub_nonces: &[MusigPubNonce],
+        their_partial_sigs: &[MusigPartialSignature],
+    ) -> Result<(), SwapError> {
+        let input_count = claim.tx.input.len();
+        if their_pub_nonces.len() != input_count || their_partial_sigs.len() != input_count {
+            return Err(SwapError::InvalidCooperativeSignature);
+        }
+
+        let prevouts: Vec<TxOut> = claim
+            .claimables
+            .iter()
+            .map(|u| u.utxo.tx_out.clone())
+            .collect();
+        for (n, c) in claim.claimables.iter().enumerate() {
+            let key_agg_cache = self.tweaked_key_agg_cache(&c.swap)?;
+            let sighash = key_spend_sighash(&claim.tx, &prevouts, n)?;
+            let msg = secp256k1::Message::from_digest(sighash.to_byte_array());
+            let agg_nonce = MusigAggNonce::new(
+                &self.musig_secp,
+                &[&claim.our_pub_nonces[n], &their_pub_nonces[n]],
+            );
+            let musig_session = MusigSession::new(&self.musig_secp, &key_agg_cache, agg_nonce, msg);
+
+            // TODO: Remove conversion once bitcoin crate contains musig module.
+            let refund_pubkey =
+                secp256k1::PublicKey::from_slice(&c.swap.public.refund_pubkey.serialize())
+                    .map_err(TaprootError::from)?;
+            if !musig_session.partial_verify(
+                &self.musig_secp,
+                &key_agg_cache,
+                their_partial_sigs[n],
+                their_pub_nonces[n],
+                refund_pubkey,
+            ) {
+                trace!(
+                    input_index = n,
+                    "invalid cooperative claim partial signature"
+                );
+                return Err(SwapError::InvalidCooperativeSignature);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Combines our partial signatures with the ones from the user into the
+    /// key path witness of every input. Fails if the user's partial
+    /// signatures don't result in valid signatures.
+    pub async fn sign_cooperative_claim_tx(
+        &self,
+        claim: CooperativeClaimTx,
+        their_pub_nonces: &[MusigPubNonce],
+        their_partial_sigs: &[MusigPartialSignature],
+    ) -> Result<Transaction, SwapError> {
+        let input_count = claim.tx.input.len();
+        if their_pub_nonces.len() != input_count || their_partial_sigs.len() != input_count {
+            self.release_signing_sessions(claim.our_sessions).await;
+            return Err(SwapError::InvalidCooperativeSignature);
+        }
+
+        let mut tx = claim.tx;
+        let prevouts: Vec<TxOut> = claim
+            .claimables
+            .iter()
+            .map(|u| u.utxo.tx_out.clone())
+            .collect();
+        let mut sessions = claim.our_sessions.into_iter().enumerate();
+        while let Some((n, our_session)) = sessions.next() {
+            let result = self
+                .sign_cooperative_input(
+                    &tx,
+                    &prevouts,
+                    n,
+                    &claim.claimables[n].swap,
+                    our_session,
+                    &claim.our_pub_nonces[n],
+                    &their_pub_nonces[n],
+                    &their_partial_sigs[n],
+                )
+                .await;
+            match result {
+                Ok(signature) => tx.input[n].witness = vec![signature.to_vec()].into(),
+                Err(e) => {
+                    self.release_signing_sessions(sessions.map(|(_, s)| s).collect())
+                        .await;
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(tx)
+    }
+
+    pub async fn partial_sign_refund_tx(
         &self,
         swap: &Swap,
         tx: Transaction,
@@ -304,57 +615,327 @@ where
         input_index: usize,
         their_pub_nonce: MusigPubNonce,
     ) -> Result<(MusigPartialSignature, MusigPubNonce), SwapError> {
-        let tweak = self.taproot_spend_info(swap)?.tap_tweak();
+        let key_agg_cache = self.tweaked_key_agg_cache(swap)?;
+        let sighash = key_spend_sighash(&tx, &prevouts, input_index)?;
+        let (our_session, our_pub_nonce) =
+            self.signing_session(swap, &key_agg_cache, sighash).await?;
+        let partial_sig = self
+            .partial_sign(
+                swap,
+                &key_agg_cache,
+                our_session,
+                &our_pub_nonce,
+                &their_pub_nonce,
+                sighash,
+            )
+            .await?;
+        Ok((partial_sig, our_pub_nonce))
+    }
+
+    /// Combines our partial signature for input `input_index` with the one
+    /// from the user, and verifies the result against the swap output key.
+    #[allow(clippy::too_many_arguments)]
+    async fn sign_cooperative_input(
+        &self,
+        tx: &Transaction,
+        prevouts: &[TxOut],
+        input_index: usize,
+        swap: &Swap,
+        our_session: SigningSession,
+        our_pub_nonce: &MusigPubNonce,
+        their_pub_nonce: &MusigPubNonce,
+        their_partial_sig: &MusigPartialSignature,
+    ) -> Result<Signature, SwapError> {
+        let key_agg_cache = self.tweaked_key_agg_cache(swap)?;
+        let sighash = key_spend_sighash(tx, prevouts, input_index)?;
+        let our_partial_sig = self
+            .partial_sign(
+                swap,
+                &key_agg_cache,
+                our_session,
+                our_pub_nonce,
+                their_pub_nonce,
+                sighash,
+            )
+            .await?;
+        let msg = secp256k1::Message::from_digest(sighash.to_byte_array());
+        let agg_nonce = MusigAggNonce::new(&self.musig_secp, &[our_pub_nonce, their_pub_nonce]);
+        let musig_session = MusigSession::new(&self.musig_secp, &key_agg_cache, agg_nonce, msg);
+        let signature = musig_session.partial_sig_agg(&[&our_partial_sig, their_partial_sig]);
+
+        // The aggregate signature is only valid if the user signed the same
+        // message with their share of the key.
+        let signature = schnorr::Signature::from_slice(&signature.to_byte_array())
+            .map_err(TaprootError::BitcoinSecp256k1)?;
+        let output_key = self.swap_spend_info(swap)?.output_key().to_inner();
+        self.secp
+            .verify_schnorr(&signature, &Message::from(sighash), &output_key)
+            .map_err(|e| {
+                trace!("invalid cooperative claim signature: {:?}", e);
+                SwapError::InvalidCooperativeSignature
+            })?;
+
+        Ok(Signature {
+            signature,
+            sighash_type: TapSighashType::Default,
+        })
+    }
+
+    /// Signs a script path spend of input `input_index` with a local key.
+    fn sign_script_spend(
+        &self,
+        privkey: &SecretKey,
+        tx: &Transaction,
+        prevouts: &[TxOut],
+        input_index: usize,
+        leaf_script: &Script,
+    ) -> Result<schnorr::Signature, SwapError> {
+        let leaf_hash = TapLeafHash::from_script(leaf_script, LeafVersion::TapScript);
+        let mut sighasher = SighashCache::new(tx);
+        let sighash = sighasher
+            .taproot_script_spend_signature_hash(
+                input_index,
+                &Prevouts::All(prevouts),
+                leaf_hash,
+                TapSighashType::Default,
+            )
+            .map_err(TaprootError::TaprootSighash)?;
+
+        let rnd = self.privkey_provider.new_private_key()?.secret_bytes();
+        let msg = Message::from(sighash);
+        Ok(self
+            .secp
+            .sign_schnorr_with_aux_rand(&msg, &privkey.keypair(&self.secp), &rnd))
+    }
+
+    /// Starts our side of a MuSig2 session to sign `sighash` for the swap
+    /// output key.
+    async fn signing_session(
+        &self,
+        swap: &Swap,
+        key_agg_cache: &MusigKeyAggCache,
+        sighash: TapSighash,
+    ) -> Result<(SigningSession, MusigPubNonce), SwapError> {
+        match &swap.private.claim_key {
+            ClaimKey::Local { .. } => {
+                let msg = secp256k1::Message::from_digest(sighash.to_byte_array());
+                let (sec_nonce, pub_nonce) = self.nonce_gen(swap, key_agg_cache, msg)?;
+                Ok((SigningSession::Local(sec_nonce), pub_nonce))
+            }
+            ClaimKey::Remote(key) => {
+                let spend_info = self.swap_spend_info(swap)?;
+                let script_root = spend_info
+                    .merkle_root()
+                    .ok_or(TaprootError::MissingMerkleRoot)?;
+                let session = self
+                    .privkey_provider
+                    .create_musig_session(
+                        key,
+                        &[swap.public.claim_pubkey, swap.public.refund_pubkey],
+                        script_root,
+                    )
+                    .await?;
+
+                // The remote signer aggregates the keys itself, make sure it
+                // signs for the swap output.
+                if session.combined_key != spend_info.output_key().to_inner() {
+                    self.release_signing_sessions(vec![SigningSession::Remote(session.id)])
+                        .await;
+                    return Err(PrivateKeyError::General(
+                        "remote musig session is for a different key".into(),
+                    )
+                    .into());
+                }
+                Ok((SigningSession::Remote(session.id), session.pub_nonce))
+            }
+        }
+    }
+
+    /// Creates our partial signature for `sighash`. Consumes the session, so
+    /// it can only sign once.
+    async fn partial_sign(
+        &self,
+        swap: &Swap,
+        key_agg_cache: &MusigKeyAggCache,
+        our_session: SigningSession,
+        our_pub_nonce: &MusigPubNonce,
+        their_pub_nonce: &MusigPubNonce,
+        sighash: TapSighash,
+    ) -> Result<MusigPartialSignature, SwapError> {
+        let our_sec_nonce = match our_session {
+            SigningSession::Local(sec_nonce) => sec_nonce,
+            SigningSession::Remote(session_id) => {
+                return Ok(self
+                    .privkey_provider
+                    .musig_sign(&session_id, their_pub_nonce, sighash)
+                    .await?)
+            }
+        };
+        let claim_privkey = match &swap.private.claim_key {
+            ClaimKey::Local { privkey, .. } => privkey,
+            ClaimKey::Remote(_) => return Err(PrivateKeyError::RemoteKeyUnavailable.into()),
+        };
+
+        // TODO: Remove conversion once bitcoin crate contains musig module.
+        let claim_privkey = secp256k1::SecretKey::from_byte_array(&claim_privkey.secret_bytes())?;
+        let msg = secp256k1::Message::from_digest(sighash.to_byte_array());
+        let agg_nonce = MusigAggNonce::new(&self.musig_secp, &[our_pub_nonce, their_pub_nonce]);
+        let musig_session = MusigSession::new(&self.musig_secp, key_agg_cache, agg_nonce, msg);
+        Ok(musig_session.partial_sign(
+            &self.musig_secp,
+            our_sec_nonce,
+            &claim_privkey.keypair(&self.musig_secp),
+            key_agg_cache,
+        )?)
+    }
+
+    async fn release_signing_sessions(&self, sessions: Vec<SigningSession>) {
+        for session in sessions {
+            if let SigningSession::Remote(session_id) = session {
+                if let Err(e) = self
+                    .privkey_provider
+                    .cleanup_musig_session(&session_id)
+                    .await
+                {
+                    warn!("failed to clean up remote musig session: {:?}", e);
+                }
+            }
+        }
+    }
+
+    /// Builds a claim transaction without witnesses, with the fee based on
+    /// `input_witness_size` for every input. Returns the claimables in input
+    /// order.
+    fn unsigned_claim_tx(
+        &self,
+        claimables: &[ClaimableUtxo],
+        fee: &FeeEstimate,
+        current_height: u64,
+        destination_address: Address,
+        input_witness_size: usize,
+    ) -> Result<(Transaction, Vec<ClaimableUtxo>), SwapError> {
+        // Sort by outpoint to reproducibly craft the same tx.
+        let mut claimables = claimables.to_vec();
+        claimables.sort_by(|a, b| a.utxo.outpoint.cmp(&b.utxo.outpoint));
+        let total_value = claimables
+            .iter()
+            .fold(0u64, |sum, r| sum + r.utxo.tx_out.value.to_sat());
+        let mut tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::from_height(current_height as u32)?,
+            input: claimables
+                .iter()
+                .map(|r| TxIn {
+                    previous_output: r.utxo.outpoint,
+                    script_sig: ScriptBuf::default(),
+                    sequence: Sequence::ZERO,
+                    witness: Witness::default(),
+                })
+                .collect(),
+            output: vec![TxOut {
+                script_pubkey: destination_address.into(),
+                value: Amount::from_sat(total_value),
+            }],
+        };
+
+        let weight = tx
+            .weight()
+            .checked_add(Weight::from_wu(
+                (input_witness_size * tx.input.len()) as u64,
+            ))
+            .ok_or(SwapError::InvalidWeight)?;
+        let fee_msat = weight.to_wu() * fee.sat_per_kw as u64;
+        let fee_sat = fee_msat.div_ceil(1000);
+        let value_after_fees_sat = total_value.saturating_sub(fee_sat);
+        if value_after_fees_sat < self.dust_limit_sat {
+            trace!(
+                total_value,
+                fee_sat,
+                value_after_fees_sat,
+                dust_limit_sat = self.dust_limit_sat
+            );
+            return Err(SwapError::AmountTooLow);
+        }
+        tx.output[0].value = Amount::from_sat(value_after_fees_sat);
+        Ok((tx, claimables))
+    }
+
+    /// The MuSig2 key aggregation of the swap keys, tweaked with the taptree
+    /// so it can sign for the swap output key.
+    fn tweaked_key_agg_cache(&self, swap: &Swap) -> Result<MusigKeyAggCache, SwapError> {
+        let tweak = self.swap_spend_info(swap)?.tap_tweak();
         let tweak_scalar = tweak.to_scalar();
 
         // TODO: Remove conversion once bitcoin crate contains musig module.
         let tweak_scalar = secp256k1::Scalar::from_be_bytes(tweak_scalar.to_be_bytes())?;
-        let claim_pubkey = secp256k1::PublicKey::from_slice(&swap.public.claim_pubkey.serialize())?;
-        let claim_privkey =
-            secp256k1::SecretKey::from_byte_array(&swap.private.claim_privkey.secret_bytes())?;
-
-        let mut key_agg_cache = self.key_agg_cache(swap)?;
+        let mut key_agg_cache =
+            self.key_agg_cache(&swap.public.claim_pubkey, &swap.public.refund_pubkey)?;
         let _ = key_agg_cache.pubkey_xonly_tweak_add(&self.musig_secp, &tweak_scalar)?;
+        Ok(key_agg_cache)
+    }
+
+    fn nonce_gen(
+        &self,
+        swap: &Swap,
+        key_agg_cache: &MusigKeyAggCache,
+        msg: secp256k1::Message,
+    ) -> Result<(MusigSecNonce, MusigPubNonce), SwapError> {
+        // TODO: Remove conversion once bitcoin crate contains musig module.
+        let claim_pubkey = secp256k1::PublicKey::from_slice(&swap.public.claim_pubkey.serialize())?;
         let session_id = MusigSecRand::assume_unique_per_nonce_gen(
             self.privkey_provider.new_private_key()?.secret_bytes(),
         );
-
-        let mut sighasher = SighashCache::new(tx);
-        let prevouts = Prevouts::All(&prevouts);
-        let sighash = sighasher
-            .taproot_key_spend_signature_hash(input_index, &prevouts, TapSighashType::Default)
-            .map_err(TaprootError::TaprootSighash)?;
-        let msg = secp256k1::Message::from_digest(sighash.to_byte_array());
         let extra_rand = self.privkey_provider.new_private_key()?.secret_bytes();
-
-        let (our_sec_nonce, our_pub_nonce) = key_agg_cache.nonce_gen(
+        Ok(key_agg_cache.nonce_gen(
             &self.musig_secp,
             session_id,
             claim_pubkey,
             msg,
             Some(extra_rand),
-        )?;
-        let agg_nonce = MusigAggNonce::new(&self.musig_secp, &[&our_pub_nonce, &their_pub_nonce]);
-        let musig_session = MusigSession::new(&self.musig_secp, &key_agg_cache, agg_nonce, msg);
+        )?)
+    }
 
-        let partial_sig = musig_session.partial_sign(
-            &self.musig_secp,
-            our_sec_nonce,
-            &claim_privkey.keypair(&self.musig_secp),
-            &key_agg_cache,
-        )?;
-        Ok((partial_sig, our_pub_nonce))
+    fn fake_address(&self) -> Result<Address, SwapError> {
+        Ok(Address::p2wpkh(
+            &CompressedPublicKey::from_slice(&[0x02; 33]).map_err(|e| {
+                error!("failed to create fake pubkey: {:?}", e);
+                SwapError::FakeAddress
+            })?,
+            self.network,
+        ))
     }
 
-    fn key_agg_cache(&self, swap: &Swap) -> Result<MusigKeyAggCache, TaprootError> {
+    fn key_agg_cache(
+        &self,
+        claim_pubkey: &PublicKey,
+        refund_pubkey: &PublicKey,
+    ) -> Result<MusigKeyAggCache, TaprootError> {
         // TODO: Remove conversion once bitcoin crate contains musig module.
-        let cp = secp256k1::PublicKey::from_slice(&swap.public.claim_pubkey.serialize())?;
-        let rp = secp256k1::PublicKey::from_slice(&swap.public.refund_pubkey.serialize())?;
+        let cp = secp256k1::PublicKey::from_slice(&claim_pubkey.serialize())?;
+        let rp = secp256k1::PublicKey::from_slice(&refund_pubkey.serialize())?;
         Ok(MusigKeyAggCache::new(&self.musig_secp, &[&cp, &rp]))
     }
 
-    fn taproot_spend_info(&self, swap: &Swap) -> Result<TaprootSpendInfo, TaprootError> {
-        let m = self.key_agg_cache(swap)?;
+    fn swap_spend_info(&self, swap: &Swap) -> Result<TaprootSpendInfo, TaprootError> {
+        self.taproot_spend_info(
+            &swap.public.claim_pubkey,
+            &swap.public.refund_pubkey,
+            &swap.public.claim_script,
+            &swap.public.refund_script,
+        )
+    }
+
+    /// Builds the taproot output for a pair of swap keys. The internal key is
+    /// the MuSig2 aggregate of both keys, the claim and refund scripts are the
+    /// leaves of the taptree.
+    pub fn taproot_spend_info(
+        &self,
+        claim_pubkey: &PublicKey,
+        refund_pubkey: &PublicKey,
+        claim_script: &Script,
+        refund_script: &Script,
+    ) -> Result<TaprootSpendInfo, TaprootError> {
+        let m = self.key_agg_cache(claim_pubkey, refund_pubkey)?;
         let internal_key = m.agg_pk();
 
         // TODO: Remove conversion once bitcoin crate contains musig module.
@@ -362,12 +943,41 @@ where
 
         // claim and refund scripts go in a taptree.
         Ok(TaprootBuilder::new()
-            .add_leaf(1, swap.public.claim_script.clone())?
-            .add_leaf(1, swap.public.refund_script.clone())?
+            .add_leaf(1, claim_script.to_owned())?
+            .add_leaf(1, refund_script.to_owned())?
             .finalize(&self.secp, internal_key)?)
     }
 }
 
+/// The claim leaf of a swap output: whoever knows the preimage of `hash` and
+/// holds the claim key can spend it.
+fn claim_script(hash: &sha256::Hash, claim_pubkey: &PublicKey) -> ScriptBuf {
+    let (x_only_claim_pubkey, _) = claim_pubkey.x_only_public_key();
+    Script::builder()
+        .push_opcode(OP_HASH160)
+        .push_slice(ripemd160::Hash::hash(hash.as_byte_array()).as_byte_array())
+        .push_opcode(OP_EQUALVERIFY)
+        .push_x_only_key(&x_only_claim_pubkey)
+        .push_opcode(OP_CHECKSIG)
+        .into_script()
+}
+
+/// The sighash for spending input `input_index` of `tx` through the key path.
+fn key_spend_sighash(
+    tx: &Transaction,
+    prevouts: &[TxOut],
+    input_index: usize,
+) -> Result<TapSighash, TaprootError> {
+    let mut sighasher = SighashCache::new(tx);
+    sighasher
+        .taproot_key_spend_signature_hash(
+            input_index,
+            &Prevouts::All(prevouts),
+            TapSighashType::Default,
+        )
+        .map_err(TaprootError::TaprootSighash)
+}
+
 #[derive(Debug, Error)]
 pub enum TaprootError {
     #[error("secp256k1: {0}")]
@@ -382,6 +992,8 @@ pub enum TaprootError {
     TaprootSighash(bitcoin::sighash::TaprootError),
     #[error("missing control block")]
     MissingControlBlock,
+    #[error("missing merkle root")]
+    MissingMerkleRoot,
 }
 
 impl From<secp256k1::Error> for TaprootError {
@@ -0,0 +1,64 @@
+use std::{io, time::Duration};
+
+use futures::Stream;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_rustls::{server::TlsStream, TlsAcceptor};
+use tracing::{debug, warn};
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Accepts connections on `listener` and yields them once the TLS handshake
+/// completed. Handshakes run concurrently, so a slow client doesn't hold up
+/// other connections. Failed handshakes are dropped rather than yielded, so
+/// they don't affect the server. Stops accepting when the stream is dropped.
+pub fn incoming(
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+) -> impl Stream<Item = Result<TlsStream<TcpStream>, io::Error>> {
+    let (tx, rx) = mpsc::channel(16);
+    tokio::spawn(accept(listener, acceptor, tx));
+    futures::stream::unfold(rx, |mut rx| async move {
+        let stream = rx.recv().await?;
+        Some((Ok(stream), rx))
+    })
+}
+
+async fn accept(
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+    tx: mpsc::Sender<TlsStream<TcpStream>>,
+) {
+    loop {
+        let (stream, peer) = tokio::select! {
+            _ = tx.closed() => break,
+            res = listener.accept() => match res {
+                Ok(conn) => conn,
+                Err(e) => {
+                    warn!("failed to accept connection: {:?}", e);
+                    tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                    continue;
+                }
+            },
+        };
+
+        if let Err(e) = stream.set_nodelay(true) {
+            debug!("failed to set nodelay for {}: {:?}", peer, e);
+        }
+
+        let acceptor = acceptor.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                Ok(Ok(stream)) => {
+                    let _ = tx.send(stream).await;
+                }
+                Ok(Err(e)) => debug!("tls handshake with {} failed: {:?}", peer, e),
+                Err(_) => debug!("tls handshake with {} timed out", peer),
+            }
+        });
+    }
+}
@@ -0,0 +1,5 @@
+mod incoming;
+mod reloader;
+
+pub use incoming::incoming;
+pub use reloader::{CertReloader, CertReloaderParams};
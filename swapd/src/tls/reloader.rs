@@ -0,0 +1,150 @@
+use std::{
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+
+use tokio_rustls::{
+    rustls::{
+        crypto::ring::{default_provider, sign::any_supported_type},
+        server::{ClientHello, ResolvesServerCert},
+        sign::CertifiedKey,
+        ServerConfig,
+    },
+    TlsAcceptor,
+};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::FileOrCert;
+
+const ALPN_H2: &[u8] = b"h2";
+
+#[derive(Debug, thiserror::Error)]
+pub enum TlsError {
+    #[error("no certificate found")]
+    NoCertificate,
+    #[error("no private key found")]
+    NoPrivateKey,
+    #[error("{0}")]
+    General(Box<dyn std::error::Error + Sync + Send>),
+}
+
+pub struct CertReloaderParams {
+    pub cert: FileOrCert,
+    pub key: FileOrCert,
+    pub poll_interval: Duration,
+}
+
+/// Serves a TLS certificate that is reloaded when the certificate or key
+/// changes, so certificates can be rotated without restarting swapd. The
+/// certificate and key are re-read every `poll_interval`. New connections use
+/// the new certificate, existing connections are not affected. If the new
+/// certificate and key are invalid or don't match, for example because only
+/// one of them was replaced so far, the current certificate is kept.
+pub struct CertReloader {
+    cert: FileOrCert,
+    key: FileOrCert,
+    poll_interval: Duration,
+    resolver: Arc<ReloadingCertResolver>,
+    loaded: Mutex<(String, String)>,
+}
+
+impl CertReloader {
+    pub async fn new(params: CertReloaderParams) -> Result<Self, TlsError> {
+        let cert_pem = params.cert.resolve().await;
+        let key_pem = params.key.resolve().await;
+        let certified_key = parse_certified_key(&cert_pem, &key_pem)?;
+        Ok(Self {
+            cert: params.cert,
+            key: params.key,
+            poll_interval: params.poll_interval,
+            resolver: Arc::new(ReloadingCertResolver {
+                certified_key: RwLock::new(Arc::new(certified_key)),
+            }),
+            loaded: Mutex::new((cert_pem, key_pem)),
+        })
+    }
+
+    /// Returns an acceptor that always uses the most recently loaded
+    /// certificate.
+    pub fn acceptor(&self) -> Result<TlsAcceptor, TlsError> {
+        let mut config = ServerConfig::builder_with_provider(Arc::new(default_provider()))
+            .with_safe_default_protocol_versions()?
+            .with_no_client_auth()
+            .with_cert_resolver(Arc::clone(&self.resolver) as Arc<dyn ResolvesServerCert>);
+        config.alpn_protocols = vec![ALPN_H2.to_vec()];
+        Ok(TlsAcceptor::from(Arc::new(config)))
+    }
+
+    pub async fn start(&self, token: CancellationToken) {
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => {
+                    debug!("tls certificate reloader shutting down");
+                    break;
+                }
+                _ = tokio::time::sleep(self.poll_interval) => {}
+            }
+
+            if let Err(e) = self.reload().await {
+                warn!(
+                    "failed to reload tls certificate, keeping the current one: {}",
+                    e
+                );
+            }
+        }
+    }
+
+    async fn reload(&self) -> Result<(), TlsError> {
+        let cert_pem = self.cert.resolve().await;
+        let key_pem = self.key.resolve().await;
+        {
+            let loaded = self.loaded.lock().unwrap();
+            if loaded.0 == cert_pem && loaded.1 == key_pem {
+                return Ok(());
+            }
+        }
+
+        let certified_key = parse_certified_key(&cert_pem, &key_pem)?;
+        *self.resolver.certified_key.write().unwrap() = Arc::new(certified_key);
+        *self.loaded.lock().unwrap() = (cert_pem, key_pem);
+        info!("reloaded tls certificate");
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct ReloadingCertResolver {
+    certified_key: RwLock<Arc<CertifiedKey>>,
+}
+
+impl ResolvesServerCert for ReloadingCertResolver {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(Arc::clone(&self.certified_key.read().unwrap()))
+    }
+}
+
+fn parse_certified_key(cert_pem: &str, key_pem: &str) -> Result<CertifiedKey, TlsError> {
+    let certs = rustls_pemfile::certs(&mut cert_pem.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        return Err(TlsError::NoCertificate);
+    }
+
+    let key =
+        rustls_pemfile::private_key(&mut key_pem.as_bytes())?.ok_or(TlsError::NoPrivateKey)?;
+    let certified_key = CertifiedKey::new(certs, any_supported_type(&key)?);
+    certified_key.keys_match()?;
+    Ok(certified_key)
+}
+
+impl From<std::io::Error> for TlsError {
+    fn from(value: std::io::Error) -> Self {
+        TlsError::General(Box::new(value))
+    }
+}
+
+impl From<tokio_rustls::rustls::Error> for TlsError {
+    fn from(value: tokio_rustls::rustls::Error) -> Self {
+        TlsError::General(Box::new(value))
+    }
+}
@@ -1,4 +1,4 @@
-use bitcoin::Address;
+use bitcoin::{Address, OutPoint, Txid};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -14,4 +14,32 @@ pub enum WalletError {
 #[async_trait::async_trait]
 pub trait Wallet {
     async fn new_address(&self) -> Result<Address, WalletError>;
+
+    /// Balance of the wallet in satoshis, including unconfirmed outputs.
+    async fn balance_sat(&self) -> Result<u64, WalletError>;
+
+    /// Sends `amount_sat` from the wallet to `address` with the given fee
+    /// rate. Returns the id of the published transaction.
+    async fn send_to_address(
+        &self,
+        address: &Address,
+        amount_sat: u64,
+        fee_per_kw: u32,
+    ) -> Result<Txid, WalletError>;
+
+    /// Returns the id of a transaction in the wallet that pays to `address`,
+    /// confirmed or not. Transactions that were double spent or abandoned are
+    /// no longer in the wallet.
+    async fn find_tx_to_address(&self, address: &Address) -> Result<Option<Txid>, WalletError>;
+
+    /// Spends the wallet output `outpoint` entirely to `address` with the
+    /// given fee rate, also if `outpoint` is unconfirmed. Used to bump the fee
+    /// of the parent transaction through CPFP. Returns the id of the
+    /// published transaction.
+    async fn sweep_output(
+        &self,
+        outpoint: &OutPoint,
+        address: &Address,
+        fee_per_kw: u32,
+    ) -> Result<Txid, WalletError>;
 }